@@ -0,0 +1,183 @@
+use log::{debug, info};
+use network::client::{Account, Client};
+use network::clientbound::ClientboundPacket;
+use network::protocol::SupportedVersion;
+use network::serverbound;
+use std::io;
+use std::ops::Add;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const TICK: Duration = Duration::from_millis(50);
+const WALK_SPEED: f64 = 0.2;
+const TURN_SPEED: f32 = 3.0;
+const DEFAULT_SPAWN: (f64, f64, f64) = (0.5, 64.0, 0.5);
+
+pub struct BotOptions {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub version: &'static SupportedVersion,
+    pub account: Option<Account>,
+    pub walk: bool,
+    pub chat_interval: Option<Duration>,
+}
+
+/// A headless player which joins a server, then walks in circles and chats
+/// until it is stopped.
+pub struct Bot {
+    client: Client,
+    options: BotOptions,
+    position: (f64, f64, f64),
+    yaw: f32,
+    pitch: f32,
+    last_chat: Instant,
+    messages_sent: u32,
+    packets_received: u64,
+}
+
+impl Bot {
+    pub fn join(options: BotOptions) -> io::Result<Self> {
+        let mut client = Client::connect(&options.host, options.port)?;
+        client.set_version(options.version);
+        client.login(&options.username, options.account.as_ref())?;
+
+        info!(
+            "{} joined as {}.",
+            client.get_username(),
+            client.get_uuid().to_hyphenated()
+        );
+
+        Ok(Self {
+            client,
+            options,
+            position: DEFAULT_SPAWN,
+            yaw: 0.0,
+            pitch: 0.0,
+            last_chat: Instant::now(),
+            messages_sent: 0,
+            packets_received: 0,
+        })
+    }
+
+    /// Plays until `run` is cleared or `duration` elapsed, then disconnects.
+    pub fn run(&mut self, run: &AtomicBool, duration: Option<Duration>) -> io::Result<()> {
+        let started = Instant::now();
+        let mut next_tick = started + TICK;
+
+        while run.load(Ordering::SeqCst) {
+            if let Some(duration) = duration {
+                if started.elapsed() >= duration {
+                    break;
+                }
+            }
+
+            let now = Instant::now();
+            if now >= next_tick {
+                self.tick()?;
+                next_tick += TICK;
+                continue;
+            }
+
+            // Zero is not a valid timeout
+            let timeout = (next_tick - now).max(Duration::from_millis(1));
+            self.client.set_read_timeout(Some(timeout))?;
+
+            match self.client.read_packet() {
+                Ok(packet) => self.handle_packet(packet)?,
+                Err(ref e)
+                    if e.kind() == io::ErrorKind::WouldBlock
+                        || e.kind() == io::ErrorKind::TimedOut => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.client.disconnect()
+    }
+
+    pub fn get_username(&self) -> &String {
+        self.client.get_username()
+    }
+
+    pub fn get_packets_received(&self) -> u64 {
+        self.packets_received
+    }
+
+    fn handle_packet(&mut self, packet: ClientboundPacket) -> io::Result<()> {
+        self.packets_received += 1;
+
+        match packet {
+            ClientboundPacket::PlayerPositionAndLook(p) => {
+                self.position.0 = relative(p.flags, 0x01, self.position.0, p.x);
+                self.position.1 = relative(p.flags, 0x02, self.position.1, p.y);
+                self.position.2 = relative(p.flags, 0x04, self.position.2, p.z);
+                self.yaw = relative(p.flags, 0x08, self.yaw, p.yaw);
+                self.pitch = relative(p.flags, 0x10, self.pitch, p.pitch);
+
+                self.client.send_packet(
+                    &serverbound::play::TeleportConfirmPacket {
+                        teleport_id: p.teleport_id,
+                    }
+                    .into(),
+                )?;
+            }
+            ClientboundPacket::ChatMessage(p) => {
+                if let Ok(message) = p.message.to_string() {
+                    debug!("{} received chat: {}", self.get_username(), message);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn tick(&mut self) -> io::Result<()> {
+        if self.options.walk {
+            self.walk()?;
+        }
+
+        if let Some(interval) = self.options.chat_interval {
+            if self.last_chat.elapsed() >= interval {
+                self.messages_sent += 1;
+                self.last_chat = Instant::now();
+
+                let message = format!(
+                    "Hello from {} (#{})",
+                    self.get_username(),
+                    self.messages_sent
+                );
+                self.client.send_chat(&message)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn walk(&mut self) -> io::Result<()> {
+        self.yaw = (self.yaw + TURN_SPEED) % 360.0;
+
+        let radians = f64::from(self.yaw).to_radians();
+        self.position.0 -= radians.sin() * WALK_SPEED;
+        self.position.2 += radians.cos() * WALK_SPEED;
+
+        self.client.send_packet(
+            &serverbound::play::PlayerPositionAndRotationPacket {
+                x: self.position.0,
+                y: self.position.1,
+                z: self.position.2,
+                yaw: self.yaw,
+                pitch: self.pitch,
+                on_ground: true,
+            }
+            .into(),
+        )
+    }
+}
+
+/// Each flag bit of a teleport marks the matching field as relative.
+fn relative<T: Add<Output = T>>(flags: u8, bit: u8, current: T, value: T) -> T {
+    if flags & bit != 0 {
+        current + value
+    } else {
+        value
+    }
+}
@@ -0,0 +1,262 @@
+use bot::{Bot, BotOptions};
+use env_logger::Env;
+use log::{error, info, warn};
+use network::client::{Account, Client};
+use network::protocol::{find_version_by_name, latest_version, SupportedVersion};
+use std::env;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+const USAGE: &str = "Usage: minecrate-bot [options] <host[:port]>
+
+Options:
+    -n, --count <n>          Number of bots to spawn (default: 1)
+    --name <name>            Username, suffixed by the bot index when spawning
+                             several bots (default: Bot)
+    --version <release>      Release to connect with (default: latest supported)
+    --delay <ms>             Delay between two bot spawns (default: 100)
+    --duration <secs>        Disconnect the bots after this long
+    --chat <secs>            Chat interval, 0 to stay silent (default: 10)
+    --no-walk                Stand still instead of walking in circles
+    --status                 Only ping the server and exit
+    --access-token <token>   Join online-mode servers with this account
+    --profile <uuid>         Profile UUID of the account";
+
+struct Arguments {
+    host: String,
+    port: u16,
+    count: usize,
+    name: String,
+    version: &'static SupportedVersion,
+    delay: Duration,
+    duration: Option<Duration>,
+    chat_interval: Option<Duration>,
+    walk: bool,
+    status: bool,
+    account: Option<Account>,
+}
+
+#[derive(Default)]
+struct Stats {
+    online: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+fn main() {
+    env_logger::from_env(Env::default().default_filter_or("info")).init();
+
+    let args = match parse_arguments(env::args().skip(1).collect()) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if args.status {
+        process::exit(status(&args));
+    }
+
+    let run = Arc::new(AtomicBool::new(true));
+    let run_cpy = run.clone();
+    ctrlc::set_handler(move || {
+        warn!("Received interruption signal...");
+        run_cpy.store(false, Ordering::SeqCst);
+    })
+    .expect("Failed to set interrupt handler");
+
+    let stats = Arc::new(Stats::default());
+    let mut handles = vec![];
+
+    for i in 0..args.count {
+        if !run.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let options = BotOptions {
+            host: args.host.clone(),
+            port: args.port,
+            username: if args.count == 1 {
+                args.name.clone()
+            } else {
+                format!("{}{}", args.name, i)
+            },
+            version: args.version,
+            account: args.account.clone(),
+            walk: args.walk,
+            chat_interval: args.chat_interval,
+        };
+        let run = run.clone();
+        let stats = stats.clone();
+        let duration = args.duration;
+
+        handles.push(thread::spawn(move || {
+            let username = options.username.clone();
+            let result = Bot::join(options).and_then(|mut bot| {
+                stats.online.fetch_add(1, Ordering::SeqCst);
+                let result = bot.run(&run, duration);
+
+                stats.online.fetch_sub(1, Ordering::SeqCst);
+                info!(
+                    "{} left after receiving {} packets.",
+                    bot.get_username(),
+                    bot.get_packets_received()
+                );
+                result
+            });
+
+            if let Err(e) = result {
+                error!("{} failed: {}", username, e);
+                stats.failed.fetch_add(1, Ordering::SeqCst);
+            }
+        }));
+
+        thread::sleep(args.delay);
+    }
+
+    info!(
+        "Spawned {} bots, {} online, {} failed.",
+        handles.len(),
+        stats.online.load(Ordering::SeqCst),
+        stats.failed.load(Ordering::SeqCst)
+    );
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    let failed = stats.failed.load(Ordering::SeqCst);
+    info!("All bots left, {} failed.", failed);
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+fn status(args: &Arguments) -> i32 {
+    let result = Client::connect(&args.host, args.port).and_then(|mut client| {
+        client.set_version(args.version);
+        client.status()
+    });
+
+    match result {
+        Ok((payload, latency)) => {
+            info!(
+                "{} ({}), {}/{} players, {} ms.",
+                payload.version.name,
+                payload.version.protocol,
+                payload.players.online,
+                payload.players.max,
+                latency.as_millis()
+            );
+            0
+        }
+        Err(e) => {
+            error!("Failed to ping the server: {}", e);
+            1
+        }
+    }
+}
+
+fn parse_arguments(args: Vec<String>) -> Result<Arguments, String> {
+    let mut address = None;
+    let mut count = 1;
+    let mut name = String::from("Bot");
+    let mut version = latest_version();
+    let mut delay = Duration::from_millis(100);
+    let mut duration = None;
+    let mut chat_interval = Some(Duration::from_secs(10));
+    let mut walk = true;
+    let mut status = false;
+    let mut access_token = None;
+    let mut profile = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {}", arg))
+        };
+
+        match arg.as_str() {
+            "-n" | "--count" => count = parse_number(&value()?)?,
+            "--name" => name = value()?,
+            "--version" => {
+                let release = value()?;
+                version = find_version_by_name(&release)
+                    .ok_or_else(|| format!("Unsupported release {}", release))?;
+            }
+            "--delay" => delay = Duration::from_millis(parse_number(&value()?)?),
+            "--duration" => duration = Some(Duration::from_secs(parse_number(&value()?)?)),
+            "--chat" => {
+                chat_interval = match parse_number(&value()?)? {
+                    0 => None,
+                    secs => Some(Duration::from_secs(secs)),
+                }
+            }
+            "--no-walk" => walk = false,
+            "--status" => status = true,
+            "--access-token" => access_token = Some(value()?),
+            "--profile" => profile = Some(Uuid::parse_str(&value()?).map_err(|e| e.to_string())?),
+            "-h" | "--help" => return Err(String::from("Help requested")),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+            _ => address = Some(arg),
+        }
+    }
+
+    let address = address.ok_or_else(|| String::from("Missing server address"))?;
+    let (host, port) = match address.rfind(':') {
+        Some(i) => (
+            address[..i].to_string(),
+            address[i + 1..]
+                .parse()
+                .map_err(|_| format!("Invalid port in {}", address))?,
+        ),
+        None => (address, 25565),
+    };
+
+    let account = match (access_token, profile) {
+        (Some(access_token), Some(profile_id)) => Some(Account {
+            access_token,
+            profile_id,
+        }),
+        (None, None) => None,
+        _ => return Err(String::from("--access-token and --profile go together")),
+    };
+
+    if account.is_some() && count != 1 {
+        return Err(String::from("An account can only be used by a single bot"));
+    }
+
+    let longest_name = if count == 1 {
+        name.len()
+    } else {
+        name.len() + (count - 1).to_string().len()
+    };
+    if longest_name > 16 {
+        return Err(String::from("Usernames can't be longer than 16 characters"));
+    }
+
+    Ok(Arguments {
+        host,
+        port,
+        count,
+        name,
+        version,
+        delay,
+        duration,
+        chat_interval,
+        walk,
+        status,
+        account,
+    })
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number {}", value))
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::convert::TryFrom;
 
 use crate::chat_color::ChatColor;
 
@@ -27,6 +28,29 @@ pub enum ChatMessageType {
     GameInfo,
 }
 
+impl From<ChatMessageType> for u8 {
+    fn from(value: ChatMessageType) -> Self {
+        match value {
+            ChatMessageType::Chat => 0,
+            ChatMessageType::System => 1,
+            ChatMessageType::GameInfo => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for ChatMessageType {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ChatMessageType::Chat),
+            1 => Ok(ChatMessageType::System),
+            2 => Ok(ChatMessageType::GameInfo),
+            _ => Err("Unknown ChatMessageType"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ChatSetting {
     Full,
@@ -114,6 +138,9 @@ pub struct ChatComponentString {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct ChatComponentTranslation {
     translate: String,
+    #[serde(default = "def_none")]
+    #[serde(skip_serializing_if = "skip_none")]
+    with: Option<Vec<Chat>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -227,6 +254,19 @@ impl Chat {
         }
     }
 
+    /// Text the client translates, `with` filling the placeholders of the
+    /// translation.
+    pub fn new_translation(key: &str, with: Vec<Chat>) -> Chat {
+        let mut chat = Chat::new_text("");
+
+        chat.component_string = None;
+        chat.component_translation = Some(ChatComponentTranslation {
+            translate: key.to_string(),
+            with: if with.is_empty() { None } else { Some(with) },
+        });
+        chat
+    }
+
     pub fn from_string(text: &str) -> Result<Chat, serde_json::Error> {
         serde_json::from_str(text)
     }
@@ -8,6 +8,26 @@ pub enum Dimension {
     Custom(i32),
 }
 
+impl Dimension {
+    pub fn from_identifier(value: &str) -> Option<Self> {
+        match value {
+            "minecraft:overworld" => Some(Dimension::Overworld),
+            "minecraft:the_nether" => Some(Dimension::Nether),
+            "minecraft:the_end" => Some(Dimension::End),
+            _ => None,
+        }
+    }
+
+    pub fn to_identifier(self) -> Option<&'static str> {
+        match self {
+            Dimension::Overworld => Some("minecraft:overworld"),
+            Dimension::Nether => Some("minecraft:the_nether"),
+            Dimension::End => Some("minecraft:the_end"),
+            Dimension::Custom(_) => None,
+        }
+    }
+}
+
 impl From<Dimension> for i32 {
     fn from(value: Dimension) -> i32 {
         match value {
@@ -0,0 +1,45 @@
+/// Types of the entities the server spawns. Their ids in the entity type
+/// registry change between versions, so the protocol maps them.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum EntityType {
+    Cow,
+    FallingBlock,
+    Item,
+    Zombie,
+}
+
+impl EntityType {
+    /// Category the mob spawns and despawns with, `None` for entities which
+    /// aren't mobs.
+    pub fn get_category(self) -> Option<MobCategory> {
+        match self {
+            EntityType::Zombie => Some(MobCategory::Monster),
+            EntityType::Cow => Some(MobCategory::Creature),
+            EntityType::FallingBlock | EntityType::Item => None,
+        }
+    }
+}
+
+/// Kinds of mobs, each spawning under its own conditions and up to its own
+/// cap.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum MobCategory {
+    Monster,
+    Creature,
+    Ambient,
+    WaterCreature,
+}
+
+impl MobCategory {
+    pub const ALL: [MobCategory; 4] = [
+        MobCategory::Monster,
+        MobCategory::Creature,
+        MobCategory::Ambient,
+        MobCategory::WaterCreature,
+    ];
+
+    /// Whether mobs of the category stay when no player is around.
+    pub fn is_persistent(self) -> bool {
+        self == MobCategory::Creature
+    }
+}
@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
 use std::convert::From;
 use std::convert::TryFrom;
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Gamemode {
     Survival,
     Creative,
@@ -9,6 +11,27 @@ pub enum Gamemode {
     Spectator,
 }
 
+impl Gamemode {
+    pub fn from_string(value: &str) -> Option<Self> {
+        match value {
+            "survival" => Some(Gamemode::Survival),
+            "creative" => Some(Gamemode::Creative),
+            "adventure" => Some(Gamemode::Adventure),
+            "spectator" => Some(Gamemode::Spectator),
+            _ => None,
+        }
+    }
+
+    pub fn to_string(self) -> &'static str {
+        match self {
+            Gamemode::Survival => "survival",
+            Gamemode::Creative => "creative",
+            Gamemode::Adventure => "adventure",
+            Gamemode::Spectator => "spectator",
+        }
+    }
+}
+
 impl From<Gamemode> for u8 {
     fn from(value: Gamemode) -> Self {
         match value {
@@ -0,0 +1,23 @@
+/// Items in an inventory slot, by id in the item registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    pub item: i32,
+    pub count: i8,
+    pub nbt: Option<nbt::Blob>,
+}
+
+impl ItemStack {
+    pub fn new(item: i32, count: i8) -> Self {
+        Self {
+            item,
+            count,
+            nbt: None,
+        }
+    }
+
+    /// Whether both stacks hold the same items, which can be stacked
+    /// together.
+    pub fn is_same_item(&self, other: &ItemStack) -> bool {
+        self.item == other.item && self.nbt == other.nbt
+    }
+}
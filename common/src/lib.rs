@@ -2,6 +2,10 @@ pub mod chat;
 pub mod chat_color;
 pub mod difficulty;
 pub mod dimension;
+pub mod entity_type;
 pub mod gamemode;
+pub mod item;
 pub mod level_type;
+pub mod location;
+pub mod metadata;
 pub mod utils;
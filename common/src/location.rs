@@ -0,0 +1,21 @@
+/// Where an entity stands and where it looks, angles being in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Location {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Location {
+    pub fn new(x: f64, y: f64, z: f64, yaw: f32, pitch: f32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+        }
+    }
+}
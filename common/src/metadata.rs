@@ -0,0 +1,173 @@
+use cgmath::Vector3;
+use std::convert::From;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+use crate::chat::Chat;
+use crate::item::ItemStack;
+
+/// Value of an entity metadata field, along with its type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataValue {
+    Byte(i8),
+    VarInt(i32),
+    Float(f32),
+    String(String),
+    Chat(Chat),
+    OptChat(Option<Chat>),
+    Slot(Option<ItemStack>),
+    Boolean(bool),
+    /// Rotations around the x, y and z axes, in degrees.
+    Rotation(f32, f32, f32),
+    Position(Vector3<i32>),
+    OptPosition(Option<Vector3<i32>>),
+    Direction(Direction),
+    OptUuid(Option<Uuid>),
+    /// Block state id, absent for air.
+    OptBlockId(Option<i32>),
+    Nbt(nbt::Blob),
+    Particle(Particle),
+    VillagerData(VillagerData),
+    OptVarInt(Option<i32>),
+    Pose(Pose),
+}
+
+impl MetadataValue {
+    /// Id of the type of the value in the protocol.
+    pub fn get_type_id(&self) -> i32 {
+        match self {
+            MetadataValue::Byte(_) => 0,
+            MetadataValue::VarInt(_) => 1,
+            MetadataValue::Float(_) => 2,
+            MetadataValue::String(_) => 3,
+            MetadataValue::Chat(_) => 4,
+            MetadataValue::OptChat(_) => 5,
+            MetadataValue::Slot(_) => 6,
+            MetadataValue::Boolean(_) => 7,
+            MetadataValue::Rotation(..) => 8,
+            MetadataValue::Position(_) => 9,
+            MetadataValue::OptPosition(_) => 10,
+            MetadataValue::Direction(_) => 11,
+            MetadataValue::OptUuid(_) => 12,
+            MetadataValue::OptBlockId(_) => 13,
+            MetadataValue::Nbt(_) => 14,
+            MetadataValue::Particle(_) => 15,
+            MetadataValue::VillagerData(_) => 16,
+            MetadataValue::OptVarInt(_) => 17,
+            MetadataValue::Pose(_) => 18,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl From<Direction> for i32 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Down => 0,
+            Direction::Up => 1,
+            Direction::North => 2,
+            Direction::South => 3,
+            Direction::West => 4,
+            Direction::East => 5,
+        }
+    }
+}
+
+impl TryFrom<i32> for Direction {
+    type Error = &'static str;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Direction::Down),
+            1 => Ok(Direction::Up),
+            2 => Ok(Direction::North),
+            3 => Ok(Direction::South),
+            4 => Ok(Direction::West),
+            5 => Ok(Direction::East),
+            _ => Err("Unknown Direction"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Pose {
+    Standing,
+    FallFlying,
+    Sleeping,
+    Swimming,
+    SpinAttack,
+    Sneaking,
+    Dying,
+}
+
+impl From<Pose> for i32 {
+    fn from(value: Pose) -> Self {
+        match value {
+            Pose::Standing => 0,
+            Pose::FallFlying => 1,
+            Pose::Sleeping => 2,
+            Pose::Swimming => 3,
+            Pose::SpinAttack => 4,
+            Pose::Sneaking => 5,
+            Pose::Dying => 6,
+        }
+    }
+}
+
+impl TryFrom<i32> for Pose {
+    type Error = &'static str;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Pose::Standing),
+            1 => Ok(Pose::FallFlying),
+            2 => Ok(Pose::Sleeping),
+            3 => Ok(Pose::Swimming),
+            4 => Ok(Pose::SpinAttack),
+            5 => Ok(Pose::Sneaking),
+            6 => Ok(Pose::Dying),
+            _ => Err("Unknown Pose"),
+        }
+    }
+}
+
+/// Type, profession and level of a villager, by id in their registries.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct VillagerData {
+    pub villager_type: i32,
+    pub profession: i32,
+    pub level: i32,
+}
+
+/// A particle, by id in the particle registry, which differs between
+/// versions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    pub id: i32,
+    pub data: ParticleData,
+}
+
+/// Extra data of the few particles which have some.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParticleData {
+    None,
+    /// Block state of `block` and `falling_dust`.
+    Block(i32),
+    /// Color of `dust`, with components from 0 to 1.
+    Dust {
+        red: f32,
+        green: f32,
+        blue: f32,
+        scale: f32,
+    },
+    Item(Option<ItemStack>),
+}
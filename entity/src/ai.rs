@@ -0,0 +1,530 @@
+use common::gamemode::Gamemode;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use specs::prelude::*;
+use specs::Component;
+use world::world::World as Terrain;
+
+use crate::components::{BoundingBox, Health, OnGround, Player, Position, Rotation, Velocity};
+use crate::pathfinding::{Node, Path, PathCache};
+
+/// Vertical velocity of a jump, in blocks per tick.
+const JUMP_VELOCITY: f64 = 0.42;
+/// Share of its speed a mob gains every tick it walks, on the ground.
+const GROUND_ACCELERATION: f64 = 0.4;
+/// Share of its speed a mob gains every tick it walks, in the air.
+const AIR_ACCELERATION: f64 = 0.05;
+/// Distance at which a node of a path counts as reached.
+const NODE_REACH: f64 = 0.5;
+/// Ticks a mob tries to reach the next node of its path before giving up.
+const STUCK_TICKS: u32 = 100;
+/// Ticks a mob remembers being hurt.
+const HURT_MEMORY: u32 = 100;
+
+/// Parts of a mob a goal takes over, which no other goal can use meanwhile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Controls {
+    pub movement: bool,
+    pub look: bool,
+}
+
+impl Controls {
+    pub const MOVEMENT: Controls = Controls {
+        movement: true,
+        look: false,
+    };
+    pub const LOOK: Controls = Controls {
+        movement: false,
+        look: true,
+    };
+    pub const ALL: Controls = Controls {
+        movement: true,
+        look: true,
+    };
+
+    fn overlaps(self, other: Controls) -> bool {
+        (self.movement && other.movement) || (self.look && other.look)
+    }
+
+    fn add(&mut self, other: Controls) {
+        self.movement |= other.movement;
+        self.look |= other.look;
+    }
+}
+
+/// A player a mob can see, notice or attack.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Target {
+    pub entity: Entity,
+    pub position: Position,
+    pub gamemode: Gamemode,
+}
+
+impl Target {
+    /// Whether mobs may attack the player.
+    pub fn is_vulnerable(&self) -> bool {
+        self.gamemode == Gamemode::Survival || self.gamemode == Gamemode::Adventure
+    }
+}
+
+/// A melee hit a mob lands this tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attack {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub damage: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct Attacks(pub Vec<Attack>);
+
+/// Random numbers of the mobs, seeded so tests see the same mobs every run.
+pub struct AiRandom(pub StdRng);
+
+impl Default for AiRandom {
+    fn default() -> Self {
+        Self(StdRng::seed_from_u64(0))
+    }
+}
+
+/// What a goal knows about its mob and the world around it.
+pub struct MobContext<'a> {
+    pub entity: Entity,
+    pub position: Position,
+    pub bounding_box: BoundingBox,
+    /// Blocks per tick the mob walks at, before the speed of its goals.
+    pub movement_speed: f64,
+    pub attack_damage: f32,
+    pub navigation: &'a mut Navigation,
+    /// Where the mob should look this tick.
+    pub look_at: Option<Position>,
+    pub hurt_by: Option<Entity>,
+    pub hurt_ticks: u32,
+    pub players: &'a [Target],
+    pub terrain: &'a Terrain,
+    pub random: &'a mut StdRng,
+    pub attacks: &'a mut Vec<Attack>,
+}
+
+impl<'a> MobContext<'a> {
+    /// Closest player within `range` blocks matching `filter`.
+    pub fn get_nearest_player<F>(&self, range: f64, filter: F) -> Option<Target>
+    where
+        F: Fn(&Target) -> bool,
+    {
+        self.players
+            .iter()
+            .filter(|player| filter(player))
+            .map(|player| (distance_squared(&self.position, &player.position), player))
+            .filter(|(distance, _)| *distance <= range * range)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, player)| *player)
+    }
+
+    pub fn get_player(&self, entity: Entity) -> Option<Target> {
+        self.players
+            .iter()
+            .find(|player| player.entity == entity)
+            .copied()
+    }
+
+    /// Looks at the eyes of a player.
+    pub fn look_at_player(&mut self, player: &Target) {
+        let position = player.position;
+
+        self.look_at = Some(Position::new(
+            position.x,
+            position.y + BoundingBox::PLAYER_EYE_HEIGHT,
+            position.z,
+        ));
+    }
+
+    /// Block the mob stands in.
+    pub fn get_node(&self) -> Node {
+        to_node(&self.position)
+    }
+}
+
+/// A behavior of a mob, run by its `Brain` when no goal with a higher
+/// priority needs the same controls.
+pub trait Goal: Send + Sync {
+    fn get_controls(&self) -> Controls;
+
+    fn can_start(&mut self, mob: &mut MobContext) -> bool;
+
+    /// Whether the goal keeps running once started.
+    fn can_continue(&mut self, mob: &mut MobContext) -> bool {
+        self.can_start(mob)
+    }
+
+    fn start(&mut self, _mob: &mut MobContext) {}
+
+    fn stop(&mut self, _mob: &mut MobContext) {}
+
+    fn tick(&mut self, mob: &mut MobContext);
+}
+
+struct PrioritizedGoal {
+    priority: u32,
+    goal: Box<dyn Goal>,
+    running: bool,
+}
+
+/// Goals of a mob, by priority, the lowest number going first.
+#[derive(Component, Default)]
+#[storage(DenseVecStorage)]
+pub struct Brain {
+    goals: Vec<PrioritizedGoal>,
+    navigation: Navigation,
+    hurt_by: Option<Entity>,
+    hurt_ticks: u32,
+}
+
+impl Brain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_goal<G: Goal + 'static>(mut self, priority: u32, goal: G) -> Self {
+        self.add_goal(priority, goal);
+        self
+    }
+
+    pub fn add_goal<G: Goal + 'static>(&mut self, priority: u32, goal: G) {
+        let index = self
+            .goals
+            .iter()
+            .position(|goal| goal.priority > priority)
+            .unwrap_or(self.goals.len());
+
+        self.goals.insert(
+            index,
+            PrioritizedGoal {
+                priority,
+                goal: Box::new(goal),
+                running: false,
+            },
+        );
+    }
+
+    /// Remembers being hurt, by an entity or not, which makes some mobs
+    /// panic.
+    pub fn hurt(&mut self, attacker: Option<Entity>) {
+        self.hurt_by = attacker;
+        self.hurt_ticks = HURT_MEMORY;
+    }
+
+    pub fn get_navigation(&self) -> &Navigation {
+        &self.navigation
+    }
+
+    /// Picks the goals to run by priority, each needing controls no goal
+    /// before it took, stops the others, then ticks those picked.
+    fn update(&mut self, mob: &mut MobContext) {
+        let mut used = Controls::default();
+        let mut selected = Vec::with_capacity(self.goals.len());
+
+        for goal in &mut self.goals {
+            let controls = goal.goal.get_controls();
+            let wanted = !controls.overlaps(used)
+                && if goal.running {
+                    goal.goal.can_continue(mob)
+                } else {
+                    goal.goal.can_start(mob)
+                };
+
+            if wanted {
+                used.add(controls);
+            }
+            selected.push(wanted);
+        }
+
+        for (goal, wanted) in self.goals.iter_mut().zip(&selected) {
+            if goal.running && !wanted {
+                goal.running = false;
+                goal.goal.stop(mob);
+            }
+        }
+        for (goal, wanted) in self.goals.iter_mut().zip(&selected) {
+            if *wanted {
+                if !goal.running {
+                    goal.running = true;
+                    goal.goal.start(mob);
+                }
+                goal.goal.tick(mob);
+            }
+        }
+    }
+}
+
+/// Where a mob walks to, and the path it follows to get there.
+#[derive(Debug, Clone, Default)]
+pub struct Navigation {
+    destination: Option<Node>,
+    speed: f64,
+    path: Option<Path>,
+    stuck_ticks: u32,
+}
+
+impl Navigation {
+    /// Walks to a block at `speed` times the speed of the mob.
+    pub fn move_to(&mut self, destination: Node, speed: f64) {
+        if self.destination != Some(destination) {
+            self.path = None;
+            self.stuck_ticks = 0;
+        }
+        self.destination = Some(destination);
+        self.speed = speed;
+    }
+
+    pub fn stop(&mut self) {
+        self.destination = None;
+        self.path = None;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.destination.is_none()
+    }
+
+    pub fn get_destination(&self) -> Option<Node> {
+        self.destination
+    }
+
+    pub fn get_path(&self) -> Option<&Path> {
+        self.path.as_ref()
+    }
+}
+
+/// Mob stats the AI needs.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[storage(VecStorage)]
+pub struct Mob {
+    /// Blocks per tick the mob walks at.
+    pub movement_speed: f64,
+    pub attack_damage: f32,
+}
+
+/// Runs the goals of the mobs, then moves them along their paths.
+pub struct AiSystem;
+
+impl<'a> System<'a> for AiSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Terrain>,
+        Write<'a, PathCache>,
+        Write<'a, AiRandom>,
+        Write<'a, Attacks>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Mob>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, BoundingBox>,
+        ReadStorage<'a, OnGround>,
+        WriteStorage<'a, Rotation>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, Brain>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            terrain,
+            mut path_cache,
+            mut random,
+            mut attacks,
+            players,
+            healths,
+            mobs,
+            positions,
+            bounding_boxes,
+            on_grounds,
+            mut rotations,
+            mut velocities,
+            mut brains,
+        ): Self::SystemData,
+    ) {
+        // Dead players wait for their respawn out of sight of the mobs
+        let targets: Vec<Target> = (&entities, &players, &positions)
+            .join()
+            .filter(|(entity, _, _)| !healths.get(*entity).is_some_and(|health| health.is_dead()))
+            .map(|(entity, player, position)| Target {
+                entity,
+                position: *position,
+                gamemode: player.gamemode,
+            })
+            .collect();
+
+        path_cache.tick();
+        attacks.0.clear();
+
+        for (entity, mob, position, bounding_box, rotation, velocity, brain) in (
+            &entities,
+            &mobs,
+            &positions,
+            &bounding_boxes,
+            &mut rotations,
+            &mut velocities,
+            &mut brains,
+        )
+            .join()
+        {
+            let on_ground = on_grounds.get(entity).is_some_and(|on_ground| on_ground.0);
+            let mut navigation = std::mem::take(&mut brain.navigation);
+            let mut mob = MobContext {
+                entity,
+                position: *position,
+                bounding_box: *bounding_box,
+                movement_speed: mob.movement_speed,
+                attack_damage: mob.attack_damage,
+                navigation: &mut navigation,
+                look_at: None,
+                hurt_by: brain.hurt_by,
+                hurt_ticks: brain.hurt_ticks,
+                players: &targets,
+                terrain: &terrain,
+                random: &mut random.0,
+                attacks: &mut attacks.0,
+            };
+
+            brain.update(&mut mob);
+            let look_at = mob.look_at;
+            let movement_speed = mob.movement_speed;
+
+            brain.hurt_ticks = brain.hurt_ticks.saturating_sub(1);
+            if brain.hurt_ticks == 0 {
+                brain.hurt_by = None;
+            }
+
+            navigate(
+                &mut navigation,
+                &mut path_cache,
+                &terrain,
+                position,
+                bounding_box,
+                on_ground,
+                movement_speed,
+                rotation,
+                velocity,
+            );
+            brain.navigation = navigation;
+
+            if let Some(target) = look_at {
+                look_at_position(position, bounding_box, &target, rotation);
+            }
+        }
+    }
+}
+
+/// Walks a mob along its path, finding one first if needed.
+#[allow(clippy::too_many_arguments)]
+fn navigate(
+    navigation: &mut Navigation,
+    path_cache: &mut PathCache,
+    terrain: &Terrain,
+    position: &Position,
+    bounding_box: &BoundingBox,
+    on_ground: bool,
+    movement_speed: f64,
+    rotation: &mut Rotation,
+    velocity: &mut Velocity,
+) {
+    let destination = match navigation.destination {
+        Some(destination) => destination,
+        None => return,
+    };
+    if navigation.path.is_none() {
+        navigation.path =
+            path_cache.find_path(terrain, bounding_box, to_node(position), destination);
+        if navigation.path.is_none() {
+            navigation.stop();
+            return;
+        }
+    }
+
+    let path = navigation.path.as_mut().expect("Path was just found");
+    let next = loop {
+        match path.get_next() {
+            Some(node) if is_reached(position, node) => {
+                path.advance();
+                navigation.stuck_ticks = 0;
+            }
+            Some(node) => break node,
+            None => {
+                navigation.stop();
+                return;
+            }
+        }
+    };
+
+    navigation.stuck_ticks += 1;
+    if navigation.stuck_ticks > STUCK_TICKS {
+        navigation.stop();
+        return;
+    }
+
+    let dx = f64::from(next.0) + 0.5 - position.x;
+    let dz = f64::from(next.2) + 0.5 - position.z;
+    let length = (dx * dx + dz * dz).sqrt();
+    let acceleration = if on_ground {
+        GROUND_ACCELERATION
+    } else {
+        AIR_ACCELERATION
+    };
+    let speed = movement_speed * navigation.speed * acceleration;
+
+    if length > f64::EPSILON {
+        velocity.x += dx / length * speed;
+        velocity.z += dz / length * speed;
+        rotation.yaw = get_yaw(dx, dz);
+        rotation.pitch = 0.0;
+    }
+    if on_ground && f64::from(next.1) > position.y + 0.5 {
+        velocity.y = JUMP_VELOCITY;
+    }
+}
+
+fn is_reached(position: &Position, node: Node) -> bool {
+    let dx = f64::from(node.0) + 0.5 - position.x;
+    let dz = f64::from(node.2) + 0.5 - position.z;
+
+    dx * dx + dz * dz <= NODE_REACH * NODE_REACH && (f64::from(node.1) - position.y).abs() < 1.0
+}
+
+fn look_at_position(
+    position: &Position,
+    bounding_box: &BoundingBox,
+    target: &Position,
+    rotation: &mut Rotation,
+) {
+    let dx = target.x - position.x;
+    let dy = target.y - (position.y + bounding_box.height * 0.85);
+    let dz = target.z - position.z;
+    let horizontal = (dx * dx + dz * dz).sqrt();
+
+    rotation.yaw = get_yaw(dx, dz);
+    rotation.pitch = (-dy.atan2(horizontal).to_degrees()) as f32;
+}
+
+/// Yaw looking along a direction, 0 being south and 90 west.
+fn get_yaw(dx: f64, dz: f64) -> f32 {
+    (-dx).atan2(dz).to_degrees() as f32
+}
+
+/// Node of the pathfinder a position is in.
+pub fn to_node(position: &Position) -> Node {
+    (
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    )
+}
+
+pub fn distance_squared(from: &Position, to: &Position) -> f64 {
+    let dx = to.x - from.x;
+    let dy = to.y - from.y;
+    let dz = to.z - from.z;
+
+    dx * dx + dy * dy + dz * dz
+}
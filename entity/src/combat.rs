@@ -0,0 +1,378 @@
+use common::gamemode::Gamemode;
+use specs::prelude::*;
+use specs::Component;
+use std::collections::HashMap;
+
+use crate::ai::{distance_squared, Attacks, Brain};
+use crate::components::{Health, OnGround, Player, Position, Rotation, Velocity};
+use crate::inventory::Inventory;
+use crate::metadata::{EntityFlag, Metadata};
+
+/// Ticks during which an entity only takes the damage of hits stronger than
+/// the last one, the first half of them not even that.
+pub const INVULNERABILITY_TICKS: u32 = 20;
+/// Blocks between a player and the entities they can hit.
+pub const REACH: f64 = 6.0;
+/// Ticks a dead mob stays for its death animation.
+pub const DEATH_TICKS: u32 = 20;
+
+const KNOCKBACK: f64 = 0.4;
+/// Knockback added by a sprinting attacker.
+const SPRINT_KNOCKBACK: f64 = 0.5;
+const CRITICAL_MULTIPLIER: f32 = 1.5;
+
+/// Damage and speed of the attacks made with an item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weapon {
+    pub damage: f32,
+    /// Attacks per second at full strength.
+    pub attack_speed: f64,
+}
+
+impl Weapon {
+    pub const FIST: Weapon = Weapon {
+        damage: 1.0,
+        attack_speed: 4.0,
+    };
+
+    pub fn new(damage: f32, attack_speed: f64) -> Self {
+        Self {
+            damage,
+            attack_speed,
+        }
+    }
+
+    /// Ticks after an attack until the next one has its full strength.
+    pub fn get_cooldown(&self) -> f64 {
+        20.0 / self.attack_speed
+    }
+}
+
+/// Items players attack with, by id in the item registry. Other items hit
+/// like a fist.
+#[derive(Debug, Default)]
+pub struct Weapons {
+    weapons: HashMap<i32, Weapon>,
+}
+
+impl Weapons {
+    pub fn new() -> Self {
+        Self {
+            weapons: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, item: Option<i32>) -> Weapon {
+        item.and_then(|item| self.weapons.get(&item))
+            .copied()
+            .unwrap_or(Weapon::FIST)
+    }
+
+    pub fn set(&mut self, item: i32, weapon: Weapon) {
+        self.weapons.insert(item, weapon);
+    }
+}
+
+/// Hits taken and given by a living entity.
+#[derive(Component, Debug, Clone, Default)]
+#[storage(DenseVecStorage)]
+pub struct Combat {
+    invulnerability: u32,
+    last_damage: f32,
+    /// Ticks since the entity last attacked.
+    attack_ticks: u32,
+    death_ticks: u32,
+    falling: bool,
+}
+
+impl Combat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerability > 0
+    }
+
+    /// Share of the damage of a weapon an attack would deal now, from 0.2
+    /// right after an attack to 1 once the cooldown is over.
+    pub fn get_attack_strength(&self, weapon: &Weapon) -> f32 {
+        let progress = ((f64::from(self.attack_ticks) + 0.5) / weapon.get_cooldown()).min(1.0);
+
+        (0.2 + progress * progress * 0.8) as f32
+    }
+
+    /// Records whether the entity is falling, which makes its attacks
+    /// critical hits.
+    pub fn set_falling(&mut self, falling: bool) {
+        self.falling = falling;
+    }
+}
+
+/// Something the players around have to see or be told.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CombatEvent {
+    /// Only hits going through no invulnerability are `animated`, with a
+    /// hurt animation and a knockback.
+    Hurt {
+        entity: Entity,
+        attacker: Option<Entity>,
+        animated: bool,
+    },
+    Critical {
+        entity: Entity,
+    },
+    Death {
+        entity: Entity,
+        attacker: Option<Entity>,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct CombatEvents(pub Vec<CombatEvent>);
+
+/// Storages needed to hurt an entity.
+pub type CombatData<'a> = (
+    Entities<'a>,
+    ReadStorage<'a, Player>,
+    ReadStorage<'a, Position>,
+    ReadStorage<'a, OnGround>,
+    WriteStorage<'a, Health>,
+    WriteStorage<'a, Combat>,
+    WriteStorage<'a, Velocity>,
+    WriteStorage<'a, Metadata>,
+    WriteStorage<'a, Brain>,
+    Write<'a, CombatEvents>,
+);
+
+/// Counts down the invulnerabilities and attack cooldowns, applies the
+/// attacks of the mobs, and removes the mobs dead for long enough.
+pub struct CombatSystem;
+
+impl<'a> System<'a> for CombatSystem {
+    type SystemData = (Read<'a, Attacks>, CombatData<'a>);
+
+    fn run(&mut self, (attacks, mut data): Self::SystemData) {
+        {
+            let (entities, players, _, _, healths, combats, ..) = &mut data;
+
+            for (entity, combat, health) in (&*entities, &mut *combats, &*healths).join() {
+                combat.invulnerability = combat.invulnerability.saturating_sub(1);
+                combat.attack_ticks = combat.attack_ticks.saturating_add(1);
+
+                // Dead players stay until they respawn
+                if health.is_dead() && players.get(entity).is_none() {
+                    combat.death_ticks += 1;
+                    if combat.death_ticks >= DEATH_TICKS {
+                        let _ = entities.delete(entity);
+                    }
+                }
+            }
+        }
+
+        for attack in &attacks.0 {
+            hurt(
+                &mut data,
+                attack.target,
+                attack.damage,
+                Some(attack.attacker),
+                None,
+            );
+        }
+    }
+}
+
+/// Deals damage to an entity, knocking it back from its attacker and
+/// further in the `extra_knockback` direction. Returns whether it was hurt.
+pub fn hurt(
+    data: &mut CombatData,
+    target: Entity,
+    amount: f32,
+    attacker: Option<Entity>,
+    extra_knockback: Option<(f64, f64)>,
+) -> bool {
+    let (
+        entities,
+        players,
+        positions,
+        on_grounds,
+        healths,
+        combats,
+        velocities,
+        metadata,
+        brains,
+        events,
+    ) = data;
+
+    if !entities.is_alive(target) || amount <= 0.0 {
+        return false;
+    }
+    if players.get(target).is_some_and(|player| {
+        player.gamemode == Gamemode::Creative || player.gamemode == Gamemode::Spectator
+    }) {
+        return false;
+    }
+    let health = match healths.get_mut(target) {
+        Some(health) if !health.is_dead() => health,
+        _ => return false,
+    };
+    let combat = match combats.entry(target) {
+        Ok(entry) => entry.or_insert_with(Combat::default),
+        Err(_) => return false,
+    };
+
+    let animated = combat.invulnerability <= INVULNERABILITY_TICKS / 2;
+    let damage = if animated {
+        combat.invulnerability = INVULNERABILITY_TICKS;
+        amount
+    } else if amount > combat.last_damage {
+        amount - combat.last_damage
+    } else {
+        return false;
+    };
+    combat.last_damage = amount;
+
+    health.current = (health.current - damage).max(0.0);
+    let dead = health.is_dead();
+    if let Some(metadata) = metadata.get_mut(target) {
+        metadata.set_health(health.current);
+    }
+
+    if animated {
+        let on_ground = on_grounds.get(target).is_some_and(|on_ground| on_ground.0);
+
+        if let Some(velocity) = velocities.get_mut(target) {
+            // The clients of players only get the knockback itself
+            if players.get(target).is_some() {
+                *velocity = Velocity::default();
+            }
+            let from = attacker.and_then(|attacker| positions.get(attacker));
+            if let (Some(from), Some(to)) = (from, positions.get(target)) {
+                knock_back(velocity, on_ground, KNOCKBACK, from.x - to.x, from.z - to.z);
+            }
+            if let Some((x, z)) = extra_knockback {
+                knock_back(velocity, on_ground, SPRINT_KNOCKBACK, x, z);
+            }
+        }
+        if let Some(brain) = brains.get_mut(target) {
+            brain.hurt(attacker);
+        }
+    }
+    events.0.push(CombatEvent::Hurt {
+        entity: target,
+        attacker,
+        animated,
+    });
+
+    if dead {
+        brains.remove(target);
+        events.0.push(CombatEvent::Death {
+            entity: target,
+            attacker,
+        });
+    }
+    true
+}
+
+/// Attack of a player with the item they hold, critical when they fall
+/// and knocking further when they sprint. Returns whether the target was
+/// hurt.
+pub fn attack(world: &World, attacker: Entity, target: Entity) -> bool {
+    if attacker == target || !world.is_alive(target) {
+        return false;
+    }
+
+    let (damage, critical, extra_knockback) = {
+        let players = world.read_storage::<Player>();
+        let healths = world.read_storage::<Health>();
+        let positions = world.read_storage::<Position>();
+        let rotations = world.read_storage::<Rotation>();
+        let on_grounds = world.read_storage::<OnGround>();
+        let inventories = world.read_storage::<Inventory>();
+        let metadata = world.read_storage::<Metadata>();
+        let mut combats = world.write_storage::<Combat>();
+
+        if !players
+            .get(attacker)
+            .is_some_and(|player| player.gamemode != Gamemode::Spectator)
+            || healths.get(attacker).is_none_or(|health| health.is_dead())
+            || players
+                .get(target)
+                .is_some_and(|player| player.gamemode == Gamemode::Spectator)
+        {
+            return false;
+        }
+        match (positions.get(attacker), positions.get(target)) {
+            (Some(from), Some(to)) if distance_squared(from, to) <= REACH * REACH => {}
+            _ => return false,
+        }
+
+        let held = inventories
+            .get(attacker)
+            .and_then(|inventory| inventory.get_held())
+            .map(|stack| stack.item);
+        let weapon = world.fetch::<Weapons>().get(held);
+        let combat = match combats.entry(attacker) {
+            Ok(entry) => entry.or_insert_with(Combat::default),
+            Err(_) => return false,
+        };
+        let strength = combat.get_attack_strength(&weapon);
+        let sprinting = metadata
+            .get(attacker)
+            .is_some_and(|metadata| metadata.get_flag(EntityFlag::Sprinting));
+        let on_ground = on_grounds
+            .get(attacker)
+            .is_some_and(|on_ground| on_ground.0);
+        let charged = strength > 0.9;
+        let critical = charged && combat.falling && !on_ground && !sprinting;
+        combat.attack_ticks = 0;
+
+        let mut damage = weapon.damage * strength;
+        if critical {
+            damage *= CRITICAL_MULTIPLIER;
+        }
+        let extra_knockback = if sprinting && charged {
+            let yaw =
+                f64::from(rotations.get(attacker).copied().unwrap_or_default().yaw).to_radians();
+            Some((yaw.sin(), -yaw.cos()))
+        } else {
+            None
+        };
+        (damage, critical, extra_knockback)
+    };
+
+    let mut data: CombatData = world.system_data();
+    if !hurt(&mut data, target, damage, Some(attacker), extra_knockback) {
+        return false;
+    }
+    drop(data);
+
+    if critical {
+        world
+            .write_resource::<CombatEvents>()
+            .0
+            .push(CombatEvent::Critical { entity: target });
+    }
+    // Knocking someone back takes the momentum of the sprint
+    if extra_knockback.is_some() {
+        if let Some(metadata) = world.write_storage::<Metadata>().get_mut(attacker) {
+            metadata.set_flag(EntityFlag::Sprinting, false);
+        }
+    }
+    true
+}
+
+/// Pushes an entity away from the `(x, z)` direction, and up if it stands
+/// on the ground.
+fn knock_back(velocity: &mut Velocity, on_ground: bool, strength: f64, x: f64, z: f64) {
+    let length = (x * x + z * z).sqrt();
+    if length < 1.0e-4 {
+        return;
+    }
+
+    velocity.x = velocity.x / 2.0 - x / length * strength;
+    velocity.z = velocity.z / 2.0 - z / length * strength;
+    if on_ground {
+        velocity.y = (velocity.y / 2.0 + strength).min(0.4);
+    }
+}
@@ -0,0 +1,129 @@
+use common::gamemode::Gamemode;
+use specs::prelude::*;
+use specs::Component;
+use uuid::Uuid;
+
+/// Feet of the entity, in blocks.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+#[storage(VecStorage)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Position {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// Where the entity looks, in degrees.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+#[storage(VecStorage)]
+pub struct Rotation {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Rotation {
+    pub fn new(yaw: f32, pitch: f32) -> Self {
+        Self { yaw, pitch }
+    }
+}
+
+/// Blocks moved per tick.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+#[storage(VecStorage)]
+pub struct Velocity {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Velocity {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[storage(VecStorage)]
+pub struct OnGround(pub bool);
+
+/// Size of the box around the entity, centered on its position.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[storage(VecStorage)]
+pub struct BoundingBox {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl BoundingBox {
+    pub const PLAYER: BoundingBox = BoundingBox {
+        width: 0.6,
+        height: 1.8,
+    };
+    pub const ITEM: BoundingBox = BoundingBox {
+        width: 0.25,
+        height: 0.25,
+    };
+    /// Height of the eyes of a standing player, above their feet.
+    pub const PLAYER_EYE_HEIGHT: f64 = 1.62;
+
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[storage(VecStorage)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub const PLAYER_MAX: f32 = 20.0;
+
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// Entity of a connected player.
+#[derive(Component, Debug, Clone, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct Player {
+    pub uuid: Uuid,
+    pub username: String,
+    pub gamemode: Gamemode,
+    /// Id of the network stream of the player.
+    pub connection: u64,
+}
+
+impl Player {
+    pub fn new(uuid: Uuid, username: &str, gamemode: Gamemode, connection: u64) -> Self {
+        Self {
+            uuid,
+            username: username.to_string(),
+            gamemode,
+            connection,
+        }
+    }
+}
+
+/// Registers every component, so systems and entities can use them.
+pub fn register(world: &mut World) {
+    world.register::<Position>();
+    world.register::<Rotation>();
+    world.register::<Velocity>();
+    world.register::<OnGround>();
+    world.register::<BoundingBox>();
+    world.register::<Health>();
+    world.register::<Player>();
+}
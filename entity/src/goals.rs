@@ -0,0 +1,247 @@
+use rand::Rng;
+use specs::Entity;
+
+use crate::ai::{distance_squared, to_node, Attack, Controls, Goal, MobContext};
+use crate::pathfinding::{is_walkable, Node};
+
+/// Ticks between two melee hits.
+const ATTACK_COOLDOWN: u32 = 20;
+/// Ticks between two paths to a moving target.
+const REPATH_TICKS: u32 = 10;
+
+/// Walks to random places around, now and then.
+pub struct Wander {
+    speed: f64,
+    chance: u32,
+}
+
+impl Wander {
+    pub fn new(speed: f64) -> Self {
+        Self { speed, chance: 120 }
+    }
+}
+
+impl Goal for Wander {
+    fn get_controls(&self) -> Controls {
+        Controls::MOVEMENT
+    }
+
+    fn can_start(&mut self, mob: &mut MobContext) -> bool {
+        mob.random.gen_range(0, self.chance) == 0
+    }
+
+    fn can_continue(&mut self, mob: &mut MobContext) -> bool {
+        !mob.navigation.is_done()
+    }
+
+    fn start(&mut self, mob: &mut MobContext) {
+        match get_random_destination(mob, 10, 7) {
+            Some(destination) => mob.navigation.move_to(destination, self.speed),
+            None => mob.navigation.stop(),
+        }
+    }
+
+    fn stop(&mut self, mob: &mut MobContext) {
+        mob.navigation.stop();
+    }
+
+    fn tick(&mut self, _mob: &mut MobContext) {}
+}
+
+/// Looks at a player nearby for a little while.
+pub struct LookAtPlayer {
+    range: f64,
+    target: Option<Entity>,
+    ticks: u32,
+}
+
+impl LookAtPlayer {
+    pub fn new(range: f64) -> Self {
+        Self {
+            range,
+            target: None,
+            ticks: 0,
+        }
+    }
+}
+
+impl Goal for LookAtPlayer {
+    fn get_controls(&self) -> Controls {
+        Controls::LOOK
+    }
+
+    fn can_start(&mut self, mob: &mut MobContext) -> bool {
+        if mob.random.gen::<f32>() >= 0.02 {
+            return false;
+        }
+        self.target = mob
+            .get_nearest_player(self.range, |_| true)
+            .map(|player| player.entity);
+        self.target.is_some()
+    }
+
+    fn can_continue(&mut self, mob: &mut MobContext) -> bool {
+        let range = self.range;
+
+        self.ticks > 0
+            && self
+                .target
+                .and_then(|target| mob.get_player(target))
+                .is_some_and(|player| {
+                    distance_squared(&mob.position, &player.position) <= range * range
+                })
+    }
+
+    fn start(&mut self, mob: &mut MobContext) {
+        self.ticks = 40 + mob.random.gen_range(0, 40);
+    }
+
+    fn stop(&mut self, _mob: &mut MobContext) {
+        self.target = None;
+    }
+
+    fn tick(&mut self, mob: &mut MobContext) {
+        self.ticks = self.ticks.saturating_sub(1);
+        if let Some(player) = self.target.and_then(|target| mob.get_player(target)) {
+            mob.look_at_player(&player);
+        }
+    }
+}
+
+/// Chases the closest player who can be hurt and hits them.
+pub struct MeleeAttack {
+    speed: f64,
+    range: f64,
+    target: Option<Entity>,
+    cooldown: u32,
+    repath: u32,
+}
+
+impl MeleeAttack {
+    pub fn new(speed: f64) -> Self {
+        Self {
+            speed,
+            range: 35.0,
+            target: None,
+            cooldown: 0,
+            repath: 0,
+        }
+    }
+}
+
+impl Goal for MeleeAttack {
+    fn get_controls(&self) -> Controls {
+        Controls::ALL
+    }
+
+    fn can_start(&mut self, mob: &mut MobContext) -> bool {
+        self.target = mob
+            .get_nearest_player(self.range, |player| player.is_vulnerable())
+            .map(|player| player.entity);
+        self.target.is_some()
+    }
+
+    fn can_continue(&mut self, mob: &mut MobContext) -> bool {
+        let range = self.range;
+
+        self.target
+            .and_then(|target| mob.get_player(target))
+            .is_some_and(|player| {
+                player.is_vulnerable()
+                    && distance_squared(&mob.position, &player.position) <= range * range
+            })
+    }
+
+    fn start(&mut self, _mob: &mut MobContext) {
+        self.repath = 0;
+    }
+
+    fn stop(&mut self, mob: &mut MobContext) {
+        self.target = None;
+        mob.navigation.stop();
+    }
+
+    fn tick(&mut self, mob: &mut MobContext) {
+        let player = match self.target.and_then(|target| mob.get_player(target)) {
+            Some(player) => player,
+            None => return,
+        };
+
+        mob.look_at_player(&player);
+        self.cooldown = self.cooldown.saturating_sub(1);
+        self.repath = self.repath.saturating_sub(1);
+        if self.repath == 0 || mob.navigation.is_done() {
+            self.repath = REPATH_TICKS + mob.random.gen_range(0, 7);
+            mob.navigation
+                .move_to(to_node(&player.position), self.speed);
+        }
+
+        let reach = mob.bounding_box.width * 2.0;
+        let reach = reach * reach + 0.6;
+        if self.cooldown == 0 && distance_squared(&mob.position, &player.position) <= reach {
+            self.cooldown = ATTACK_COOLDOWN;
+            mob.attacks.push(Attack {
+                attacker: mob.entity,
+                target: player.entity,
+                damage: mob.attack_damage,
+            });
+        }
+    }
+}
+
+/// Runs around after being hurt.
+pub struct Panic {
+    speed: f64,
+}
+
+impl Panic {
+    pub fn new(speed: f64) -> Self {
+        Self { speed }
+    }
+}
+
+impl Goal for Panic {
+    fn get_controls(&self) -> Controls {
+        Controls::MOVEMENT
+    }
+
+    fn can_start(&mut self, mob: &mut MobContext) -> bool {
+        mob.hurt_ticks > 0
+    }
+
+    fn can_continue(&mut self, mob: &mut MobContext) -> bool {
+        mob.hurt_ticks > 0
+    }
+
+    fn stop(&mut self, mob: &mut MobContext) {
+        mob.navigation.stop();
+    }
+
+    fn tick(&mut self, mob: &mut MobContext) {
+        if mob.navigation.is_done() {
+            if let Some(destination) = get_random_destination(mob, 5, 4) {
+                mob.navigation.move_to(destination, self.speed);
+            }
+        }
+    }
+}
+
+/// A block to stand on around the mob, if one is found in a few tries.
+fn get_random_destination(mob: &mut MobContext, horizontal: i32, vertical: i32) -> Option<Node> {
+    let (x, y, z) = mob.get_node();
+
+    for _ in 0..10 {
+        let nx = x + mob.random.gen_range(-horizontal, horizontal + 1);
+        let nz = z + mob.random.gen_range(-horizontal, horizontal + 1);
+        let ground = (y - vertical..=y + vertical)
+            .rev()
+            .map(|ny| (nx, ny, nz))
+            .find(|node| is_walkable(mob.terrain, &mob.bounding_box, *node));
+
+        match ground {
+            Some(node) if node != (x, y, z) => return Some(node),
+            _ => continue,
+        }
+    }
+    None
+}
@@ -0,0 +1,652 @@
+use common::item::ItemStack;
+use specs::prelude::*;
+use specs::Component;
+use std::collections::{BTreeSet, HashMap};
+
+/// Items a stack holds at most, until item properties are known.
+pub const MAX_STACK_SIZE: i8 = 64;
+pub const HOTBAR_SIZE: usize = 9;
+/// Slots of the hotbar and of the main inventory.
+pub const SIZE: usize = 36;
+/// First armor slot, the armor going from the boots to the helmet.
+pub const ARMOR: usize = 36;
+pub const OFFHAND: usize = 40;
+/// First slot of the 2×2 crafting grid.
+pub const CRAFTING: usize = 41;
+const SLOTS: usize = 45;
+
+/// Slots of the inventory window: the crafting output and grid, the armor
+/// from the helmet, the main inventory, the hotbar and the offhand.
+pub const WINDOW_SIZE: usize = 46;
+/// Window slot of the clicks made outside of the window.
+pub const OUTSIDE: i16 = -999;
+
+/// Stacks of the window slots which changed.
+pub type SlotChanges = Vec<(i16, Option<ItemStack>)>;
+
+/// Modes of a drag, from the button which started it.
+const LEFT_DRAG: i8 = 0;
+const RIGHT_DRAG: i8 = 1;
+const MIDDLE_DRAG: i8 = 2;
+
+/// Window slot showing an inventory slot.
+pub fn to_window_slot(slot: usize) -> i16 {
+    let window = match slot {
+        0..=8 => slot + 36,
+        9..=35 => slot,
+        36..=39 => 8 - (slot - ARMOR),
+        OFFHAND => 45,
+        _ => slot - CRAFTING + 1,
+    };
+    window as i16
+}
+
+/// Inventory slot a window slot shows, or `None` for the crafting output
+/// and the slots out of the window.
+pub fn from_window_slot(window: i16) -> Option<usize> {
+    let slot = match window {
+        1..=4 => CRAFTING + window as usize - 1,
+        5..=8 => ARMOR + (8 - window) as usize,
+        9..=35 => window as usize,
+        36..=44 => window as usize - 36,
+        45 => OFFHAND,
+        _ => return None,
+    };
+    Some(slot)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArmorSlot {
+    Feet,
+    Legs,
+    Chest,
+    Head,
+}
+
+impl ArmorSlot {
+    pub fn get_slot(self) -> usize {
+        ARMOR + self as usize
+    }
+}
+
+/// Items players can wear, by id in the item registry. Other items don't
+/// go in the armor slots.
+#[derive(Debug, Default)]
+pub struct Armor {
+    items: HashMap<i32, ArmorSlot>,
+}
+
+impl Armor {
+    pub fn new() -> Self {
+        Self {
+            items: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, item: i32) -> Option<ArmorSlot> {
+        self.items.get(&item).copied()
+    }
+
+    pub fn set(&mut self, item: i32, slot: ArmorSlot) {
+        self.items.insert(item, slot);
+    }
+}
+
+/// What a click in a window does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickMode {
+    /// Picks up or puts down items with the cursor.
+    Pickup,
+    /// Shift click, moving a stack to another part of the inventory.
+    QuickMove,
+    /// Number key, swapping a slot with one of the hotbar.
+    Swap,
+    /// Middle click, taking a full stack in creative mode.
+    Clone,
+    /// Drop key, throwing items out of a slot.
+    Throw,
+    /// Spreads the stack of the cursor over the slots it is dragged on.
+    Drag,
+    /// Double click, gathering items like those of the cursor.
+    PickupAll,
+}
+
+impl ClickMode {
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(ClickMode::Pickup),
+            1 => Some(ClickMode::QuickMove),
+            2 => Some(ClickMode::Swap),
+            3 => Some(ClickMode::Clone),
+            4 => Some(ClickMode::Throw),
+            5 => Some(ClickMode::Drag),
+            6 => Some(ClickMode::PickupAll),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of a click.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClickResult {
+    /// Stack the client computes too and sends along with the click, telling
+    /// whether both sides still agree.
+    pub clicked: Option<ItemStack>,
+    /// Stacks thrown out of the inventory.
+    pub dropped: Vec<ItemStack>,
+}
+
+/// Drag going on, with the slots the cursor went over.
+#[derive(Debug, Clone, PartialEq)]
+struct Drag {
+    mode: i8,
+    slots: Vec<usize>,
+}
+
+/// Inventory of a player, the hotbar being its first slots, then the main
+/// inventory, the armor, the offhand and the crafting grid.
+#[derive(Component, Debug, Clone, PartialEq)]
+#[storage(HashMapStorage)]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+    selected: usize,
+    /// Stack carried by the mouse while the window is open.
+    cursor: Option<ItemStack>,
+    drag: Option<Drag>,
+    /// Slots the client hasn't been told about.
+    dirty: BTreeSet<usize>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self {
+            slots: vec![None; SLOTS],
+            selected: 0,
+            cursor: None,
+            drag: None,
+            dirty: BTreeSet::new(),
+        }
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&ItemStack> {
+        self.slots.get(slot)?.as_ref()
+    }
+
+    pub fn set(&mut self, slot: usize, stack: Option<ItemStack>) {
+        if slot < SLOTS {
+            self.put(slot, stack);
+        }
+    }
+
+    /// Hotbar slot in the hand of the player.
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, slot: usize) {
+        if slot < HOTBAR_SIZE {
+            self.selected = slot;
+        }
+    }
+
+    pub fn get_held(&self) -> Option<&ItemStack> {
+        self.get(self.selected)
+    }
+
+    /// Takes up to `count` items from the hand.
+    pub fn take_held(&mut self, count: i8) -> Option<ItemStack> {
+        self.take(self.selected, count)
+    }
+
+    pub fn get_cursor(&self) -> Option<&ItemStack> {
+        self.cursor.as_ref()
+    }
+
+    pub fn set_cursor(&mut self, stack: Option<ItemStack>) {
+        self.cursor = stack.filter(|stack| stack.count > 0);
+    }
+
+    /// Adds a stack to those of the same item first, then to the first empty
+    /// slots, returning what didn't fit.
+    pub fn insert(&mut self, mut stack: ItemStack) -> Option<ItemStack> {
+        for (slot, current) in self.slots[..SIZE].iter_mut().enumerate() {
+            if stack.count <= 0 {
+                break;
+            }
+            if let Some(current) = current
+                .as_mut()
+                .filter(|current| current.is_same_item(&stack))
+            {
+                let moved = stack.count.min(MAX_STACK_SIZE - current.count).max(0);
+
+                if moved > 0 {
+                    current.count += moved;
+                    stack.count -= moved;
+                    self.dirty.insert(slot);
+                }
+            }
+        }
+        for (slot, current) in self.slots[..SIZE].iter_mut().enumerate() {
+            if stack.count <= 0 {
+                break;
+            }
+            if current.is_none() {
+                let mut moved = stack.clone();
+                moved.count = stack.count.min(MAX_STACK_SIZE);
+                stack.count -= moved.count;
+                *current = Some(moved);
+                self.dirty.insert(slot);
+            }
+        }
+        Some(stack).filter(|stack| stack.count > 0)
+    }
+
+    /// Contents of the inventory window, by window slot.
+    pub fn get_window_items(&self) -> Vec<Option<ItemStack>> {
+        (0..WINDOW_SIZE as i16)
+            .map(|window| from_window_slot(window).and_then(|slot| self.slots[slot].clone()))
+            .collect()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Takes the slots which changed since the last call, by window slot.
+    pub fn take_changes(&mut self) -> SlotChanges {
+        std::mem::take(&mut self.dirty)
+            .into_iter()
+            .map(|slot| (to_window_slot(slot), self.slots[slot].clone()))
+            .collect()
+    }
+
+    /// Applies a click in the inventory window the way the client does, so
+    /// both agree on the result as long as they agreed before.
+    pub fn click(
+        &mut self,
+        window_slot: i16,
+        button: i8,
+        mode: ClickMode,
+        creative: bool,
+        armor: &Armor,
+    ) -> ClickResult {
+        let mut result = ClickResult::default();
+
+        if mode == ClickMode::Drag {
+            self.drag(window_slot, button, creative, armor);
+            return result;
+        }
+        // Any other click cancels a drag
+        if self.drag.take().is_some() {
+            return result;
+        }
+
+        let slot = from_window_slot(window_slot);
+        match mode {
+            ClickMode::Pickup | ClickMode::QuickMove if button == 0 || button == 1 => {
+                if window_slot == OUTSIDE {
+                    let count = if button == 0 { i8::MAX } else { 1 };
+                    result.dropped.extend(self.take_cursor(count));
+                } else if let Some(slot) = slot {
+                    if mode == ClickMode::QuickMove {
+                        result.clicked = self.quick_move(slot, armor);
+                    } else {
+                        result.clicked = self.slots[slot].clone();
+                        self.pickup(slot, button == 1, armor);
+                    }
+                }
+            }
+            ClickMode::Swap if (0..HOTBAR_SIZE as i8).contains(&button) => {
+                if let Some(slot) = slot {
+                    result.dropped = self.swap(slot, button as usize, armor);
+                }
+            }
+            ClickMode::Clone if creative && self.cursor.is_none() => {
+                if let Some(mut stack) = slot.and_then(|slot| self.slots[slot].clone()) {
+                    stack.count = MAX_STACK_SIZE;
+                    self.cursor = Some(stack);
+                }
+            }
+            ClickMode::Throw if self.cursor.is_none() => {
+                let count = if button == 0 { 1 } else { i8::MAX };
+                result
+                    .dropped
+                    .extend(slot.and_then(|slot| self.take(slot, count)));
+            }
+            ClickMode::PickupAll if (0..WINDOW_SIZE as i16).contains(&window_slot) => {
+                self.pickup_all(slot, button != 0);
+            }
+            _ => {}
+        }
+        result
+    }
+
+    /// Gives back what the closed window holds: the stack of the cursor is
+    /// thrown and the crafting grid goes back to the inventory. Returns the
+    /// stacks to throw.
+    pub fn close(&mut self) -> Vec<ItemStack> {
+        let mut dropped: Vec<ItemStack> = self.cursor.take().into_iter().collect();
+
+        self.drag = None;
+        for slot in CRAFTING..SLOTS {
+            if let Some(stack) = self.slots[slot].take() {
+                self.dirty.insert(slot);
+                dropped.extend(self.insert(stack));
+            }
+        }
+        dropped
+    }
+
+    fn put(&mut self, slot: usize, stack: Option<ItemStack>) {
+        self.slots[slot] = stack.filter(|stack| stack.count > 0);
+        self.dirty.insert(slot);
+    }
+
+    fn take(&mut self, slot: usize, count: i8) -> Option<ItemStack> {
+        let mut stack = self.slots.get(slot)?.clone()?;
+        let taken = split(&mut stack, count);
+
+        self.put(slot, Some(stack));
+        Some(taken).filter(|taken| taken.count > 0)
+    }
+
+    fn take_cursor(&mut self, count: i8) -> Option<ItemStack> {
+        let mut cursor = self.cursor.take()?;
+        let taken = split(&mut cursor, count);
+
+        self.set_cursor(Some(cursor));
+        Some(taken).filter(|taken| taken.count > 0)
+    }
+
+    /// Most items a slot holds.
+    fn get_limit(slot: usize) -> i8 {
+        if (ARMOR..OFFHAND).contains(&slot) {
+            1
+        } else {
+            MAX_STACK_SIZE
+        }
+    }
+
+    fn accepts(slot: usize, stack: &ItemStack, armor: &Armor) -> bool {
+        if (ARMOR..OFFHAND).contains(&slot) {
+            armor
+                .get(stack.item)
+                .is_some_and(|armor| armor.get_slot() == slot)
+        } else {
+            true
+        }
+    }
+
+    fn pickup(&mut self, slot: usize, right: bool, armor: &Armor) {
+        let limit = Self::get_limit(slot);
+
+        match (self.slots[slot].clone(), self.cursor.take()) {
+            (None, Some(mut cursor)) => {
+                if Self::accepts(slot, &cursor, armor) {
+                    let count = if right { 1 } else { cursor.count };
+                    self.put(slot, Some(split(&mut cursor, count.min(limit))));
+                }
+                self.set_cursor(Some(cursor));
+            }
+            (Some(mut stack), None) => {
+                let count = if right {
+                    (stack.count + 1) / 2
+                } else {
+                    stack.count
+                };
+                self.cursor = Some(split(&mut stack, count));
+                self.put(slot, Some(stack));
+            }
+            (Some(mut stack), Some(mut cursor)) => {
+                if Self::accepts(slot, &cursor, armor) {
+                    if stack.is_same_item(&cursor) {
+                        let count = if right { 1 } else { cursor.count };
+                        let count = count.min(limit - stack.count).max(0);
+
+                        stack.count += count;
+                        cursor.count -= count;
+                    } else if cursor.count <= limit {
+                        std::mem::swap(&mut stack, &mut cursor);
+                    }
+                }
+                self.put(slot, Some(stack));
+                self.set_cursor(Some(cursor));
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// Shift click, returning the stack clicked if some of it is left.
+    fn quick_move(&mut self, slot: usize, armor: &Armor) -> Option<ItemStack> {
+        let mut clicked = None;
+
+        while let Some(moved) = self.transfer(slot, armor) {
+            if self.slots[slot]
+                .as_ref()
+                .is_none_or(|stack| stack.item != moved.item)
+            {
+                break;
+            }
+            clicked = Some(moved);
+        }
+        clicked
+    }
+
+    /// Moves the stack of a slot to the other part of the inventory,
+    /// returning it as it was if any of it moved.
+    fn transfer(&mut self, slot: usize, armor: &Armor) -> Option<ItemStack> {
+        let original = self.slots[slot].clone()?;
+        let worn = armor
+            .get(original.item)
+            .map(ArmorSlot::get_slot)
+            .filter(|worn| self.slots[*worn].is_none());
+        let main: Vec<usize> = (HOTBAR_SIZE..SIZE).chain(0..HOTBAR_SIZE).collect();
+        let targets = match (slot, worn) {
+            (0..=35, Some(worn)) => vec![worn],
+            (0..=8, None) => (HOTBAR_SIZE..SIZE).collect(),
+            (9..=35, None) => (0..HOTBAR_SIZE).collect(),
+            _ => main,
+        };
+
+        let mut stack = original.clone();
+        self.merge(&mut stack, &targets, armor);
+        if stack.count == original.count {
+            return None;
+        }
+        self.put(slot, Some(stack));
+        Some(original)
+    }
+
+    /// Adds a stack to the target slots holding the same item, then to the
+    /// first empty one.
+    fn merge(&mut self, stack: &mut ItemStack, targets: &[usize], armor: &Armor) {
+        for &target in targets {
+            if stack.count <= 0 {
+                return;
+            }
+            let limit = Self::get_limit(target);
+            if let Some(mut current) = self.slots[target].clone() {
+                if current.is_same_item(stack) && current.count < limit {
+                    let moved = stack.count.min(limit - current.count);
+
+                    current.count += moved;
+                    stack.count -= moved;
+                    self.put(target, Some(current));
+                }
+            }
+        }
+        for &target in targets {
+            if stack.count <= 0 {
+                return;
+            }
+            if self.slots[target].is_none() && Self::accepts(target, stack, armor) {
+                let limit = Self::get_limit(target);
+
+                self.put(target, Some(split(stack, limit)));
+                return;
+            }
+        }
+    }
+
+    /// Swaps a slot with a hotbar slot, returning what didn't fit back in
+    /// the inventory.
+    fn swap(&mut self, slot: usize, hotbar: usize, armor: &Armor) -> Vec<ItemStack> {
+        let limit = Self::get_limit(slot);
+
+        match (self.slots[hotbar].clone(), self.slots[slot].clone()) {
+            (None, Some(stack)) => {
+                self.put(slot, None);
+                self.put(hotbar, Some(stack));
+            }
+            (Some(mut held), None) if Self::accepts(slot, &held, armor) => {
+                self.put(slot, Some(split(&mut held, limit)));
+                self.put(hotbar, Some(held));
+            }
+            (Some(mut held), Some(stack)) if Self::accepts(slot, &held, armor) => {
+                if held.count > limit {
+                    self.put(slot, Some(split(&mut held, limit)));
+                    self.put(hotbar, Some(held));
+                    return self.insert(stack).into_iter().collect();
+                }
+                self.put(slot, Some(held));
+                self.put(hotbar, Some(stack));
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn drag(&mut self, window_slot: i16, button: i8, creative: bool, armor: &Armor) {
+        let event = button & 3;
+        let mode = (button >> 2) & 3;
+        let cursor = match self.cursor.clone() {
+            Some(cursor) => cursor,
+            None => {
+                self.drag = None;
+                return;
+            }
+        };
+
+        match (self.drag.take(), event) {
+            (None, 0)
+                if mode == LEFT_DRAG || mode == RIGHT_DRAG || (mode == MIDDLE_DRAG && creative) =>
+            {
+                self.drag = Some(Drag {
+                    mode,
+                    slots: vec![],
+                });
+            }
+            (Some(mut drag), 1) => {
+                if let Some(slot) = from_window_slot(window_slot) {
+                    let fits = self.slots[slot]
+                        .as_ref()
+                        .is_none_or(|stack| stack.is_same_item(&cursor));
+
+                    if fits
+                        && Self::accepts(slot, &cursor, armor)
+                        && (drag.mode == MIDDLE_DRAG || cursor.count as usize > drag.slots.len())
+                        && !drag.slots.contains(&slot)
+                    {
+                        drag.slots.push(slot);
+                    }
+                }
+                self.drag = Some(drag);
+            }
+            (Some(drag), 2) => self.spread(&cursor, &drag),
+            _ => {}
+        }
+    }
+
+    /// Ends a drag, giving each slot its share of the cursor.
+    fn spread(&mut self, cursor: &ItemStack, drag: &Drag) {
+        let slots = drag.slots.len() as i32;
+        let mut left = i32::from(cursor.count);
+
+        if slots == 0 {
+            return;
+        }
+        for &slot in &drag.slots {
+            let existing = self.slots[slot]
+                .as_ref()
+                .filter(|stack| stack.is_same_item(cursor));
+            if self.slots[slot].is_some() && existing.is_none() {
+                continue;
+            }
+            if drag.mode != MIDDLE_DRAG && i32::from(cursor.count) < slots {
+                continue;
+            }
+
+            let existing = existing.map_or(0, |stack| i32::from(stack.count));
+            let share = match drag.mode {
+                LEFT_DRAG => i32::from(cursor.count) / slots,
+                RIGHT_DRAG => 1,
+                _ => i32::from(MAX_STACK_SIZE),
+            };
+            let count = (existing + share).min(i32::from(Self::get_limit(slot)));
+            let mut stack = cursor.clone();
+
+            left -= count - existing;
+            stack.count = count as i8;
+            self.put(slot, Some(stack));
+        }
+
+        let mut cursor = cursor.clone();
+        cursor.count = left.max(0) as i8;
+        self.set_cursor(Some(cursor));
+    }
+
+    /// Double click, filling the cursor with the items of the same kind,
+    /// the full stacks last.
+    fn pickup_all(&mut self, clicked: Option<usize>, backwards: bool) {
+        let mut cursor = match self.cursor.clone() {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        if clicked.is_some_and(|slot| self.slots[slot].is_some()) {
+            return;
+        }
+
+        let mut windows: Vec<i16> = (0..WINDOW_SIZE as i16).collect();
+        if backwards {
+            windows.reverse();
+        }
+        for full_stacks in [false, true] {
+            for window in &windows {
+                if cursor.count >= MAX_STACK_SIZE {
+                    break;
+                }
+                let slot = match from_window_slot(*window) {
+                    Some(slot) => slot,
+                    None => continue,
+                };
+                let mut stack = match self.slots[slot].clone() {
+                    Some(stack) if stack.is_same_item(&cursor) => stack,
+                    _ => continue,
+                };
+                if !full_stacks && stack.count == MAX_STACK_SIZE {
+                    continue;
+                }
+
+                let taken = split(&mut stack, MAX_STACK_SIZE - cursor.count);
+                cursor.count += taken.count;
+                self.put(slot, Some(stack));
+            }
+        }
+        self.cursor = Some(cursor);
+    }
+}
+
+/// Takes up to `count` items off a stack.
+fn split(stack: &mut ItemStack, count: i8) -> ItemStack {
+    let mut taken = stack.clone();
+
+    taken.count = count.min(stack.count).max(0);
+    stack.count -= taken.count;
+    taken
+}
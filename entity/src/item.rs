@@ -0,0 +1,192 @@
+use common::gamemode::Gamemode;
+use common::item::ItemStack;
+use specs::prelude::*;
+use specs::Component;
+use std::collections::HashSet;
+use world::aabb::Aabb;
+
+use crate::components::{BoundingBox, Player, Position};
+use crate::inventory::{Inventory, MAX_STACK_SIZE};
+use crate::metadata::Metadata;
+use crate::physics::get_aabb;
+
+/// Ticks after which an item disappears, five minutes.
+pub const DESPAWN_AGE: u32 = 6000;
+/// Ticks before an item dropped by a player can be picked up.
+pub const PLAYER_DROP_DELAY: u32 = 40;
+/// Ticks before an item dropped by a block can be picked up.
+pub const BLOCK_DROP_DELAY: u32 = 10;
+
+/// Distance under which two items of the same kind merge.
+const MERGE_DISTANCE: f64 = 0.5;
+
+/// An item stack lying on the ground.
+#[derive(Component, Debug, Clone, PartialEq)]
+#[storage(DenseVecStorage)]
+pub struct DroppedItem {
+    pub stack: ItemStack,
+    /// Ticks left before the item can be picked up.
+    pub pickup_delay: u32,
+    pub age: u32,
+}
+
+impl DroppedItem {
+    pub fn new(stack: ItemStack, pickup_delay: u32) -> Self {
+        Self {
+            stack,
+            pickup_delay,
+            age: 0,
+        }
+    }
+
+    fn can_merge_with(&self, other: &DroppedItem) -> bool {
+        self.stack.is_same_item(&other.stack)
+            && self.stack.count + other.stack.count <= MAX_STACK_SIZE
+    }
+}
+
+/// Items picked up this tick, the whole stack or a part of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pickup {
+    pub item: Entity,
+    pub collector: Entity,
+    pub count: i8,
+}
+
+#[derive(Debug, Default)]
+pub struct Pickups(pub Vec<Pickup>);
+
+/// Ages, merges and despawns dropped items, and puts those players walk
+/// over in their inventory.
+pub struct ItemSystem;
+
+impl<'a> System<'a> for ItemSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, BoundingBox>,
+        WriteStorage<'a, DroppedItem>,
+        WriteStorage<'a, Metadata>,
+        WriteStorage<'a, Inventory>,
+        Write<'a, Pickups>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            players,
+            positions,
+            bounding_boxes,
+            mut items,
+            mut metadata,
+            mut inventories,
+            mut pickups,
+        ): Self::SystemData,
+    ) {
+        let mut removed = HashSet::new();
+
+        for (entity, item) in (&entities, &mut items).join() {
+            item.age += 1;
+            item.pickup_delay = item.pickup_delay.saturating_sub(1);
+            if item.age >= DESPAWN_AGE {
+                removed.insert(entity);
+            }
+        }
+
+        let boxes: Vec<(Entity, Aabb)> = (&entities, &items, &positions, &bounding_boxes)
+            .join()
+            .map(|(entity, _, position, bounding_box)| (entity, get_aabb(position, bounding_box)))
+            .collect();
+
+        for (i, (entity, aabb)) in boxes.iter().enumerate() {
+            let area = aabb.grow(MERGE_DISTANCE, 0.0, MERGE_DISTANCE);
+
+            for (other, other_aabb) in &boxes[i + 1..] {
+                if removed.contains(entity)
+                    || removed.contains(other)
+                    || !area.intersects(other_aabb)
+                {
+                    continue;
+                }
+
+                let (first, second) = match (items.get(*entity), items.get(*other)) {
+                    (Some(first), Some(second)) if first.can_merge_with(second) => {
+                        (first.clone(), second.clone())
+                    }
+                    _ => continue,
+                };
+                // The larger stack takes the other one
+                let (kept, merged, taken) = if second.stack.count > first.stack.count {
+                    (*other, *entity, first)
+                } else {
+                    (*entity, *other, second)
+                };
+
+                if let Some(item) = items.get_mut(kept) {
+                    item.stack.count += taken.stack.count;
+                    item.pickup_delay = item.pickup_delay.max(taken.pickup_delay);
+                    item.age = item.age.min(taken.age);
+                    if let Some(metadata) = metadata.get_mut(kept) {
+                        metadata.set_item(Some(item.stack.clone()));
+                    }
+                }
+                removed.insert(merged);
+            }
+        }
+
+        for (collector, player, position, bounding_box, inventory) in (
+            &entities,
+            &players,
+            &positions,
+            &bounding_boxes,
+            &mut inventories,
+        )
+            .join()
+        {
+            if player.gamemode == Gamemode::Spectator {
+                continue;
+            }
+
+            let reach = get_aabb(position, bounding_box).grow(1.0, 0.5, 1.0);
+            for (entity, aabb) in &boxes {
+                if removed.contains(entity) || !reach.intersects(aabb) {
+                    continue;
+                }
+                let item = match items.get_mut(*entity) {
+                    Some(item) if item.pickup_delay == 0 => item,
+                    _ => continue,
+                };
+
+                let left = inventory.insert(item.stack.clone());
+                let left_count = left.as_ref().map_or(0, |left| left.count);
+                if left_count == item.stack.count {
+                    continue;
+                }
+
+                pickups.0.push(Pickup {
+                    item: *entity,
+                    collector,
+                    count: item.stack.count - left_count,
+                });
+                match left {
+                    Some(left) => {
+                        item.stack = left;
+                        if let Some(metadata) = metadata.get_mut(*entity) {
+                            metadata.set_item(Some(item.stack.clone()));
+                        }
+                    }
+                    None => {
+                        removed.insert(*entity);
+                    }
+                }
+            }
+        }
+
+        for entity in removed {
+            let _ = entities.delete(entity);
+        }
+    }
+}
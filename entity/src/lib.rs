@@ -1,4 +1,37 @@
+use common::entity_type::EntityType;
+use common::gamemode::Gamemode;
+use common::item::ItemStack;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use specs::prelude::*;
+use specs::shred::{Fetch, FetchMut};
+use uuid::Uuid;
+use world::world::World as Terrain;
+
+pub mod ai;
+pub mod combat;
+pub mod components;
+pub mod goals;
+pub mod inventory;
+pub mod item;
+pub mod metadata;
+pub mod mobs;
+pub mod pathfinding;
+pub mod physics;
+pub mod spawning;
+pub mod tracker;
+
+pub use specs::Entity;
+
+use ai::{AiRandom, AiSystem, Attack, Attacks, Brain};
+use combat::{Combat, CombatEvent, CombatEvents, CombatSystem, Weapons};
+use components::{BoundingBox, Health, OnGround, Player, Position, Rotation, Velocity};
+use inventory::{Armor, ClickMode, ClickResult, Inventory, SlotChanges};
+use item::{DroppedItem, ItemSystem};
+use metadata::Metadata;
+use physics::{Physics, PhysicsSystem};
+use spawning::SpawnSystem;
+use tracker::{EntityKind, EntityTracker, Tracked, TrackerUpdate, TrackerUpdates, Viewer};
 
 pub struct ECSWorld<'a, 'b> {
     world: World,
@@ -8,8 +41,19 @@ pub struct ECSWorld<'a, 'b> {
 impl<'a, 'b> ECSWorld<'a, 'b> {
     pub fn new() -> Self {
         let mut world = World::new();
-        let mut dispatcher = DispatcherBuilder::new().build();
+        let mut dispatcher = DispatcherBuilder::new()
+            .with(SpawnSystem::default(), "spawning", &[])
+            .with(AiSystem, "ai", &["spawning"])
+            .with(CombatSystem, "combat", &["ai"])
+            .with(PhysicsSystem, "physics", &["combat"])
+            .with(ItemSystem, "items", &["physics"])
+            .with(EntityTracker, "entity_tracker", &["items"])
+            .build();
 
+        components::register(&mut world);
+        world.insert(Terrain::default());
+        world.insert(Weapons::new());
+        world.insert(Armor::new());
         dispatcher.setup(&mut world);
 
         Self { world, dispatcher }
@@ -19,6 +63,231 @@ impl<'a, 'b> ECSWorld<'a, 'b> {
         self.dispatcher.dispatch(&self.world);
         self.world.maintain();
     }
+
+    pub fn get_world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// Blocks the entities collide with.
+    pub fn get_terrain(&self) -> Fetch<'_, Terrain> {
+        self.world.fetch::<Terrain>()
+    }
+
+    pub fn get_terrain_mut(&mut self) -> FetchMut<'_, Terrain> {
+        self.world.fetch_mut::<Terrain>()
+    }
+
+    /// Seeds the random numbers the mobs use to make their choices.
+    pub fn set_ai_seed(&mut self, seed: u64) {
+        self.world.insert(AiRandom(StdRng::seed_from_u64(seed)));
+    }
+
+    /// Creates the entity of a player who just logged in.
+    pub fn create_player(
+        &mut self,
+        player: Player,
+        position: Position,
+        rotation: Rotation,
+    ) -> Entity {
+        let tracked = Tracked::new(EntityKind::Player, player.uuid);
+
+        self.world
+            .create_entity()
+            .with(position)
+            .with(rotation)
+            .with(Velocity::default())
+            .with(OnGround(false))
+            .with(BoundingBox::PLAYER)
+            .with(Health::new(Health::PLAYER_MAX))
+            .with(Metadata::living(Health::PLAYER_MAX))
+            .with(Combat::new())
+            .with(Inventory::new())
+            .with(tracked)
+            .with(Viewer::default())
+            .with(player)
+            .build()
+    }
+
+    /// Drops an item stack, which can be picked up after `pickup_delay`
+    /// ticks.
+    pub fn spawn_item(
+        &mut self,
+        position: Position,
+        velocity: Velocity,
+        stack: ItemStack,
+        pickup_delay: u32,
+    ) -> Entity {
+        let tracked = Tracked::new(EntityKind::Object(EntityType::Item, 1), Uuid::new_v4());
+
+        self.world
+            .create_entity()
+            .with(position)
+            .with(Rotation::default())
+            .with(velocity)
+            .with(OnGround(false))
+            .with(BoundingBox::ITEM)
+            .with(Physics::ITEM)
+            .with(Metadata::item(stack.clone()))
+            .with(tracked)
+            .with(DroppedItem::new(stack, pickup_delay))
+            .build()
+    }
+
+    /// Spawns a mob with its goals, or returns `None` if the entity type
+    /// isn't a mob which can be spawned.
+    pub fn spawn_mob(&mut self, entity_type: EntityType, position: Position) -> Option<Entity> {
+        let builder = self.world.create_entity();
+
+        mobs::build_mob(builder, entity_type, position, Rotation::default())
+            .map(|builder| builder.build())
+    }
+
+    /// Tells a mob it was hurt, returning false if the entity isn't a mob.
+    pub fn hurt_mob(&mut self, entity: Entity, attacker: Option<Entity>) -> bool {
+        match self.world.write_storage::<Brain>().get_mut(entity) {
+            Some(brain) => {
+                brain.hurt(attacker);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Items players attack with.
+    pub fn get_weapons(&self) -> Fetch<'_, Weapons> {
+        self.world.fetch::<Weapons>()
+    }
+
+    pub fn get_weapons_mut(&mut self) -> FetchMut<'_, Weapons> {
+        self.world.fetch_mut::<Weapons>()
+    }
+
+    /// Items players can wear.
+    pub fn get_armor(&self) -> Fetch<'_, Armor> {
+        self.world.fetch::<Armor>()
+    }
+
+    pub fn get_armor_mut(&mut self) -> FetchMut<'_, Armor> {
+        self.world.fetch_mut::<Armor>()
+    }
+
+    /// Applies a click of a player in their inventory window, or returns
+    /// `None` if the entity has no inventory.
+    pub fn click_window(
+        &mut self,
+        entity: Entity,
+        slot: i16,
+        button: i8,
+        mode: ClickMode,
+    ) -> Option<ClickResult> {
+        let creative = self
+            .get_component::<Player>(entity)
+            .is_some_and(|player| player.gamemode == Gamemode::Creative);
+        let armor = self.world.fetch::<Armor>();
+        let mut inventories = self.world.write_storage::<Inventory>();
+
+        Some(
+            inventories
+                .get_mut(entity)?
+                .click(slot, button, mode, creative, &armor),
+        )
+    }
+
+    /// Takes the inventory slots which changed for each player since the
+    /// last call, by window slot.
+    pub fn take_inventory_changes(&mut self) -> Vec<(Uuid, SlotChanges)> {
+        let players = self.world.read_storage::<Player>();
+        let mut inventories = self.world.write_storage::<Inventory>();
+
+        (&players, &mut inventories)
+            .join()
+            .filter(|(_, inventory)| inventory.is_dirty())
+            .map(|(player, inventory)| (player.uuid, inventory.take_changes()))
+            .collect()
+    }
+
+    /// Makes a player attack an entity, returning whether it was hurt.
+    pub fn attack(&mut self, attacker: Entity, target: Entity) -> bool {
+        combat::attack(&self.world, attacker, target)
+    }
+
+    /// Brings a dead player back to life with full health. The entity
+    /// spawns again for the players who saw it die.
+    pub fn respawn_player(&mut self, entity: Entity) -> bool {
+        let max = match self.get_component::<Health>(entity) {
+            Some(health) if health.is_dead() => health.max,
+            _ => return false,
+        };
+
+        self.set_component(entity, Health::new(max));
+        self.set_component(entity, Combat::new());
+        self.set_component(entity, Velocity::default());
+        if let Some(metadata) = self.world.write_storage::<Metadata>().get_mut(entity) {
+            metadata.set_health(max);
+        }
+        for viewer in (&mut self.world.write_storage::<Viewer>()).join() {
+            viewer.forget(entity);
+        }
+        true
+    }
+
+    /// Takes the hurts, critical hits and deaths of the last tick.
+    pub fn take_combat_events(&mut self) -> Vec<CombatEvent> {
+        std::mem::take(&mut self.world.write_resource::<CombatEvents>().0)
+    }
+
+    /// Takes the hits the mobs landed during the last tick.
+    pub fn take_attacks(&mut self) -> Vec<Attack> {
+        std::mem::take(&mut self.world.write_resource::<Attacks>().0)
+    }
+
+    pub fn remove_entity(&mut self, entity: Entity) {
+        let _ = self.world.delete_entity(entity);
+        self.world.maintain();
+    }
+
+    /// Takes the updates the tracker made for the players since the last
+    /// call, by UUID.
+    pub fn take_tracker_updates(&mut self) -> Vec<(Uuid, TrackerUpdate)> {
+        std::mem::take(&mut self.world.write_resource::<TrackerUpdates>().0)
+    }
+
+    pub fn get_player(&self, uuid: &Uuid) -> Option<Entity> {
+        let entities = self.world.entities();
+        let players = self.world.read_storage::<Player>();
+
+        (&entities, &players)
+            .join()
+            .find(|(_, player)| player.uuid == *uuid)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Living entity with the given network id.
+    pub fn get_entity(&self, network_id: i32) -> Option<Entity> {
+        if network_id < 0 {
+            return None;
+        }
+        let entity = self.world.entities().entity(network_id as u32);
+
+        if self.world.is_alive(entity) {
+            Some(entity)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_component<C: Component + Clone>(&self, entity: Entity) -> Option<C> {
+        self.world.read_storage::<C>().get(entity).cloned()
+    }
+
+    /// Adds the component to the entity, or replaces it.
+    pub fn set_component<C: Component>(&mut self, entity: Entity, component: C) {
+        let _ = self.world.write_storage::<C>().insert(entity, component);
+    }
 }
 
 impl<'a, 'b> Default for ECSWorld<'a, 'b> {
@@ -26,3 +295,8 @@ impl<'a, 'b> Default for ECSWorld<'a, 'b> {
         Self::new()
     }
 }
+
+/// Id of the entity in the protocol, unique among the living entities.
+pub fn get_network_id(entity: Entity) -> i32 {
+    entity.id() as i32
+}
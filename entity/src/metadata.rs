@@ -0,0 +1,181 @@
+use common::chat::Chat;
+use common::item::ItemStack;
+use common::metadata::{MetadataValue, Pose};
+use specs::prelude::*;
+use specs::Component;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Bit mask of the `EntityFlag`s.
+pub const FLAGS: u8 = 0;
+/// Ticks of air left under water.
+pub const AIR: u8 = 1;
+pub const CUSTOM_NAME: u8 = 2;
+pub const CUSTOM_NAME_VISIBLE: u8 = 3;
+pub const SILENT: u8 = 4;
+pub const NO_GRAVITY: u8 = 5;
+pub const POSE: u8 = 6;
+/// Stack shown by an item entity.
+pub const ITEM: u8 = 7;
+/// Health of a living entity.
+pub const HEALTH: u8 = 8;
+
+const MAX_AIR: i32 = 300;
+
+/// States every entity shares in its `FLAGS` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityFlag {
+    OnFire,
+    Sneaking,
+    Sprinting,
+    Swimming,
+    Invisible,
+    Glowing,
+    FlyingWithElytra,
+}
+
+impl EntityFlag {
+    fn get_mask(self) -> i8 {
+        match self {
+            EntityFlag::OnFire => 0x01,
+            EntityFlag::Sneaking => 0x02,
+            EntityFlag::Sprinting => 0x08,
+            EntityFlag::Swimming => 0x10,
+            EntityFlag::Invisible => 0x20,
+            EntityFlag::Glowing => 0x40,
+            EntityFlag::FlyingWithElytra => 0x80u8 as i8,
+        }
+    }
+}
+
+/// Fields the clients need to render an entity, by index, along with those
+/// which changed since they were last sent.
+#[derive(Component, Debug, Clone)]
+#[storage(DenseVecStorage)]
+pub struct Metadata {
+    values: BTreeMap<u8, MetadataValue>,
+    dirty: BTreeSet<u8>,
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metadata {
+    /// Fields every entity has, with their default values.
+    pub fn new() -> Self {
+        let values = vec![
+            (FLAGS, MetadataValue::Byte(0)),
+            (AIR, MetadataValue::VarInt(MAX_AIR)),
+            (CUSTOM_NAME, MetadataValue::OptChat(None)),
+            (CUSTOM_NAME_VISIBLE, MetadataValue::Boolean(false)),
+            (SILENT, MetadataValue::Boolean(false)),
+            (NO_GRAVITY, MetadataValue::Boolean(false)),
+            (POSE, MetadataValue::Pose(Pose::Standing)),
+        ];
+
+        Self {
+            values: values.into_iter().collect(),
+            dirty: BTreeSet::new(),
+        }
+    }
+
+    /// Fields of a living entity.
+    pub fn living(health: f32) -> Self {
+        let mut metadata = Self::new();
+
+        metadata.values.insert(HEALTH, MetadataValue::Float(health));
+        metadata
+    }
+
+    /// Fields of an item entity.
+    pub fn item(stack: ItemStack) -> Self {
+        let mut metadata = Self::new();
+
+        metadata
+            .values
+            .insert(ITEM, MetadataValue::Slot(Some(stack)));
+        metadata
+    }
+
+    pub fn get(&self, index: u8) -> Option<&MetadataValue> {
+        self.values.get(&index)
+    }
+
+    /// Changes a field, which is sent again only if its value differs.
+    pub fn set(&mut self, index: u8, value: MetadataValue) {
+        if self.values.get(&index) != Some(&value) {
+            self.values.insert(index, value);
+            self.dirty.insert(index);
+        }
+    }
+
+    pub fn get_flag(&self, flag: EntityFlag) -> bool {
+        match self.get(FLAGS) {
+            Some(MetadataValue::Byte(flags)) => flags & flag.get_mask() != 0,
+            _ => false,
+        }
+    }
+
+    pub fn set_flag(&mut self, flag: EntityFlag, value: bool) {
+        let flags = match self.get(FLAGS) {
+            Some(MetadataValue::Byte(flags)) => *flags,
+            _ => 0,
+        };
+        let flags = if value {
+            flags | flag.get_mask()
+        } else {
+            flags & !flag.get_mask()
+        };
+
+        self.set(FLAGS, MetadataValue::Byte(flags));
+    }
+
+    pub fn get_pose(&self) -> Pose {
+        match self.get(POSE) {
+            Some(MetadataValue::Pose(pose)) => *pose,
+            _ => Pose::Standing,
+        }
+    }
+
+    pub fn set_pose(&mut self, pose: Pose) {
+        self.set(POSE, MetadataValue::Pose(pose));
+    }
+
+    /// Shows a name above the entity, even when not looked at if `visible`.
+    pub fn set_custom_name(&mut self, name: Option<Chat>, visible: bool) {
+        self.set(CUSTOM_NAME, MetadataValue::OptChat(name));
+        self.set(CUSTOM_NAME_VISIBLE, MetadataValue::Boolean(visible));
+    }
+
+    pub fn set_health(&mut self, health: f32) {
+        self.set(HEALTH, MetadataValue::Float(health));
+    }
+
+    pub fn set_item(&mut self, stack: Option<ItemStack>) {
+        self.set(ITEM, MetadataValue::Slot(stack));
+    }
+
+    /// Every field, as sent when the entity spawns.
+    pub fn get_entries(&self) -> Vec<(u8, MetadataValue)> {
+        self.values
+            .iter()
+            .map(|(index, value)| (*index, value.clone()))
+            .collect()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Takes the fields which changed since the last call.
+    pub fn take_changes(&mut self) -> Vec<(u8, MetadataValue)> {
+        let dirty = std::mem::take(&mut self.dirty);
+
+        dirty
+            .into_iter()
+            .filter_map(|index| Some((index, self.values.get(&index)?.clone())))
+            .collect()
+    }
+}
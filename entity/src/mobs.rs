@@ -0,0 +1,84 @@
+use common::entity_type::EntityType;
+use specs::world::Builder;
+use uuid::Uuid;
+
+use crate::ai::{Brain, Mob};
+use crate::combat::Combat;
+use crate::components::{BoundingBox, Health, OnGround, Position, Rotation, Velocity};
+use crate::goals::{LookAtPlayer, MeleeAttack, Panic, Wander};
+use crate::metadata::Metadata;
+use crate::physics::Physics;
+use crate::tracker::{EntityKind, Tracked};
+
+/// What a kind of mob is made of.
+pub struct MobKind {
+    pub bounding_box: BoundingBox,
+    pub health: f32,
+    pub stats: Mob,
+}
+
+/// Mobs which can be spawned, or `None` for other entity types.
+pub fn get_kind(entity_type: EntityType) -> Option<MobKind> {
+    match entity_type {
+        EntityType::Zombie => Some(MobKind {
+            bounding_box: BoundingBox::new(0.6, 1.95),
+            health: 20.0,
+            stats: Mob {
+                movement_speed: 0.23,
+                attack_damage: 3.0,
+            },
+        }),
+        EntityType::Cow => Some(MobKind {
+            bounding_box: BoundingBox::new(0.9, 1.4),
+            health: 10.0,
+            stats: Mob {
+                movement_speed: 0.2,
+                attack_damage: 0.0,
+            },
+        }),
+        _ => None,
+    }
+}
+
+/// Goals of a new mob.
+pub fn get_brain(entity_type: EntityType) -> Brain {
+    match entity_type {
+        EntityType::Zombie => Brain::new()
+            .with_goal(2, MeleeAttack::new(1.0))
+            .with_goal(7, Wander::new(1.0))
+            .with_goal(8, LookAtPlayer::new(8.0)),
+        EntityType::Cow => Brain::new()
+            .with_goal(1, Panic::new(2.0))
+            .with_goal(6, Wander::new(1.0))
+            .with_goal(7, LookAtPlayer::new(6.0)),
+        _ => Brain::new(),
+    }
+}
+
+/// Adds the components of a mob to an entity being built, or returns `None`
+/// if the entity type isn't a mob which can be spawned.
+pub fn build_mob<B: Builder>(
+    builder: B,
+    entity_type: EntityType,
+    position: Position,
+    rotation: Rotation,
+) -> Option<B> {
+    let kind = get_kind(entity_type)?;
+    let tracked = Tracked::new(EntityKind::Living(entity_type), Uuid::new_v4());
+
+    Some(
+        builder
+            .with(position)
+            .with(rotation)
+            .with(Velocity::default())
+            .with(OnGround(false))
+            .with(kind.bounding_box)
+            .with(Physics::LIVING)
+            .with(Health::new(kind.health))
+            .with(Metadata::living(kind.health))
+            .with(Combat::new())
+            .with(tracked)
+            .with(kind.stats)
+            .with(get_brain(entity_type)),
+    )
+}
@@ -0,0 +1,263 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use world::aabb::Aabb;
+use world::world::World as Terrain;
+
+use crate::components::BoundingBox;
+
+/// Blocks a mob may drop down in one move.
+pub const MAX_FALL: i32 = 3;
+/// Nodes explored before giving up on reaching the goal.
+pub const MAX_VISITED: usize = 1024;
+/// Ticks during which a path found for a start and goal is reused.
+const CACHE_TICKS: u64 = 20;
+
+const JUMP_COST: f64 = 0.5;
+
+/// Block an entity stands in, by its feet.
+pub type Node = (i32, i32, i32);
+
+/// Start, goal and size of the entity in hundredths of a block.
+type CacheKey = (Node, Node, u32, u32);
+
+/// Nodes to walk through, from the start to the goal or the closest node to
+/// it which could be reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    nodes: Vec<Node>,
+    index: usize,
+}
+
+impl Path {
+    pub fn get_nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Node the entity is walking to.
+    pub fn get_next(&self) -> Option<Node> {
+        self.nodes.get(self.index).copied()
+    }
+
+    pub fn advance(&mut self) {
+        self.index += 1;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.index >= self.nodes.len()
+    }
+
+    pub fn get_end(&self) -> Option<Node> {
+        self.nodes.last().copied()
+    }
+}
+
+/// Finds a path for an entity of the given size with A*, walking on solid
+/// blocks, jumping up a block at most and falling `MAX_FALL` blocks at most.
+pub fn find_path(
+    terrain: &Terrain,
+    bounding_box: &BoundingBox,
+    start: Node,
+    goal: Node,
+) -> Option<Path> {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Node, Node> = HashMap::new();
+    let mut costs: HashMap<Node, f64> = HashMap::new();
+    let mut closest = (distance(start, goal), start);
+    let mut visited = 0;
+
+    costs.insert(start, 0.0);
+    open.push(Reverse(Candidate {
+        estimate: distance(start, goal),
+        node: start,
+    }));
+
+    while let Some(Reverse(Candidate { node, .. })) = open.pop() {
+        if node == goal {
+            closest = (0.0, node);
+            break;
+        }
+        visited += 1;
+        if visited > MAX_VISITED {
+            break;
+        }
+
+        let cost = costs[&node];
+        for (neighbor, step_cost) in get_neighbors(terrain, bounding_box, node) {
+            let neighbor_cost = cost + step_cost;
+            if costs
+                .get(&neighbor)
+                .is_some_and(|known| *known <= neighbor_cost)
+            {
+                continue;
+            }
+
+            let remaining = distance(neighbor, goal);
+            if remaining < closest.0 {
+                closest = (remaining, neighbor);
+            }
+            costs.insert(neighbor, neighbor_cost);
+            came_from.insert(neighbor, node);
+            open.push(Reverse(Candidate {
+                estimate: neighbor_cost + remaining,
+                node: neighbor,
+            }));
+        }
+    }
+
+    let mut nodes = vec![closest.1];
+    while let Some(previous) = came_from.get(nodes.last()?) {
+        nodes.push(*previous);
+    }
+    nodes.reverse();
+
+    if nodes.len() < 2 {
+        return None;
+    }
+    // The entity already stands on the first node
+    Some(Path { nodes, index: 1 })
+}
+
+/// Whether an entity fits at a node and has a block to stand on.
+pub fn is_walkable(terrain: &Terrain, bounding_box: &BoundingBox, node: Node) -> bool {
+    let (x, y, z) = node;
+
+    is_passable(terrain, bounding_box, node)
+        && !terrain
+            .shapes
+            .get(terrain.get_block(x, y - 1, z))
+            .is_empty()
+}
+
+/// Whether an entity fits at a node.
+pub fn is_passable(terrain: &Terrain, bounding_box: &BoundingBox, node: Node) -> bool {
+    let (x, y, z) = node;
+    let half_width = bounding_box.width / 2.0;
+    let aabb = Aabb::new(
+        f64::from(x) + 0.5 - half_width,
+        f64::from(y),
+        f64::from(z) + 0.5 - half_width,
+        f64::from(x) + 0.5 + half_width,
+        f64::from(y) + bounding_box.height,
+        f64::from(z) + 0.5 + half_width,
+    );
+
+    terrain.get_collision_boxes(&aabb).is_empty()
+}
+
+/// Nodes reachable from `node` in one move, with their cost.
+fn get_neighbors(terrain: &Terrain, bounding_box: &BoundingBox, node: Node) -> Vec<(Node, f64)> {
+    let (x, y, z) = node;
+    let mut neighbors = vec![];
+
+    for (dx, dz) in &[(0, -1), (0, 1), (-1, 0), (1, 0)] {
+        let (nx, nz) = (x + dx, z + dz);
+
+        if is_walkable(terrain, bounding_box, (nx, y, nz)) {
+            neighbors.push(((nx, y, nz), 1.0));
+        } else if !is_passable(terrain, bounding_box, (nx, y, nz)) {
+            // Jumping needs room above the head
+            if is_passable(terrain, bounding_box, (x, y + 1, z))
+                && is_walkable(terrain, bounding_box, (nx, y + 1, nz))
+            {
+                neighbors.push(((nx, y + 1, nz), 1.0 + JUMP_COST));
+            }
+        } else {
+            for depth in 1..=MAX_FALL {
+                let below = (nx, y - depth, nz);
+
+                if is_walkable(terrain, bounding_box, below) {
+                    neighbors.push((below, 1.0 + f64::from(depth) * JUMP_COST));
+                    break;
+                }
+                if !is_passable(terrain, bounding_box, below) {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Diagonals which don't cut a corner
+    for (dx, dz) in &[(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+        let diagonal = (x + dx, y, z + dz);
+
+        if is_walkable(terrain, bounding_box, diagonal)
+            && is_passable(terrain, bounding_box, (x + dx, y, z))
+            && is_passable(terrain, bounding_box, (x, y, z + dz))
+        {
+            neighbors.push((diagonal, std::f64::consts::SQRT_2));
+        }
+    }
+    neighbors
+}
+
+fn distance(from: Node, to: Node) -> f64 {
+    let dx = f64::from(to.0 - from.0);
+    let dy = f64::from(to.1 - from.1);
+    let dz = f64::from(to.2 - from.2);
+
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+/// Node to explore, ordered by its estimated cost, then by position so
+/// paths are the same from one run to the other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    estimate: f64,
+    node: Node,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.estimate
+            .total_cmp(&other.estimate)
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+/// Paths found recently, shared by the mobs going to the same place.
+#[derive(Debug, Default)]
+pub struct PathCache {
+    tick: u64,
+    paths: HashMap<CacheKey, (u64, Option<Path>)>,
+}
+
+impl PathCache {
+    /// Forgets the paths which may be outdated by changes to the terrain.
+    pub fn tick(&mut self) {
+        let tick = self.tick;
+
+        self.tick += 1;
+        self.paths
+            .retain(|_, (found, _)| tick.saturating_sub(*found) < CACHE_TICKS);
+    }
+
+    pub fn find_path(
+        &mut self,
+        terrain: &Terrain,
+        bounding_box: &BoundingBox,
+        start: Node,
+        goal: Node,
+    ) -> Option<Path> {
+        let key = (
+            start,
+            goal,
+            (bounding_box.width * 100.0) as u32,
+            (bounding_box.height * 100.0) as u32,
+        );
+        let tick = self.tick;
+
+        self.paths
+            .entry(key)
+            .or_insert_with(|| (tick, find_path(terrain, bounding_box, start, goal)))
+            .1
+            .clone()
+    }
+}
@@ -0,0 +1,175 @@
+use specs::prelude::*;
+use specs::Component;
+use world::aabb::Aabb;
+use world::world::World as Terrain;
+
+use crate::components::{BoundingBox, OnGround, Position, Velocity};
+
+/// Velocity kept on the ground, for blocks other than ice and slime.
+const SLIPPERINESS: f64 = 0.6;
+
+/// How an entity falls and slows down, like vanilla does for its type.
+/// Players have none, their clients moving them.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[storage(VecStorage)]
+pub struct Physics {
+    /// Blocks per tick removed from the vertical velocity every tick.
+    pub gravity: f64,
+    /// Part of the vertical velocity kept every tick.
+    pub drag: f64,
+    /// Part of the horizontal velocity kept every tick in the air.
+    pub horizontal_drag: f64,
+    /// Whether gravity applies before the move, rather than after it.
+    pub gravity_first: bool,
+    /// Height of the blocks the entity walks up without jumping.
+    pub step_height: f64,
+}
+
+impl Physics {
+    pub const LIVING: Physics = Physics {
+        gravity: 0.08,
+        drag: 0.98,
+        horizontal_drag: 0.91,
+        gravity_first: false,
+        step_height: 0.6,
+    };
+    pub const ITEM: Physics = Physics {
+        gravity: 0.04,
+        drag: 0.98,
+        horizontal_drag: 0.98,
+        gravity_first: true,
+        step_height: 0.0,
+    };
+}
+
+/// Moves the entities with physics by their velocity, stopping them against
+/// the blocks of the terrain.
+pub struct PhysicsSystem;
+
+impl<'a> System<'a> for PhysicsSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Terrain>,
+        ReadStorage<'a, Physics>,
+        ReadStorage<'a, BoundingBox>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, OnGround>,
+    );
+
+    fn run(
+        &mut self,
+        (terrain, physics, bounding_boxes, mut positions, mut velocities, mut on_grounds): Self::SystemData,
+    ) {
+        for (physics, bounding_box, position, velocity, on_ground) in (
+            &physics,
+            &bounding_boxes,
+            &mut positions,
+            &mut velocities,
+            &mut on_grounds,
+        )
+            .join()
+        {
+            if physics.gravity_first {
+                velocity.y -= physics.gravity;
+            }
+
+            let aabb = get_aabb(position, bounding_box);
+            let wanted = (velocity.x, velocity.y, velocity.z);
+            let (x, y, z) = move_and_collide(&terrain, &aabb, wanted, physics, on_ground.0);
+
+            position.x += x;
+            position.y += y;
+            position.z += z;
+            on_ground.0 = y != wanted.1 && wanted.1 < 0.0;
+
+            if x != wanted.0 {
+                velocity.x = 0.0;
+            }
+            if y != wanted.1 {
+                velocity.y = 0.0;
+            }
+            if z != wanted.2 {
+                velocity.z = 0.0;
+            }
+
+            if !physics.gravity_first {
+                velocity.y -= physics.gravity;
+            }
+            let horizontal_drag = if on_ground.0 {
+                physics.horizontal_drag * SLIPPERINESS
+            } else {
+                physics.horizontal_drag
+            };
+            velocity.x *= horizontal_drag;
+            velocity.y *= physics.drag;
+            velocity.z *= horizontal_drag;
+        }
+    }
+}
+
+/// Box of an entity standing at `position`.
+pub fn get_aabb(position: &Position, bounding_box: &BoundingBox) -> Aabb {
+    let half_width = bounding_box.width / 2.0;
+
+    Aabb::new(
+        position.x - half_width,
+        position.y,
+        position.z - half_width,
+        position.x + half_width,
+        position.y + bounding_box.height,
+        position.z + half_width,
+    )
+}
+
+/// How far an entity really moves when it wants to move by `wanted`,
+/// stepping up the blocks low enough if it can.
+fn move_and_collide(
+    terrain: &Terrain,
+    aabb: &Aabb,
+    wanted: (f64, f64, f64),
+    physics: &Physics,
+    on_ground: bool,
+) -> (f64, f64, f64) {
+    let (x, y, z) = wanted;
+    let boxes = terrain.get_collision_boxes(&aabb.expand_towards(x, y, z));
+    let moved = collide(&boxes, aabb, wanted);
+    let blocked = moved.0 != x || moved.2 != z;
+    let landing = on_ground || (moved.1 != y && y < 0.0);
+
+    if physics.step_height <= 0.0 || !blocked || !landing {
+        return moved;
+    }
+
+    let step = physics.step_height;
+    let boxes = terrain.get_collision_boxes(&aabb.expand_towards(x, step, z));
+    let up = collide(&boxes, aabb, (0.0, step, 0.0)).1;
+    let raised = aabb.offset(0.0, up, 0.0);
+    let (step_x, _, step_z) = collide(&boxes, &raised, (x, 0.0, z));
+    let down = collide(&boxes, &raised.offset(step_x, 0.0, step_z), (0.0, -up, 0.0)).1;
+
+    if step_x * step_x + step_z * step_z > moved.0 * moved.0 + moved.2 * moved.2 {
+        (step_x, up + down, step_z)
+    } else {
+        moved
+    }
+}
+
+/// Clips a move against boxes, vertically first, then along the horizontal
+/// axis with the larger move first, like vanilla.
+fn collide(boxes: &[Aabb], aabb: &Aabb, (x, y, z): (f64, f64, f64)) -> (f64, f64, f64) {
+    let y = boxes.iter().fold(y, |y, block| block.clip_y(aabb, y));
+    let aabb = aabb.offset(0.0, y, 0.0);
+
+    if x.abs() < z.abs() {
+        let z = boxes.iter().fold(z, |z, block| block.clip_z(&aabb, z));
+        let aabb = aabb.offset(0.0, 0.0, z);
+        let x = boxes.iter().fold(x, |x, block| block.clip_x(&aabb, x));
+        (x, y, z)
+    } else {
+        let x = boxes.iter().fold(x, |x, block| block.clip_x(&aabb, x));
+        let aabb = aabb.offset(x, 0.0, 0.0);
+        let z = boxes.iter().fold(z, |z, block| block.clip_z(&aabb, z));
+        (x, y, z)
+    }
+}
@@ -0,0 +1,284 @@
+use common::entity_type::{EntityType, MobCategory};
+use common::gamemode::Gamemode;
+use rand::rngs::StdRng;
+use rand::Rng;
+use specs::prelude::*;
+use std::collections::BTreeSet;
+use world::biome::SpawnEntry;
+use world::world::World as Terrain;
+
+use crate::ai::{distance_squared, AiRandom};
+use crate::components::{Player, Position, Rotation};
+use crate::mobs;
+use crate::pathfinding::{is_passable, is_walkable};
+use crate::tracker::{EntityKind, Tracked};
+
+/// Chunks around a player, in each direction, where mobs spawn.
+pub const CHUNK_RADIUS: i32 = 8;
+/// Chunks around a single player, which the caps are given for.
+const CAP_CHUNKS: u32 = 289;
+/// Blocks from the closest player within which no mob spawns.
+pub const MIN_PLAYER_DISTANCE: f64 = 24.0;
+/// Blocks from the closest player beyond which mobs despawn at once.
+pub const DESPAWN_DISTANCE: f64 = 128.0;
+/// Blocks from the closest player beyond which mobs may despawn.
+pub const RANDOM_DESPAWN_DISTANCE: f64 = 32.0;
+/// Mobs far from players despawn on average once in that many ticks.
+const RANDOM_DESPAWN_CHANCE: u32 = 800;
+/// Ticks between two rounds of creature spawning.
+pub const CREATURE_INTERVAL: u64 = 400;
+
+/// Packs tried per chunk, and mobs tried per pack.
+const PACKS: u32 = 3;
+const PACK_TRIES: u32 = 4;
+/// Light at or below which monsters may spawn.
+const MAX_MONSTER_LIGHT: u8 = 7;
+/// Light above which creatures may spawn.
+const MIN_CREATURE_LIGHT: u8 = 8;
+
+/// Spawns mobs around the players, up to the caps of their category in the
+/// world, and despawns those left far from every player.
+#[derive(Default)]
+pub struct SpawnSystem {
+    tick: u64,
+}
+
+impl<'a> System<'a> for SpawnSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Terrain>,
+        Write<'a, AiRandom>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Tracked>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, terrain, mut random, lazy, players, positions, tracked): Self::SystemData,
+    ) {
+        let random = &mut random.0;
+        let tick = self.tick;
+        self.tick += 1;
+
+        // Spectators neither spawn mobs nor keep them around
+        let players: Vec<Position> = (&players, &positions)
+            .join()
+            .filter(|(player, _)| player.gamemode != Gamemode::Spectator)
+            .map(|(_, position)| *position)
+            .collect();
+        if players.is_empty() {
+            return;
+        }
+
+        let mut counts = [0; 4];
+        for (entity, tracked, position) in (&entities, &tracked, &positions).join() {
+            let category = match tracked.kind {
+                EntityKind::Living(entity_type) => entity_type.get_category(),
+                _ => None,
+            };
+            let category = match category {
+                Some(category) => category,
+                None => continue,
+            };
+
+            if !category.is_persistent() && should_despawn(random, &players, position) {
+                let _ = entities.delete(entity);
+            } else {
+                counts[category as usize] += 1;
+            }
+        }
+
+        if !terrain.spawning.enabled {
+            return;
+        }
+
+        let chunks = get_spawn_chunks(&terrain, &players);
+        for category in &MobCategory::ALL {
+            if category.is_persistent() && !tick.is_multiple_of(CREATURE_INTERVAL) {
+                continue;
+            }
+
+            let cap = terrain.spawning.get_cap(*category) * chunks.len() as u32 / CAP_CHUNKS;
+            let mut count = counts[*category as usize];
+            for chunk in &chunks {
+                if count >= cap {
+                    break;
+                }
+                for (entity_type, position, rotation) in
+                    spawn_in_chunk(&terrain, random, &players, *category, *chunk)
+                {
+                    let builder = lazy.create_entity(&entities);
+
+                    if let Some(builder) = mobs::build_mob(builder, entity_type, position, rotation)
+                    {
+                        builder.build();
+                        count += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Chunks within `CHUNK_RADIUS` of a player which are loaded, in the same
+/// order every time.
+fn get_spawn_chunks(terrain: &Terrain, players: &[Position]) -> BTreeSet<(i32, i32)> {
+    let mut chunks = BTreeSet::new();
+
+    for position in players {
+        let (chunk_x, chunk_z) = (
+            (position.x.floor() as i32) >> 4,
+            (position.z.floor() as i32) >> 4,
+        );
+
+        for x in chunk_x - CHUNK_RADIUS..=chunk_x + CHUNK_RADIUS {
+            for z in chunk_z - CHUNK_RADIUS..=chunk_z + CHUNK_RADIUS {
+                if terrain.is_chunk_loaded(x, z) {
+                    chunks.insert((x, z));
+                }
+            }
+        }
+    }
+    chunks
+}
+
+/// Tries a few packs of mobs from a random block of a chunk, returning
+/// those which can spawn.
+fn spawn_in_chunk(
+    terrain: &Terrain,
+    random: &mut StdRng,
+    players: &[Position],
+    category: MobCategory,
+    (chunk_x, chunk_z): (i32, i32),
+) -> Vec<(EntityType, Position, Rotation)> {
+    let start_x = chunk_x * 16 + random.gen_range(0, 16);
+    let start_z = chunk_z * 16 + random.gen_range(0, 16);
+    let y = random.gen_range(0, terrain.get_height(start_x, start_z) + 1);
+    let mut spawned = vec![];
+
+    if !terrain
+        .shapes
+        .get(terrain.get_block(start_x, y, start_z))
+        .is_empty()
+    {
+        return spawned;
+    }
+
+    for _ in 0..PACKS {
+        let (mut x, mut z) = (start_x, start_z);
+        let mut entry: Option<SpawnEntry> = None;
+        let mut group_size = 0;
+        let mut pack = 0;
+
+        for _ in 0..PACK_TRIES {
+            x += random.gen_range(0, 6) - random.gen_range(0, 6);
+            z += random.gen_range(0, 6) - random.gen_range(0, 6);
+            let position = Position::new(f64::from(x) + 0.5, f64::from(y), f64::from(z) + 0.5);
+
+            let closest = players
+                .iter()
+                .map(|player| distance_squared(player, &position))
+                .fold(f64::INFINITY, f64::min);
+            if !(MIN_PLAYER_DISTANCE * MIN_PLAYER_DISTANCE..=DESPAWN_DISTANCE * DESPAWN_DISTANCE)
+                .contains(&closest)
+            {
+                continue;
+            }
+
+            if entry.is_none() {
+                let entries = terrain.spawns.get(terrain.get_biome(x, y, z), category);
+                entry = pick_entry(random, entries);
+                match entry {
+                    Some(entry) => {
+                        group_size = random.gen_range(entry.min_group, entry.max_group + 1)
+                    }
+                    None => break,
+                }
+            }
+            let entry = entry.expect("Entry was just picked");
+
+            if can_spawn_at(terrain, random, category, entry.entity_type, (x, y, z)) {
+                let rotation = Rotation::new(random.gen_range(0.0, 360.0), 0.0);
+
+                spawned.push((entry.entity_type, position, rotation));
+                pack += 1;
+                if pack >= group_size {
+                    break;
+                }
+            }
+        }
+    }
+    spawned
+}
+
+/// Picks a mob at random among those of a biome, by their weight.
+fn pick_entry(random: &mut StdRng, entries: &[SpawnEntry]) -> Option<SpawnEntry> {
+    let total: u32 = entries.iter().map(|entry| entry.weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = random.gen_range(0, total);
+    for entry in entries {
+        if roll < entry.weight {
+            return Some(*entry);
+        }
+        roll -= entry.weight;
+    }
+    None
+}
+
+/// Whether the blocks and the light at a position let a mob spawn there.
+fn can_spawn_at(
+    terrain: &Terrain,
+    random: &mut StdRng,
+    category: MobCategory,
+    entity_type: EntityType,
+    (x, y, z): (i32, i32, i32),
+) -> bool {
+    let kind = match mobs::get_kind(entity_type) {
+        Some(kind) => kind,
+        None => return false,
+    };
+    let settings = &terrain.spawning;
+    let light = terrain.get_light(x, y, z);
+
+    match category {
+        MobCategory::Monster => {
+            light <= random.gen_range(0, MAX_MONSTER_LIGHT + 1)
+                && is_walkable(terrain, &kind.bounding_box, (x, y, z))
+        }
+        MobCategory::Creature => {
+            light > MIN_CREATURE_LIGHT
+                && settings
+                    .creature_blocks
+                    .contains(&terrain.get_block(x, y - 1, z))
+                && is_walkable(terrain, &kind.bounding_box, (x, y, z))
+        }
+        MobCategory::Ambient => {
+            light <= random.gen_range(0, MAX_MONSTER_LIGHT + 1)
+                && is_passable(terrain, &kind.bounding_box, (x, y, z))
+        }
+        MobCategory::WaterCreature => {
+            settings.water_blocks.contains(&terrain.get_block(x, y, z))
+                && settings
+                    .water_blocks
+                    .contains(&terrain.get_block(x, y - 1, z))
+        }
+    }
+}
+
+/// Whether a mob far from every player should go away this tick.
+fn should_despawn(random: &mut StdRng, players: &[Position], position: &Position) -> bool {
+    let closest = players
+        .iter()
+        .map(|player| distance_squared(player, position))
+        .fold(f64::INFINITY, f64::min);
+
+    closest > DESPAWN_DISTANCE * DESPAWN_DISTANCE
+        || (closest > RANDOM_DESPAWN_DISTANCE * RANDOM_DESPAWN_DISTANCE
+            && random.gen_range(0, RANDOM_DESPAWN_CHANCE) == 0)
+}
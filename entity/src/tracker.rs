@@ -0,0 +1,352 @@
+use common::entity_type::EntityType;
+use common::metadata::MetadataValue;
+use specs::prelude::*;
+use specs::Component;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+use crate::components::{OnGround, Player, Position, Rotation, Velocity};
+use crate::get_network_id;
+use crate::item::Pickups;
+use crate::metadata::Metadata;
+
+/// Relative moves are sent in 1/4096 of a block.
+const MOVE_SCALE: f64 = 4096.0;
+
+/// How an entity is shown to the clients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntityKind {
+    Player,
+    /// Mobs.
+    Living(EntityType),
+    /// Other entities, with their spawn data.
+    Object(EntityType, i32),
+}
+
+impl EntityKind {
+    /// Blocks away from a player within which the entity is shown.
+    pub fn get_tracking_range(self) -> f64 {
+        match self {
+            EntityKind::Player | EntityKind::Living(_) => 48.0,
+            EntityKind::Object(..) => 32.0,
+        }
+    }
+}
+
+/// An entity shown to the players around it, along with the state they last
+/// received.
+#[derive(Component, Debug, Clone)]
+#[storage(DenseVecStorage)]
+pub struct Tracked {
+    pub kind: EntityKind,
+    pub uuid: Uuid,
+    position: (i64, i64, i64),
+    yaw: u8,
+    pitch: u8,
+    on_ground: bool,
+}
+
+impl Tracked {
+    pub fn new(kind: EntityKind, uuid: Uuid) -> Self {
+        Self {
+            kind,
+            uuid,
+            position: (0, 0, 0),
+            yaw: 0,
+            pitch: 0,
+            on_ground: false,
+        }
+    }
+}
+
+/// Entities a player can see.
+#[derive(Component, Debug, Clone, Default)]
+#[storage(HashMapStorage)]
+pub struct Viewer {
+    visible: HashSet<Entity>,
+}
+
+impl Viewer {
+    pub fn can_see(&self, entity: Entity) -> bool {
+        self.visible.contains(&entity)
+    }
+
+    /// Makes the entity spawn again for the player at the next tick if it's
+    /// still in range.
+    pub fn forget(&mut self, entity: Entity) {
+        self.visible.remove(&entity);
+    }
+}
+
+/// Something a player has to be told about an entity, by its network id.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackerUpdate {
+    Spawn {
+        id: i32,
+        uuid: Uuid,
+        kind: EntityKind,
+        position: Position,
+        rotation: Rotation,
+        velocity: Velocity,
+    },
+    Destroy(Vec<i32>),
+    /// Moves in 1/4096 of a block.
+    Move {
+        id: i32,
+        delta: (i16, i16, i16),
+        on_ground: bool,
+    },
+    MoveAndRotate {
+        id: i32,
+        delta: (i16, i16, i16),
+        rotation: Rotation,
+        on_ground: bool,
+    },
+    Rotate {
+        id: i32,
+        rotation: Rotation,
+        on_ground: bool,
+    },
+    HeadLook {
+        id: i32,
+        yaw: f32,
+    },
+    Teleport {
+        id: i32,
+        position: Position,
+        rotation: Rotation,
+        on_ground: bool,
+    },
+    /// Animates an item flying to the entity picking it up.
+    Collect {
+        id: i32,
+        collector: i32,
+        count: i8,
+    },
+    /// Every field on spawn, then only those which changed.
+    Metadata {
+        id: i32,
+        metadata: Vec<(u8, MetadataValue)>,
+    },
+}
+
+/// Updates for every player, by UUID, in the order they must be sent.
+#[derive(Debug, Default)]
+pub struct TrackerUpdates(pub Vec<(Uuid, TrackerUpdate)>);
+
+/// Spawns and destroys entities as they come in and out of the range of
+/// each player, and sends the moves of those in range.
+pub struct EntityTracker;
+
+impl<'a> System<'a> for EntityTracker {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Rotation>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, OnGround>,
+        WriteStorage<'a, Metadata>,
+        WriteStorage<'a, Tracked>,
+        WriteStorage<'a, Viewer>,
+        Write<'a, Pickups>,
+        Write<'a, TrackerUpdates>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            players,
+            positions,
+            rotations,
+            velocities,
+            on_grounds,
+            mut metadata,
+            mut tracked,
+            mut viewers,
+            mut pickups,
+            mut updates,
+        ): Self::SystemData,
+    ) {
+        let mut moves = vec![];
+        let pickups = std::mem::take(&mut pickups.0);
+
+        for (entity, tracked, position, metadata) in
+            (&entities, &mut tracked, &positions, (&mut metadata).maybe()).join()
+        {
+            let rotation = rotations.get(entity).copied().unwrap_or_default();
+            let on_ground = on_grounds.get(entity).is_some_and(|on_ground| on_ground.0);
+            let mut updates = get_move(entity, tracked, *position, rotation, on_ground);
+
+            if let Some(metadata) = metadata.filter(|metadata| metadata.is_dirty()) {
+                updates.push(TrackerUpdate::Metadata {
+                    id: get_network_id(entity),
+                    metadata: metadata.take_changes(),
+                });
+            }
+            moves.push((entity, updates));
+        }
+
+        for (viewer_entity, player, viewer, viewer_position) in
+            (&entities, &players, &mut viewers, &positions).join()
+        {
+            let mut destroyed = vec![];
+            let mut spawned = vec![];
+            let mut spawned_entities = HashSet::new();
+
+            viewer.visible.retain(|entity| {
+                let in_range = match (tracked.get(*entity), positions.get(*entity)) {
+                    (Some(tracked), Some(position)) => {
+                        is_in_range(viewer_position, position, tracked.kind)
+                    }
+                    _ => false,
+                };
+
+                if !in_range {
+                    destroyed.push(get_network_id(*entity));
+                }
+                in_range
+            });
+
+            for (entity, tracked, position) in (&entities, &tracked, &positions).join() {
+                if entity == viewer_entity
+                    || viewer.visible.contains(&entity)
+                    || !is_in_range(viewer_position, position, tracked.kind)
+                {
+                    continue;
+                }
+
+                let rotation = rotations.get(entity).copied().unwrap_or_default();
+                viewer.visible.insert(entity);
+                spawned_entities.insert(entity);
+                spawned.push(TrackerUpdate::Spawn {
+                    id: get_network_id(entity),
+                    uuid: tracked.uuid,
+                    kind: tracked.kind,
+                    position: *position,
+                    rotation,
+                    velocity: velocities.get(entity).copied().unwrap_or_default(),
+                });
+                spawned.push(TrackerUpdate::HeadLook {
+                    id: get_network_id(entity),
+                    yaw: rotation.yaw,
+                });
+                if let Some(metadata) = metadata.get(entity) {
+                    spawned.push(TrackerUpdate::Metadata {
+                        id: get_network_id(entity),
+                        metadata: metadata.get_entries(),
+                    });
+                }
+            }
+
+            // Ids of destroyed entities may be given to the spawned ones
+            if !destroyed.is_empty() {
+                updates
+                    .0
+                    .push((player.uuid, TrackerUpdate::Destroy(destroyed)));
+            }
+            for pickup in &pickups {
+                if pickup.collector == viewer_entity || viewer.visible.contains(&pickup.item) {
+                    updates.0.push((
+                        player.uuid,
+                        TrackerUpdate::Collect {
+                            id: get_network_id(pickup.item),
+                            collector: get_network_id(pickup.collector),
+                            count: pickup.count,
+                        },
+                    ));
+                }
+            }
+            // Entities spawned this tick are already where they should be
+            for (entity, entity_moves) in &moves {
+                if viewer.visible.contains(entity) && !spawned_entities.contains(entity) {
+                    for update in entity_moves {
+                        updates.0.push((player.uuid, update.clone()));
+                    }
+                }
+            }
+            for update in spawned {
+                updates.0.push((player.uuid, update));
+            }
+        }
+    }
+}
+
+/// Updates the state sent for an entity, returning how it changed.
+fn get_move(
+    entity: Entity,
+    tracked: &mut Tracked,
+    position: Position,
+    rotation: Rotation,
+    on_ground: bool,
+) -> Vec<TrackerUpdate> {
+    let id = get_network_id(entity);
+    let scaled = (
+        (position.x * MOVE_SCALE).round() as i64,
+        (position.y * MOVE_SCALE).round() as i64,
+        (position.z * MOVE_SCALE).round() as i64,
+    );
+    let delta = (
+        i16::try_from(scaled.0 - tracked.position.0),
+        i16::try_from(scaled.1 - tracked.position.1),
+        i16::try_from(scaled.2 - tracked.position.2),
+    );
+    let (yaw, pitch) = (to_angle(rotation.yaw), to_angle(rotation.pitch));
+    let rotated = yaw != tracked.yaw || pitch != tracked.pitch;
+    let moved = scaled != tracked.position || on_ground != tracked.on_ground;
+    let mut updates = vec![];
+
+    if !moved && rotated {
+        updates.push(TrackerUpdate::Rotate {
+            id,
+            rotation,
+            on_ground,
+        });
+    } else if moved {
+        updates.push(match delta {
+            (Ok(x), Ok(y), Ok(z)) if !rotated => TrackerUpdate::Move {
+                id,
+                delta: (x, y, z),
+                on_ground,
+            },
+            (Ok(x), Ok(y), Ok(z)) => TrackerUpdate::MoveAndRotate {
+                id,
+                delta: (x, y, z),
+                rotation,
+                on_ground,
+            },
+            _ => TrackerUpdate::Teleport {
+                id,
+                position,
+                rotation,
+                on_ground,
+            },
+        });
+    }
+    if rotated {
+        updates.push(TrackerUpdate::HeadLook {
+            id,
+            yaw: rotation.yaw,
+        });
+    }
+
+    tracked.position = scaled;
+    tracked.yaw = yaw;
+    tracked.pitch = pitch;
+    tracked.on_ground = on_ground;
+    updates
+}
+
+fn is_in_range(viewer: &Position, position: &Position, kind: EntityKind) -> bool {
+    let range = kind.get_tracking_range();
+
+    (viewer.x - position.x).abs() <= range && (viewer.z - position.z).abs() <= range
+}
+
+/// Angle as sent to the clients, in 1/256 of a turn.
+fn to_angle(degrees: f32) -> u8 {
+    (degrees * 256.0 / 360.0).round() as i32 as u8
+}
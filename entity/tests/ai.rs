@@ -0,0 +1,98 @@
+use common::entity_type::EntityType;
+use common::gamemode::Gamemode;
+use entity::ai::Brain;
+use entity::components::{Player, Position, Rotation};
+use specs::prelude::*;
+use uuid::Uuid;
+
+mod support;
+
+use support::ecs;
+
+fn distance(from: &Position, to: &Position) -> f64 {
+    ((to.x - from.x).powi(2) + (to.z - from.z).powi(2)).sqrt()
+}
+
+#[test]
+fn only_mobs_can_be_spawned() {
+    let mut ecs = ecs();
+
+    assert!(ecs
+        .spawn_mob(EntityType::Zombie, Position::new(0.5, 64.0, 0.5))
+        .is_some());
+    assert!(ecs
+        .spawn_mob(EntityType::Item, Position::new(0.5, 64.0, 0.5))
+        .is_none());
+}
+
+#[test]
+fn zombie_walks_to_players_and_attacks_them() {
+    let mut ecs = ecs();
+    let zombie = ecs
+        .spawn_mob(EntityType::Zombie, Position::new(0.5, 64.0, 0.5))
+        .unwrap();
+    let player = ecs.create_player(
+        Player::new(Uuid::new_v4(), "Steve", Gamemode::Survival, 0),
+        Position::new(8.5, 64.0, 0.5),
+        Rotation::default(),
+    );
+    let mut attacks = vec![];
+
+    for _ in 0..100 {
+        ecs.tick();
+        attacks.extend(ecs.take_attacks());
+    }
+
+    let zombie_position = ecs.get_component::<Position>(zombie).unwrap();
+    let player_position = ecs.get_component::<Position>(player).unwrap();
+    assert!(distance(&zombie_position, &player_position) < 1.5);
+    assert!(!attacks.is_empty());
+    assert!(attacks
+        .iter()
+        .all(|attack| attack.attacker == zombie && attack.target == player));
+}
+
+#[test]
+fn zombie_ignores_creative_players() {
+    let mut ecs = ecs();
+    ecs.spawn_mob(EntityType::Zombie, Position::new(0.5, 64.0, 0.5))
+        .unwrap();
+    ecs.create_player(
+        Player::new(Uuid::new_v4(), "Steve", Gamemode::Creative, 0),
+        Position::new(1.5, 64.0, 0.5),
+        Rotation::default(),
+    );
+
+    for _ in 0..100 {
+        ecs.tick();
+        assert!(ecs.take_attacks().is_empty());
+    }
+}
+
+#[test]
+fn cow_panics_when_hurt() {
+    let mut ecs = ecs();
+    let cow = ecs
+        .spawn_mob(EntityType::Cow, Position::new(0.5, 64.0, 0.5))
+        .unwrap();
+
+    ecs.tick();
+    let start = ecs.get_component::<Position>(cow).unwrap();
+    assert!(ecs.hurt_mob(cow, None));
+    ecs.tick();
+
+    let destination = ecs
+        .get_world()
+        .read_storage::<Brain>()
+        .get(cow)
+        .unwrap()
+        .get_navigation()
+        .get_destination();
+    assert!(destination.is_some());
+
+    for _ in 0..20 {
+        ecs.tick();
+    }
+    let position = ecs.get_component::<Position>(cow).unwrap();
+    assert!(distance(&start, &position) > 1.0);
+}
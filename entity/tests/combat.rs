@@ -0,0 +1,183 @@
+use common::entity_type::EntityType;
+use common::gamemode::Gamemode;
+use common::item::ItemStack;
+use entity::combat::{Combat, CombatEvent, Weapon, DEATH_TICKS};
+use entity::components::{Health, OnGround, Player, Position, Rotation, Velocity};
+use entity::inventory::Inventory;
+use entity::{ECSWorld, Entity};
+use specs::prelude::*;
+use uuid::Uuid;
+
+mod support;
+
+use support::ecs;
+
+const IRON_SWORD: i32 = 595;
+
+fn player(ecs: &mut ECSWorld, x: f64, gamemode: Gamemode) -> Entity {
+    ecs.create_player(
+        Player::new(Uuid::new_v4(), "Steve", gamemode, 0),
+        Position::new(x, 64.0, 0.5),
+        Rotation::default(),
+    )
+}
+
+/// A cow standing still on the floor, and a player next to it whose attack
+/// is fully charged.
+fn cow_and_player() -> (ECSWorld<'static, 'static>, Entity, Entity) {
+    let mut ecs = ecs();
+    let cow = ecs
+        .spawn_mob(EntityType::Cow, Position::new(2.5, 64.0, 0.5))
+        .unwrap();
+    let player = player(&mut ecs, 0.5, Gamemode::Survival);
+
+    for _ in 0..10 {
+        ecs.tick();
+    }
+    ecs.set_component(cow, Position::new(2.5, 64.0, 0.5));
+    ecs.set_component(cow, Velocity::default());
+    ecs.take_combat_events();
+    (ecs, cow, player)
+}
+
+fn health(ecs: &ECSWorld, entity: Entity) -> f32 {
+    ecs.get_component::<Health>(entity).unwrap().current
+}
+
+#[test]
+fn fists_deal_one_damage_and_invulnerability_stops_weaker_hits() {
+    let (mut ecs, cow, player) = cow_and_player();
+
+    assert!(ecs.attack(player, cow));
+    assert_eq!(health(&ecs, cow), 9.0);
+    assert_eq!(
+        ecs.take_combat_events(),
+        vec![CombatEvent::Hurt {
+            entity: cow,
+            attacker: Some(player),
+            animated: true,
+        }]
+    );
+
+    // Right after an attack the next one is too weak to get through
+    assert!(!ecs.attack(player, cow));
+    assert_eq!(health(&ecs, cow), 9.0);
+}
+
+#[test]
+fn weapons_and_critical_hits_deal_more_damage() {
+    let (mut ecs, cow, player) = cow_and_player();
+    let mut inventory = Inventory::new();
+
+    ecs.get_weapons_mut().set(IRON_SWORD, Weapon::new(6.0, 1.6));
+    inventory.set(0, Some(ItemStack::new(IRON_SWORD, 1)));
+    ecs.set_component(player, inventory);
+    for _ in 0..15 {
+        ecs.tick();
+    }
+    ecs.take_combat_events();
+
+    let mut combat = ecs.get_component::<Combat>(player).unwrap();
+    combat.set_falling(true);
+    ecs.set_component(player, combat);
+    ecs.set_component(player, OnGround(false));
+    assert!(ecs.attack(player, cow));
+    assert_eq!(health(&ecs, cow), 1.0);
+    assert!(ecs
+        .take_combat_events()
+        .contains(&CombatEvent::Critical { entity: cow }));
+}
+
+#[test]
+fn attacks_need_reach() {
+    let mut ecs = ecs();
+    let cow = ecs
+        .spawn_mob(EntityType::Cow, Position::new(0.5, 64.0, 0.5))
+        .unwrap();
+    let player = player(&mut ecs, 7.5, Gamemode::Survival);
+
+    assert!(!ecs.attack(player, cow));
+    assert_eq!(health(&ecs, cow), 10.0);
+}
+
+#[test]
+fn hits_knock_back_away_from_the_attacker() {
+    let (mut ecs, cow, player) = cow_and_player();
+
+    assert!(ecs.attack(player, cow));
+    let velocity = ecs.get_component::<Velocity>(cow).unwrap();
+    assert!(velocity.x > 0.3);
+    assert!(velocity.y > 0.3);
+    assert!(velocity.z.abs() < 1.0e-6);
+}
+
+#[test]
+fn dead_mobs_are_removed_after_their_animation() {
+    let (mut ecs, cow, player) = cow_and_player();
+
+    ecs.set_component(
+        cow,
+        Health {
+            current: 1.0,
+            max: 10.0,
+        },
+    );
+    assert!(ecs.attack(player, cow));
+    assert!(ecs.take_combat_events().contains(&CombatEvent::Death {
+        entity: cow,
+        attacker: Some(player),
+    }));
+
+    for _ in 0..DEATH_TICKS - 1 {
+        ecs.tick();
+    }
+    assert!(ecs.get_world().is_alive(cow));
+    ecs.tick();
+    assert!(!ecs.get_world().is_alive(cow));
+}
+
+#[test]
+fn zombies_kill_players_who_then_respawn() {
+    let mut ecs = ecs();
+    let zombie = ecs
+        .spawn_mob(EntityType::Zombie, Position::new(0.5, 64.0, 0.5))
+        .unwrap();
+    let player = player(&mut ecs, 1.5, Gamemode::Survival);
+    let mut events = vec![];
+
+    ecs.set_component(
+        player,
+        Health {
+            current: 5.0,
+            max: 20.0,
+        },
+    );
+    for _ in 0..100 {
+        ecs.tick();
+        events.extend(ecs.take_combat_events());
+    }
+
+    assert_eq!(health(&ecs, player), 0.0);
+    assert!(events.contains(&CombatEvent::Death {
+        entity: player,
+        attacker: Some(zombie),
+    }));
+    assert!(ecs.get_world().is_alive(player));
+
+    assert!(ecs.respawn_player(player));
+    assert_eq!(health(&ecs, player), 20.0);
+    assert!(!ecs.respawn_player(player));
+}
+
+#[test]
+fn creative_players_cannot_be_hurt() {
+    let mut ecs = ecs();
+    let attacker = player(&mut ecs, 0.5, Gamemode::Survival);
+    let target = player(&mut ecs, 1.5, Gamemode::Creative);
+
+    for _ in 0..10 {
+        ecs.tick();
+    }
+    assert!(!ecs.attack(attacker, target));
+    assert_eq!(health(&ecs, target), 20.0);
+}
@@ -0,0 +1,192 @@
+use common::item::ItemStack;
+use entity::inventory::{
+    from_window_slot, to_window_slot, Armor, ArmorSlot, ClickMode, ClickResult, Inventory, ARMOR,
+    CRAFTING, OFFHAND, OUTSIDE,
+};
+
+const STONE: i32 = 1;
+const DIRT: i32 = 10;
+const IRON_HELMET: i32 = 591;
+
+fn stack(item: i32, count: i8) -> Option<ItemStack> {
+    Some(ItemStack::new(item, count))
+}
+
+fn click(inventory: &mut Inventory, window_slot: i16, button: i8, mode: ClickMode) -> ClickResult {
+    inventory.click(window_slot, button, mode, false, &Armor::new())
+}
+
+#[test]
+fn window_slots_map_to_inventory_slots() {
+    assert_eq!(to_window_slot(0), 36);
+    assert_eq!(to_window_slot(9), 9);
+    assert_eq!(to_window_slot(ARMOR + 3), 5);
+    assert_eq!(to_window_slot(OFFHAND), 45);
+    assert_eq!(to_window_slot(CRAFTING), 1);
+
+    assert_eq!(from_window_slot(0), None);
+    assert_eq!(from_window_slot(44), Some(8));
+    assert_eq!(from_window_slot(8), Some(ARMOR));
+    assert_eq!(from_window_slot(OUTSIDE), None);
+    for slot in 0..CRAFTING + 4 {
+        assert_eq!(from_window_slot(to_window_slot(slot)), Some(slot));
+    }
+}
+
+#[test]
+fn clicks_pick_up_split_and_put_down_stacks() {
+    let mut inventory = Inventory::new();
+    inventory.set(9, stack(STONE, 10));
+
+    // Right click takes the bigger half
+    let result = click(&mut inventory, 9, 1, ClickMode::Pickup);
+    assert_eq!(result.clicked, stack(STONE, 10));
+    assert_eq!(inventory.get_cursor(), stack(STONE, 5).as_ref());
+    assert_eq!(inventory.get(9), stack(STONE, 5).as_ref());
+
+    // Right click puts down a single item
+    click(&mut inventory, 10, 1, ClickMode::Pickup);
+    assert_eq!(inventory.get(10), stack(STONE, 1).as_ref());
+    assert_eq!(inventory.get_cursor(), stack(STONE, 4).as_ref());
+
+    // Left click merges the whole cursor
+    click(&mut inventory, 9, 0, ClickMode::Pickup);
+    assert_eq!(inventory.get(9), stack(STONE, 9).as_ref());
+    assert_eq!(inventory.get_cursor(), None);
+}
+
+#[test]
+fn clicks_swap_different_items_and_drop_outside() {
+    let mut inventory = Inventory::new();
+    inventory.set(9, stack(STONE, 10));
+    inventory.set(10, stack(DIRT, 3));
+
+    click(&mut inventory, 9, 0, ClickMode::Pickup);
+    click(&mut inventory, 10, 0, ClickMode::Pickup);
+    assert_eq!(inventory.get(10), stack(STONE, 10).as_ref());
+    assert_eq!(inventory.get_cursor(), stack(DIRT, 3).as_ref());
+
+    let result = click(&mut inventory, OUTSIDE, 1, ClickMode::Pickup);
+    assert_eq!(result.dropped, vec![ItemStack::new(DIRT, 1)]);
+    let result = click(&mut inventory, OUTSIDE, 0, ClickMode::Pickup);
+    assert_eq!(result.dropped, vec![ItemStack::new(DIRT, 2)]);
+    assert_eq!(inventory.get_cursor(), None);
+}
+
+#[test]
+fn shift_clicks_move_stacks_between_hotbar_and_main_inventory() {
+    let mut inventory = Inventory::new();
+    inventory.set(0, stack(STONE, 60));
+    inventory.set(9, stack(STONE, 10));
+
+    // The main inventory goes to the hotbar, filling up existing stacks first
+    let result = click(&mut inventory, 9, 0, ClickMode::QuickMove);
+    assert_eq!(result.clicked, None);
+    assert_eq!(inventory.get(0), stack(STONE, 64).as_ref());
+    assert_eq!(inventory.get(1), stack(STONE, 6).as_ref());
+    assert_eq!(inventory.get(9), None);
+
+    // The hotbar goes to the main inventory
+    click(&mut inventory, 36, 0, ClickMode::QuickMove);
+    assert_eq!(inventory.get(0), None);
+    assert_eq!(inventory.get(9), stack(STONE, 64).as_ref());
+}
+
+#[test]
+fn shift_clicks_wear_armor() {
+    let mut inventory = Inventory::new();
+    let mut armor = Armor::new();
+    armor.set(IRON_HELMET, ArmorSlot::Head);
+    inventory.set(9, stack(IRON_HELMET, 1));
+
+    inventory.click(9, 0, ClickMode::QuickMove, false, &armor);
+    assert_eq!(
+        inventory.get(ArmorSlot::Head.get_slot()),
+        stack(IRON_HELMET, 1).as_ref()
+    );
+
+    // Other items don't fit armor slots
+    inventory.set(10, stack(STONE, 1));
+    click(&mut inventory, 10, 0, ClickMode::Pickup);
+    click(&mut inventory, 7, 0, ClickMode::Pickup);
+    assert_eq!(inventory.get(ArmorSlot::Legs.get_slot()), None);
+    assert_eq!(inventory.get_cursor(), stack(STONE, 1).as_ref());
+}
+
+#[test]
+fn number_keys_swap_with_the_hotbar() {
+    let mut inventory = Inventory::new();
+    inventory.set(2, stack(DIRT, 5));
+    inventory.set(20, stack(STONE, 7));
+
+    click(&mut inventory, 20, 2, ClickMode::Swap);
+    assert_eq!(inventory.get(2), stack(STONE, 7).as_ref());
+    assert_eq!(inventory.get(20), stack(DIRT, 5).as_ref());
+}
+
+#[test]
+fn drags_spread_the_cursor_evenly() {
+    let mut inventory = Inventory::new();
+    inventory.set_cursor(stack(STONE, 10));
+
+    click(&mut inventory, OUTSIDE, 0, ClickMode::Drag);
+    for window_slot in 9..12 {
+        click(&mut inventory, window_slot, 1, ClickMode::Drag);
+    }
+    click(&mut inventory, OUTSIDE, 2, ClickMode::Drag);
+
+    for slot in 9..12 {
+        assert_eq!(inventory.get(slot), stack(STONE, 3).as_ref());
+    }
+    assert_eq!(inventory.get_cursor(), stack(STONE, 1).as_ref());
+}
+
+#[test]
+fn right_drags_put_one_item_per_slot() {
+    let mut inventory = Inventory::new();
+    inventory.set_cursor(stack(STONE, 10));
+
+    click(&mut inventory, OUTSIDE, 4, ClickMode::Drag);
+    for window_slot in 9..13 {
+        click(&mut inventory, window_slot, 5, ClickMode::Drag);
+    }
+    click(&mut inventory, OUTSIDE, 6, ClickMode::Drag);
+
+    for slot in 9..13 {
+        assert_eq!(inventory.get(slot), stack(STONE, 1).as_ref());
+    }
+    assert_eq!(inventory.get_cursor(), stack(STONE, 6).as_ref());
+}
+
+#[test]
+fn double_clicks_gather_matching_items() {
+    let mut inventory = Inventory::new();
+    inventory.set(9, stack(STONE, 10));
+    inventory.set(15, stack(STONE, 20));
+    inventory.set(0, stack(STONE, 5));
+    inventory.set(1, stack(DIRT, 5));
+
+    click(&mut inventory, 9, 0, ClickMode::Pickup);
+    click(&mut inventory, 9, 0, ClickMode::PickupAll);
+    assert_eq!(inventory.get_cursor(), stack(STONE, 35).as_ref());
+    assert_eq!(inventory.get(15), None);
+    assert_eq!(inventory.get(0), None);
+    assert_eq!(inventory.get(1), stack(DIRT, 5).as_ref());
+}
+
+#[test]
+fn closing_returns_the_crafting_grid_and_drops_the_cursor() {
+    let mut inventory = Inventory::new();
+    inventory.set(CRAFTING, stack(STONE, 4));
+    inventory.set_cursor(stack(DIRT, 2));
+    inventory.take_changes();
+
+    assert_eq!(inventory.close(), vec![ItemStack::new(DIRT, 2)]);
+    assert_eq!(inventory.get(CRAFTING), None);
+    assert_eq!(inventory.get(0), stack(STONE, 4).as_ref());
+    assert_eq!(inventory.get_cursor(), None);
+
+    let changes = inventory.take_changes();
+    assert!(changes.contains(&(1, None)));
+    assert!(changes.contains(&(36, stack(STONE, 4))));
+}
@@ -0,0 +1,114 @@
+use common::gamemode::Gamemode;
+use common::item::ItemStack;
+use entity::components::{Player, Position, Rotation, Velocity};
+use entity::inventory::Inventory;
+use entity::item::{DroppedItem, DESPAWN_AGE};
+use entity::tracker::TrackerUpdate;
+use entity::{ECSWorld, Entity};
+use uuid::Uuid;
+
+mod support;
+
+use support::ecs;
+
+const STEVE: Uuid = Uuid::from_u128(1);
+const COBBLESTONE: i32 = 14;
+const DIRT: i32 = 10;
+
+fn drop(ecs: &mut ECSWorld, x: f64, item: i32, count: i8, pickup_delay: u32) -> Entity {
+    ecs.spawn_item(
+        Position::new(x, 64.0, 0.5),
+        Velocity::default(),
+        ItemStack::new(item, count),
+        pickup_delay,
+    )
+}
+
+fn player(ecs: &mut ECSWorld, x: f64) -> Entity {
+    ecs.create_player(
+        Player::new(STEVE, "Steve", Gamemode::Survival, 0),
+        Position::new(x, 64.0, 0.5),
+        Rotation::default(),
+    )
+}
+
+#[test]
+fn close_stacks_of_the_same_item_merge() {
+    let mut ecs = ecs();
+    let small = drop(&mut ecs, 0.5, COBBLESTONE, 3, 10);
+    let large = drop(&mut ecs, 0.9, COBBLESTONE, 5, 0);
+    let other = drop(&mut ecs, 0.7, DIRT, 1, 0);
+    let far = drop(&mut ecs, 4.5, COBBLESTONE, 1, 0);
+
+    ecs.tick();
+    let merged = ecs.get_component::<DroppedItem>(large).unwrap();
+    assert_eq!(merged.stack.count, 8);
+    assert_eq!(merged.pickup_delay, 9);
+    assert_eq!(ecs.get_component::<DroppedItem>(small), None);
+    assert!(ecs.get_component::<DroppedItem>(other).is_some());
+    assert!(ecs.get_component::<DroppedItem>(far).is_some());
+}
+
+#[test]
+fn players_pick_up_items_once_the_delay_is_over() {
+    let mut ecs = ecs();
+    let steve = player(&mut ecs, 0.5);
+    let item = drop(&mut ecs, 1.0, COBBLESTONE, 3, 2);
+    let item_id = entity::get_network_id(item);
+
+    ecs.tick();
+    assert!(ecs.get_component::<DroppedItem>(item).is_some());
+
+    ecs.tick();
+    let collected = ecs
+        .take_tracker_updates()
+        .into_iter()
+        .any(|(uuid, update)| {
+            uuid == STEVE
+                && update
+                    == TrackerUpdate::Collect {
+                        id: item_id,
+                        collector: entity::get_network_id(steve),
+                        count: 3,
+                    }
+        });
+    assert!(collected);
+    assert_eq!(ecs.get_component::<DroppedItem>(item), None);
+    assert_eq!(
+        ecs.get_component::<Inventory>(steve).unwrap().get(0),
+        Some(&ItemStack::new(COBBLESTONE, 3))
+    );
+}
+
+#[test]
+fn items_despawn_after_five_minutes() {
+    let mut ecs = ecs();
+    let item = drop(&mut ecs, 0.5, COBBLESTONE, 1, 0);
+
+    for _ in 1..DESPAWN_AGE {
+        ecs.tick();
+    }
+    assert!(ecs.get_component::<DroppedItem>(item).is_some());
+
+    ecs.tick();
+    assert_eq!(ecs.get_component::<DroppedItem>(item), None);
+}
+
+#[test]
+fn inventory_fills_stacks_before_empty_slots() {
+    let mut inventory = Inventory::new();
+    inventory.set(4, Some(ItemStack::new(COBBLESTONE, 60)));
+
+    assert_eq!(inventory.insert(ItemStack::new(COBBLESTONE, 70)), None);
+    assert_eq!(inventory.get(4), Some(&ItemStack::new(COBBLESTONE, 64)));
+    assert_eq!(inventory.get(0), Some(&ItemStack::new(COBBLESTONE, 64)));
+    assert_eq!(inventory.get(1), Some(&ItemStack::new(COBBLESTONE, 2)));
+
+    for slot in 0..36 {
+        inventory.set(slot, Some(ItemStack::new(DIRT, 64)));
+    }
+    assert_eq!(
+        inventory.insert(ItemStack::new(COBBLESTONE, 5)),
+        Some(ItemStack::new(COBBLESTONE, 5))
+    );
+}
@@ -0,0 +1,94 @@
+use entity::components::BoundingBox;
+use entity::pathfinding::{find_path, PathCache};
+
+mod support;
+
+use support::{terrain, STONE};
+
+#[test]
+fn path_goes_around_walls() {
+    let mut terrain = terrain();
+    for z in -3..=3 {
+        terrain.set_block(2, 64, z, STONE);
+        terrain.set_block(2, 65, z, STONE);
+    }
+
+    let path = find_path(&terrain, &BoundingBox::PLAYER, (0, 64, 0), (4, 64, 0)).unwrap();
+    let nodes = path.get_nodes();
+
+    assert_eq!(nodes.first(), Some(&(0, 64, 0)));
+    assert_eq!(path.get_end(), Some((4, 64, 0)));
+    assert!(nodes.iter().all(|node| node.1 == 64));
+    assert!(nodes.iter().any(|node| node.2.abs() > 3));
+    assert!(!nodes.contains(&(2, 64, 0)));
+}
+
+#[test]
+fn path_jumps_one_block_but_not_two() {
+    let mut terrain = terrain();
+    for x in 2..8 {
+        for z in -8..8 {
+            terrain.set_block(x, 64, z, STONE);
+        }
+    }
+
+    let path = find_path(&terrain, &BoundingBox::PLAYER, (0, 64, 0), (4, 65, 0)).unwrap();
+    assert_eq!(path.get_end(), Some((4, 65, 0)));
+
+    for x in 2..8 {
+        for z in -8..8 {
+            terrain.set_block(x, 65, z, STONE);
+        }
+    }
+    let path = find_path(&terrain, &BoundingBox::PLAYER, (0, 64, 0), (4, 66, 0)).unwrap();
+    // Only the closest node below the ledge can be reached
+    assert_eq!(path.get_end(), Some((1, 64, 0)));
+}
+
+#[test]
+fn path_falls_three_blocks_at_most() {
+    let mut terrain = terrain();
+    for x in 2..8 {
+        for z in -8..8 {
+            terrain.set_block(x, 63, z, 0);
+        }
+    }
+    for z in -8..8 {
+        for x in 2..5 {
+            terrain.set_block(x, 60, z, STONE);
+        }
+        for x in 5..8 {
+            terrain.set_block(x, 56, z, STONE);
+        }
+    }
+
+    let path = find_path(&terrain, &BoundingBox::PLAYER, (0, 64, 0), (4, 61, 0)).unwrap();
+    assert_eq!(path.get_end(), Some((4, 61, 0)));
+    assert!(path.get_nodes().iter().all(|node| node.1 >= 61));
+
+    let path = find_path(&terrain, &BoundingBox::PLAYER, (4, 61, 0), (6, 57, 0));
+    assert!(path.is_none());
+}
+
+#[test]
+fn cache_reuses_paths_until_they_expire() {
+    let mut terrain = terrain();
+    let mut cache = PathCache::default();
+
+    let path = cache.find_path(&terrain, &BoundingBox::PLAYER, (0, 64, 0), (4, 64, 0));
+    assert_eq!(path.unwrap().get_end(), Some((4, 64, 0)));
+
+    // Walls the goal off across the whole floor
+    for z in -16..16 {
+        terrain.set_block(2, 64, z, STONE);
+        terrain.set_block(2, 65, z, STONE);
+    }
+    let path = cache.find_path(&terrain, &BoundingBox::PLAYER, (0, 64, 0), (4, 64, 0));
+    assert_eq!(path.unwrap().get_end(), Some((4, 64, 0)));
+
+    for _ in 0..=20 {
+        cache.tick();
+    }
+    let path = cache.find_path(&terrain, &BoundingBox::PLAYER, (0, 64, 0), (4, 64, 0));
+    assert_ne!(path.unwrap().get_end(), Some((4, 64, 0)));
+}
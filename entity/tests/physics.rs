@@ -0,0 +1,122 @@
+use entity::components::{BoundingBox, OnGround, Position, Velocity};
+use entity::physics::Physics;
+use entity::{ECSWorld, Entity};
+use specs::prelude::*;
+use world::aabb::Aabb;
+
+mod support;
+
+use support::STONE;
+
+const SLAB: u16 = 2;
+
+/// The stone floor, with slabs half a block high.
+fn ecs() -> ECSWorld<'static, 'static> {
+    let mut ecs = support::ecs();
+    ecs.get_terrain_mut()
+        .shapes
+        .set(SLAB, vec![Aabb::new(0.0, 0.0, 0.0, 1.0, 0.5, 1.0)]);
+    ecs
+}
+
+fn spawn(ecs: &mut ECSWorld, physics: Physics, position: Position, velocity: Velocity) -> Entity {
+    ecs.get_world_mut()
+        .create_entity()
+        .with(position)
+        .with(velocity)
+        .with(OnGround(false))
+        .with(BoundingBox::new(0.6, 1.8))
+        .with(physics)
+        .build()
+}
+
+fn position(ecs: &ECSWorld, entity: Entity) -> Position {
+    ecs.get_component::<Position>(entity).unwrap()
+}
+
+#[test]
+fn gravity_applies_after_moving_for_mobs_and_before_for_items() {
+    let mut ecs = ecs();
+    let mob = spawn(
+        &mut ecs,
+        Physics::LIVING,
+        Position::new(0.5, 70.0, 0.5),
+        Velocity::default(),
+    );
+    let item = spawn(
+        &mut ecs,
+        Physics::ITEM,
+        Position::new(2.5, 70.0, 0.5),
+        Velocity::default(),
+    );
+
+    ecs.tick();
+    assert_eq!(position(&ecs, mob).y, 70.0);
+    assert_eq!(position(&ecs, item).y, 70.0 - 0.04);
+
+    ecs.tick();
+    assert_eq!(position(&ecs, mob).y, 70.0 - 0.08 * 0.98);
+    assert_eq!(
+        ecs.get_component::<Velocity>(item).unwrap().y,
+        (-0.04 * 0.98 - 0.04) * 0.98
+    );
+}
+
+#[test]
+fn falling_entities_land_on_blocks() {
+    let mut ecs = ecs();
+    let mob = spawn(
+        &mut ecs,
+        Physics::LIVING,
+        Position::new(0.5, 70.0, 0.5),
+        Velocity::default(),
+    );
+
+    for _ in 0..40 {
+        ecs.tick();
+    }
+    assert_eq!(position(&ecs, mob), Position::new(0.5, 64.0, 0.5));
+    assert_eq!(ecs.get_component::<OnGround>(mob), Some(OnGround(true)));
+}
+
+#[test]
+fn walls_stop_horizontal_moves() {
+    let mut ecs = ecs();
+    ecs.get_terrain_mut().set_block(2, 64, 0, STONE);
+    ecs.get_terrain_mut().set_block(2, 65, 0, STONE);
+    let mob = spawn(
+        &mut ecs,
+        Physics::LIVING,
+        Position::new(0.5, 64.0, 0.5),
+        Velocity::new(2.0, 0.0, 0.0),
+    );
+
+    ecs.tick();
+    assert_eq!(position(&ecs, mob).x, 2.0 - 0.3);
+    assert_eq!(ecs.get_component::<Velocity>(mob).unwrap().x, 0.0);
+}
+
+#[test]
+fn mobs_step_up_slabs_but_not_full_blocks() {
+    let mut ecs = ecs();
+    ecs.get_terrain_mut().set_block(1, 64, 0, SLAB);
+    ecs.get_terrain_mut().set_block(1, 64, 2, STONE);
+    let on_slab = spawn(
+        &mut ecs,
+        Physics::LIVING,
+        Position::new(0.5, 64.0, 0.5),
+        Velocity::new(0.5, 0.0, 0.0),
+    );
+    let against_block = spawn(
+        &mut ecs,
+        Physics::LIVING,
+        Position::new(0.5, 64.0, 2.5),
+        Velocity::new(0.5, 0.0, 0.0),
+    );
+    ecs.set_component(on_slab, OnGround(true));
+    ecs.set_component(against_block, OnGround(true));
+
+    ecs.tick();
+    assert_eq!(position(&ecs, on_slab), Position::new(1.0, 64.5, 0.5));
+    assert_eq!(position(&ecs, against_block), Position::new(0.7, 64.0, 2.5));
+}
@@ -0,0 +1,46 @@
+use common::gamemode::Gamemode;
+use entity::components::{Health, OnGround, Player, Position, Rotation};
+use entity::ECSWorld;
+use uuid::Uuid;
+
+fn player(ecs: &mut ECSWorld, uuid: Uuid, connection: u64) -> entity::Entity {
+    ecs.create_player(
+        Player::new(uuid, "Steve", Gamemode::Survival, connection),
+        Position::new(0.5, 64.0, 0.5),
+        Rotation::default(),
+    )
+}
+
+#[test]
+fn players_are_found_by_uuid() {
+    let mut ecs = ECSWorld::new();
+    let first = player(&mut ecs, Uuid::from_u128(1), 0);
+    let second = player(&mut ecs, Uuid::from_u128(2), 1);
+
+    assert_ne!(
+        entity::get_network_id(first),
+        entity::get_network_id(second)
+    );
+    assert_eq!(ecs.get_player(&Uuid::from_u128(2)), Some(second));
+    assert_eq!(
+        ecs.get_component::<Health>(first),
+        Some(Health::new(Health::PLAYER_MAX))
+    );
+    assert_eq!(ecs.get_component::<OnGround>(first), Some(OnGround(false)));
+
+    ecs.set_component(first, Position::new(1.0, 2.0, 3.0));
+    assert_eq!(
+        ecs.get_component::<Position>(first),
+        Some(Position::new(1.0, 2.0, 3.0))
+    );
+}
+
+#[test]
+fn removed_players_are_gone() {
+    let mut ecs = ECSWorld::new();
+    let steve = player(&mut ecs, Uuid::from_u128(1), 0);
+
+    ecs.remove_entity(steve);
+    assert_eq!(ecs.get_player(&Uuid::from_u128(1)), None);
+    assert_eq!(ecs.get_component::<Position>(steve), None);
+}
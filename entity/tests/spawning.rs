@@ -0,0 +1,110 @@
+use common::entity_type::EntityType;
+use common::gamemode::Gamemode;
+use entity::components::{Player, Position, Rotation};
+use entity::tracker::{EntityKind, Tracked};
+use entity::ECSWorld;
+use specs::prelude::*;
+use uuid::Uuid;
+
+const STONE: u16 = 1;
+const GRASS: u16 = 9;
+
+/// Floor whose top is at y = 64, over the 17x17 chunks around the origin.
+fn ecs(floor: u16) -> ECSWorld<'static, 'static> {
+    let mut ecs = ECSWorld::new();
+    let mut terrain = ecs.get_terrain_mut();
+
+    for x in -128..144 {
+        for z in -128..144 {
+            terrain.set_block(x, 63, z, floor);
+        }
+    }
+    drop(terrain);
+    ecs.create_player(
+        Player::new(Uuid::new_v4(), "Steve", Gamemode::Survival, 0),
+        Position::new(8.5, 64.0, 8.5),
+        Rotation::default(),
+    );
+    ecs
+}
+
+fn count(ecs: &ECSWorld, entity_type: EntityType) -> usize {
+    ecs.get_world()
+        .read_storage::<Tracked>()
+        .join()
+        .filter(|tracked| tracked.kind == EntityKind::Living(entity_type))
+        .count()
+}
+
+#[test]
+fn creatures_spawn_on_grass_up_to_their_cap() {
+    let mut ecs = ecs(GRASS);
+    ecs.get_terrain_mut().spawning.creature_cap = 4;
+
+    ecs.tick();
+    let cows = count(&ecs, EntityType::Cow);
+    assert!(cows >= 4);
+
+    for _ in 0..400 {
+        ecs.tick();
+    }
+    assert_eq!(count(&ecs, EntityType::Cow), cows);
+    assert_eq!(count(&ecs, EntityType::Zombie), 0);
+}
+
+#[test]
+fn creatures_need_grass() {
+    let mut ecs = ecs(STONE);
+
+    ecs.tick();
+    assert_eq!(count(&ecs, EntityType::Cow), 0);
+}
+
+#[test]
+fn monsters_spawn_in_the_dark_only() {
+    let mut ecs = ecs(STONE);
+
+    for _ in 0..20 {
+        ecs.tick();
+    }
+    assert_eq!(count(&ecs, EntityType::Zombie), 0);
+
+    let mut terrain = ecs.get_terrain_mut();
+    for x in -128..144 {
+        for z in -128..144 {
+            terrain.set_block(x, 67, z, STONE);
+        }
+    }
+    drop(terrain);
+    for _ in 0..20 {
+        ecs.tick();
+    }
+    assert!(count(&ecs, EntityType::Zombie) > 0);
+}
+
+#[test]
+fn nothing_spawns_when_disabled() {
+    let mut ecs = ecs(GRASS);
+    ecs.get_terrain_mut().spawning.enabled = false;
+
+    for _ in 0..401 {
+        ecs.tick();
+    }
+    assert_eq!(count(&ecs, EntityType::Cow), 0);
+}
+
+#[test]
+fn monsters_far_from_players_despawn() {
+    let mut ecs = ecs(STONE);
+    ecs.get_terrain_mut().spawning.enabled = false;
+    let zombie = ecs
+        .spawn_mob(EntityType::Zombie, Position::new(140.5, 64.0, 140.5))
+        .unwrap();
+    let cow = ecs
+        .spawn_mob(EntityType::Cow, Position::new(140.5, 64.0, 140.5))
+        .unwrap();
+
+    ecs.tick();
+    assert!(!ecs.get_world().is_alive(zombie));
+    assert!(ecs.get_world().is_alive(cow));
+}
@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+
+use entity::ECSWorld;
+use world::world::World as Terrain;
+
+pub const STONE: u16 = 1;
+
+/// Lays a stone floor whose top is at y = 64.
+fn lay_floor(terrain: &mut Terrain) {
+    for x in -16..16 {
+        for z in -16..16 {
+            terrain.set_block(x, 63, z, STONE);
+        }
+    }
+}
+
+/// Terrain with the stone floor.
+pub fn terrain() -> Terrain {
+    let mut terrain = Terrain::default();
+    lay_floor(&mut terrain);
+    terrain
+}
+
+/// Stone floor whose top is at y = 64.
+pub fn ecs() -> ECSWorld<'static, 'static> {
+    let mut ecs = ECSWorld::new();
+    lay_floor(&mut ecs.get_terrain_mut());
+    ecs
+}
@@ -0,0 +1,166 @@
+use common::gamemode::Gamemode;
+use common::metadata::{MetadataValue, Pose};
+use entity::components::{Health, Player, Position, Rotation};
+use entity::metadata::{self, EntityFlag, Metadata};
+use entity::tracker::{EntityKind, TrackerUpdate};
+use entity::{ECSWorld, Entity};
+use uuid::Uuid;
+
+const STEVE: Uuid = Uuid::from_u128(1);
+const ALEX: Uuid = Uuid::from_u128(2);
+
+fn player(ecs: &mut ECSWorld, uuid: Uuid, x: f64) -> Entity {
+    ecs.create_player(
+        Player::new(uuid, "Player", Gamemode::Survival, 0),
+        Position::new(x, 64.0, 0.0),
+        Rotation::default(),
+    )
+}
+
+/// Updates sent to a player on the next tick.
+fn tick(ecs: &mut ECSWorld, viewer: Uuid) -> Vec<TrackerUpdate> {
+    ecs.tick();
+    ecs.take_tracker_updates()
+        .into_iter()
+        .filter(|(uuid, _)| *uuid == viewer)
+        .map(|(_, update)| update)
+        .collect()
+}
+
+fn move_to(ecs: &mut ECSWorld, entity: Entity, x: f64) {
+    ecs.set_component(entity, Position::new(x, 64.0, 0.0));
+}
+
+#[test]
+fn players_in_range_see_each_other() {
+    let mut ecs = ECSWorld::new();
+    let steve = player(&mut ecs, STEVE, 0.0);
+    let alex = player(&mut ecs, ALEX, 10.0);
+    let alex_id = entity::get_network_id(alex);
+
+    assert_eq!(
+        tick(&mut ecs, STEVE),
+        vec![
+            TrackerUpdate::Spawn {
+                id: alex_id,
+                uuid: ALEX,
+                kind: EntityKind::Player,
+                position: Position::new(10.0, 64.0, 0.0),
+                rotation: Rotation::default(),
+                velocity: Default::default(),
+            },
+            TrackerUpdate::HeadLook {
+                id: alex_id,
+                yaw: 0.0
+            },
+            TrackerUpdate::Metadata {
+                id: alex_id,
+                metadata: Metadata::living(Health::PLAYER_MAX).get_entries(),
+            },
+        ]
+    );
+    assert!(tick(&mut ecs, STEVE).is_empty());
+    assert!(ecs
+        .get_component::<entity::tracker::Viewer>(steve)
+        .unwrap()
+        .can_see(alex));
+}
+
+#[test]
+fn small_moves_are_relative_and_large_ones_teleport() {
+    let mut ecs = ECSWorld::new();
+    player(&mut ecs, STEVE, 0.0);
+    let alex = player(&mut ecs, ALEX, 10.0);
+    let alex_id = entity::get_network_id(alex);
+    tick(&mut ecs, STEVE);
+
+    move_to(&mut ecs, alex, 10.5);
+    assert_eq!(
+        tick(&mut ecs, STEVE),
+        vec![TrackerUpdate::Move {
+            id: alex_id,
+            delta: (2048, 0, 0),
+            on_ground: false,
+        }]
+    );
+
+    move_to(&mut ecs, alex, 30.0);
+    assert_eq!(
+        tick(&mut ecs, STEVE),
+        vec![TrackerUpdate::Teleport {
+            id: alex_id,
+            position: Position::new(30.0, 64.0, 0.0),
+            rotation: Rotation::default(),
+            on_ground: false,
+        }]
+    );
+
+    ecs.set_component(alex, Rotation::new(90.0, 0.0));
+    assert_eq!(
+        tick(&mut ecs, STEVE),
+        vec![
+            TrackerUpdate::Rotate {
+                id: alex_id,
+                rotation: Rotation::new(90.0, 0.0),
+                on_ground: false,
+            },
+            TrackerUpdate::HeadLook {
+                id: alex_id,
+                yaw: 90.0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn entities_leaving_range_or_removed_are_destroyed() {
+    let mut ecs = ECSWorld::new();
+    player(&mut ecs, STEVE, 0.0);
+    let alex = player(&mut ecs, ALEX, 10.0);
+    let alex_id = entity::get_network_id(alex);
+    tick(&mut ecs, STEVE);
+
+    move_to(&mut ecs, alex, 100.0);
+    assert_eq!(
+        tick(&mut ecs, STEVE),
+        vec![TrackerUpdate::Destroy(vec![alex_id])]
+    );
+
+    move_to(&mut ecs, alex, 20.0);
+    assert!(matches!(
+        tick(&mut ecs, STEVE)[0],
+        TrackerUpdate::Spawn { id, .. } if id == alex_id
+    ));
+
+    ecs.remove_entity(alex);
+    assert_eq!(
+        tick(&mut ecs, STEVE),
+        vec![TrackerUpdate::Destroy(vec![alex_id])]
+    );
+}
+
+#[test]
+fn only_changed_metadata_is_sent() {
+    let mut ecs = ECSWorld::new();
+    player(&mut ecs, STEVE, 0.0);
+    let alex = player(&mut ecs, ALEX, 10.0);
+    let alex_id = entity::get_network_id(alex);
+    tick(&mut ecs, STEVE);
+
+    let mut alex_metadata = ecs.get_component::<Metadata>(alex).unwrap();
+    alex_metadata.set_flag(EntityFlag::Sneaking, true);
+    alex_metadata.set_pose(Pose::Sneaking);
+    alex_metadata.set_health(Health::PLAYER_MAX);
+    ecs.set_component(alex, alex_metadata);
+    assert_eq!(
+        tick(&mut ecs, STEVE),
+        vec![TrackerUpdate::Metadata {
+            id: alex_id,
+            metadata: vec![
+                (metadata::FLAGS, MetadataValue::Byte(0x02)),
+                (metadata::POSE, MetadataValue::Pose(Pose::Sneaking)),
+            ],
+        }]
+    );
+    assert!(tick(&mut ecs, STEVE).is_empty());
+}
@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use network::buffer::Buffer;
+use network::packet::{Packet, State};
+use network::protocol::ProtocolVersion;
+use network::serverbound::ServerboundPacket;
+
+fuzz_target!(|data: &[u8]| {
+    for version in &[ProtocolVersion::V1_15, ProtocolVersion::V1_16] {
+        let mut buffer = Buffer::new();
+        buffer.extend(data);
+
+        // Decode packets back to back, as a stream would
+        while ServerboundPacket::deserialize(&mut buffer, &State::Login, *version).is_ok() {}
+    }
+});
@@ -0,0 +1,167 @@
+use network::buffer::Buffer;
+use network::capture::{CaptureReader, CaptureRecord, Direction};
+use network::clientbound::ClientboundPacket;
+use network::packet::{Packet, State};
+use network::protocol::find_version;
+use network::serverbound::ServerboundPacket;
+use std::env;
+use std::io;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+const USAGE: &str = "Usage:
+    minecrate-capture print <capture>
+    minecrate-capture replay <capture> <host:port>";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["print", path] => print(path),
+        ["replay", path, address] => replay(path, address),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn print(path: &str) -> io::Result<()> {
+    let mut start = None;
+
+    for record in CaptureReader::open(path)? {
+        let record = record?;
+        let start = *start.get_or_insert(record.timestamp);
+        let elapsed = record.timestamp.saturating_sub(start) as f64 / 1_000_000.0;
+        let arrow = match record.direction {
+            Direction::Inbound => "C->S",
+            Direction::Outbound => "S->C",
+        };
+
+        println!(
+            "[{:>10.3}s] {} {:?} {}",
+            elapsed,
+            arrow,
+            record.state,
+            describe(&record)
+        );
+    }
+    Ok(())
+}
+
+fn describe(record: &CaptureRecord) -> String {
+    let version = match find_version(record.protocol) {
+        Some(version) => version.version,
+        None => return format!("<unsupported protocol {}>", record.protocol),
+    };
+
+    let mut buffer = Buffer::new();
+    buffer.extend(&record.frame);
+
+    let decoded = match record.direction {
+        Direction::Inbound => ServerboundPacket::deserialize(&mut buffer, &record.state, version)
+            .map(|packet| format!("{:?}", packet)),
+        Direction::Outbound => ClientboundPacket::deserialize(&mut buffer, &record.state, version)
+            .map(|packet| format!("{:?}", packet)),
+    };
+
+    match decoded {
+        Ok(packet) => packet,
+        Err(e) => {
+            buffer.reset_cursor();
+            let packet_id = buffer
+                .read_varint()
+                .and_then(|_| buffer.read_varint())
+                .map(|id| format!("0x{:02X}", id))
+                .unwrap_or_else(|_| String::from("?"));
+
+            format!(
+                "<packet {}, {} bytes: {}>",
+                packet_id,
+                record.frame.len(),
+                e
+            )
+        }
+    }
+}
+
+/// Sends the client side of a capture back to a server, keeping the original
+/// timing. Replaying stops at the encryption response, as everything past it
+/// is encrypted with a key the server won't agree on again.
+fn replay(path: &str, address: &str) -> io::Result<()> {
+    let mut stream = TcpStream::connect(address)?;
+    let mut responses = stream.try_clone()?;
+
+    let reader = thread::spawn(move || {
+        let mut total = 0;
+        let mut tmp = [0; 4096];
+
+        while let Ok(count) = responses.read(&mut tmp) {
+            if count == 0 {
+                break;
+            }
+            total += count;
+        }
+        total
+    });
+
+    let mut previous = None;
+    let mut sent = 0;
+
+    for record in CaptureReader::open(path)? {
+        let record = record?;
+        if record.direction != Direction::Inbound {
+            continue;
+        }
+
+        if record.state == State::Login && is_encryption_response(&record) {
+            eprintln!("warning: stopping at the encryption response, encrypted sessions can't be replayed");
+            break;
+        }
+
+        if let Some(previous) = previous {
+            let delay = record.timestamp.saturating_sub(previous);
+            thread::sleep(Duration::from_micros(delay));
+        }
+        previous = Some(record.timestamp);
+
+        stream.write_all(&record.frame)?;
+        sent += 1;
+    }
+
+    // Leave the server some time to answer the last packets
+    thread::sleep(Duration::from_secs(1));
+    stream.shutdown(Shutdown::Both)?;
+
+    let received = reader.join().unwrap_or(0);
+    println!("Replayed {} packets, received {} bytes.", sent, received);
+    Ok(())
+}
+
+fn is_encryption_response(record: &CaptureRecord) -> bool {
+    let version = match find_version(record.protocol) {
+        Some(version) => version.version,
+        None => return false,
+    };
+
+    let mut buffer = Buffer::new();
+    buffer.extend(&record.frame);
+
+    matches!(
+        ServerboundPacket::deserialize(&mut buffer, &record.state, version),
+        Ok(ServerboundPacket::EncryptionResponse(_))
+    )
+}
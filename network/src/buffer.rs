@@ -8,6 +8,14 @@ use uuid::Uuid;
 
 use cgmath::Vector3;
 use common::chat::Chat;
+use common::item::ItemStack;
+use common::metadata::{MetadataValue, Particle, ParticleData, VillagerData};
+use std::convert::TryFrom;
+
+use crate::protocol::ProtocolVersion;
+
+/// The longest string the protocol allows, in characters.
+pub const MAX_STRING_LENGTH: usize = 32767;
 
 #[derive(Default)]
 pub struct Buffer {
@@ -47,6 +55,11 @@ impl Buffer {
         self.cursor
     }
 
+    /// Moves the cursor, e.g. past a payload which isn't read.
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.inner.len());
+    }
+
     pub fn len(&self) -> usize {
         self.inner.len()
     }
@@ -206,19 +219,39 @@ impl Buffer {
 
     #[allow(dead_code)]
     pub fn read_string(&mut self) -> io::Result<String> {
-        let len = self.read_varint()? as usize;
+        self.read_string_max(MAX_STRING_LENGTH)
+    }
+
+    /// Reads a string of at most `max` characters, counted in UTF-16 code
+    /// units as vanilla does.
+    #[allow(dead_code)]
+    pub fn read_string_max(&mut self, max: usize) -> io::Result<String> {
+        let len = self.read_varint()?;
+
+        // A character takes up to 4 bytes, longer strings are rejected before being read
+        if len < 0 || len as usize > max * 4 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("String of {} bytes is longer than {} characters", len, max),
+            ));
+        }
 
+        let len = len as usize;
         if !self.has_at_least(len) {
             Err(io::Error::new(
                 io::ErrorKind::Other,
                 format!("Failed to read a string of length {} from stream", len),
             ))
         } else {
-            let tmp = self.inner[self.cursor..self.cursor + len].to_vec();
-            match str::from_utf8(&tmp) {
+            match str::from_utf8(&self.inner[self.cursor..self.cursor + len]) {
+                Ok(value) if value.encode_utf16().count() > max => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("String is longer than {} characters", max),
+                )),
                 Ok(value) => {
+                    let value = String::from(value);
                     self.cursor += len;
-                    Ok(String::from(value))
+                    Ok(value)
                 }
                 Err(_) => Err(io::Error::new(
                     io::ErrorKind::Other,
@@ -324,12 +357,14 @@ impl Buffer {
         Err(io::Error::new(io::ErrorKind::InvalidData, "Loop ended"))
     }
 
+    /// Positions pack 26 bits of X, then 26 of Z and 12 of Y, the layout
+    /// used since 1.14.
     #[allow(dead_code)]
     pub fn read_position(&mut self) -> io::Result<Vector3<i32>> {
         let value = self.read_ulong()?;
         let mut x = (value >> 38) as i32;
-        let mut y = ((value >> 26) & 0xfff) as i32;
-        let mut z = (value << 38 >> 38) as i32;
+        let mut y = (value & 0xfff) as i32;
+        let mut z = ((value >> 12) & 0x3ffffff) as i32;
 
         if x >= 1 << 25 {
             x -= 1 << 26;
@@ -345,43 +380,11 @@ impl Buffer {
 
     #[allow(dead_code)]
     pub fn write_position(&mut self, value: &Vector3<i32>) -> io::Result<()> {
-        let x = if value.x >= 0 {
-            value.x as u64
-        } else {
-            (value.x + (1 << 26)) as u64
-        };
-
-        let y = if value.y >= 0 {
-            value.y as u64
-        } else {
-            (value.y + (1 << 12)) as u64
-        };
-
-        let z = if value.z >= 0 {
-            value.z as u64
-        } else {
-            (value.z + (1 << 26)) as u64
-        };
+        let x = position_component(value.x, 26, "X is out of range")?;
+        let y = position_component(value.y, 12, "Y is out of range")?;
+        let z = position_component(value.z, 26, "Z is out of range")?;
 
-        if x & (!0x03ff_ffff) != 0 {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "X is out of range",
-            ))
-        } else if y & (!0xfff) != 0 {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Y is out of range",
-            ))
-        } else if z & (!0x03ff_ffff) != 0 {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Z is out of range",
-            ))
-        } else {
-            let encoded = ((x & 0x03ff_ffff) << 38) | ((y & 0xfff) << 26) | (z & 0x03ff_ffff);
-            self.write_ulong(encoded)
-        }
+        self.write_ulong((x << 38) | (z << 12) | y)
     }
 
     #[allow(dead_code)]
@@ -427,24 +430,24 @@ impl Buffer {
     }
 
     #[allow(dead_code)]
-    pub fn write_byte_array(&mut self, vec: &Vec<i8>) -> io::Result<()> {
-        // Create a Vec<u8> out of the backing of Vec<i8>
-        let temp_vec = unsafe {
-            // Safety: This is safe as long than temp_vec isn't used in a mutable way.
-            Vec::from_raw_parts(vec.as_ptr() as *mut u8, vec.len(), vec.capacity())
-        };
-
-        let result = self.write_ubyte_array(&temp_vec);
-
-        // Ensure that we leak the temporary object as we don't want it to detroy the real object
-        std::mem::forget(temp_vec);
-
-        result
+    pub fn write_byte_array(&mut self, slice: &[i8]) -> io::Result<()> {
+        let bytes: Vec<u8> = slice.iter().map(|&value| value as u8).collect();
+        self.write_ubyte_array(&bytes)
     }
 
     #[allow(dead_code)]
     pub fn read_ubyte_array(&mut self, size: usize) -> io::Result<Vec<u8>> {
-        self.read_array(Buffer::read_ubyte, size)
+        // Checked up-front so that a forged size never drives an allocation
+        if !self.has_at_least(size) {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("Failed to read an array of {} bytes from stream", size),
+            ));
+        }
+
+        let value = self.inner[self.cursor..self.cursor + size].to_vec();
+        self.cursor += size;
+        Ok(value)
     }
 
     #[allow(dead_code)]
@@ -452,6 +455,20 @@ impl Buffer {
         self.inner.write_all(slice)
     }
 
+    #[allow(dead_code)]
+    pub fn read_nbt(&mut self) -> io::Result<nbt::Blob> {
+        let mut rdr = Cursor::new(&self.inner[self.cursor..]);
+        let value = nbt::Blob::from_reader(&mut rdr)?;
+        self.cursor += rdr.position() as usize;
+        Ok(value)
+    }
+
+    #[allow(dead_code)]
+    pub fn write_nbt(&mut self, value: &nbt::Blob) -> io::Result<()> {
+        value.to_writer(&mut self.inner)?;
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn read_chat(&mut self) -> io::Result<Chat> {
         match Chat::from_string(&self.read_string()?) {
@@ -467,4 +484,269 @@ impl Buffer {
             Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
         }
     }
+
+    /// Reads an NBT compound, or nothing if it starts with TAG_End.
+    #[allow(dead_code)]
+    pub fn read_optional_nbt(&mut self) -> io::Result<Option<nbt::Blob>> {
+        if self.read_ubyte()? == 0 {
+            return Ok(None);
+        }
+        self.cursor -= 1;
+        self.read_nbt().map(Some)
+    }
+
+    #[allow(dead_code)]
+    pub fn write_optional_nbt(&mut self, value: Option<&nbt::Blob>) -> io::Result<()> {
+        match value {
+            Some(value) => self.write_nbt(value),
+            None => self.write_ubyte(0),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn read_slot(&mut self) -> io::Result<Option<ItemStack>> {
+        if !self.read_bool()? {
+            return Ok(None);
+        }
+
+        Ok(Some(ItemStack {
+            item: self.read_varint()?,
+            count: self.read_byte()?,
+            nbt: self.read_optional_nbt()?,
+        }))
+    }
+
+    #[allow(dead_code)]
+    pub fn write_slot(&mut self, value: Option<&ItemStack>) -> io::Result<()> {
+        match value {
+            Some(stack) => {
+                self.write_bool(true)?;
+                self.write_varint(stack.item)?;
+                self.write_byte(stack.count)?;
+                self.write_optional_nbt(stack.nbt.as_ref())
+            }
+            None => self.write_bool(false),
+        }
+    }
+
+    /// Reads entity metadata up to its end marker, as (index, value) pairs.
+    /// Particles are read with the registry of `version`.
+    #[allow(dead_code)]
+    pub fn read_metadata(
+        &mut self,
+        version: ProtocolVersion,
+    ) -> io::Result<Vec<(u8, MetadataValue)>> {
+        let mut entries = vec![];
+
+        loop {
+            let index = self.read_ubyte()?;
+            if index == METADATA_END {
+                return Ok(entries);
+            }
+
+            let value = self.read_metadata_value(version)?;
+            entries.push((index, value));
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn write_metadata(&mut self, entries: &[(u8, MetadataValue)]) -> io::Result<()> {
+        for (index, value) in entries {
+            if *index == METADATA_END {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Metadata index 255 marks the end",
+                ));
+            }
+            self.write_ubyte(*index)?;
+            self.write_metadata_value(value)?;
+        }
+        self.write_ubyte(METADATA_END)
+    }
+
+    fn read_metadata_value(&mut self, version: ProtocolVersion) -> io::Result<MetadataValue> {
+        let value = match self.read_varint()? {
+            0 => MetadataValue::Byte(self.read_byte()?),
+            1 => MetadataValue::VarInt(self.read_varint()?),
+            2 => MetadataValue::Float(self.read_float()?),
+            3 => MetadataValue::String(self.read_string()?),
+            4 => MetadataValue::Chat(self.read_chat()?),
+            5 => MetadataValue::OptChat(self.read_optional(Buffer::read_chat)?),
+            6 => MetadataValue::Slot(self.read_slot()?),
+            7 => MetadataValue::Boolean(self.read_bool()?),
+            8 => {
+                MetadataValue::Rotation(self.read_float()?, self.read_float()?, self.read_float()?)
+            }
+            9 => MetadataValue::Position(self.read_position()?),
+            10 => MetadataValue::OptPosition(self.read_optional(Buffer::read_position)?),
+            11 => MetadataValue::Direction(read_enum(self.read_varint()?)?),
+            12 => MetadataValue::OptUuid(self.read_optional(Buffer::read_uuid)?),
+            13 => MetadataValue::OptBlockId(match self.read_varint()? {
+                0 => None,
+                id => Some(id),
+            }),
+            14 => MetadataValue::Nbt(self.read_nbt()?),
+            15 => MetadataValue::Particle(self.read_particle(version)?),
+            16 => MetadataValue::VillagerData(VillagerData {
+                villager_type: self.read_varint()?,
+                profession: self.read_varint()?,
+                level: self.read_varint()?,
+            }),
+            17 => MetadataValue::OptVarInt(match self.read_varint()? {
+                0 => None,
+                value => Some(value.wrapping_sub(1)),
+            }),
+            18 => MetadataValue::Pose(read_enum(self.read_varint()?)?),
+            id => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown metadata type {}", id),
+                ))
+            }
+        };
+        Ok(value)
+    }
+
+    fn write_metadata_value(&mut self, value: &MetadataValue) -> io::Result<()> {
+        self.write_varint(value.get_type_id())?;
+
+        match value {
+            MetadataValue::Byte(value) => self.write_byte(*value),
+            MetadataValue::VarInt(value) => self.write_varint(*value),
+            MetadataValue::Float(value) => self.write_float(*value),
+            MetadataValue::String(value) => self.write_string(value),
+            MetadataValue::Chat(value) => self.write_chat(value),
+            MetadataValue::OptChat(value) => {
+                self.write_optional(Buffer::write_chat, value.as_ref())
+            }
+            MetadataValue::Slot(value) => self.write_slot(value.as_ref()),
+            MetadataValue::Boolean(value) => self.write_bool(*value),
+            MetadataValue::Rotation(x, y, z) => {
+                self.write_float(*x)?;
+                self.write_float(*y)?;
+                self.write_float(*z)
+            }
+            MetadataValue::Position(value) => self.write_position(value),
+            MetadataValue::OptPosition(value) => {
+                self.write_optional(Buffer::write_position, value.as_ref())
+            }
+            MetadataValue::Direction(value) => self.write_varint((*value).into()),
+            MetadataValue::OptUuid(value) => {
+                self.write_optional(Buffer::write_uuid, value.as_ref())
+            }
+            MetadataValue::OptBlockId(value) => self.write_varint(value.unwrap_or(0)),
+            MetadataValue::Nbt(value) => self.write_nbt(value),
+            MetadataValue::Particle(value) => self.write_particle(value),
+            MetadataValue::VillagerData(value) => {
+                self.write_varint(value.villager_type)?;
+                self.write_varint(value.profession)?;
+                self.write_varint(value.level)
+            }
+            MetadataValue::OptVarInt(value) => {
+                self.write_varint(value.map_or(0, |value| value.wrapping_add(1)))
+            }
+            MetadataValue::Pose(value) => self.write_varint((*value).into()),
+        }
+    }
+
+    fn read_particle(&mut self, version: ProtocolVersion) -> io::Result<Particle> {
+        let id = self.read_varint()?;
+        let data = match get_particle_data_kind(id, version) {
+            ParticleDataKind::None => ParticleData::None,
+            ParticleDataKind::Block => ParticleData::Block(self.read_varint()?),
+            ParticleDataKind::Dust => ParticleData::Dust {
+                red: self.read_float()?,
+                green: self.read_float()?,
+                blue: self.read_float()?,
+                scale: self.read_float()?,
+            },
+            ParticleDataKind::Item => ParticleData::Item(self.read_slot()?),
+        };
+        Ok(Particle { id, data })
+    }
+
+    fn write_particle(&mut self, particle: &Particle) -> io::Result<()> {
+        self.write_varint(particle.id)?;
+
+        match &particle.data {
+            ParticleData::None => Ok(()),
+            ParticleData::Block(state) => self.write_varint(*state),
+            ParticleData::Dust {
+                red,
+                green,
+                blue,
+                scale,
+            } => {
+                self.write_float(*red)?;
+                self.write_float(*green)?;
+                self.write_float(*blue)?;
+                self.write_float(*scale)
+            }
+            ParticleData::Item(stack) => self.write_slot(stack.as_ref()),
+        }
+    }
+
+    /// Reads a boolean, then the value if it is true.
+    fn read_optional<F, T>(&mut self, f: F) -> io::Result<Option<T>>
+    where
+        F: Fn(&mut Buffer) -> io::Result<T>,
+    {
+        if self.read_bool()? {
+            f(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn write_optional<F, T>(&mut self, f: F, value: Option<&T>) -> io::Result<()>
+    where
+        F: Fn(&mut Buffer, &T) -> io::Result<()>,
+    {
+        self.write_bool(value.is_some())?;
+        match value {
+            Some(value) => f(self, value),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Index ending a list of metadata.
+const METADATA_END: u8 = 0xFF;
+
+enum ParticleDataKind {
+    None,
+    Block,
+    Dust,
+    Item,
+}
+
+/// Layout of the data of a particle, whose ids changed in 1.16.
+fn get_particle_data_kind(id: i32, version: ProtocolVersion) -> ParticleDataKind {
+    let item = match version {
+        ProtocolVersion::V1_15 => 32,
+        ProtocolVersion::V1_16 => 34,
+    };
+
+    match id {
+        3 | 23 => ParticleDataKind::Block,
+        14 => ParticleDataKind::Dust,
+        id if id == item => ParticleDataKind::Item,
+        _ => ParticleDataKind::None,
+    }
+}
+
+fn read_enum<T: TryFrom<i32, Error = &'static str>>(value: i32) -> io::Result<T> {
+    T::try_from(value).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Encodes a signed position component as two's complement on `bits` bits.
+fn position_component(value: i32, bits: u32, error: &'static str) -> io::Result<u64> {
+    let bound = 1i64 << (bits - 1);
+    let value = i64::from(value);
+
+    if value < -bound || value >= bound {
+        Err(io::Error::new(io::ErrorKind::InvalidData, error))
+    } else {
+        Ok(value as u64 & ((1 << bits) - 1))
+    }
 }
@@ -0,0 +1,169 @@
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::packet::State;
+
+const MAGIC: &[u8; 4] = b"MCAP";
+const FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl From<Direction> for u8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Inbound => 0,
+            Direction::Outbound => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Direction::Inbound),
+            1 => Ok(Direction::Outbound),
+            _ => Err("Unknown Direction"),
+        }
+    }
+}
+
+/// A single decrypted frame, including its length prefix, as it went through
+/// the connection.
+#[derive(Debug)]
+pub struct CaptureRecord {
+    /// Microseconds since the UNIX epoch.
+    pub timestamp: u64,
+    pub direction: Direction,
+    pub state: State,
+    pub protocol: i32,
+    pub frame: Vec<u8>,
+}
+
+/// Records the frames of one connection to a capture file.
+///
+/// The file starts with the `MCAP` magic and a format version, followed by
+/// records laid out as: timestamp (u64), direction (u8), state (u8),
+/// protocol (i32), frame length (u32) and the frame itself, all big-endian.
+pub struct CaptureWriter {
+    out: BufWriter<File>,
+}
+
+impl CaptureWriter {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+
+        out.write_all(MAGIC)?;
+        out.write_u8(FORMAT_VERSION)?;
+        Ok(Self { out })
+    }
+
+    pub fn record(
+        &mut self,
+        direction: Direction,
+        state: State,
+        protocol: i32,
+        frame: &[u8],
+    ) -> io::Result<()> {
+        let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_micros() as u64,
+            Err(_) => 0,
+        };
+
+        self.out.write_u64::<BigEndian>(timestamp)?;
+        self.out.write_u8(u8::from(direction))?;
+        self.out.write_u8(u8::from(state))?;
+        self.out.write_i32::<BigEndian>(protocol)?;
+        self.out.write_u32::<BigEndian>(frame.len() as u32)?;
+        self.out.write_all(frame)?;
+        self.out.flush()
+    }
+}
+
+pub struct CaptureReader<R: Read> {
+    input: R,
+}
+
+impl CaptureReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut input: R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        input.read_exact(&mut magic)?;
+
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Not a capture file",
+            ));
+        }
+
+        let format_version = input.read_u8()?;
+        if format_version != FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported capture format version {}", format_version),
+            ));
+        }
+
+        Ok(Self { input })
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<CaptureRecord>> {
+        let timestamp = match self.input.read_u64::<BigEndian>() {
+            Ok(timestamp) => timestamp,
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let direction = Direction::try_from(self.input.read_u8()?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let state = State::try_from(self.input.read_u8()?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let protocol = self.input.read_i32::<BigEndian>()?;
+        let frame_len = self.input.read_u32::<BigEndian>()?;
+
+        let mut frame = vec![];
+        self.input
+            .by_ref()
+            .take(u64::from(frame_len))
+            .read_to_end(&mut frame)?;
+
+        if frame.len() != frame_len as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Truncated capture record",
+            ));
+        }
+
+        Ok(Some(CaptureRecord {
+            timestamp,
+            direction,
+            state,
+            protocol,
+            frame,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = io::Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
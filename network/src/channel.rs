@@ -0,0 +1,98 @@
+use std::io;
+
+use crate::buffer::Buffer;
+
+pub const BRAND_CHANNEL: &str = "minecraft:brand";
+pub const REGISTER_CHANNEL: &str = "minecraft:register";
+pub const UNREGISTER_CHANNEL: &str = "minecraft:unregister";
+/// Channels a client can register at once, like vanilla allows.
+pub const MAX_CHANNELS: usize = 128;
+
+/// A message exchanged over a named plugin channel.
+pub trait ChannelMessage: Sized {
+    const CHANNEL: &'static str;
+
+    fn decode(data: &[u8]) -> io::Result<Self>;
+    fn encode(&self) -> io::Result<Vec<u8>>;
+}
+
+#[derive(Debug)]
+pub struct BrandMessage {
+    pub brand: String,
+}
+
+impl ChannelMessage for BrandMessage {
+    const CHANNEL: &'static str = BRAND_CHANNEL;
+
+    fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut buffer = Buffer::new();
+        buffer.extend(data);
+
+        Ok(Self {
+            brand: buffer.read_string()?,
+        })
+    }
+
+    fn encode(&self) -> io::Result<Vec<u8>> {
+        let mut buffer = Buffer::new();
+        buffer.write_string(&self.brand)?;
+        Ok(buffer.as_raw().to_vec())
+    }
+}
+
+#[derive(Debug)]
+pub struct RegisterMessage {
+    pub channels: Vec<String>,
+}
+
+impl ChannelMessage for RegisterMessage {
+    const CHANNEL: &'static str = REGISTER_CHANNEL;
+
+    fn decode(data: &[u8]) -> io::Result<Self> {
+        Ok(Self {
+            channels: decode_channel_list(data)?,
+        })
+    }
+
+    fn encode(&self) -> io::Result<Vec<u8>> {
+        Ok(encode_channel_list(&self.channels))
+    }
+}
+
+#[derive(Debug)]
+pub struct UnregisterMessage {
+    pub channels: Vec<String>,
+}
+
+impl ChannelMessage for UnregisterMessage {
+    const CHANNEL: &'static str = UNREGISTER_CHANNEL;
+
+    fn decode(data: &[u8]) -> io::Result<Self> {
+        Ok(Self {
+            channels: decode_channel_list(data)?,
+        })
+    }
+
+    fn encode(&self) -> io::Result<Vec<u8>> {
+        Ok(encode_channel_list(&self.channels))
+    }
+}
+
+/// Channel lists are sent as NUL-separated names, without any length prefix.
+fn decode_channel_list(data: &[u8]) -> io::Result<Vec<String>> {
+    match std::str::from_utf8(data) {
+        Ok(value) => Ok(value
+            .split('\0')
+            .filter(|channel| !channel.is_empty())
+            .map(String::from)
+            .collect()),
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Failed to read an UTF-8 channel list",
+        )),
+    }
+}
+
+fn encode_channel_list(channels: &[String]) -> Vec<u8> {
+    channels.join("\0").into_bytes()
+}
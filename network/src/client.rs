@@ -0,0 +1,376 @@
+use common::chat::Chat;
+use log::debug;
+use openssl::rsa::Rsa;
+use openssl::symm;
+use rand::thread_rng;
+use rand::Rng;
+use std::io;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::buffer::Buffer;
+use crate::clientbound::login::EncryptionRequestPacket;
+use crate::clientbound::status::StatusResponsePayload;
+use crate::clientbound::ClientboundPacket;
+use crate::mojang;
+use crate::packet::{Packet, State, UnknownPacket};
+use crate::protocol::{latest_version, SupportedVersion};
+use crate::serverbound;
+use crate::serverbound::ServerboundPacket;
+use crate::utils::rsa_encrypt;
+
+/// A Mojang account used to join online-mode servers.
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub access_token: String,
+    pub profile_id: Uuid,
+}
+
+/// The client side of a connection, as a vanilla client would drive it.
+///
+/// Unlike `Stream`, reads are blocking (up to the configured read timeout),
+/// and play packets unknown to the packet tables are skipped instead of
+/// failing the connection.
+pub struct Client {
+    handle: TcpStream,
+    buf: Buffer,
+    state: State,
+    version: &'static SupportedVersion,
+    host: String,
+    port: u16,
+    in_cipher: Option<symm::Crypter>,
+    out_cipher: Option<symm::Crypter>,
+    username: String,
+    uuid: Uuid,
+}
+
+impl Client {
+    pub fn connect(host: &str, port: u16) -> io::Result<Self> {
+        let address = match (host, port).to_socket_addrs()?.next() {
+            Some(address) => address,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Failed to resolve {}", host),
+                ))
+            }
+        };
+
+        let handle = TcpStream::connect(address)?;
+        handle.set_nodelay(true)?;
+
+        Ok(Self {
+            handle,
+            buf: Buffer::new(),
+            state: State::Handshake,
+            version: latest_version(),
+            host: host.to_string(),
+            port,
+            in_cipher: None,
+            out_cipher: None,
+            username: String::new(),
+            uuid: Uuid::nil(),
+        })
+    }
+
+    /// Queries the server list ping, returning the response and the ping
+    /// round-trip time. The connection can't be used afterwards.
+    pub fn status(&mut self) -> io::Result<(StatusResponsePayload, Duration)> {
+        self.handshake(State::Status)?;
+        self.send_packet(&serverbound::status::StatusRequestPacket {}.into())?;
+
+        let payload = match self.read_packet()? {
+            ClientboundPacket::StatusResponse(response) => response.payload,
+            packet => return Err(unexpected(&packet)),
+        };
+
+        let sent = Instant::now();
+        let challenge = thread_rng().gen::<i64>();
+        self.send_packet(&serverbound::status::PingPacket { payload: challenge }.into())?;
+
+        match self.read_packet()? {
+            ClientboundPacket::Pong(ref pong) if pong.payload == challenge => {
+                Ok((payload, sent.elapsed()))
+            }
+            packet => Err(unexpected(&packet)),
+        }
+    }
+
+    /// Logs in and returns once the server switched to the play state. An
+    /// account is required to join online-mode servers.
+    pub fn login(&mut self, username: &str, account: Option<&Account>) -> io::Result<()> {
+        self.handshake(State::Login)?;
+        self.send_packet(
+            &serverbound::login::LoginStartPacket {
+                username: username.to_string(),
+            }
+            .into(),
+        )?;
+
+        loop {
+            match self.read_packet()? {
+                ClientboundPacket::EncryptionRequest(ref request) => match account {
+                    Some(account) => self.encrypt(request, account)?,
+                    None => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::PermissionDenied,
+                            "Server is in online mode, an account is required",
+                        ))
+                    }
+                },
+                ClientboundPacket::LoginPluginRequest(ref request) => {
+                    // Login queries are only understood by modded clients
+                    self.send_packet(
+                        &serverbound::login::LoginPluginResponsePacket {
+                            message_id: request.message_id,
+                            successful: false,
+                            data: vec![],
+                        }
+                        .into(),
+                    )?;
+                }
+                ClientboundPacket::LoginSuccess(success) => {
+                    self.username = success.username;
+                    self.uuid = success.uuid;
+                    self.state = State::Play;
+                    return Ok(());
+                }
+                ClientboundPacket::Disconnect(ref disconnect) => {
+                    return Err(disconnected(&disconnect.reason))
+                }
+                packet => return Err(unexpected(&packet)),
+            }
+        }
+    }
+
+    /// Reads the next packet, blocking until one is available or the read
+    /// timeout expires.
+    ///
+    /// While playing, keep-alives and teleports are answered before the
+    /// packet is returned, and a disconnect is turned into an error.
+    pub fn read_packet(&mut self) -> io::Result<ClientboundPacket> {
+        loop {
+            let mut frame = self.read_frame()?;
+
+            match ClientboundPacket::deserialize(&mut frame, &self.state, self.version.version) {
+                Ok(packet) => {
+                    self.handle_packet(&packet)?;
+                    return Ok(packet);
+                }
+                Err(ref e)
+                    if UnknownPacket::from_error(e).is_some() && self.state == State::Play =>
+                {
+                    debug!("Skipping unsupported packet: {:?}.", frame);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn send_packet(&mut self, packet: &ServerboundPacket) -> io::Result<()> {
+        let packet_id = match packet.get_id(self.version.version) {
+            Some(packet_id) => packet_id,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{:?} does not exist in {}", packet, self.version.name),
+                ))
+            }
+        };
+
+        let mut out_buf = Buffer::new();
+        out_buf.write_varint(packet_id)?;
+        packet.serialize(&mut out_buf, self.version.version)?;
+
+        let mut final_out_buf = Buffer::new();
+        final_out_buf.write_varint(out_buf.len() as i32)?;
+        final_out_buf.extend(out_buf.as_raw());
+
+        match &mut self.out_cipher {
+            Some(cipher) => {
+                let mut tmp = vec![0; final_out_buf.len() + 16];
+                let n = cipher.update(final_out_buf.as_raw(), &mut tmp)?;
+                self.handle.write_all(&tmp[..n])
+            }
+            None => self.handle.write_all(final_out_buf.as_raw()),
+        }
+    }
+
+    pub fn send_chat(&mut self, message: &str) -> io::Result<()> {
+        self.send_packet(
+            &serverbound::play::ChatMessagePacket {
+                message: message.to_string(),
+            }
+            .into(),
+        )
+    }
+
+    pub fn disconnect(&mut self) -> io::Result<()> {
+        self.handle.shutdown(Shutdown::Both)
+    }
+
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.handle.set_read_timeout(timeout)
+    }
+
+    pub fn set_version(&mut self, version: &'static SupportedVersion) {
+        self.version = version;
+    }
+
+    pub fn get_version(&self) -> &'static SupportedVersion {
+        self.version
+    }
+
+    pub fn get_state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn get_username(&self) -> &String {
+        &self.username
+    }
+
+    pub fn get_uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    fn handshake(&mut self, next: State) -> io::Result<()> {
+        let packet = serverbound::handshake::HandshakePacket {
+            protocol: self.version.protocol,
+            address: self.host.clone(),
+            port: self.port,
+            next: i32::from(u8::from(next)),
+        };
+
+        self.send_packet(&packet.into())?;
+        self.state = next;
+        Ok(())
+    }
+
+    fn encrypt(&mut self, request: &EncryptionRequestPacket, account: &Account) -> io::Result<()> {
+        let shared_secret = thread_rng().gen::<[u8; 16]>();
+        let key = Rsa::public_key_from_der(&request.public_key)?;
+
+        mojang::join(
+            &account.access_token,
+            &account.profile_id,
+            request.server_id.as_bytes(),
+            &shared_secret,
+            &request.public_key,
+        )?;
+
+        self.send_packet(
+            &serverbound::login::EncryptionResponsePacket {
+                shared_secret: rsa_encrypt(&key, &shared_secret)?,
+                verify_token: rsa_encrypt(&key, &request.verify_token)?,
+            }
+            .into(),
+        )?;
+        self.set_encryption_key(&shared_secret)
+    }
+
+    fn set_encryption_key(&mut self, key: &[u8]) -> io::Result<()> {
+        let cipher = symm::Cipher::aes_128_cfb8();
+
+        self.in_cipher = Some(symm::Crypter::new(
+            cipher,
+            symm::Mode::Decrypt,
+            key,
+            Some(key),
+        )?);
+        self.out_cipher = Some(symm::Crypter::new(
+            cipher,
+            symm::Mode::Encrypt,
+            key,
+            Some(key),
+        )?);
+        Ok(())
+    }
+
+    fn handle_packet(&mut self, packet: &ClientboundPacket) -> io::Result<()> {
+        match packet {
+            ClientboundPacket::KeepAlive(ref keep_alive) => {
+                self.send_packet(&serverbound::play::KeepAlivePacket { id: keep_alive.id }.into())
+            }
+            ClientboundPacket::PlayerPositionAndLook(ref position) => self.send_packet(
+                &serverbound::play::TeleportConfirmPacket {
+                    teleport_id: position.teleport_id,
+                }
+                .into(),
+            ),
+            ClientboundPacket::DisconnectPlay(ref disconnect) => {
+                Err(disconnected(&disconnect.reason))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the next complete frame, including its length prefix.
+    fn read_frame(&mut self) -> io::Result<Buffer> {
+        loop {
+            self.buf.reset_cursor();
+
+            match self.buf.read_varint() {
+                Ok(len) if len < 0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Negative packet length",
+                    ))
+                }
+                Ok(len) if self.buf.has_at_least(len as usize) => {
+                    let end = self.buf.cursor() + len as usize;
+                    let raw: Vec<u8> = self.buf.drain(0..end).collect();
+
+                    let mut frame = Buffer::new();
+                    frame.extend(&raw);
+                    return Ok(frame);
+                }
+                Ok(_) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+                Err(e) => return Err(e),
+            }
+
+            self.read()?;
+        }
+    }
+
+    fn read(&mut self) -> io::Result<()> {
+        let mut tmp = [0; 4096];
+        let count = self.handle.read(&mut tmp)?;
+
+        if count == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "Connection closed by the server",
+            ));
+        }
+
+        match &mut self.in_cipher {
+            Some(cipher) => {
+                let mut decrypted = vec![0; count + 16];
+                let n = cipher.update(&tmp[..count], &mut decrypted)?;
+                self.buf.extend(&decrypted[..n]);
+            }
+            None => self.buf.extend(&tmp[..count]),
+        }
+        Ok(())
+    }
+}
+
+fn unexpected(packet: &ClientboundPacket) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Unexpected packet: {:?}", packet),
+    )
+}
+
+fn disconnected(reason: &Chat) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ConnectionAborted,
+        format!(
+            "Disconnected: {}",
+            reason.to_string().unwrap_or_else(|_| String::from("?"))
+        ),
+    )
+}
@@ -1,13 +1,13 @@
 use common::chat::Chat;
-use std::io;
 use uuid::Uuid;
 
-use crate::buffer::Buffer;
 use crate::clientbound::ClientboundPacket;
 
-#[derive(Debug)]
-pub struct DisconnectPacket {
-    reason: Chat,
+packet_payload! {
+    #[derive(Debug)]
+    pub struct DisconnectPacket {
+        pub reason: Chat => Chat,
+    }
 }
 
 impl DisconnectPacket {
@@ -15,26 +15,15 @@ impl DisconnectPacket {
     pub fn new(reason: Chat) -> ClientboundPacket {
         ClientboundPacket::Disconnect(DisconnectPacket { reason })
     }
-
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
-        Ok(ClientboundPacket::Disconnect(DisconnectPacket {
-            reason: buffer.read_chat()?,
-        }))
-    }
-
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        buffer.write_chat(&self.reason)?;
-        Ok(())
-    }
 }
 
-#[derive(Debug)]
-pub struct EncryptionRequestPacket {
-    server_id: String,
-    public_key_length: i32,
-    public_key: Vec<u8>,
-    verify_token_length: i32,
-    verify_token: Vec<u8>,
+packet_payload! {
+    #[derive(Debug)]
+    pub struct EncryptionRequestPacket {
+        pub server_id: String => Str,
+        pub public_key: Vec<u8> => Bytes,
+        pub verify_token: Vec<u8> => Bytes,
+    }
 }
 
 impl EncryptionRequestPacket {
@@ -42,45 +31,18 @@ impl EncryptionRequestPacket {
     pub fn new(server_id: &str, public_key: &[u8], verify_token: &[u8]) -> ClientboundPacket {
         ClientboundPacket::EncryptionRequest(EncryptionRequestPacket {
             server_id: server_id.to_string(),
-            public_key_length: public_key.len() as i32,
             public_key: public_key.to_vec(),
-            verify_token_length: verify_token.len() as i32,
             verify_token: verify_token.to_vec(),
         })
     }
-
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
-        let server_id = buffer.read_string()?;
-        let public_key_length = buffer.read_varint()?;
-        let public_key = buffer.read_ubyte_array(public_key_length as usize)?;
-        let verify_token_length = buffer.read_varint()?;
-        let verify_token = buffer.read_ubyte_array(verify_token_length as usize)?;
-
-        Ok(ClientboundPacket::EncryptionRequest(
-            EncryptionRequestPacket {
-                server_id,
-                public_key_length,
-                public_key,
-                verify_token_length,
-                verify_token,
-            },
-        ))
-    }
-
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        buffer.write_string(&self.server_id)?;
-        buffer.write_varint(self.public_key_length)?;
-        buffer.write_ubyte_array(&self.public_key)?;
-        buffer.write_varint(self.verify_token_length)?;
-        buffer.write_ubyte_array(&self.verify_token)?;
-        Ok(())
-    }
 }
 
-#[derive(Debug)]
-pub struct LoginSuccessPacket {
-    uuid: Uuid,
-    username: String,
+packet_payload! {
+    #[derive(Debug)]
+    pub struct LoginSuccessPacket {
+        pub uuid: Uuid => LegacyUuidString,
+        pub username: String => Str,
+    }
 }
 
 impl LoginSuccessPacket {
@@ -91,24 +53,24 @@ impl LoginSuccessPacket {
             username: username.to_string(),
         })
     }
+}
 
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
-        match Uuid::parse_str(&buffer.read_string()?) {
-            Ok(uuid) => Ok(ClientboundPacket::LoginSuccess(LoginSuccessPacket {
-                uuid,
-                username: buffer.read_string()?,
-            })),
-            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
-        }
+packet_payload! {
+    #[derive(Debug)]
+    pub struct LoginPluginRequestPacket {
+        pub message_id: i32 => VarInt,
+        pub channel: String => Str,
+        pub data: Vec<u8> => RemainingBytes,
     }
+}
 
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        buffer.write_string(
-            self.uuid
-                .to_hyphenated()
-                .encode_lower(&mut Uuid::encode_buffer()),
-        )?;
-        buffer.write_string(&self.username)?;
-        Ok(())
+impl LoginPluginRequestPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(message_id: i32, channel: &str, data: &[u8]) -> ClientboundPacket {
+        ClientboundPacket::LoginPluginRequest(LoginPluginRequestPacket {
+            message_id,
+            channel: channel.to_string(),
+            data: data.to_vec(),
+        })
     }
 }
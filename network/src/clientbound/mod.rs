@@ -1,127 +1,55 @@
-use std::io;
-
 pub mod login;
 pub mod play;
 pub mod status;
 
-use crate::buffer::Buffer;
-use crate::packet::{Packet, State};
-
-#[derive(Debug)]
-#[allow(clippy::large_enum_variant)]
-pub enum ClientboundPacket {
-    // Play
-    ServerDifficulty(play::ServerDifficultyPacket),
-    PluginMessage(play::PluginMessagePacket),
-    DisconnectPlay(play::DisconnectPlayPacket),
-    JoinGame(play::JoinGamePacket),
-    PlayerAbilities(play::PlayerAbilitiesPacket),
-
-    // Status
-    StatusResponse(status::StatusResponsePacket),
-    Pong(status::PongPacket),
-
-    // Login
-    Disconnect(login::DisconnectPacket),
-    EncryptionRequest(login::EncryptionRequestPacket),
-    LoginSuccess(login::LoginSuccessPacket),
-}
-
-impl Packet for ClientboundPacket {
-    fn deserialize(buffer: &mut Buffer, state: &State) -> io::Result<Self> {
-        let packet_len = buffer.read_varint()?;
-
-        if buffer.has_at_least(packet_len as usize) {
-            let cursor_before = buffer.cursor();
-            let packet_id = buffer.read_varint()?;
-            let payload_size = (packet_len as usize) - (buffer.cursor() - cursor_before);
-
-            match *state {
-                State::Handshake => match packet_id {
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-                State::Play => match packet_id {
-                    0x0D => play::ServerDifficultyPacket::deserialize(buffer),
-                    0x19 => play::PluginMessagePacket::deserialize(buffer, payload_size),
-                    0x1B => play::DisconnectPlayPacket::deserialize(buffer),
-                    0x26 => play::JoinGamePacket::deserialize(buffer),
-                    0x32 => play::PlayerAbilitiesPacket::deserialize(buffer),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-                State::Status => match packet_id {
-                    0x0 => status::StatusResponsePacket::deserialize(buffer),
-                    0x1 => status::PongPacket::deserialize(buffer),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-                State::Login => match packet_id {
-                    0x0 => login::DisconnectPacket::deserialize(buffer),
-                    0x1 => login::EncryptionRequestPacket::deserialize(buffer),
-                    0x2 => login::LoginSuccessPacket::deserialize(buffer),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-            }
-        } else {
-            Err(io::Error::from(io::ErrorKind::WouldBlock))
+packet_enum! {
+    #[derive(Debug)]
+    #[allow(clippy::large_enum_variant)]
+    pub enum ClientboundPacket {
+        Play {
+            SpawnEntity(play::SpawnEntityPacket) { V1_15 => 0x00, V1_16 => 0x00 },
+            SpawnLivingEntity(play::SpawnLivingEntityPacket) { V1_15 => 0x03, V1_16 => 0x02 },
+            SpawnPlayer(play::SpawnPlayerPacket) { V1_15 => 0x05, V1_16 => 0x04 },
+            EntityAnimation(play::EntityAnimationPacket) { V1_15 => 0x06, V1_16 => 0x05 },
+            BlockChange(play::BlockChangePacket) { V1_15 => 0x0C, V1_16 => 0x0B },
+            ServerDifficulty(play::ServerDifficultyPacket) { V1_15 => 0x0E, V1_16 => 0x0D },
+            ChatMessage(play::ChatMessagePacket) { V1_15 => 0x0F, V1_16 => 0x0E },
+            WindowConfirmation(play::WindowConfirmationPacket) { V1_15 => 0x13, V1_16 => 0x12 },
+            WindowItems(play::WindowItemsPacket) { V1_15 => 0x15, V1_16 => 0x14 },
+            SetSlot(play::SetSlotPacket) { V1_15 => 0x17, V1_16 => 0x16 },
+            PluginMessage(play::PluginMessagePacket) { V1_15 => 0x19, V1_16 => 0x18 },
+            DisconnectPlay(play::DisconnectPlayPacket) { V1_15 => 0x1B, V1_16 => 0x1A },
+            EntityStatus(play::EntityStatusPacket) { V1_15 => 0x1C, V1_16 => 0x1B },
+            ChangeGameState(play::ChangeGameStatePacket) { V1_15 => 0x1F, V1_16 => 0x1E },
+            KeepAlive(play::KeepAlivePacket) { V1_15 => 0x21, V1_16 => 0x20 },
+            JoinGame(play::JoinGamePacket) { V1_15 => 0x26, V1_16 => 0x25 },
+            EntityPosition(play::EntityPositionPacket) { V1_15 => 0x29, V1_16 => 0x28 },
+            EntityPositionAndRotation(play::EntityPositionAndRotationPacket) { V1_15 => 0x2A, V1_16 => 0x29 },
+            EntityRotation(play::EntityRotationPacket) { V1_15 => 0x2B, V1_16 => 0x2A },
+            PlayerAbilities(play::PlayerAbilitiesPacket) { V1_15 => 0x32, V1_16 => 0x31 },
+            CombatEvent(play::CombatEventPacket) { V1_15 => 0x33, V1_16 => 0x32 },
+            PlayerInfo(play::PlayerInfoPacket) { V1_15 => 0x34, V1_16 => 0x33 },
+            PlayerPositionAndLook(play::PlayerPositionAndLookPacket) { V1_15 => 0x36, V1_16 => 0x35 },
+            DestroyEntities(play::DestroyEntitiesPacket) { V1_15 => 0x38, V1_16 => 0x37 },
+            Respawn(play::RespawnPacket) { V1_15 => 0x3B, V1_16 => 0x3A },
+            EntityHeadLook(play::EntityHeadLookPacket) { V1_15 => 0x3C, V1_16 => 0x3B },
+            HeldItemChange(play::HeldItemChangePacket) { V1_15 => 0x40, V1_16 => 0x3F },
+            EntityMetadata(play::EntityMetadataPacket) { V1_15 => 0x44, V1_16 => 0x44 },
+            EntityVelocity(play::EntityVelocityPacket) { V1_15 => 0x46, V1_16 => 0x46 },
+            UpdateHealth(play::UpdateHealthPacket) { V1_15 => 0x49, V1_16 => 0x49 },
+            PlayerListHeaderAndFooter(play::PlayerListHeaderAndFooterPacket) { V1_15 => 0x54, V1_16 => 0x53 },
+            CollectItem(play::CollectItemPacket) { V1_15 => 0x56, V1_16 => 0x55 },
+            EntityTeleport(play::EntityTeleportPacket) { V1_15 => 0x57, V1_16 => 0x56 },
         }
-    }
-
-    fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        match *self {
-            // Play
-            ClientboundPacket::ServerDifficulty(ref x) => x.serialize(buffer),
-            ClientboundPacket::PluginMessage(ref x) => x.serialize(buffer),
-            ClientboundPacket::DisconnectPlay(ref x) => x.serialize(buffer),
-            ClientboundPacket::JoinGame(ref x) => x.serialize(buffer),
-            ClientboundPacket::PlayerAbilities(ref x) => x.serialize(buffer),
-
-            // Status
-            ClientboundPacket::StatusResponse(ref x) => x.serialize(buffer),
-            ClientboundPacket::Pong(ref x) => x.serialize(buffer),
-
-            // Login
-            ClientboundPacket::Disconnect(ref x) => x.serialize(buffer),
-            ClientboundPacket::EncryptionRequest(ref x) => x.serialize(buffer),
-            ClientboundPacket::LoginSuccess(ref x) => x.serialize(buffer),
-        }
-    }
-
-    fn get_id(&self) -> i32 {
-        match *self {
-            // Play
-            ClientboundPacket::ServerDifficulty(_) => 0x0D,
-            ClientboundPacket::PluginMessage(_) => 0x19,
-            ClientboundPacket::DisconnectPlay(_) => 0x1B,
-            ClientboundPacket::JoinGame(_) => 0x26,
-            ClientboundPacket::PlayerAbilities(_) => 0x32,
-
-            // Status
-            ClientboundPacket::StatusResponse(_) => 0x0,
-            ClientboundPacket::Pong(_) => 0x1,
-
-            // Login
-            ClientboundPacket::Disconnect(_) => 0x0,
-            ClientboundPacket::EncryptionRequest(_) => 0x1,
-            ClientboundPacket::LoginSuccess(_) => 0x2,
+        Status {
+            StatusResponse(status::StatusResponsePacket) { V1_15 => 0x0, V1_16 => 0x0 },
+            Pong(status::PongPacket) { V1_15 => 0x1, V1_16 => 0x1 },
         }
-    }
-
-    fn get_state(&self) -> State {
-        match *self {
-            // Play
-            ClientboundPacket::ServerDifficulty(_) => State::Play,
-            ClientboundPacket::PluginMessage(_) => State::Play,
-            ClientboundPacket::DisconnectPlay(_) => State::Play,
-            ClientboundPacket::JoinGame(_) => State::Play,
-            ClientboundPacket::PlayerAbilities(_) => State::Play,
-
-            // Status
-            ClientboundPacket::StatusResponse(_) => State::Status,
-            ClientboundPacket::Pong(_) => State::Status,
-
-            // Login
-            ClientboundPacket::Disconnect(_) => State::Login,
-            ClientboundPacket::EncryptionRequest(_) => State::Login,
-            ClientboundPacket::LoginSuccess(_) => State::Login,
+        Login {
+            Disconnect(login::DisconnectPacket) { V1_15 => 0x0, V1_16 => 0x0 },
+            EncryptionRequest(login::EncryptionRequestPacket) { V1_15 => 0x1, V1_16 => 0x1 },
+            LoginSuccess(login::LoginSuccessPacket) { V1_15 => 0x2, V1_16 => 0x2 },
+            LoginPluginRequest(login::LoginPluginRequestPacket) { V1_15 => 0x4, V1_16 => 0x4 },
         }
     }
 }
@@ -0,0 +1,19 @@
+use cgmath::Vector3;
+
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Replaces a single block, `block_id` being the new block state.
+    #[derive(Debug)]
+    pub struct BlockChangePacket {
+        pub location: Vector3<i32> => Position,
+        pub block_id: i32 => VarInt,
+    }
+}
+
+impl BlockChangePacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(location: Vector3<i32>, block_id: i32) -> ClientboundPacket {
+        ClientboundPacket::BlockChange(BlockChangePacket { location, block_id })
+    }
+}
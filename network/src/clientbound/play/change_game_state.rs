@@ -0,0 +1,19 @@
+use crate::clientbound::ClientboundPacket;
+
+/// Reason of a Change Game State telling the player its new gamemode.
+pub const CHANGE_GAMEMODE: u8 = 3;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct ChangeGameStatePacket {
+        pub reason: u8 => UByte,
+        pub value: f32 => Float,
+    }
+}
+
+impl ChangeGameStatePacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(reason: u8, value: f32) -> ClientboundPacket {
+        ClientboundPacket::ChangeGameState(ChangeGameStatePacket { reason, value })
+    }
+}
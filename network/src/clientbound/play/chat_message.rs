@@ -0,0 +1,61 @@
+use common::chat::{Chat, ChatMessageType};
+use std::convert::TryFrom;
+use std::io;
+use uuid::Uuid;
+
+use crate::buffer::Buffer;
+use crate::clientbound::ClientboundPacket;
+use crate::packet::PacketPayload;
+use crate::protocol::ProtocolVersion;
+
+#[derive(Debug)]
+pub struct ChatMessagePacket {
+    pub message: Chat,
+    pub position: ChatMessageType,
+    pub sender: Uuid,
+}
+
+impl ChatMessagePacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(message: Chat, position: ChatMessageType, sender: Uuid) -> ClientboundPacket {
+        ClientboundPacket::ChatMessage(ChatMessagePacket {
+            message,
+            position,
+            sender,
+        })
+    }
+}
+
+impl PacketPayload for ChatMessagePacket {
+    fn deserialize(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Self> {
+        let message = buffer.read_chat()?;
+        let position = ChatMessageType::try_from(buffer.read_ubyte()?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // The sender was only added in 1.16
+        let sender = match version {
+            ProtocolVersion::V1_15 => Uuid::nil(),
+            _ => buffer.read_uuid()?,
+        };
+
+        Ok(ChatMessagePacket {
+            message,
+            position,
+            sender,
+        })
+    }
+
+    fn serialize(&self, buffer: &mut Buffer, version: ProtocolVersion) -> io::Result<()> {
+        buffer.write_chat(&self.message)?;
+        buffer.write_ubyte(u8::from(self.position))?;
+
+        if version != ProtocolVersion::V1_15 {
+            buffer.write_uuid(&self.sender)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Plays the animation of an entity picking up an item, arrow or
+    /// experience orb, before it is destroyed.
+    #[derive(Debug)]
+    pub struct CollectItemPacket {
+        pub collected_entity_id: i32 => VarInt,
+        pub collector_entity_id: i32 => VarInt,
+        pub count: i32 => VarInt,
+    }
+}
+
+impl CollectItemPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        collected_entity_id: i32,
+        collector_entity_id: i32,
+        count: i32,
+    ) -> ClientboundPacket {
+        ClientboundPacket::CollectItem(CollectItemPacket {
+            collected_entity_id,
+            collector_entity_id,
+            count,
+        })
+    }
+}
@@ -0,0 +1,89 @@
+use common::chat::Chat;
+use std::io;
+
+use crate::buffer::Buffer;
+use crate::clientbound::ClientboundPacket;
+use crate::packet::PacketPayload;
+use crate::protocol::ProtocolVersion;
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum CombatEvent {
+    EnterCombat,
+    /// `duration` in ticks, `entity_id` being the last attacker or -1.
+    EndCombat {
+        duration: i32,
+        entity_id: i32,
+    },
+    /// Shows the death screen with `message`, `entity_id` being the killer
+    /// or -1.
+    EntityDead {
+        player_id: i32,
+        entity_id: i32,
+        message: Chat,
+    },
+}
+
+#[derive(Debug)]
+pub struct CombatEventPacket {
+    pub event: CombatEvent,
+}
+
+impl CombatEventPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(event: CombatEvent) -> ClientboundPacket {
+        ClientboundPacket::CombatEvent(CombatEventPacket { event })
+    }
+}
+
+impl PacketPayload for CombatEventPacket {
+    fn deserialize(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Self> {
+        let event = match buffer.read_varint()? {
+            0 => CombatEvent::EnterCombat,
+            1 => CombatEvent::EndCombat {
+                duration: buffer.read_varint()?,
+                entity_id: buffer.read_int()?,
+            },
+            2 => CombatEvent::EntityDead {
+                player_id: buffer.read_varint()?,
+                entity_id: buffer.read_int()?,
+                message: buffer.read_chat()?,
+            },
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Unknown combat event",
+                ))
+            }
+        };
+        Ok(CombatEventPacket { event })
+    }
+
+    fn serialize(&self, buffer: &mut Buffer, _version: ProtocolVersion) -> io::Result<()> {
+        match &self.event {
+            CombatEvent::EnterCombat => buffer.write_varint(0),
+            CombatEvent::EndCombat {
+                duration,
+                entity_id,
+            } => {
+                buffer.write_varint(1)?;
+                buffer.write_varint(*duration)?;
+                buffer.write_int(*entity_id)
+            }
+            CombatEvent::EntityDead {
+                player_id,
+                entity_id,
+                message,
+            } => {
+                buffer.write_varint(2)?;
+                buffer.write_varint(*player_id)?;
+                buffer.write_int(*entity_id)?;
+                buffer.write_chat(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct DestroyEntitiesPacket {
+        pub entity_ids: Vec<i32> => VarIntArray,
+    }
+}
+
+impl DestroyEntitiesPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_ids: Vec<i32>) -> ClientboundPacket {
+        ClientboundPacket::DestroyEntities(DestroyEntitiesPacket { entity_ids })
+    }
+}
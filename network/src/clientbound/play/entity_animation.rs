@@ -0,0 +1,23 @@
+use crate::clientbound::ClientboundPacket;
+
+pub const SWING_MAIN_ARM: u8 = 0;
+pub const SWING_OFFHAND: u8 = 3;
+pub const CRITICAL_EFFECT: u8 = 4;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct EntityAnimationPacket {
+        pub entity_id: i32 => VarInt,
+        pub animation: u8 => UByte,
+    }
+}
+
+impl EntityAnimationPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_id: i32, animation: u8) -> ClientboundPacket {
+        ClientboundPacket::EntityAnimation(EntityAnimationPacket {
+            entity_id,
+            animation,
+        })
+    }
+}
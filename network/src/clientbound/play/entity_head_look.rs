@@ -0,0 +1,20 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Turns the head of an entity, which its body follows on its own.
+    #[derive(Debug)]
+    pub struct EntityHeadLookPacket {
+        pub entity_id: i32 => VarInt,
+        pub head_yaw: f32 => Angle,
+    }
+}
+
+impl EntityHeadLookPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_id: i32, head_yaw: f32) -> ClientboundPacket {
+        ClientboundPacket::EntityHeadLook(EntityHeadLookPacket {
+            entity_id,
+            head_yaw,
+        })
+    }
+}
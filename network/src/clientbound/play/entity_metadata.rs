@@ -0,0 +1,22 @@
+use common::metadata::MetadataValue;
+
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Only carries the fields which changed, by index.
+    #[derive(Debug)]
+    pub struct EntityMetadataPacket {
+        pub entity_id: i32 => VarInt,
+        pub metadata: Vec<(u8, MetadataValue)> => Metadata,
+    }
+}
+
+impl EntityMetadataPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_id: i32, metadata: Vec<(u8, MetadataValue)>) -> ClientboundPacket {
+        ClientboundPacket::EntityMetadata(EntityMetadataPacket {
+            entity_id,
+            metadata,
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Moves an entity by less than 8 blocks on each axis, in 1/4096 of a
+    /// block.
+    #[derive(Debug)]
+    pub struct EntityPositionPacket {
+        pub entity_id: i32 => VarInt,
+        pub delta_x: i16 => Short,
+        pub delta_y: i16 => Short,
+        pub delta_z: i16 => Short,
+        pub on_ground: bool => Bool,
+    }
+}
+
+impl EntityPositionPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_id: i32, delta: (i16, i16, i16), on_ground: bool) -> ClientboundPacket {
+        ClientboundPacket::EntityPosition(EntityPositionPacket {
+            entity_id,
+            delta_x: delta.0,
+            delta_y: delta.1,
+            delta_z: delta.2,
+            on_ground,
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Moves an entity like `EntityPositionPacket` and turns it.
+    #[derive(Debug)]
+    pub struct EntityPositionAndRotationPacket {
+        pub entity_id: i32 => VarInt,
+        pub delta_x: i16 => Short,
+        pub delta_y: i16 => Short,
+        pub delta_z: i16 => Short,
+        pub yaw: f32 => Angle,
+        pub pitch: f32 => Angle,
+        pub on_ground: bool => Bool,
+    }
+}
+
+impl EntityPositionAndRotationPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        entity_id: i32,
+        delta: (i16, i16, i16),
+        yaw: f32,
+        pitch: f32,
+        on_ground: bool,
+    ) -> ClientboundPacket {
+        ClientboundPacket::EntityPositionAndRotation(EntityPositionAndRotationPacket {
+            entity_id,
+            delta_x: delta.0,
+            delta_y: delta.1,
+            delta_z: delta.2,
+            yaw,
+            pitch,
+            on_ground,
+        })
+    }
+}
@@ -0,0 +1,23 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct EntityRotationPacket {
+        pub entity_id: i32 => VarInt,
+        pub yaw: f32 => Angle,
+        pub pitch: f32 => Angle,
+        pub on_ground: bool => Bool,
+    }
+}
+
+impl EntityRotationPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_id: i32, yaw: f32, pitch: f32, on_ground: bool) -> ClientboundPacket {
+        ClientboundPacket::EntityRotation(EntityRotationPacket {
+            entity_id,
+            yaw,
+            pitch,
+            on_ground,
+        })
+    }
+}
@@ -0,0 +1,16 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct EntityStatusPacket {
+        pub entity_id: i32 => Int,
+        pub status: i8 => Byte,
+    }
+}
+
+impl EntityStatusPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_id: i32, status: i8) -> ClientboundPacket {
+        ClientboundPacket::EntityStatus(EntityStatusPacket { entity_id, status })
+    }
+}
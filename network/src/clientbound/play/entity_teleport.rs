@@ -0,0 +1,38 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Moves an entity too far for a relative move.
+    #[derive(Debug)]
+    pub struct EntityTeleportPacket {
+        pub entity_id: i32 => VarInt,
+        pub x: f64 => Double,
+        pub y: f64 => Double,
+        pub z: f64 => Double,
+        pub yaw: f32 => Angle,
+        pub pitch: f32 => Angle,
+        pub on_ground: bool => Bool,
+    }
+}
+
+impl EntityTeleportPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        entity_id: i32,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: f32,
+        pitch: f32,
+        on_ground: bool,
+    ) -> ClientboundPacket {
+        ClientboundPacket::EntityTeleport(EntityTeleportPacket {
+            entity_id,
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+            on_ground,
+        })
+    }
+}
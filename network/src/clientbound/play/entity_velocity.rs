@@ -0,0 +1,25 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Velocity in 1/8000 of a block per tick, which players get knocked
+    /// back with.
+    #[derive(Debug)]
+    pub struct EntityVelocityPacket {
+        pub entity_id: i32 => VarInt,
+        pub velocity_x: i16 => Short,
+        pub velocity_y: i16 => Short,
+        pub velocity_z: i16 => Short,
+    }
+}
+
+impl EntityVelocityPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(entity_id: i32, velocity: (i16, i16, i16)) -> ClientboundPacket {
+        ClientboundPacket::EntityVelocity(EntityVelocityPacket {
+            entity_id,
+            velocity_x: velocity.0,
+            velocity_y: velocity.1,
+            velocity_z: velocity.2,
+        })
+    }
+}
@@ -0,0 +1,16 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Selects a hotbar slot, from 0 to 8.
+    #[derive(Debug)]
+    pub struct HeldItemChangePacket {
+        pub slot: i8 => Byte,
+    }
+}
+
+impl HeldItemChangePacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(slot: i8) -> ClientboundPacket {
+        ClientboundPacket::HeldItemChange(HeldItemChangePacket { slot })
+    }
+}
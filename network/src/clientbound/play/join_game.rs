@@ -1,11 +1,15 @@
 use common::dimension::Dimension;
 use common::gamemode::Gamemode;
 use common::level_type::LevelType;
+use nbt::{Blob, Value};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::io;
 
 use crate::buffer::Buffer;
 use crate::clientbound::ClientboundPacket;
+use crate::packet::PacketPayload;
+use crate::protocol::ProtocolVersion;
 
 #[derive(Debug)]
 pub struct JoinGamePacket {
@@ -48,53 +52,83 @@ impl JoinGamePacket {
             enable_respawn_screen,
         })
     }
+}
 
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
+impl JoinGamePacket {
+    fn deserialize_v1_15(buffer: &mut Buffer) -> io::Result<Self> {
         let entity_id = buffer.read_int()?;
-        let mut gamemode_byte = buffer.read_ubyte()?;
-        let mut hardcore = false;
-
-        if gamemode_byte & 0b0000_0100 != 0 {
-            hardcore = true;
-            gamemode_byte ^= 0b0000_0100;
-        }
-
+        let (gamemode, hardcore) = read_gamemode(buffer)?;
         let dimension = Dimension::from(buffer.read_int()?);
         let hashed_seed = buffer.read_long()?;
         let max_players = buffer.read_ubyte()?;
 
-        match Gamemode::try_from(gamemode_byte) {
-            Ok(gamemode) => match LevelType::from_string(&buffer.read_string()?) {
-                Some(level_type) => Ok(ClientboundPacket::JoinGame(JoinGamePacket {
-                    entity_id,
-                    gamemode,
-                    hardcore,
-                    dimension,
-                    hashed_seed,
-                    max_players,
-                    level_type,
-                    render_distance: buffer.read_varint()?,
-                    reduced_debug_info: buffer.read_bool()?,
-                    enable_respawn_screen: buffer.read_bool()?,
-                })),
-                None => Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "Unknown level type",
-                )),
-            },
-            Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+        match LevelType::from_string(&buffer.read_string()?) {
+            Some(level_type) => Ok(JoinGamePacket {
+                entity_id,
+                gamemode,
+                hardcore,
+                dimension,
+                hashed_seed,
+                max_players,
+                level_type,
+                render_distance: buffer.read_varint()?,
+                reduced_debug_info: buffer.read_bool()?,
+                enable_respawn_screen: buffer.read_bool()?,
+            }),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unknown level type",
+            )),
         }
     }
 
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        let mut final_gamemode = u8::from(self.gamemode);
+    fn deserialize_v1_16(buffer: &mut Buffer) -> io::Result<Self> {
+        let entity_id = buffer.read_int()?;
+        let (gamemode, hardcore) = read_gamemode(buffer)?;
+        let _previous_gamemode = buffer.read_ubyte()?;
+        let world_count = buffer.read_varint()?;
+        let _world_names = buffer.read_array(Buffer::read_string, world_count as usize)?;
+        let _dimension_codec = buffer.read_nbt()?;
 
-        if self.hardcore {
-            final_gamemode |= 0b0000_0100;
-        }
+        let dimension = match Dimension::from_identifier(&buffer.read_string()?) {
+            Some(dimension) => dimension,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Unknown dimension",
+                ))
+            }
+        };
+        let _world_name = buffer.read_string()?;
+        let hashed_seed = buffer.read_long()?;
+        let max_players = buffer.read_ubyte()?;
+        let render_distance = buffer.read_varint()?;
+        let reduced_debug_info = buffer.read_bool()?;
+        let enable_respawn_screen = buffer.read_bool()?;
+        let _debug = buffer.read_bool()?;
+        let level_type = if buffer.read_bool()? {
+            LevelType::Flat
+        } else {
+            LevelType::Default
+        };
 
+        Ok(JoinGamePacket {
+            entity_id,
+            gamemode,
+            hardcore,
+            dimension,
+            hashed_seed,
+            max_players,
+            level_type,
+            render_distance,
+            reduced_debug_info,
+            enable_respawn_screen,
+        })
+    }
+
+    fn serialize_v1_15(&self, buffer: &mut Buffer) -> io::Result<()> {
         buffer.write_int(self.entity_id)?;
-        buffer.write_ubyte(final_gamemode)?;
+        buffer.write_ubyte(self.gamemode_byte())?;
         buffer.write_int(i32::from(self.dimension))?;
         buffer.write_long(self.hashed_seed)?;
         buffer.write_ubyte(self.max_players)?;
@@ -104,4 +138,165 @@ impl JoinGamePacket {
         buffer.write_bool(self.enable_respawn_screen)?;
         Ok(())
     }
+
+    fn serialize_v1_16(&self, buffer: &mut Buffer) -> io::Result<()> {
+        let dimension = match self.dimension.to_identifier() {
+            Some(dimension) => dimension,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Custom dimensions are not supported",
+                ))
+            }
+        };
+        let worlds = [
+            "minecraft:overworld",
+            "minecraft:the_nether",
+            "minecraft:the_end",
+        ];
+
+        buffer.write_int(self.entity_id)?;
+        buffer.write_ubyte(self.gamemode_byte())?;
+        buffer.write_ubyte(u8::from(self.gamemode))?;
+        buffer.write_varint(worlds.len() as i32)?;
+        for world in worlds.iter() {
+            buffer.write_string(world)?;
+        }
+        buffer.write_nbt(&dimension_codec()?)?;
+        buffer.write_string(dimension)?;
+        buffer.write_string(dimension)?;
+        buffer.write_long(self.hashed_seed)?;
+        buffer.write_ubyte(self.max_players)?;
+        buffer.write_varint(self.render_distance)?;
+        buffer.write_bool(self.reduced_debug_info)?;
+        buffer.write_bool(self.enable_respawn_screen)?;
+        buffer.write_bool(false)?;
+        buffer.write_bool(self.level_type == LevelType::Flat)?;
+        Ok(())
+    }
+
+    fn gamemode_byte(&self) -> u8 {
+        let mut final_gamemode = u8::from(self.gamemode);
+
+        if self.hardcore {
+            final_gamemode |= 0b0000_0100;
+        }
+        final_gamemode
+    }
+}
+
+impl PacketPayload for JoinGamePacket {
+    fn deserialize(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Self> {
+        match version {
+            ProtocolVersion::V1_15 => Self::deserialize_v1_15(buffer),
+            ProtocolVersion::V1_16 => Self::deserialize_v1_16(buffer),
+        }
+    }
+
+    fn serialize(&self, buffer: &mut Buffer, version: ProtocolVersion) -> io::Result<()> {
+        match version {
+            ProtocolVersion::V1_15 => self.serialize_v1_15(buffer),
+            ProtocolVersion::V1_16 => self.serialize_v1_16(buffer),
+        }
+    }
+}
+
+fn read_gamemode(buffer: &mut Buffer) -> io::Result<(Gamemode, bool)> {
+    let mut gamemode_byte = buffer.read_ubyte()?;
+    let mut hardcore = false;
+
+    if gamemode_byte & 0b0000_0100 != 0 {
+        hardcore = true;
+        gamemode_byte ^= 0b0000_0100;
+    }
+
+    match Gamemode::try_from(gamemode_byte) {
+        Ok(gamemode) => Ok((gamemode, hardcore)),
+        Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+    }
+}
+
+/// Builds the dimension types registry the 1.16 client expects in Join Game.
+fn dimension_codec() -> io::Result<Blob> {
+    let overworld = dimension_type(
+        "minecraft:overworld",
+        None,
+        "minecraft:infiniburn_overworld",
+    )
+    .with("piglin_safe", 0i8)
+    .with("natural", 1i8)
+    .with("ambient_light", 0.0f32)
+    .with("respawn_anchor_works", 0i8)
+    .with("has_skylight", 1i8)
+    .with("bed_works", 1i8)
+    .with("has_raids", 1i8)
+    .with("logical_height", 256)
+    .with("shrunk", 0i8)
+    .with("ultrawarm", 0i8)
+    .with("has_ceiling", 0i8);
+
+    let nether = dimension_type(
+        "minecraft:the_nether",
+        Some(18000),
+        "minecraft:infiniburn_nether",
+    )
+    .with("piglin_safe", 1i8)
+    .with("natural", 0i8)
+    .with("ambient_light", 0.1f32)
+    .with("respawn_anchor_works", 1i8)
+    .with("has_skylight", 0i8)
+    .with("bed_works", 0i8)
+    .with("has_raids", 0i8)
+    .with("logical_height", 128)
+    .with("shrunk", 1i8)
+    .with("ultrawarm", 1i8)
+    .with("has_ceiling", 1i8);
+
+    let end = dimension_type("minecraft:the_end", Some(6000), "minecraft:infiniburn_end")
+        .with("piglin_safe", 0i8)
+        .with("natural", 0i8)
+        .with("ambient_light", 0.0f32)
+        .with("respawn_anchor_works", 0i8)
+        .with("has_skylight", 0i8)
+        .with("bed_works", 0i8)
+        .with("has_raids", 1i8)
+        .with("logical_height", 256)
+        .with("shrunk", 0i8)
+        .with("ultrawarm", 0i8)
+        .with("has_ceiling", 0i8);
+
+    let mut codec = Blob::new();
+    codec.insert(
+        "dimension",
+        Value::List(vec![overworld.build(), nether.build(), end.build()]),
+    )?;
+    Ok(codec)
+}
+
+struct Compound(HashMap<String, Value>);
+
+impl Compound {
+    fn with<V: Into<Value>>(mut self, name: &str, value: V) -> Self {
+        self.0.insert(name.to_string(), value.into());
+        self
+    }
+
+    fn build(self) -> Value {
+        Value::Compound(self.0)
+    }
+}
+
+fn dimension_type(name: &str, fixed_time: Option<i64>, infiniburn: &str) -> Compound {
+    let compound = Compound(HashMap::new())
+        .with("name", name)
+        .with("infiniburn", infiniburn);
+
+    match fixed_time {
+        Some(time) => compound.with("fixed_time", time),
+        None => compound,
+    }
 }
@@ -0,0 +1,15 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct KeepAlivePacket {
+        pub id: i64 => Long,
+    }
+}
+
+impl KeepAlivePacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(id: i64) -> ClientboundPacket {
+        ClientboundPacket::KeepAlive(KeepAlivePacket { id })
+    }
+}
@@ -1,11 +1,67 @@
+mod block_change;
+mod change_game_state;
+mod chat_message;
+mod collect_item;
+mod combat_event;
+mod destroy_entities;
 mod disconnect;
+mod entity_animation;
+mod entity_head_look;
+mod entity_metadata;
+mod entity_position;
+mod entity_position_and_rotation;
+mod entity_rotation;
+mod entity_status;
+mod entity_teleport;
+mod entity_velocity;
+mod held_item_change;
 mod join_game;
+mod keep_alive;
 mod player_abilities;
+mod player_info;
+mod player_list_header_and_footer;
+mod player_position_and_look;
 mod plugin_message;
+mod respawn;
 mod server_difficulty;
+mod set_slot;
+mod spawn_entity;
+mod spawn_living_entity;
+mod spawn_player;
+mod update_health;
+mod window_confirmation;
+mod window_items;
 
+pub use block_change::BlockChangePacket;
+pub use change_game_state::{ChangeGameStatePacket, CHANGE_GAMEMODE};
+pub use chat_message::ChatMessagePacket;
+pub use collect_item::CollectItemPacket;
+pub use combat_event::{CombatEvent, CombatEventPacket};
+pub use destroy_entities::DestroyEntitiesPacket;
 pub use disconnect::DisconnectPlayPacket;
+pub use entity_animation::{EntityAnimationPacket, CRITICAL_EFFECT, SWING_MAIN_ARM, SWING_OFFHAND};
+pub use entity_head_look::EntityHeadLookPacket;
+pub use entity_metadata::EntityMetadataPacket;
+pub use entity_position::EntityPositionPacket;
+pub use entity_position_and_rotation::EntityPositionAndRotationPacket;
+pub use entity_rotation::EntityRotationPacket;
+pub use entity_status::EntityStatusPacket;
+pub use entity_teleport::EntityTeleportPacket;
+pub use entity_velocity::EntityVelocityPacket;
+pub use held_item_change::HeldItemChangePacket;
 pub use join_game::JoinGamePacket;
+pub use keep_alive::KeepAlivePacket;
 pub use player_abilities::PlayerAbilitiesPacket;
+pub use player_info::{PlayerInfoAction, PlayerInfoPacket, PlayerListEntry};
+pub use player_list_header_and_footer::PlayerListHeaderAndFooterPacket;
+pub use player_position_and_look::PlayerPositionAndLookPacket;
 pub use plugin_message::PluginMessagePacket;
+pub use respawn::RespawnPacket;
 pub use server_difficulty::ServerDifficultyPacket;
+pub use set_slot::{SetSlotPacket, CURSOR_SLOT, CURSOR_WINDOW};
+pub use spawn_entity::SpawnEntityPacket;
+pub use spawn_living_entity::SpawnLivingEntityPacket;
+pub use spawn_player::SpawnPlayerPacket;
+pub use update_health::UpdateHealthPacket;
+pub use window_confirmation::WindowConfirmationPacket;
+pub use window_items::WindowItemsPacket;
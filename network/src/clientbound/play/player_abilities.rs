@@ -2,6 +2,8 @@ use std::io;
 
 use crate::buffer::Buffer;
 use crate::clientbound::ClientboundPacket;
+use crate::packet::PacketPayload;
+use crate::protocol::ProtocolVersion;
 
 #[derive(Debug)]
 pub struct PlayerAbilitiesPacket {
@@ -32,21 +34,27 @@ impl PlayerAbilitiesPacket {
             field_of_view_modifier,
         })
     }
+}
 
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
+impl PacketPayload for PlayerAbilitiesPacket {
+    fn deserialize(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Self> {
         let flags = buffer.read_byte()?;
 
-        Ok(ClientboundPacket::PlayerAbilities(PlayerAbilitiesPacket {
+        Ok(PlayerAbilitiesPacket {
             invulnerability: (flags & 0b0000_0001) != 0,
             flying: (flags & 0b0000_0010) != 0,
             allow_flight: (flags & 0b0000_0100) != 0,
             instant_break: (flags & 0b0000_1000) != 0,
             flying_speed: buffer.read_float()?,
             field_of_view_modifier: buffer.read_float()?,
-        }))
+        })
     }
 
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
+    fn serialize(&self, buffer: &mut Buffer, _version: ProtocolVersion) -> io::Result<()> {
         let mut flags: i8 = 0;
 
         if self.invulnerability {
@@ -0,0 +1,213 @@
+use common::chat::Chat;
+use common::gamemode::Gamemode;
+use std::convert::TryFrom;
+use std::io;
+use uuid::Uuid;
+
+use crate::buffer::Buffer;
+use crate::clientbound::ClientboundPacket;
+use crate::mojang::ProfileProperty;
+use crate::packet::PacketPayload;
+use crate::protocol::ProtocolVersion;
+
+/// A player shown in the tab list.
+#[derive(Debug, Clone)]
+pub struct PlayerListEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    /// Skin and cape of the player.
+    pub properties: Vec<ProfileProperty>,
+    pub gamemode: Gamemode,
+    /// Milliseconds, shown as bars.
+    pub latency: i32,
+    /// Replaces the name when set.
+    pub display_name: Option<Chat>,
+}
+
+#[derive(Debug)]
+pub enum PlayerInfoAction {
+    AddPlayer(Vec<PlayerListEntry>),
+    UpdateGamemode(Vec<(Uuid, Gamemode)>),
+    UpdateLatency(Vec<(Uuid, i32)>),
+    UpdateDisplayName(Vec<(Uuid, Option<Chat>)>),
+    RemovePlayer(Vec<Uuid>),
+}
+
+/// Changes the tab list, which other players must be in to be spawned.
+#[derive(Debug)]
+pub struct PlayerInfoPacket {
+    pub action: PlayerInfoAction,
+}
+
+impl PlayerInfoPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(action: PlayerInfoAction) -> ClientboundPacket {
+        ClientboundPacket::PlayerInfo(PlayerInfoPacket { action })
+    }
+}
+
+impl PacketPayload for PlayerInfoPacket {
+    fn deserialize(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        payload_end: usize,
+    ) -> io::Result<Self> {
+        let action = buffer.read_varint()?;
+        let count = buffer.read_varint()?;
+
+        // Every player takes at least the 16 bytes of its UUID
+        if count < 0 || count as usize > payload_end.saturating_sub(buffer.cursor()) / 16 {
+            return Err(invalid_data("Player count is larger than the packet"));
+        }
+        let count = count as usize;
+
+        let action = match action {
+            0 => PlayerInfoAction::AddPlayer(
+                buffer.read_array(|buffer| read_entry(buffer, payload_end), count)?,
+            ),
+            1 => PlayerInfoAction::UpdateGamemode(buffer.read_array(
+                |buffer| Ok((buffer.read_uuid()?, read_gamemode(buffer)?)),
+                count,
+            )?),
+            2 => PlayerInfoAction::UpdateLatency(buffer.read_array(
+                |buffer| Ok((buffer.read_uuid()?, buffer.read_varint()?)),
+                count,
+            )?),
+            3 => PlayerInfoAction::UpdateDisplayName(buffer.read_array(
+                |buffer| Ok((buffer.read_uuid()?, read_display_name(buffer)?)),
+                count,
+            )?),
+            4 => PlayerInfoAction::RemovePlayer(buffer.read_array(Buffer::read_uuid, count)?),
+            _ => return Err(invalid_data("Unknown player info action")),
+        };
+        Ok(PlayerInfoPacket { action })
+    }
+
+    fn serialize(&self, buffer: &mut Buffer, _version: ProtocolVersion) -> io::Result<()> {
+        match &self.action {
+            PlayerInfoAction::AddPlayer(entries) => {
+                buffer.write_varint(0)?;
+                buffer.write_varint(entries.len() as i32)?;
+                buffer.write_array(write_entry, entries)
+            }
+            PlayerInfoAction::UpdateGamemode(players) => {
+                buffer.write_varint(1)?;
+                buffer.write_varint(players.len() as i32)?;
+                buffer.write_array(
+                    |buffer, (uuid, gamemode)| {
+                        buffer.write_uuid(uuid)?;
+                        buffer.write_varint(i32::from(u8::from(*gamemode)))
+                    },
+                    players,
+                )
+            }
+            PlayerInfoAction::UpdateLatency(players) => {
+                buffer.write_varint(2)?;
+                buffer.write_varint(players.len() as i32)?;
+                buffer.write_array(
+                    |buffer, (uuid, latency)| {
+                        buffer.write_uuid(uuid)?;
+                        buffer.write_varint(*latency)
+                    },
+                    players,
+                )
+            }
+            PlayerInfoAction::UpdateDisplayName(players) => {
+                buffer.write_varint(3)?;
+                buffer.write_varint(players.len() as i32)?;
+                buffer.write_array(
+                    |buffer, (uuid, display_name)| {
+                        buffer.write_uuid(uuid)?;
+                        write_display_name(buffer, display_name)
+                    },
+                    players,
+                )
+            }
+            PlayerInfoAction::RemovePlayer(players) => {
+                buffer.write_varint(4)?;
+                buffer.write_varint(players.len() as i32)?;
+                buffer.write_array(|buffer, uuid| buffer.write_uuid(uuid), players)
+            }
+        }
+    }
+}
+
+fn read_entry(buffer: &mut Buffer, payload_end: usize) -> io::Result<PlayerListEntry> {
+    let uuid = buffer.read_uuid()?;
+    let name = buffer.read_string_max(16)?;
+    let property_count = buffer.read_varint()?;
+
+    if property_count < 0 || property_count as usize > payload_end.saturating_sub(buffer.cursor()) {
+        return Err(invalid_data("Property count is larger than the packet"));
+    }
+    let properties = buffer.read_array(
+        |buffer| {
+            Ok(ProfileProperty {
+                name: buffer.read_string()?,
+                value: buffer.read_string()?,
+                signature: if buffer.read_bool()? {
+                    Some(buffer.read_string()?)
+                } else {
+                    None
+                },
+            })
+        },
+        property_count as usize,
+    )?;
+
+    Ok(PlayerListEntry {
+        uuid,
+        name,
+        properties,
+        gamemode: read_gamemode(buffer)?,
+        latency: buffer.read_varint()?,
+        display_name: read_display_name(buffer)?,
+    })
+}
+
+fn write_entry(buffer: &mut Buffer, entry: &PlayerListEntry) -> io::Result<()> {
+    buffer.write_uuid(&entry.uuid)?;
+    buffer.write_string(&entry.name)?;
+    buffer.write_varint(entry.properties.len() as i32)?;
+    buffer.write_array(
+        |buffer, property| {
+            buffer.write_string(&property.name)?;
+            buffer.write_string(&property.value)?;
+            buffer.write_bool(property.signature.is_some())?;
+            match &property.signature {
+                Some(signature) => buffer.write_string(signature),
+                None => Ok(()),
+            }
+        },
+        &entry.properties,
+    )?;
+    buffer.write_varint(i32::from(u8::from(entry.gamemode)))?;
+    buffer.write_varint(entry.latency)?;
+    write_display_name(buffer, &entry.display_name)
+}
+
+fn read_gamemode(buffer: &mut Buffer) -> io::Result<Gamemode> {
+    u8::try_from(buffer.read_varint()?)
+        .map_err(|_| invalid_data("Unknown Gamemode"))
+        .and_then(|gamemode| Gamemode::try_from(gamemode).map_err(invalid_data))
+}
+
+fn read_display_name(buffer: &mut Buffer) -> io::Result<Option<Chat>> {
+    if buffer.read_bool()? {
+        Ok(Some(buffer.read_chat()?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn write_display_name(buffer: &mut Buffer, display_name: &Option<Chat>) -> io::Result<()> {
+    buffer.write_bool(display_name.is_some())?;
+    match display_name {
+        Some(display_name) => buffer.write_chat(display_name),
+        None => Ok(()),
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
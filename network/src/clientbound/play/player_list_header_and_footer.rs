@@ -0,0 +1,22 @@
+use common::chat::Chat;
+
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Text shown above and below the tab list, hidden when empty.
+    #[derive(Debug)]
+    pub struct PlayerListHeaderAndFooterPacket {
+        pub header: Chat => Chat,
+        pub footer: Chat => Chat,
+    }
+}
+
+impl PlayerListHeaderAndFooterPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(header: Chat, footer: Chat) -> ClientboundPacket {
+        ClientboundPacket::PlayerListHeaderAndFooter(PlayerListHeaderAndFooterPacket {
+            header,
+            footer,
+        })
+    }
+}
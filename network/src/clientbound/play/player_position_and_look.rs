@@ -0,0 +1,37 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PlayerPositionAndLookPacket {
+        pub x: f64 => Double,
+        pub y: f64 => Double,
+        pub z: f64 => Double,
+        pub yaw: f32 => Float,
+        pub pitch: f32 => Float,
+        pub flags: u8 => UByte,
+        pub teleport_id: i32 => VarInt,
+    }
+}
+
+impl PlayerPositionAndLookPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: f32,
+        pitch: f32,
+        flags: u8,
+        teleport_id: i32,
+    ) -> ClientboundPacket {
+        ClientboundPacket::PlayerPositionAndLook(PlayerPositionAndLookPacket {
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+            flags,
+            teleport_id,
+        })
+    }
+}
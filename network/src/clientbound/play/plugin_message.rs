@@ -3,10 +3,12 @@ use std::io;
 use crate::buffer::Buffer;
 use crate::clientbound::ClientboundPacket;
 
-#[derive(Debug)]
-pub struct PluginMessagePacket {
-    channel: String,
-    data: Vec<u8>,
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PluginMessagePacket {
+        channel: String => Str,
+        data: Vec<u8> => RemainingBytes,
+    }
 }
 
 impl PluginMessagePacket {
@@ -27,22 +29,4 @@ impl PluginMessagePacket {
             data: buffer.as_raw().to_vec(),
         }))
     }
-
-    pub fn deserialize(buffer: &mut Buffer, payload_size: usize) -> io::Result<ClientboundPacket> {
-        let cursor_before = buffer.cursor();
-        let channel = buffer.read_string()?;
-        let channel_len = buffer.cursor() - cursor_before;
-        let data_size = payload_size - channel_len;
-
-        Ok(ClientboundPacket::PluginMessage(PluginMessagePacket {
-            channel,
-            data: buffer.read_ubyte_array(data_size)?,
-        }))
-    }
-
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        buffer.write_string(&self.channel)?;
-        buffer.write_ubyte_array(&self.data)?;
-        Ok(())
-    }
 }
@@ -0,0 +1,128 @@
+use common::dimension::Dimension;
+use common::gamemode::Gamemode;
+use common::level_type::LevelType;
+use std::convert::TryFrom;
+use std::io;
+
+use crate::buffer::Buffer;
+use crate::clientbound::ClientboundPacket;
+use crate::packet::PacketPayload;
+use crate::protocol::ProtocolVersion;
+
+/// Brings a dead player back, or moves them to another dimension.
+#[derive(Debug)]
+pub struct RespawnPacket {
+    dimension: Dimension,
+    hashed_seed: i64,
+    gamemode: Gamemode,
+    level_type: LevelType,
+}
+
+impl RespawnPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        dimension: Dimension,
+        hashed_seed: i64,
+        gamemode: Gamemode,
+        level_type: LevelType,
+    ) -> ClientboundPacket {
+        ClientboundPacket::Respawn(RespawnPacket {
+            dimension,
+            hashed_seed,
+            gamemode,
+            level_type,
+        })
+    }
+}
+
+impl RespawnPacket {
+    fn deserialize_v1_15(buffer: &mut Buffer) -> io::Result<Self> {
+        let dimension = Dimension::from(buffer.read_int()?);
+        let hashed_seed = buffer.read_long()?;
+        let gamemode = read_gamemode(buffer)?;
+
+        match LevelType::from_string(&buffer.read_string_max(16)?) {
+            Some(level_type) => Ok(RespawnPacket {
+                dimension,
+                hashed_seed,
+                gamemode,
+                level_type,
+            }),
+            None => Err(invalid_data("Unknown level type")),
+        }
+    }
+
+    fn deserialize_v1_16(buffer: &mut Buffer) -> io::Result<Self> {
+        let dimension = Dimension::from_identifier(&buffer.read_string()?)
+            .ok_or_else(|| invalid_data("Unknown dimension"))?;
+        let _world_name = buffer.read_string()?;
+        let hashed_seed = buffer.read_long()?;
+        let gamemode = read_gamemode(buffer)?;
+        let _previous_gamemode = buffer.read_ubyte()?;
+        let _debug = buffer.read_bool()?;
+        let level_type = if buffer.read_bool()? {
+            LevelType::Flat
+        } else {
+            LevelType::Default
+        };
+        let _copy_metadata = buffer.read_bool()?;
+
+        Ok(RespawnPacket {
+            dimension,
+            hashed_seed,
+            gamemode,
+            level_type,
+        })
+    }
+
+    fn serialize_v1_15(&self, buffer: &mut Buffer) -> io::Result<()> {
+        buffer.write_int(i32::from(self.dimension))?;
+        buffer.write_long(self.hashed_seed)?;
+        buffer.write_ubyte(u8::from(self.gamemode))?;
+        buffer.write_string(self.level_type.to_string())
+    }
+
+    fn serialize_v1_16(&self, buffer: &mut Buffer) -> io::Result<()> {
+        let dimension = self
+            .dimension
+            .to_identifier()
+            .ok_or_else(|| invalid_data("Custom dimensions are not supported"))?;
+
+        buffer.write_string(dimension)?;
+        buffer.write_string(dimension)?;
+        buffer.write_long(self.hashed_seed)?;
+        buffer.write_ubyte(u8::from(self.gamemode))?;
+        buffer.write_ubyte(u8::from(self.gamemode))?;
+        buffer.write_bool(false)?;
+        buffer.write_bool(self.level_type == LevelType::Flat)?;
+        buffer.write_bool(false)
+    }
+}
+
+impl PacketPayload for RespawnPacket {
+    fn deserialize(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Self> {
+        match version {
+            ProtocolVersion::V1_15 => Self::deserialize_v1_15(buffer),
+            ProtocolVersion::V1_16 => Self::deserialize_v1_16(buffer),
+        }
+    }
+
+    fn serialize(&self, buffer: &mut Buffer, version: ProtocolVersion) -> io::Result<()> {
+        match version {
+            ProtocolVersion::V1_15 => self.serialize_v1_15(buffer),
+            ProtocolVersion::V1_16 => self.serialize_v1_16(buffer),
+        }
+    }
+}
+
+fn read_gamemode(buffer: &mut Buffer) -> io::Result<Gamemode> {
+    Gamemode::try_from(buffer.read_ubyte()?).map_err(invalid_data)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
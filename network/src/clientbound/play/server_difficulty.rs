@@ -1,14 +1,13 @@
 use common::difficulty::Difficulty;
-use std::convert::TryFrom;
-use std::io;
 
-use crate::buffer::Buffer;
 use crate::clientbound::ClientboundPacket;
 
-#[derive(Debug)]
-pub struct ServerDifficultyPacket {
-    difficulty: Difficulty,
-    locked: bool,
+packet_payload! {
+    #[derive(Debug)]
+    pub struct ServerDifficultyPacket {
+        difficulty: Difficulty => UByte,
+        locked: bool => Bool,
+    }
 }
 
 impl ServerDifficultyPacket {
@@ -16,22 +15,4 @@ impl ServerDifficultyPacket {
     pub fn new(difficulty: Difficulty, locked: bool) -> ClientboundPacket {
         ClientboundPacket::ServerDifficulty(ServerDifficultyPacket { difficulty, locked })
     }
-
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
-        match Difficulty::try_from(buffer.read_ubyte()?) {
-            Ok(difficulty) => Ok(ClientboundPacket::ServerDifficulty(
-                ServerDifficultyPacket {
-                    difficulty,
-                    locked: buffer.read_bool()?,
-                },
-            )),
-            Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
-        }
-    }
-
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        buffer.write_ubyte(u8::from(self.difficulty))?;
-        buffer.write_bool(self.locked)?;
-        Ok(())
-    }
 }
@@ -0,0 +1,27 @@
+use common::item::ItemStack;
+
+use crate::clientbound::ClientboundPacket;
+
+/// Window and slot of the stack carried by the cursor.
+pub const CURSOR_WINDOW: i8 = -1;
+pub const CURSOR_SLOT: i16 = -1;
+
+packet_payload! {
+    #[derive(Debug)]
+    pub struct SetSlotPacket {
+        pub window_id: i8 => Byte,
+        pub slot: i16 => Short,
+        pub stack: Option<ItemStack> => Slot,
+    }
+}
+
+impl SetSlotPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(window_id: i8, slot: i16, stack: Option<ItemStack>) -> ClientboundPacket {
+        ClientboundPacket::SetSlot(SetSlotPacket {
+            window_id,
+            slot,
+            stack,
+        })
+    }
+}
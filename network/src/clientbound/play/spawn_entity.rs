@@ -0,0 +1,56 @@
+use common::entity_type::EntityType;
+use uuid::Uuid;
+
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Shows an object, such as a dropped item or an arrow.
+    #[derive(Debug)]
+    pub struct SpawnEntityPacket {
+        pub entity_id: i32 => VarInt,
+        pub uuid: Uuid => UuidBytes,
+        pub entity_type: EntityType => EntityTypeId,
+        pub x: f64 => Double,
+        pub y: f64 => Double,
+        pub z: f64 => Double,
+        pub pitch: f32 => Angle,
+        pub yaw: f32 => Angle,
+        /// Meaning depends on the type of the entity.
+        pub data: i32 => Int,
+        pub velocity_x: i16 => Short,
+        pub velocity_y: i16 => Short,
+        pub velocity_z: i16 => Short,
+    }
+}
+
+impl SpawnEntityPacket {
+    #[allow(clippy::new_ret_no_self)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        entity_id: i32,
+        uuid: Uuid,
+        entity_type: EntityType,
+        x: f64,
+        y: f64,
+        z: f64,
+        pitch: f32,
+        yaw: f32,
+        data: i32,
+        velocity: (i16, i16, i16),
+    ) -> ClientboundPacket {
+        ClientboundPacket::SpawnEntity(SpawnEntityPacket {
+            entity_id,
+            uuid,
+            entity_type,
+            x,
+            y,
+            z,
+            pitch,
+            yaw,
+            data,
+            velocity_x: velocity.0,
+            velocity_y: velocity.1,
+            velocity_z: velocity.2,
+        })
+    }
+}
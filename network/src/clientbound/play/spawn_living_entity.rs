@@ -0,0 +1,55 @@
+use common::entity_type::EntityType;
+use uuid::Uuid;
+
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Shows a mob.
+    #[derive(Debug)]
+    pub struct SpawnLivingEntityPacket {
+        pub entity_id: i32 => VarInt,
+        pub uuid: Uuid => UuidBytes,
+        pub entity_type: EntityType => EntityTypeId,
+        pub x: f64 => Double,
+        pub y: f64 => Double,
+        pub z: f64 => Double,
+        pub yaw: f32 => Angle,
+        pub pitch: f32 => Angle,
+        pub head_yaw: f32 => Angle,
+        pub velocity_x: i16 => Short,
+        pub velocity_y: i16 => Short,
+        pub velocity_z: i16 => Short,
+    }
+}
+
+impl SpawnLivingEntityPacket {
+    #[allow(clippy::new_ret_no_self)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        entity_id: i32,
+        uuid: Uuid,
+        entity_type: EntityType,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: f32,
+        pitch: f32,
+        head_yaw: f32,
+        velocity: (i16, i16, i16),
+    ) -> ClientboundPacket {
+        ClientboundPacket::SpawnLivingEntity(SpawnLivingEntityPacket {
+            entity_id,
+            uuid,
+            entity_type,
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+            head_yaw,
+            velocity_x: velocity.0,
+            velocity_y: velocity.1,
+            velocity_z: velocity.2,
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use uuid::Uuid;
+
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Shows another player, who must be in the tab list already.
+    #[derive(Debug)]
+    pub struct SpawnPlayerPacket {
+        pub entity_id: i32 => VarInt,
+        pub uuid: Uuid => UuidBytes,
+        pub x: f64 => Double,
+        pub y: f64 => Double,
+        pub z: f64 => Double,
+        pub yaw: f32 => Angle,
+        pub pitch: f32 => Angle,
+    }
+}
+
+impl SpawnPlayerPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        entity_id: i32,
+        uuid: Uuid,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: f32,
+        pitch: f32,
+    ) -> ClientboundPacket {
+        ClientboundPacket::SpawnPlayer(SpawnPlayerPacket {
+            entity_id,
+            uuid,
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Health and hunger of the player, who dies at 0 health.
+    #[derive(Debug)]
+    pub struct UpdateHealthPacket {
+        pub health: f32 => Float,
+        pub food: i32 => VarInt,
+        pub saturation: f32 => Float,
+    }
+}
+
+impl UpdateHealthPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(health: f32, food: i32, saturation: f32) -> ClientboundPacket {
+        ClientboundPacket::UpdateHealth(UpdateHealthPacket {
+            health,
+            food,
+            saturation,
+        })
+    }
+}
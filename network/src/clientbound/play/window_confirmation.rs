@@ -0,0 +1,23 @@
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Tells whether a click was applied like the client predicted. The
+    /// client answers a rejection with the same packet before clicking again.
+    #[derive(Debug)]
+    pub struct WindowConfirmationPacket {
+        pub window_id: i8 => Byte,
+        pub action_number: i16 => Short,
+        pub accepted: bool => Bool,
+    }
+}
+
+impl WindowConfirmationPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(window_id: i8, action_number: i16, accepted: bool) -> ClientboundPacket {
+        ClientboundPacket::WindowConfirmation(WindowConfirmationPacket {
+            window_id,
+            action_number,
+            accepted,
+        })
+    }
+}
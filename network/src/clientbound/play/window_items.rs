@@ -0,0 +1,19 @@
+use common::item::ItemStack;
+
+use crate::clientbound::ClientboundPacket;
+
+packet_payload! {
+    /// Every slot of a window, 0 being the inventory of the player.
+    #[derive(Debug)]
+    pub struct WindowItemsPacket {
+        pub window_id: u8 => UByte,
+        pub slots: Vec<Option<ItemStack>> => SlotArray,
+    }
+}
+
+impl WindowItemsPacket {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(window_id: u8, slots: Vec<Option<ItemStack>>) -> ClientboundPacket {
+        ClientboundPacket::WindowItems(WindowItemsPacket { window_id, slots })
+    }
+}
@@ -1,9 +1,6 @@
 use common::chat::Chat;
 use serde::{Deserialize, Serialize};
-use serde_json;
-use std::io;
 
-use crate::buffer::Buffer;
 use crate::clientbound::ClientboundPacket;
 use crate::mojang;
 
@@ -28,9 +25,11 @@ pub struct StatusResponsePayload {
     pub favicon: String,
 }
 
-#[derive(Debug)]
-pub struct StatusResponsePacket {
-    pub payload: StatusResponsePayload,
+packet_payload! {
+    #[derive(Debug)]
+    pub struct StatusResponsePacket {
+        pub payload: StatusResponsePayload => Json,
+    }
 }
 
 impl StatusResponsePacket {
@@ -38,22 +37,13 @@ impl StatusResponsePacket {
     pub fn new(payload: StatusResponsePayload) -> ClientboundPacket {
         ClientboundPacket::StatusResponse(StatusResponsePacket { payload })
     }
-
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
-        Ok(ClientboundPacket::StatusResponse(StatusResponsePacket {
-            payload: serde_json::from_str(&buffer.read_string()?)?,
-        }))
-    }
-
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        buffer.write_string(&serde_json::to_string(&self.payload)?)?;
-        Ok(())
-    }
 }
 
-#[derive(Debug)]
-pub struct PongPacket {
-    pub payload: i64,
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PongPacket {
+        pub payload: i64 => Long,
+    }
 }
 
 impl PongPacket {
@@ -61,15 +51,4 @@ impl PongPacket {
     pub fn new(payload: i64) -> ClientboundPacket {
         ClientboundPacket::Pong(PongPacket { payload })
     }
-
-    pub fn deserialize(buffer: &mut Buffer) -> io::Result<ClientboundPacket> {
-        Ok(ClientboundPacket::Pong(PongPacket {
-            payload: buffer.read_long()?,
-        }))
-    }
-
-    pub fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        buffer.write_long(self.payload)?;
-        Ok(())
-    }
 }
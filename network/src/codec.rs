@@ -0,0 +1,438 @@
+use cgmath::Vector3;
+use common::entity_type::EntityType;
+use common::item::ItemStack;
+use common::metadata::MetadataValue;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::io;
+use uuid::Uuid;
+
+use crate::buffer::Buffer;
+use crate::protocol::ProtocolVersion;
+
+/// Describes how a packet field of type `T` is laid out on the wire.
+///
+/// Codecs are zero-sized markers named after the protocol data types, so a
+/// packet field only has to state its Rust type and its wire type once.
+/// `payload_end` is the buffer position where the current packet ends.
+pub trait Codec<T> {
+    fn read(buffer: &mut Buffer, version: ProtocolVersion, payload_end: usize) -> io::Result<T>;
+    fn write(buffer: &mut Buffer, version: ProtocolVersion, value: &T) -> io::Result<()>;
+}
+
+/// A codec whose reads can be capped to a maximum length, in characters for
+/// strings and in bytes for byte arrays.
+pub trait BoundedCodec<T>: Codec<T> {
+    fn read_bounded(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        payload_end: usize,
+        max: usize,
+    ) -> io::Result<T>;
+}
+
+fn invalid_data<E: Display>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+macro_rules! primitive_codec {
+    ($codec:ident, $ty:ty, $read:ident, $write:ident) => {
+        pub struct $codec;
+
+        impl<T> Codec<T> for $codec
+        where
+            T: Copy + Into<$ty> + TryFrom<$ty>,
+            <T as TryFrom<$ty>>::Error: Display,
+        {
+            fn read(
+                buffer: &mut Buffer,
+                _version: ProtocolVersion,
+                _payload_end: usize,
+            ) -> io::Result<T> {
+                T::try_from(buffer.$read()?).map_err(invalid_data)
+            }
+
+            fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &T) -> io::Result<()> {
+                buffer.$write((*value).into())
+            }
+        }
+    };
+}
+
+primitive_codec!(Bool, bool, read_bool, write_bool);
+primitive_codec!(Byte, i8, read_byte, write_byte);
+primitive_codec!(UByte, u8, read_ubyte, write_ubyte);
+primitive_codec!(Short, i16, read_short, write_short);
+primitive_codec!(UShort, u16, read_ushort, write_ushort);
+primitive_codec!(Int, i32, read_int, write_int);
+primitive_codec!(UInt, u32, read_uint, write_uint);
+primitive_codec!(Long, i64, read_long, write_long);
+primitive_codec!(ULong, u64, read_ulong, write_ulong);
+primitive_codec!(Float, f32, read_float, write_float);
+primitive_codec!(Double, f64, read_double, write_double);
+primitive_codec!(VarInt, i32, read_varint, write_varint);
+primitive_codec!(VarLong, i64, read_varlong, write_varlong);
+
+pub struct Str;
+
+impl Codec<String> for Str {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<String> {
+        buffer.read_string()
+    }
+
+    fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &String) -> io::Result<()> {
+        buffer.write_string(value)
+    }
+}
+
+impl BoundedCodec<String> for Str {
+    fn read_bounded(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+        max: usize,
+    ) -> io::Result<String> {
+        buffer.read_string_max(max)
+    }
+}
+
+pub struct Chat;
+
+impl Codec<common::chat::Chat> for Chat {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<common::chat::Chat> {
+        buffer.read_chat()
+    }
+
+    fn write(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        value: &common::chat::Chat,
+    ) -> io::Result<()> {
+        buffer.write_chat(value)
+    }
+}
+
+/// Any serde value, sent as a JSON string.
+pub struct Json;
+
+impl<T: Serialize + DeserializeOwned> Codec<T> for Json {
+    fn read(buffer: &mut Buffer, _version: ProtocolVersion, _payload_end: usize) -> io::Result<T> {
+        Ok(serde_json::from_str(&buffer.read_string()?)?)
+    }
+
+    fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &T) -> io::Result<()> {
+        buffer.write_string(&serde_json::to_string(value)?)
+    }
+}
+
+pub struct Position;
+
+impl Codec<Vector3<i32>> for Position {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Vector3<i32>> {
+        buffer.read_position()
+    }
+
+    fn write(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        value: &Vector3<i32>,
+    ) -> io::Result<()> {
+        buffer.write_position(value)
+    }
+}
+
+/// A UUID sent as a 128-bit integer.
+pub struct UuidBytes;
+
+impl Codec<Uuid> for UuidBytes {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Uuid> {
+        buffer.read_uuid()
+    }
+
+    fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &Uuid) -> io::Result<()> {
+        buffer.write_uuid(value)
+    }
+}
+
+/// A UUID sent as its hyphenated string representation before 1.16, and as
+/// a 128-bit integer since.
+pub struct LegacyUuidString;
+
+impl Codec<Uuid> for LegacyUuidString {
+    fn read(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Uuid> {
+        match version {
+            ProtocolVersion::V1_15 => Uuid::parse_str(&buffer.read_string()?).map_err(invalid_data),
+            _ => buffer.read_uuid(),
+        }
+    }
+
+    fn write(buffer: &mut Buffer, version: ProtocolVersion, value: &Uuid) -> io::Result<()> {
+        match version {
+            ProtocolVersion::V1_15 => buffer.write_string(
+                value
+                    .to_hyphenated()
+                    .encode_lower(&mut Uuid::encode_buffer()),
+            ),
+            _ => buffer.write_uuid(value),
+        }
+    }
+}
+
+/// A byte array prefixed by its length as a VarInt.
+pub struct Bytes;
+
+impl Codec<Vec<u8>> for Bytes {
+    fn read(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        payload_end: usize,
+    ) -> io::Result<Vec<u8>> {
+        Self::read_bounded(buffer, version, payload_end, usize::MAX)
+    }
+
+    fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &Vec<u8>) -> io::Result<()> {
+        buffer.write_varint(value.len() as i32)?;
+        buffer.write_ubyte_array(value)
+    }
+}
+
+impl BoundedCodec<Vec<u8>> for Bytes {
+    fn read_bounded(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+        max: usize,
+    ) -> io::Result<Vec<u8>> {
+        let len = buffer.read_varint()?;
+
+        if len < 0 || len as usize > max {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Byte array of {} bytes is longer than {} bytes", len, max),
+            ));
+        }
+        buffer.read_ubyte_array(len as usize)
+    }
+}
+
+/// A byte array spanning the rest of the packet.
+pub struct RemainingBytes;
+
+impl Codec<Vec<u8>> for RemainingBytes {
+    fn read(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        payload_end: usize,
+    ) -> io::Result<Vec<u8>> {
+        Self::read_bounded(buffer, version, payload_end, usize::MAX)
+    }
+
+    fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &Vec<u8>) -> io::Result<()> {
+        buffer.write_ubyte_array(value)
+    }
+}
+
+impl BoundedCodec<Vec<u8>> for RemainingBytes {
+    fn read_bounded(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        payload_end: usize,
+        max: usize,
+    ) -> io::Result<Vec<u8>> {
+        match payload_end.checked_sub(buffer.cursor()) {
+            Some(len) if len > max => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Byte array of {} bytes is longer than {} bytes", len, max),
+            )),
+            Some(len) => buffer.read_ubyte_array(len),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Packet payload overflowed",
+            )),
+        }
+    }
+}
+
+/// An angle in degrees, sent in steps of 1/256 of a full turn.
+pub struct Angle;
+
+impl Codec<f32> for Angle {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<f32> {
+        Ok(f32::from(buffer.read_ubyte()?) * 360.0 / 256.0)
+    }
+
+    fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &f32) -> io::Result<()> {
+        // Wraps around, so negative angles are sent as their positive match
+        buffer.write_ubyte((value * 256.0 / 360.0).round() as i32 as u8)
+    }
+}
+
+/// An array of VarInts prefixed by its length as a VarInt.
+pub struct VarIntArray;
+
+impl Codec<Vec<i32>> for VarIntArray {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        payload_end: usize,
+    ) -> io::Result<Vec<i32>> {
+        let len = buffer.read_varint()?;
+
+        // Every VarInt takes at least a byte
+        if len < 0 || len as usize > payload_end.saturating_sub(buffer.cursor()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Array of {} VarInts is longer than the packet", len),
+            ));
+        }
+        buffer.read_array(Buffer::read_varint, len as usize)
+    }
+
+    fn write(buffer: &mut Buffer, _version: ProtocolVersion, value: &Vec<i32>) -> io::Result<()> {
+        buffer.write_varint(value.len() as i32)?;
+        buffer.write_array(|buffer, value| buffer.write_varint(*value), value)
+    }
+}
+
+/// Entity metadata, as (index, value) pairs followed by an end marker.
+pub struct Metadata;
+
+impl Codec<Vec<(u8, MetadataValue)>> for Metadata {
+    fn read(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Vec<(u8, MetadataValue)>> {
+        buffer.read_metadata(version)
+    }
+
+    fn write(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        value: &Vec<(u8, MetadataValue)>,
+    ) -> io::Result<()> {
+        buffer.write_metadata(value)
+    }
+}
+
+/// An item stack, or nothing for an empty slot.
+pub struct Slot;
+
+impl Codec<Option<ItemStack>> for Slot {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Option<ItemStack>> {
+        buffer.read_slot()
+    }
+
+    fn write(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        value: &Option<ItemStack>,
+    ) -> io::Result<()> {
+        buffer.write_slot(value.as_ref())
+    }
+}
+
+/// An array of slots prefixed by its length as a Short.
+pub struct SlotArray;
+
+impl Codec<Vec<Option<ItemStack>>> for SlotArray {
+    fn read(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        payload_end: usize,
+    ) -> io::Result<Vec<Option<ItemStack>>> {
+        let len = buffer.read_short()?;
+
+        // Every slot takes at least a byte
+        if len < 0 || len as usize > payload_end.saturating_sub(buffer.cursor()) {
+            return Err(invalid_data(format!(
+                "Array of {} slots is longer than the packet",
+                len
+            )));
+        }
+        buffer.read_array(Buffer::read_slot, len as usize)
+    }
+
+    fn write(
+        buffer: &mut Buffer,
+        _version: ProtocolVersion,
+        value: &Vec<Option<ItemStack>>,
+    ) -> io::Result<()> {
+        let len = i16::try_from(value.len()).map_err(invalid_data)?;
+
+        buffer.write_short(len)?;
+        buffer.write_array(|buffer, value| buffer.write_slot(value.as_ref()), value)
+    }
+}
+
+/// Ids of the entity types in the registry of each version.
+const ENTITY_TYPE_IDS: &[(EntityType, i32, i32)] = &[
+    (EntityType::Cow, 11, 11),
+    (EntityType::FallingBlock, 26, 26),
+    (EntityType::Item, 35, 37),
+    (EntityType::Zombie, 95, 101),
+];
+
+/// An entity type, sent as its id in the registry of the version.
+pub struct EntityTypeId;
+
+impl Codec<EntityType> for EntityTypeId {
+    fn read(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<EntityType> {
+        let id = buffer.read_varint()?;
+
+        ENTITY_TYPE_IDS
+            .iter()
+            .find(|(_, v1_15, v1_16)| match version {
+                ProtocolVersion::V1_15 => *v1_15 == id,
+                ProtocolVersion::V1_16 => *v1_16 == id,
+            })
+            .map(|(entity_type, _, _)| *entity_type)
+            .ok_or_else(|| invalid_data(format!("Unknown entity type {}", id)))
+    }
+
+    fn write(buffer: &mut Buffer, version: ProtocolVersion, value: &EntityType) -> io::Result<()> {
+        let (_, v1_15, v1_16) = ENTITY_TYPE_IDS
+            .iter()
+            .find(|(entity_type, _, _)| entity_type == value)
+            .ok_or_else(|| invalid_data(format!("Unknown entity type {:?}", value)))?;
+
+        buffer.write_varint(match version {
+            ProtocolVersion::V1_15 => *v1_15,
+            ProtocolVersion::V1_16 => *v1_16,
+        })
+    }
+}
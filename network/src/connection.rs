@@ -1,31 +1,48 @@
 use log::{debug, error, info};
 use openssl::pkey;
 use openssl::rsa::Rsa;
+use std::cell::RefCell;
 use std::io;
-use std::net::TcpListener;
+use std::net::{IpAddr, SocketAddr, TcpListener};
 use std::ops::DerefMut;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+use crate::capture::CaptureWriter;
+use crate::channel;
+use crate::channel::{BrandMessage, ChannelMessage, RegisterMessage, UnregisterMessage};
 use crate::clientbound;
 use crate::clientbound::ClientboundPacket;
+use crate::login_query::{LoginHook, DEFAULT_LOGIN_QUERY_TIMEOUT};
 use crate::mojang;
 use crate::packet::State;
+use crate::protocol::{find_version, latest_version, supported_versions_name};
+use crate::proxy;
+use crate::proxy::{ForwardedPlayer, ProxyForwarding};
 use crate::serverbound;
 use crate::serverbound::ServerboundPacket;
 use crate::stream::Stream;
+use crate::throttle::{LoginThrottle, ThrottleSettings};
 use crate::utils::rsa_decrypt;
 
-pub const PROTOCOL_VERSION: i32 = 701;
-pub const PROTOCOL_NAME: &str = "20w06a";
+/// Longest server address accepted in a handshake, unless BungeeCord
+/// forwarding appends its data to it.
+const MAX_ADDRESS_LENGTH: usize = 255;
 
 pub struct ConnectionHandler {
     streams: Arc<Mutex<Vec<Stream>>>,
     rsa: Rsa<pkey::Private>,
     rsa_pub_der: Vec<u8>,
+    proxy: ProxyForwarding,
+    login_hooks: Vec<Box<dyn LoginHook>>,
+    capture_dir: Option<PathBuf>,
+    online_mode: bool,
+    throttle: ThrottleSettings,
+    login_throttle: RefCell<LoginThrottle>,
 }
 
 impl ConnectionHandler {
@@ -38,20 +55,58 @@ impl ConnectionHandler {
             streams: Arc::new(Mutex::new(vec![])),
             rsa,
             rsa_pub_der,
+            proxy: ProxyForwarding::None,
+            login_hooks: vec![],
+            capture_dir: None,
+            online_mode: true,
+            throttle: ThrottleSettings::default(),
+            login_throttle: RefCell::new(LoginThrottle::new(
+                ThrottleSettings::default().login_delay,
+            )),
         }
     }
 
-    pub fn listen<T, P>(
+    pub fn set_proxy_forwarding(&mut self, proxy: ProxyForwarding) {
+        self.proxy = proxy;
+    }
+
+    /// Offline mode skips the Mojang authentication when no proxy is used,
+    /// trusting the username sent by the client.
+    pub fn set_online_mode(&mut self, online_mode: bool) {
+        self.online_mode = online_mode;
+    }
+
+    pub fn add_login_hook(&mut self, hook: Box<dyn LoginHook>) {
+        self.login_hooks.push(hook);
+    }
+
+    /// Per-IP limits are only applied when no proxy is used, as every
+    /// player then comes from the proxy address.
+    pub fn set_throttle(&mut self, throttle: ThrottleSettings) {
+        self.throttle = throttle;
+        self.login_throttle = RefCell::new(LoginThrottle::new(throttle.login_delay));
+    }
+
+    /// Records every new connection to its own capture file in `dir`.
+    pub fn set_capture_directory(&mut self, dir: Option<PathBuf>) {
+        self.capture_dir = dir;
+    }
+
+    /// Serves clients until `run` is cleared. `disconnect_cb` is called with
+    /// every stream about to be dropped.
+    pub fn listen<T, P, D>(
         &mut self,
         run: Arc<AtomicBool>,
         host: &str,
         port: u16,
         mut tick_cb: T,
         mut packet_cb: P,
+        mut disconnect_cb: D,
     ) -> io::Result<()>
     where
-        T: FnMut(),
+        T: FnMut(&mut [Stream]),
         P: FnMut(&mut Stream, &ServerboundPacket) -> io::Result<()>,
+        D: FnMut(&mut Stream),
     {
         let run_cpy = run.clone();
         thread::spawn({
@@ -59,6 +114,11 @@ impl ConnectionHandler {
             listener.set_nonblocking(true)?;
 
             let streams_cpy = Arc::clone(&self.streams);
+            let capture_dir = self.capture_dir.clone();
+            let max_connections_per_ip = match self.proxy {
+                ProxyForwarding::None => self.throttle.max_connections_per_ip,
+                _ => 0,
+            };
             info!("Listening on {}:{}.", host, port);
 
             move || {
@@ -66,9 +126,37 @@ impl ConnectionHandler {
                     for stream in listener.incoming() {
                         match stream {
                             Ok(s) => {
-                                streams_cpy.lock().unwrap().push(
-                                    Stream::new(s).expect("Failed to create a stream handle"),
-                                );
+                                // The client may already have reset the connection
+                                let peer = match s.peer_addr() {
+                                    Ok(peer) => peer,
+                                    Err(e) => {
+                                        debug!("Dropping a connection: {}", e);
+                                        continue;
+                                    }
+                                };
+                                if max_connections_per_ip > 0
+                                    && too_many_connections(
+                                        &streams_cpy,
+                                        peer.ip(),
+                                        max_connections_per_ip,
+                                    )
+                                {
+                                    continue;
+                                }
+
+                                let recorder = open_capture(&capture_dir, peer);
+                                let mut stream = match Stream::new(s, peer) {
+                                    Ok(stream) => stream,
+                                    Err(e) => {
+                                        error!("Failed to create a stream handle: {}", e);
+                                        continue;
+                                    }
+                                };
+
+                                if let Some(recorder) = recorder {
+                                    stream.set_recorder(recorder);
+                                }
+                                streams_cpy.lock().unwrap().push(stream);
                             }
                             Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
                                 continue;
@@ -89,42 +177,48 @@ impl ConnectionHandler {
 
                 let mut i = 0;
                 while i < streams_ref.len() {
-                    let remove = match streams_ref[i].read_packet() {
-                        Ok(packet) => {
+                    let stream = &mut streams_ref[i];
+                    let throttle = &self.throttle;
+                    let result = stream
+                        .check_limits(throttle.login_timeout, throttle.max_packets_per_second)
+                        .and_then(|_| stream.tick_keep_alive())
+                        .and_then(|_| loop {
+                            let packet = stream.read_packet()?;
                             debug!("Received packet: {:?}.", packet);
-                            match packet {
-                                ServerboundPacket::Handshake(ref p) => {
-                                    self.handle_handshake(&mut streams_ref[i], p)?;
-                                    packet_cb(&mut streams_ref[i], &packet)?
-                                }
-                                ServerboundPacket::LoginStart(ref p) => {
-                                    self.handle_login_start(&mut streams_ref[i], p)?;
-                                    packet_cb(&mut streams_ref[i], &packet)?
-                                }
-                                ServerboundPacket::EncryptionResponse(ref p) => {
-                                    self.handle_encryption_response(&mut streams_ref[i], p)?;
-                                    packet_cb(&mut streams_ref[i], &packet)?
-                                }
-                                _ => packet_cb(&mut streams_ref[i], &packet)?,
-                            }
-                            false
-                        }
+
+                            self.handle_packet(stream, &packet)?;
+                            packet_cb(stream, &packet)?;
+                            stream.check_limits(
+                                throttle.login_timeout,
+                                throttle.max_packets_per_second,
+                            )?;
+                        });
+
+                    let remove = match result {
                         Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => false,
+                        Err(ref e) if e.kind() == io::ErrorKind::ConnectionAborted => {
+                            debug!("{} closed the connection.", stream.get_remote_address());
+                            true
+                        }
                         Err(e) => {
                             error!("Disconnecting player: {}", e);
                             true
                         }
+                        Ok(()) => false,
                     };
 
                     if remove {
+                        disconnect_cb(stream);
                         streams_ref.remove(i);
                     } else {
                         i += 1;
                     }
                 }
+
+                tick_cb(streams_ref);
             }
 
-            tick_cb();
+            self.login_throttle.borrow_mut().cleanup();
             std::thread::sleep(Duration::from_millis(50));
         }
 
@@ -141,22 +235,88 @@ impl ConnectionHandler {
         Ok(())
     }
 
+    fn handle_packet(&self, stream: &mut Stream, packet: &ServerboundPacket) -> io::Result<()> {
+        let result = match packet {
+            ServerboundPacket::Handshake(ref p) => self.handle_handshake(stream, p),
+            ServerboundPacket::LoginStart(ref p) => self.handle_login_start(stream, p),
+            ServerboundPacket::EncryptionResponse(ref p) => {
+                self.handle_encryption_response(stream, p)
+            }
+            ServerboundPacket::LoginPluginResponse(ref p) => {
+                self.handle_login_plugin_response(stream, p)
+            }
+            ServerboundPacket::PluginMessage(ref p) => self.handle_plugin_message(stream, p),
+            ServerboundPacket::KeepAlive(ref p) => stream.resolve_keep_alive(p.id),
+            _ => Ok(()),
+        };
+
+        result?;
+        match stream.get_state() {
+            State::Login => self.try_login_success(stream),
+            _ => Ok(()),
+        }
+    }
+
     fn handle_handshake(
         &self,
         stream: &mut Stream,
         packet: &serverbound::handshake::HandshakePacket,
     ) -> io::Result<()> {
-        if packet.protocol != PROTOCOL_VERSION {
-            // TODO
-            Ok(())
-        } else {
-            if packet.next == 1 {
-                stream.set_state(State::Status);
+        if packet.address.len() > MAX_ADDRESS_LENGTH
+            && !matches!(self.proxy, ProxyForwarding::BungeeCord)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Server address is too long",
+            ));
+        }
+
+        let version = find_version(packet.protocol);
+
+        if let Some(version) = version {
+            stream.set_version(version);
+        }
+
+        if packet.next == 1 {
+            stream.set_state(State::Status);
+            return Ok(());
+        }
+
+        stream.set_state(State::Login);
+
+        if let ProxyForwarding::None = self.proxy {
+            let address = *stream.get_remote_address();
+
+            if !self.login_throttle.borrow_mut().try_login(address) {
+                return stream.disconnect("Connection throttled! Please wait before reconnecting.");
+            }
+        }
+
+        if version.is_none() {
+            return if packet.protocol > latest_version().protocol {
+                stream.disconnect(&format!(
+                    "Outdated server! I'm still on {}",
+                    supported_versions_name()
+                ))
             } else {
-                stream.set_state(State::Login);
+                stream.disconnect(&format!(
+                    "Outdated client! Please use {}",
+                    supported_versions_name()
+                ))
+            };
+        }
+
+        if let ProxyForwarding::BungeeCord = self.proxy {
+            match proxy::parse_bungeecord(&packet.address) {
+                Ok(player) => self.apply_forwarding(stream, player),
+                Err(_) => {
+                    return stream.disconnect(
+                        "If you wish to use IP forwarding, please enable it in your BungeeCord config as well!",
+                    )
+                }
             }
-            Ok(())
         }
+        Ok(())
     }
 
     fn handle_login_start(
@@ -166,13 +326,80 @@ impl ConnectionHandler {
     ) -> io::Result<()> {
         stream.set_username(&packet.username);
 
-        let res = clientbound::login::EncryptionRequestPacket::new(
-            "",
-            &self.rsa_pub_der,
-            stream.get_verify_challenge(),
-        );
+        match self.proxy {
+            ProxyForwarding::None if !self.online_mode => {
+                stream.set_uuid(mojang::offline_uuid(&packet.username));
+                self.authenticated(stream)
+            }
+            ProxyForwarding::None => {
+                let res = clientbound::login::EncryptionRequestPacket::new(
+                    "",
+                    &self.rsa_pub_der,
+                    stream.get_verify_challenge(),
+                );
 
-        stream.send_packet(&res)?;
+                stream.send_packet(&res)
+            }
+            ProxyForwarding::BungeeCord => self.authenticated(stream),
+            ProxyForwarding::Velocity { .. } => {
+                stream.send_login_query(
+                    proxy::VELOCITY_CHANNEL,
+                    &[],
+                    DEFAULT_LOGIN_QUERY_TIMEOUT,
+                )?;
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_login_plugin_response(
+        &self,
+        stream: &mut Stream,
+        packet: &serverbound::login::LoginPluginResponsePacket,
+    ) -> io::Result<()> {
+        let channel = match stream.resolve_login_query(packet.message_id) {
+            Some(channel) => channel,
+            None => return stream.disconnect("Unexpected login plugin response"),
+        };
+
+        match self.proxy {
+            ProxyForwarding::Velocity { ref secret } if channel == proxy::VELOCITY_CHANNEL => {
+                if !packet.successful {
+                    return stream.disconnect("This server requires you to connect with Velocity.");
+                }
+
+                match proxy::parse_velocity(&packet.data, secret) {
+                    Ok(player) => {
+                        self.apply_forwarding(stream, player);
+                        self.authenticated(stream)
+                    }
+                    Err(e) => stream.disconnect(&e.to_string()),
+                }
+            }
+            _ => {
+                for hook in &self.login_hooks {
+                    hook.on_response(stream, &channel, packet)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_plugin_message(
+        &self,
+        stream: &mut Stream,
+        packet: &serverbound::play::PluginMessagePacket,
+    ) -> io::Result<()> {
+        match packet.channel.as_str() {
+            channel::REGISTER_CHANNEL => {
+                stream.register_channels(&RegisterMessage::decode(&packet.data)?.channels)?
+            }
+            channel::UNREGISTER_CHANNEL => {
+                stream.unregister_channels(&UnregisterMessage::decode(&packet.data)?.channels)
+            }
+            channel::BRAND_CHANNEL => stream.set_brand(&BrandMessage::decode(&packet.data)?.brand),
+            _ => {}
+        }
         Ok(())
     }
 
@@ -199,19 +426,88 @@ impl ConnectionHandler {
                 Ok(profile) => {
                     stream.set_encryption_key(&decrypted_shared);
 
-                    let res_login = clientbound::login::LoginSuccessPacket::new(
-                        Uuid::parse_str(&profile.id).unwrap(),
-                        stream.get_username(),
-                    );
-
-                    stream.send_packet(&res_login)?;
-                    stream.set_state(State::Play);
-                    Ok(())
+                    match Uuid::parse_str(&profile.id) {
+                        Ok(uuid) => stream.set_uuid(uuid),
+                        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+                    }
+                    stream.set_properties(profile.properties);
+                    self.authenticated(stream)
                 }
                 Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
             }
         }
     }
+
+    fn apply_forwarding(&self, stream: &mut Stream, player: ForwardedPlayer) {
+        stream.set_remote_address(player.address);
+        stream.set_uuid(player.uuid);
+        stream.set_properties(player.properties);
+
+        if let Some(username) = player.username {
+            stream.set_username(&username);
+        }
+    }
+
+    fn authenticated(&self, stream: &mut Stream) -> io::Result<()> {
+        stream.set_authenticated(true);
+
+        for hook in &self.login_hooks {
+            hook.on_authenticated(stream)?;
+        }
+        Ok(())
+    }
+
+    fn try_login_success(&self, stream: &mut Stream) -> io::Result<()> {
+        if !stream.is_authenticated() || stream.has_pending_login_queries() {
+            return Ok(());
+        }
+
+        let res_login =
+            clientbound::login::LoginSuccessPacket::new(*stream.get_uuid(), stream.get_username());
+
+        stream.send_packet(&res_login)?;
+        stream.set_state(State::Play);
+        Ok(())
+    }
+}
+
+fn too_many_connections(streams: &Mutex<Vec<Stream>>, address: IpAddr, max: usize) -> bool {
+    let count = streams
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|stream| *stream.get_remote_address() == address)
+        .count();
+
+    if count >= max {
+        debug!(
+            "Refusing connection from {}, too many connections.",
+            address
+        );
+        true
+    } else {
+        false
+    }
+}
+
+fn open_capture(dir: &Option<PathBuf>, peer: SocketAddr) -> Option<CaptureWriter> {
+    let dir: &Path = dir.as_ref()?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!("{}-{}-{}.mcap", timestamp, peer.ip(), peer.port()));
+
+    match CaptureWriter::create(&path) {
+        Ok(recorder) => {
+            info!("Capturing {} to {}.", peer, path.display());
+            Some(recorder)
+        }
+        Err(e) => {
+            error!("Failed to create capture file {}: {}", path.display(), e);
+            None
+        }
+    }
 }
 
 impl Default for ConnectionHandler {
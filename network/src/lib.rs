@@ -1,8 +1,19 @@
+#[macro_use]
+mod macros;
+
 pub mod buffer;
+pub mod capture;
+pub mod channel;
+pub mod client;
 pub mod clientbound;
+pub mod codec;
 pub mod connection;
+pub mod login_query;
 pub mod mojang;
 pub mod packet;
+pub mod protocol;
+pub mod proxy;
 pub mod serverbound;
 pub mod stream;
+pub mod throttle;
 pub mod utils;
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::serverbound::login::LoginPluginResponsePacket;
+use crate::stream::Stream;
+
+pub const DEFAULT_LOGIN_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Lets server code talk to a client over login plugin channels before
+/// LoginSuccess is sent.
+///
+/// Queries sent with `Stream::send_login_query` hold the login back until
+/// they are answered or time out. A query that times out is answered as if
+/// the client did not understand the channel.
+pub trait LoginHook {
+    /// Called once the player is authenticated, before LoginSuccess is sent.
+    fn on_authenticated(&self, stream: &mut Stream) -> io::Result<()>;
+
+    /// Called with every answer to a query sent on `channel`.
+    fn on_response(
+        &self,
+        stream: &mut Stream,
+        channel: &str,
+        packet: &LoginPluginResponsePacket,
+    ) -> io::Result<()>;
+}
+
+#[derive(Debug)]
+struct PendingQuery {
+    channel: String,
+    deadline: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct LoginQueryTracker {
+    next_message_id: i32,
+    pending: HashMap<i32, PendingQuery>,
+}
+
+impl LoginQueryTracker {
+    pub fn new() -> Self {
+        Self {
+            next_message_id: 0,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, channel: &str, timeout: Duration) -> i32 {
+        let message_id = self.next_message_id;
+        self.next_message_id = self.next_message_id.wrapping_add(1);
+
+        self.pending.insert(
+            message_id,
+            PendingQuery {
+                channel: channel.to_string(),
+                deadline: Instant::now() + timeout,
+            },
+        );
+        message_id
+    }
+
+    /// Returns the channel of the answered query, if it was pending.
+    pub fn resolve(&mut self, message_id: i32) -> Option<String> {
+        self.pending.remove(&message_id).map(|query| query.channel)
+    }
+
+    pub fn next_expired(&self) -> Option<i32> {
+        let now = Instant::now();
+
+        self.pending
+            .iter()
+            .find(|(_, query)| query.deadline <= now)
+            .map(|(message_id, _)| *message_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
@@ -0,0 +1,202 @@
+/// Declares a packet struct along with its `PacketPayload` implementation.
+///
+/// Each field states its Rust type and the codec used on the wire. Fields are
+/// read and written in declaration order. Strings and byte arrays can be
+/// capped with a maximum length, past which reading fails.
+///
+/// ```ignore
+/// packet_payload! {
+///     #[derive(Debug)]
+///     pub struct LoginStartPacket {
+///         pub username: String => Str(16),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! packet_payload {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident {
+        $($(#[$fmeta:meta])* $fvis:vis $field:ident: $ty:ty => $codec:ident $(($max:expr))?),* $(,)*
+    }) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$fmeta])* $fvis $field: $ty,)*
+        }
+
+        impl $crate::packet::PacketPayload for $name {
+            #[allow(unused_variables)]
+            fn deserialize(
+                buffer: &mut $crate::buffer::Buffer,
+                version: $crate::protocol::ProtocolVersion,
+                payload_end: usize,
+            ) -> ::std::io::Result<Self> {
+                Ok(Self {
+                    $($field: $crate::read_field!(
+                        $ty,
+                        $codec,
+                        buffer,
+                        version,
+                        payload_end
+                        $(, $max)?
+                    )?,)*
+                })
+            }
+
+            #[allow(unused_variables)]
+            fn serialize(
+                &self,
+                buffer: &mut $crate::buffer::Buffer,
+                version: $crate::protocol::ProtocolVersion,
+            ) -> ::std::io::Result<()> {
+                $(<$crate::codec::$codec as $crate::codec::Codec<$ty>>::write(
+                    buffer,
+                    version,
+                    &self.$field,
+                )?;)*
+                Ok(())
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! read_field {
+    ($ty:ty, $codec:ident, $buffer:ident, $version:ident, $payload_end:ident) => {
+        <$crate::codec::$codec as $crate::codec::Codec<$ty>>::read($buffer, $version, $payload_end)
+    };
+    ($ty:ty, $codec:ident, $buffer:ident, $version:ident, $payload_end:ident, $max:expr) => {
+        <$crate::codec::$codec as $crate::codec::BoundedCodec<$ty>>::read_bounded(
+            $buffer,
+            $version,
+            $payload_end,
+            $max,
+        )
+    };
+}
+
+/// Declares the packet enum of one direction and implements `Packet` for it.
+///
+/// Packets are grouped by state, and each one declares its ID for every
+/// protocol version it exists in: the deserialization dispatch, `get_id` and
+/// `get_state` are all generated from this table.
+///
+/// ```ignore
+/// packet_enum! {
+///     pub enum ClientboundPacket {
+///         Status {
+///             Pong(status::PongPacket) { V1_15 => 0x1, V1_16 => 0x1 },
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! packet_enum {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident {
+        $($state:ident {
+            $($variant:ident($packet:ty) { $($version:ident => $id:literal),* $(,)* }),* $(,)*
+        })*
+    }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($($variant($packet),)*)*
+        }
+
+        $($(
+            impl From<$packet> for $name {
+                fn from(packet: $packet) -> Self {
+                    $name::$variant(packet)
+                }
+            }
+        )*)*
+
+        impl $crate::packet::Packet for $name {
+            fn deserialize(
+                buffer: &mut $crate::buffer::Buffer,
+                state: &$crate::packet::State,
+                version: $crate::protocol::ProtocolVersion,
+            ) -> ::std::io::Result<Self> {
+                let packet_len = buffer.read_varint()?;
+
+                if packet_len < 1 {
+                    return Err(::std::io::Error::new(
+                        ::std::io::ErrorKind::InvalidData,
+                        format!("Invalid packet length {}", packet_len),
+                    ));
+                }
+
+                if buffer.has_at_least(packet_len as usize) {
+                    let payload_end = buffer.cursor() + packet_len as usize;
+                    let packet_id = buffer.read_varint()?;
+
+                    #[allow(unreachable_patterns)]
+                    match *state {
+                        $($crate::packet::State::$state => {
+                            $($(
+                                if version == $crate::protocol::ProtocolVersion::$version
+                                    && packet_id == $id
+                                {
+                                    let packet =
+                                        <$packet as $crate::packet::PacketPayload>::deserialize(
+                                            buffer,
+                                            version,
+                                            payload_end,
+                                        )?;
+
+                                    if buffer.cursor() != payload_end {
+                                        return Err(::std::io::Error::new(
+                                            ::std::io::ErrorKind::InvalidData,
+                                            format!(
+                                                "{} declared {} bytes but used {}",
+                                                stringify!($variant),
+                                                packet_len,
+                                                packet_len as usize + buffer.cursor() - payload_end,
+                                            ),
+                                        ));
+                                    }
+                                    return Ok($name::$variant(packet));
+                                }
+                            )*)*
+                        })*
+                        _ => {}
+                    }
+
+                    buffer.set_cursor(payload_end);
+                    Err(::std::io::Error::new(
+                        ::std::io::ErrorKind::Other,
+                        $crate::packet::UnknownPacket { id: packet_id },
+                    ))
+                } else {
+                    Err(::std::io::Error::from(::std::io::ErrorKind::WouldBlock))
+                }
+            }
+
+            fn serialize(
+                &self,
+                buffer: &mut $crate::buffer::Buffer,
+                version: $crate::protocol::ProtocolVersion,
+            ) -> ::std::io::Result<()> {
+                match *self {
+                    $($($name::$variant(ref x) => {
+                        $crate::packet::PacketPayload::serialize(x, buffer, version)
+                    })*)*
+                }
+            }
+
+            fn get_id(&self, version: $crate::protocol::ProtocolVersion) -> Option<i32> {
+                match *self {
+                    $($($name::$variant(_) => match version {
+                        $($crate::protocol::ProtocolVersion::$version => Some($id),)*
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    },)*)*
+                }
+            }
+
+            fn get_state(&self) -> $crate::packet::State {
+                match *self {
+                    $($($name::$variant(_) => $crate::packet::State::$state,)*)*
+                }
+            }
+        }
+    };
+}
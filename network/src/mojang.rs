@@ -3,13 +3,32 @@ use reqwest;
 use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 use std::io;
+use std::time::Duration;
+use uuid::Uuid;
 
 const ENDPOINT: &str = "https://sessionserver.mojang.com/session/minecraft/hasJoined";
+const JOIN_ENDPOINT: &str = "https://sessionserver.mojang.com/session/minecraft/join";
+const PROFILE_ENDPOINT: &str = "https://api.mojang.com/users/profiles/minecraft";
+/// Profiles are looked up by commands on the tick thread, which a slow API
+/// mustn't hold for long.
+pub const PROFILE_LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileProperty {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Profile {
     pub id: String,
     pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<ProfileProperty>,
 }
 
 pub fn has_joined(
@@ -39,6 +58,90 @@ pub fn has_joined(
     }
 }
 
+/// Looks up the profile owning a username, without its properties.
+pub fn lookup_profile(username: &str) -> io::Result<Option<Profile>> {
+    let uri = format!("{}/{}", PROFILE_ENDPOINT, username);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(PROFILE_LOOKUP_TIMEOUT)
+        .build()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    match client.get(&uri).send() {
+        // Unknown usernames get an empty response
+        Ok(res) if res.status() == reqwest::StatusCode::NO_CONTENT => Ok(None),
+        Ok(res) => match res
+            .text()
+            .map(|text| serde_json::from_str::<Profile>(&text))
+        {
+            Ok(Ok(profile)) => Ok(Some(profile)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Failed to parse profile",
+            )),
+        },
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::Other,
+            "Failed to query profile",
+        )),
+    }
+}
+
+#[derive(Serialize)]
+struct JoinRequest<'a> {
+    #[serde(rename = "accessToken")]
+    access_token: &'a str,
+    #[serde(rename = "selectedProfile")]
+    selected_profile: String,
+    #[serde(rename = "serverId")]
+    server_id: String,
+}
+
+/// The client side of `has_joined`: tells the session server that this
+/// account is about to join the server identified by the given hash inputs.
+pub fn join(
+    access_token: &str,
+    profile_id: &Uuid,
+    server_id: &[u8],
+    shared_secret: &[u8],
+    server_key: &[u8],
+) -> io::Result<()> {
+    let request = JoinRequest {
+        access_token,
+        selected_profile: profile_id.to_simple().to_string(),
+        server_id: create_server_hash(server_id, shared_secret, server_key),
+    };
+
+    match reqwest::blocking::Client::new()
+        .post(JOIN_ENDPOINT)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&request)?)
+        .send()
+    {
+        Ok(res) if res.status().is_success() => Ok(()),
+        Ok(res) => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("Session server refused to join: {}", res.status()),
+        )),
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::Other,
+            "Failed to reach the session server",
+        )),
+    }
+}
+
+/// The UUID an offline-mode server gives to a player, as derived by vanilla
+/// from "OfflinePlayer:<username>" (a version 3 UUID).
+pub fn offline_uuid(username: &str) -> Uuid {
+    let name = format!("OfflinePlayer:{}", username);
+    let digest = hash::hash(MessageDigest::md5(), name.as_bytes()).unwrap();
+
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest);
+    bytes[6] = (bytes[6] & 0x0f) | 0x30;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    Uuid::from_bytes(bytes)
+}
+
 fn create_server_hash(server_id: &[u8], shared_secret: &[u8], key: &[u8]) -> String {
     let mut tmp = server_id.to_vec();
     tmp.extend(shared_secret);
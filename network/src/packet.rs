@@ -1,8 +1,12 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
 use std::io;
 
 use crate::buffer::Buffer;
+use crate::protocol::ProtocolVersion;
 
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum State {
     Handshake,
     Play,
@@ -10,9 +14,84 @@ pub enum State {
     Login,
 }
 
+impl State {
+    /// Largest frame a client may send in this state. Handshakes leave room
+    /// for BungeeCord forwarding, and login for a full plugin response.
+    pub fn max_frame_length(self) -> usize {
+        match self {
+            State::Handshake => 32_768,
+            State::Status => 64,
+            State::Login => 1_048_576 + 64,
+            State::Play => 2_097_151,
+        }
+    }
+}
+
+impl From<State> for u8 {
+    fn from(value: State) -> Self {
+        match value {
+            State::Handshake => 0,
+            State::Status => 1,
+            State::Login => 2,
+            State::Play => 3,
+        }
+    }
+}
+
+impl TryFrom<u8> for State {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(State::Handshake),
+            1 => Ok(State::Status),
+            2 => Ok(State::Login),
+            3 => Ok(State::Play),
+            _ => Err("Unknown State"),
+        }
+    }
+}
+
+/// Error for a packet missing from the packet tables. Its frame is skipped,
+/// so reading can go on with the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownPacket {
+    pub id: i32,
+}
+
+impl fmt::Display for UnknownPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown packet id 0x{:02X}", self.id)
+    }
+}
+
+impl Error for UnknownPacket {}
+
+impl UnknownPacket {
+    /// Returns the unknown packet an error is about, if it is one.
+    pub fn from_error(e: &io::Error) -> Option<Self> {
+        e.get_ref()?.downcast_ref::<UnknownPacket>().copied()
+    }
+}
+
 pub trait Packet: Sized {
-    fn deserialize(buffer: &mut Buffer, state: &State) -> io::Result<Self>;
-    fn serialize(&self, buffer: &mut Buffer) -> io::Result<()>;
-    fn get_id(&self) -> i32;
+    fn deserialize(
+        buffer: &mut Buffer,
+        state: &State,
+        version: ProtocolVersion,
+    ) -> io::Result<Self>;
+    fn serialize(&self, buffer: &mut Buffer, version: ProtocolVersion) -> io::Result<()>;
+    /// Returns `None` when the packet does not exist in the given version.
+    fn get_id(&self, version: ProtocolVersion) -> Option<i32>;
     fn get_state(&self) -> State;
 }
+
+/// The body of a single packet, without its length and ID.
+pub trait PacketPayload: Sized {
+    fn deserialize(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        payload_end: usize,
+    ) -> io::Result<Self>;
+    fn serialize(&self, buffer: &mut Buffer, version: ProtocolVersion) -> io::Result<()>;
+}
@@ -0,0 +1,64 @@
+/// Packet layout generations. Releases sharing the same packet IDs and field
+/// layouts map to the same variant.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ProtocolVersion {
+    V1_15,
+    V1_16,
+}
+
+#[derive(Debug)]
+pub struct SupportedVersion {
+    pub name: &'static str,
+    pub protocol: i32,
+    pub version: ProtocolVersion,
+}
+
+/// Every release a client may connect with, from the oldest to the newest.
+pub const SUPPORTED_VERSIONS: &[SupportedVersion] = &[
+    SupportedVersion {
+        name: "1.15",
+        protocol: 573,
+        version: ProtocolVersion::V1_15,
+    },
+    SupportedVersion {
+        name: "1.15.1",
+        protocol: 575,
+        version: ProtocolVersion::V1_15,
+    },
+    SupportedVersion {
+        name: "1.15.2",
+        protocol: 578,
+        version: ProtocolVersion::V1_15,
+    },
+    SupportedVersion {
+        name: "1.16",
+        protocol: 735,
+        version: ProtocolVersion::V1_16,
+    },
+    SupportedVersion {
+        name: "1.16.1",
+        protocol: 736,
+        version: ProtocolVersion::V1_16,
+    },
+];
+
+pub fn find_version(protocol: i32) -> Option<&'static SupportedVersion> {
+    SUPPORTED_VERSIONS
+        .iter()
+        .find(|version| version.protocol == protocol)
+}
+
+pub fn find_version_by_name(name: &str) -> Option<&'static SupportedVersion> {
+    SUPPORTED_VERSIONS
+        .iter()
+        .find(|version| version.name == name)
+}
+
+pub fn latest_version() -> &'static SupportedVersion {
+    &SUPPORTED_VERSIONS[SUPPORTED_VERSIONS.len() - 1]
+}
+
+/// A human readable range of the supported releases, such as "1.15-1.16.1".
+pub fn supported_versions_name() -> String {
+    format!("{}-{}", SUPPORTED_VERSIONS[0].name, latest_version().name)
+}
@@ -0,0 +1,128 @@
+use openssl::memcmp;
+use std::io;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+use crate::buffer::Buffer;
+use crate::mojang::ProfileProperty;
+use crate::utils::hmac_sha256;
+
+pub const VELOCITY_CHANNEL: &str = "velocity:player_info";
+const VELOCITY_FORWARDING_VERSION: i32 = 1;
+const VELOCITY_SIGNATURE_LENGTH: usize = 32;
+
+#[derive(Debug, Clone)]
+pub enum ProxyForwarding {
+    None,
+    BungeeCord,
+    Velocity { secret: String },
+}
+
+#[derive(Debug)]
+pub struct ForwardedPlayer {
+    pub address: IpAddr,
+    pub uuid: Uuid,
+    pub username: Option<String>,
+    pub properties: Vec<ProfileProperty>,
+}
+
+/// Parses the BungeeCord legacy forwarding data appended to the handshake's
+/// server address, formatted as `host\0ip\0uuid[\0properties]`.
+pub fn parse_bungeecord(address: &str) -> io::Result<ForwardedPlayer> {
+    let parts: Vec<&str> = address.split('\0').collect();
+
+    if parts.len() < 3 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Missing BungeeCord forwarding data",
+        ));
+    }
+
+    let address = match parts[1].parse::<IpAddr>() {
+        Ok(address) => address,
+        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    };
+
+    let uuid = match Uuid::parse_str(parts[2]) {
+        Ok(uuid) => uuid,
+        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    };
+
+    let properties = match parts.get(3) {
+        Some(json) => serde_json::from_str::<Vec<ProfileProperty>>(json)?,
+        None => vec![],
+    };
+
+    Ok(ForwardedPlayer {
+        address,
+        uuid,
+        username: None,
+        properties,
+    })
+}
+
+/// Verifies and parses the payload of a Velocity modern forwarding response.
+///
+/// The payload is prefixed by an HMAC-SHA256 signature of the remaining bytes,
+/// keyed with the secret shared between the proxy and the server.
+pub fn parse_velocity(data: &[u8], secret: &str) -> io::Result<ForwardedPlayer> {
+    if data.len() < VELOCITY_SIGNATURE_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Velocity forwarding data is too short",
+        ));
+    }
+
+    let (signature, payload) = data.split_at(VELOCITY_SIGNATURE_LENGTH);
+    let expected = hmac_sha256(secret.as_bytes(), payload)?;
+
+    if !memcmp::eq(signature, &expected) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Unable to verify player details",
+        ));
+    }
+
+    let mut buffer = Buffer::new();
+    buffer.extend(payload);
+
+    let version = buffer.read_varint()?;
+    if version != VELOCITY_FORWARDING_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported Velocity forwarding version {}", version),
+        ));
+    }
+
+    let address = match buffer.read_string()?.parse::<IpAddr>() {
+        Ok(address) => address,
+        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    };
+    let uuid = buffer.read_uuid()?;
+    let username = buffer.read_string()?;
+    let properties_count = buffer.read_varint()?;
+    let properties = buffer.read_array(read_velocity_property, properties_count as usize)?;
+
+    Ok(ForwardedPlayer {
+        address,
+        uuid,
+        username: Some(username),
+        properties,
+    })
+}
+
+fn read_velocity_property(buffer: &mut Buffer) -> io::Result<ProfileProperty> {
+    let name = buffer.read_string()?;
+    let value = buffer.read_string()?;
+    let signature = if buffer.read_bool()? {
+        Some(buffer.read_string()?)
+    } else {
+        None
+    };
+
+    Ok(ProfileProperty {
+        name,
+        value,
+        signature,
+    })
+}
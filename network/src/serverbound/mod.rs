@@ -1,104 +1,42 @@
-use std::io;
-
 pub mod handshake;
 pub mod login;
+pub mod play;
 pub mod status;
 
-use crate::buffer::Buffer;
-use crate::packet::{Packet, State};
-
-#[derive(Debug)]
-#[allow(clippy::large_enum_variant)]
-pub enum ServerboundPacket {
-    // Handshake
-    Handshake(handshake::HandshakePacket),
-
-    // Play
-
-    // Status
-    StatusRequest(status::StatusRequestPacket),
-    Ping(status::PingPacket),
-
-    // Login
-    LoginStart(login::LoginStartPacket),
-    EncryptionResponse(login::EncryptionResponsePacket),
-}
-
-impl Packet for ServerboundPacket {
-    fn deserialize(buffer: &mut Buffer, state: &State) -> io::Result<Self> {
-        let packet_len = buffer.read_varint()?;
-
-        if buffer.has_at_least(packet_len as usize) {
-            let cursor_before = buffer.cursor();
-            let packet_id = buffer.read_varint()?;
-            let _payload_size = (packet_len as usize) - (buffer.cursor() - cursor_before);
-
-            match *state {
-                State::Handshake => match packet_id {
-                    0x0 => handshake::HandshakePacket::deserialize(buffer),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-                State::Play => match packet_id {
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-                State::Status => match packet_id {
-                    0x0 => status::StatusRequestPacket::deserialize(buffer),
-                    0x1 => status::PingPacket::deserialize(buffer),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-                State::Login => match packet_id {
-                    0x0 => login::LoginStartPacket::deserialize(buffer),
-                    0x1 => login::EncryptionResponsePacket::deserialize(buffer),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "Unknown packet id")),
-                },
-            }
-        } else {
-            Err(io::Error::from(io::ErrorKind::WouldBlock))
+packet_enum! {
+    #[derive(Debug)]
+    #[allow(clippy::large_enum_variant)]
+    pub enum ServerboundPacket {
+        Handshake {
+            Handshake(handshake::HandshakePacket) { V1_15 => 0x0, V1_16 => 0x0 },
         }
-    }
-
-    fn serialize(&self, buffer: &mut Buffer) -> io::Result<()> {
-        match *self {
-            // Handsha;e
-            ServerboundPacket::Handshake(ref x) => x.serialize(buffer),
-
-            // Status
-            ServerboundPacket::StatusRequest(ref x) => x.serialize(buffer),
-            ServerboundPacket::Ping(ref x) => x.serialize(buffer),
-
-            // Login
-            ServerboundPacket::LoginStart(ref x) => x.serialize(buffer),
-            ServerboundPacket::EncryptionResponse(ref x) => x.serialize(buffer),
+        Play {
+            TeleportConfirm(play::TeleportConfirmPacket) { V1_15 => 0x00, V1_16 => 0x00 },
+            ChatMessage(play::ChatMessagePacket) { V1_15 => 0x03, V1_16 => 0x03 },
+            ClientStatus(play::ClientStatusPacket) { V1_15 => 0x04, V1_16 => 0x04 },
+            WindowConfirmation(play::WindowConfirmationPacket) { V1_15 => 0x07, V1_16 => 0x07 },
+            ClickWindow(play::ClickWindowPacket) { V1_15 => 0x09, V1_16 => 0x09 },
+            CloseWindow(play::CloseWindowPacket) { V1_15 => 0x0A, V1_16 => 0x0A },
+            PluginMessage(play::PluginMessagePacket) { V1_15 => 0x0B, V1_16 => 0x0B },
+            InteractEntity(play::InteractEntityPacket) { V1_15 => 0x0E, V1_16 => 0x0E },
+            KeepAlive(play::KeepAlivePacket) { V1_15 => 0x0F, V1_16 => 0x10 },
+            PlayerPosition(play::PlayerPositionPacket) { V1_15 => 0x11, V1_16 => 0x12 },
+            PlayerPositionAndRotation(play::PlayerPositionAndRotationPacket) { V1_15 => 0x12, V1_16 => 0x13 },
+            PlayerRotation(play::PlayerRotationPacket) { V1_15 => 0x13, V1_16 => 0x14 },
+            PlayerMovement(play::PlayerMovementPacket) { V1_15 => 0x14, V1_16 => 0x15 },
+            PlayerDigging(play::PlayerDiggingPacket) { V1_15 => 0x1A, V1_16 => 0x1B },
+            EntityAction(play::EntityActionPacket) { V1_15 => 0x1B, V1_16 => 0x1C },
+            HeldItemChange(play::HeldItemChangePacket) { V1_15 => 0x23, V1_16 => 0x24 },
+            Animation(play::AnimationPacket) { V1_15 => 0x2A, V1_16 => 0x2B },
         }
-    }
-
-    fn get_id(&self) -> i32 {
-        match *self {
-            // Handshake
-            ServerboundPacket::Handshake(_) => 0x0,
-
-            // Status
-            ServerboundPacket::StatusRequest(_) => 0x0,
-            ServerboundPacket::Ping(_) => 0x1,
-
-            // Login
-            ServerboundPacket::LoginStart(_) => 0x0,
-            ServerboundPacket::EncryptionResponse(_) => 0x1,
+        Status {
+            StatusRequest(status::StatusRequestPacket) { V1_15 => 0x0, V1_16 => 0x0 },
+            Ping(status::PingPacket) { V1_15 => 0x1, V1_16 => 0x1 },
         }
-    }
-
-    fn get_state(&self) -> State {
-        match *self {
-            // Handshake
-            ServerboundPacket::Handshake(_) => State::Handshake,
-
-            // Status
-            ServerboundPacket::StatusRequest(_) => State::Status,
-            ServerboundPacket::Ping(_) => State::Status,
-
-            // Login
-            ServerboundPacket::LoginStart(_) => State::Login,
-            ServerboundPacket::EncryptionResponse(_) => State::Login,
+        Login {
+            LoginStart(login::LoginStartPacket) { V1_15 => 0x0, V1_16 => 0x0 },
+            EncryptionResponse(login::EncryptionResponsePacket) { V1_15 => 0x1, V1_16 => 0x1 },
+            LoginPluginResponse(login::LoginPluginResponsePacket) { V1_15 => 0x2, V1_16 => 0x2 },
         }
     }
 }
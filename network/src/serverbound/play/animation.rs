@@ -0,0 +1,7 @@
+packet_payload! {
+    /// Arm swing, of the main hand (0) or the off hand (1).
+    #[derive(Debug)]
+    pub struct AnimationPacket {
+        pub hand: i32 => VarInt,
+    }
+}
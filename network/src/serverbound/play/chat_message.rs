@@ -0,0 +1,6 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct ChatMessagePacket {
+        pub message: String => Str(256),
+    }
+}
@@ -0,0 +1,15 @@
+use common::item::ItemStack;
+
+packet_payload! {
+    /// `mode` and `button` tell what the click does, `clicked_item` being
+    /// the stack the client expects the click to return.
+    #[derive(Debug)]
+    pub struct ClickWindowPacket {
+        pub window_id: u8 => UByte,
+        pub slot: i16 => Short,
+        pub button: i8 => Byte,
+        pub action_number: i16 => Short,
+        pub mode: i32 => VarInt,
+        pub clicked_item: Option<ItemStack> => Slot,
+    }
+}
@@ -0,0 +1,7 @@
+packet_payload! {
+    /// `action` is 0 to respawn after dying, 1 to ask for the statistics.
+    #[derive(Debug)]
+    pub struct ClientStatusPacket {
+        pub action: i32 => VarInt,
+    }
+}
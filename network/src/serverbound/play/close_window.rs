@@ -0,0 +1,6 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct CloseWindowPacket {
+        pub window_id: u8 => UByte,
+    }
+}
@@ -0,0 +1,10 @@
+packet_payload! {
+    /// `action` goes from 0 (start sneaking) to 8 (start flying with an
+    /// elytra), `jump_boost` only being set for horse jumps.
+    #[derive(Debug)]
+    pub struct EntityActionPacket {
+        pub entity_id: i32 => VarInt,
+        pub action: i32 => VarInt,
+        pub jump_boost: i32 => VarInt,
+    }
+}
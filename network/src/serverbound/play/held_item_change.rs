@@ -0,0 +1,6 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct HeldItemChangePacket {
+        pub slot: i16 => Short,
+    }
+}
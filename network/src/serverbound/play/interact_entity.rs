@@ -0,0 +1,94 @@
+use std::io;
+
+use crate::buffer::Buffer;
+use crate::packet::PacketPayload;
+use crate::protocol::ProtocolVersion;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InteractAction {
+    /// Right click with a hand, 0 being the main one.
+    Interact {
+        hand: i32,
+    },
+    Attack,
+    /// Right click at a point of the entity, relative to its position.
+    InteractAt {
+        target: (f32, f32, f32),
+        hand: i32,
+    },
+}
+
+/// Attacks or uses an entity, by its network id.
+#[derive(Debug)]
+pub struct InteractEntityPacket {
+    pub entity_id: i32,
+    pub action: InteractAction,
+    /// Only sent since 1.16.
+    pub sneaking: bool,
+}
+
+impl PacketPayload for InteractEntityPacket {
+    fn deserialize(
+        buffer: &mut Buffer,
+        version: ProtocolVersion,
+        _payload_end: usize,
+    ) -> io::Result<Self> {
+        let entity_id = buffer.read_varint()?;
+        let action = match buffer.read_varint()? {
+            0 => InteractAction::Interact {
+                hand: buffer.read_varint()?,
+            },
+            1 => InteractAction::Attack,
+            2 => InteractAction::InteractAt {
+                target: (
+                    buffer.read_float()?,
+                    buffer.read_float()?,
+                    buffer.read_float()?,
+                ),
+                hand: buffer.read_varint()?,
+            },
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Unknown interaction",
+                ))
+            }
+        };
+        let sneaking = match version {
+            ProtocolVersion::V1_15 => false,
+            _ => buffer.read_bool()?,
+        };
+
+        Ok(InteractEntityPacket {
+            entity_id,
+            action,
+            sneaking,
+        })
+    }
+
+    fn serialize(&self, buffer: &mut Buffer, version: ProtocolVersion) -> io::Result<()> {
+        buffer.write_varint(self.entity_id)?;
+        match self.action {
+            InteractAction::Interact { hand } => {
+                buffer.write_varint(0)?;
+                buffer.write_varint(hand)?;
+            }
+            InteractAction::Attack => buffer.write_varint(1)?,
+            InteractAction::InteractAt {
+                target: (x, y, z),
+                hand,
+            } => {
+                buffer.write_varint(2)?;
+                buffer.write_float(x)?;
+                buffer.write_float(y)?;
+                buffer.write_float(z)?;
+                buffer.write_varint(hand)?;
+            }
+        }
+
+        if version != ProtocolVersion::V1_15 {
+            buffer.write_bool(self.sneaking)?;
+        }
+        Ok(())
+    }
+}
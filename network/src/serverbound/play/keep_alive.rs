@@ -0,0 +1,6 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct KeepAlivePacket {
+        pub id: i64 => Long,
+    }
+}
@@ -0,0 +1,35 @@
+mod animation;
+mod chat_message;
+mod click_window;
+mod client_status;
+mod close_window;
+mod entity_action;
+mod held_item_change;
+mod interact_entity;
+mod keep_alive;
+mod player_digging;
+mod player_movement;
+mod player_position;
+mod player_position_and_rotation;
+mod player_rotation;
+mod plugin_message;
+mod teleport_confirm;
+mod window_confirmation;
+
+pub use animation::AnimationPacket;
+pub use chat_message::ChatMessagePacket;
+pub use click_window::ClickWindowPacket;
+pub use client_status::ClientStatusPacket;
+pub use close_window::CloseWindowPacket;
+pub use entity_action::EntityActionPacket;
+pub use held_item_change::HeldItemChangePacket;
+pub use interact_entity::{InteractAction, InteractEntityPacket};
+pub use keep_alive::KeepAlivePacket;
+pub use player_digging::PlayerDiggingPacket;
+pub use player_movement::PlayerMovementPacket;
+pub use player_position::PlayerPositionPacket;
+pub use player_position_and_rotation::PlayerPositionAndRotationPacket;
+pub use player_rotation::PlayerRotationPacket;
+pub use plugin_message::PluginMessagePacket;
+pub use teleport_confirm::TeleportConfirmPacket;
+pub use window_confirmation::WindowConfirmationPacket;
@@ -0,0 +1,12 @@
+use cgmath::Vector3;
+
+packet_payload! {
+    /// `status` goes from 0 (started digging) to 6 (swap item in hand), a
+    /// block being broken once it is 2 (finished digging).
+    #[derive(Debug)]
+    pub struct PlayerDiggingPacket {
+        pub status: i32 => VarInt,
+        pub location: Vector3<i32> => Position,
+        pub face: i8 => Byte,
+    }
+}
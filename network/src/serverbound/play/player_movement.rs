@@ -0,0 +1,6 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PlayerMovementPacket {
+        pub on_ground: bool => Bool,
+    }
+}
@@ -0,0 +1,9 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PlayerPositionPacket {
+        pub x: f64 => Double,
+        pub y: f64 => Double,
+        pub z: f64 => Double,
+        pub on_ground: bool => Bool,
+    }
+}
@@ -0,0 +1,11 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PlayerPositionAndRotationPacket {
+        pub x: f64 => Double,
+        pub y: f64 => Double,
+        pub z: f64 => Double,
+        pub yaw: f32 => Float,
+        pub pitch: f32 => Float,
+        pub on_ground: bool => Bool,
+    }
+}
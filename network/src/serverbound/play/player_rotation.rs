@@ -0,0 +1,8 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PlayerRotationPacket {
+        pub yaw: f32 => Float,
+        pub pitch: f32 => Float,
+        pub on_ground: bool => Bool,
+    }
+}
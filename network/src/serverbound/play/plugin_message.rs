@@ -0,0 +1,7 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct PluginMessagePacket {
+        pub channel: String => Str(32767),
+        pub data: Vec<u8> => RemainingBytes(32767),
+    }
+}
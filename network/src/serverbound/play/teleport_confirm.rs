@@ -0,0 +1,6 @@
+packet_payload! {
+    #[derive(Debug)]
+    pub struct TeleportConfirmPacket {
+        pub teleport_id: i32 => VarInt,
+    }
+}
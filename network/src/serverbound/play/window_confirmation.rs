@@ -0,0 +1,9 @@
+packet_payload! {
+    /// Sent back after a rejected click, once the client undid it.
+    #[derive(Debug)]
+    pub struct WindowConfirmationPacket {
+        pub window_id: i8 => Byte,
+        pub action_number: i16 => Short,
+        pub accepted: bool => Bool,
+    }
+}
@@ -1,78 +1,324 @@
+use common::chat::Chat;
+use log::{debug, error};
 use openssl::symm;
 use rand::thread_rng;
 use rand::Rng;
+use std::collections::HashSet;
 use std::io;
 use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
 
 use crate::buffer::Buffer;
+use crate::capture::{CaptureWriter, Direction};
+use crate::channel::{ChannelMessage, MAX_CHANNELS};
+use crate::clientbound;
 use crate::clientbound::ClientboundPacket;
-use crate::packet::{Packet, State};
+use crate::login_query::LoginQueryTracker;
+use crate::mojang::ProfileProperty;
+use crate::packet::{Packet, State, UnknownPacket};
+use crate::protocol::{latest_version, ProtocolVersion, SupportedVersion};
+use crate::serverbound;
 use crate::serverbound::ServerboundPacket;
+use crate::throttle::PacketRate;
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(0);
 
 pub struct Stream {
+    id: u64,
     handle: TcpStream,
     buf: Buffer,
     state: State,
+    version: &'static SupportedVersion,
     verify_challenge: [u8; 4],
     in_cipher: Option<symm::Crypter>,
     out_cipher: Option<symm::Crypter>,
     username: String,
+    uuid: Uuid,
+    properties: Vec<ProfileProperty>,
+    remote_address: IpAddr,
+    authenticated: bool,
+    login_queries: LoginQueryTracker,
+    channels: HashSet<String>,
+    brand: Option<String>,
+    recorder: Option<CaptureWriter>,
+    keep_alive: Option<(i64, Instant)>,
+    last_keep_alive: Instant,
+    latency: Duration,
+    connected_at: Instant,
+    packet_rate: PacketRate,
+    closed: bool,
+    kicked: Option<String>,
 }
 
 impl Stream {
-    pub fn new(handle: TcpStream) -> io::Result<Self> {
+    pub fn new(handle: TcpStream, peer: SocketAddr) -> io::Result<Self> {
         handle.set_nonblocking(true)?;
+
         Ok(Self {
+            id: NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed),
             handle,
             buf: Buffer::new(),
             state: State::Handshake,
+            version: latest_version(),
             verify_challenge: thread_rng().gen::<[u8; 4]>(),
             in_cipher: None,
             out_cipher: None,
             username: String::new(),
+            uuid: Uuid::nil(),
+            properties: vec![],
+            remote_address: peer.ip(),
+            authenticated: false,
+            login_queries: LoginQueryTracker::new(),
+            channels: HashSet::new(),
+            brand: None,
+            recorder: None,
+            keep_alive: None,
+            last_keep_alive: Instant::now(),
+            latency: Duration::from_secs(0),
+            connected_at: Instant::now(),
+            packet_rate: PacketRate::new(),
+            closed: false,
+            kicked: None,
         })
     }
 
     pub fn read_packet(&mut self) -> io::Result<ServerboundPacket> {
+        if let Some(reason) = &self.kicked {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, reason.clone()));
+        }
+
+        if let Some(message_id) = self.login_queries.next_expired() {
+            // An unanswered query is reported as not understood by the client
+            return Ok(ServerboundPacket::LoginPluginResponse(
+                serverbound::login::LoginPluginResponsePacket {
+                    message_id,
+                    successful: false,
+                    data: vec![],
+                },
+            ));
+        }
+
         self.read()?;
-        if !self.buf.is_empty() {
+        loop {
+            if !self.has_frame()? {
+                return Err(self.nothing_to_read());
+            }
+
             self.buf.reset_cursor();
-            match ServerboundPacket::deserialize(&mut self.buf, &self.state) {
+            let result =
+                ServerboundPacket::deserialize(&mut self.buf, &self.state, self.version.version);
+            let unknown = result.as_ref().err().and_then(UnknownPacket::from_error);
+
+            match result {
                 Ok(packet) => {
-                    self.buf.drain(0..self.buf.cursor());
-                    Ok(packet)
+                    self.consume_frame();
+                    return Ok(packet);
+                }
+                // Clients send plenty of play packets which aren't handled
+                Err(_) if unknown.is_some() && self.state == State::Play => {
+                    debug!("Skipping {}.", unknown.unwrap());
+                    self.consume_frame();
+                }
+                // The frame is complete, so running out of bytes means its
+                // payload is malformed
+                Err(e) => {
+                    let e = if e.kind() == io::ErrorKind::UnexpectedEof
+                        || e.kind() == io::ErrorKind::WouldBlock
+                    {
+                        io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+                    } else {
+                        e
+                    };
+                    self.disconnect(&format!("Bad packet: {}", e))?;
+                    return Err(e);
                 }
-                Err(e) => Err(e),
             }
-        } else {
-            Err(io::Error::from(io::ErrorKind::WouldBlock))
         }
     }
 
     pub fn send_packet(&mut self, packet: &ClientboundPacket) -> io::Result<()> {
+        let packet_id = match packet.get_id(self.version.version) {
+            Some(packet_id) => packet_id,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{:?} does not exist in {}", packet, self.version.name),
+                ))
+            }
+        };
+
         let mut out_buf = Buffer::new();
-        out_buf.write_varint(packet.get_id())?;
-        packet.serialize(&mut out_buf)?;
+        out_buf.write_varint(packet_id)?;
+        packet.serialize(&mut out_buf, self.version.version)?;
 
+        debug!("Sending packet: {:?}.", packet);
+        self.send_frame(packet.get_state(), &out_buf)
+    }
+
+    /// Sends a packet built by someone else, such as a plugin, for the
+    /// current state and the protocol version of the client.
+    pub fn send_raw_packet(&mut self, packet_id: i32, data: &[u8]) -> io::Result<()> {
+        let mut out_buf = Buffer::new();
+        out_buf.write_varint(packet_id)?;
+        out_buf.extend(data);
+
+        debug!("Sending raw packet 0x{:02X}.", packet_id);
+        self.send_frame(self.state, &out_buf)
+    }
+
+    fn send_frame(&mut self, state: State, payload: &Buffer) -> io::Result<()> {
         let mut final_out_buf = Buffer::new();
-        final_out_buf.write_varint(out_buf.len() as i32)?;
-        final_out_buf.extend(out_buf.as_raw());
+        final_out_buf.write_varint(payload.len() as i32)?;
+        final_out_buf.extend(payload.as_raw());
 
-        println!("{:?}", final_out_buf);
+        self.record(Direction::Outbound, state, final_out_buf.as_raw());
 
         match &mut self.out_cipher {
             Some(cipher) => {
                 let mut tmp = vec![0; final_out_buf.len() + 16];
-                let n = cipher.update(&final_out_buf.as_raw(), &mut tmp)?;
+                let n = cipher.update(final_out_buf.as_raw(), &mut tmp)?;
                 self.handle.write_all(&tmp[..n])?
             }
-            None => self.handle.write_all(&final_out_buf.as_raw())?,
+            None => self.handle.write_all(final_out_buf.as_raw())?,
         };
 
         Ok(())
     }
 
+    pub fn send_channel_message<M: ChannelMessage>(&mut self, message: &M) -> io::Result<()> {
+        self.send_packet(&clientbound::play::PluginMessagePacket::new(
+            M::CHANNEL,
+            &message.encode()?,
+        ))
+    }
+
+    pub fn send_login_query(
+        &mut self,
+        channel: &str,
+        data: &[u8],
+        timeout: Duration,
+    ) -> io::Result<i32> {
+        let message_id = self.login_queries.register(channel, timeout);
+
+        self.send_packet(&clientbound::login::LoginPluginRequestPacket::new(
+            message_id, channel, data,
+        ))?;
+        Ok(message_id)
+    }
+
+    pub fn resolve_login_query(&mut self, message_id: i32) -> Option<String> {
+        self.login_queries.resolve(message_id)
+    }
+
+    pub fn has_pending_login_queries(&self) -> bool {
+        !self.login_queries.is_empty()
+    }
+
+    /// Sends a keep-alive every few seconds while playing, and drops the
+    /// client if it didn't answer the previous one in time.
+    pub fn tick_keep_alive(&mut self) -> io::Result<()> {
+        if self.state != State::Play {
+            return Ok(());
+        }
+
+        match self.keep_alive {
+            Some((_, sent)) if sent.elapsed() > KEEP_ALIVE_TIMEOUT => self.disconnect("Timed out"),
+            Some(_) => Ok(()),
+            None if self.last_keep_alive.elapsed() >= KEEP_ALIVE_INTERVAL => {
+                let id = match SystemTime::now().duration_since(UNIX_EPOCH) {
+                    Ok(elapsed) => elapsed.as_millis() as i64,
+                    Err(_) => 0,
+                };
+
+                self.send_packet(&clientbound::play::KeepAlivePacket::new(id))?;
+                self.keep_alive = Some((id, Instant::now()));
+                self.last_keep_alive = Instant::now();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn resolve_keep_alive(&mut self, id: i64) -> io::Result<()> {
+        match self.keep_alive {
+            Some((expected, sent)) if expected == id => {
+                self.latency = sent.elapsed();
+                self.keep_alive = None;
+                Ok(())
+            }
+            _ => self.disconnect("Invalid keep-alive"),
+        }
+    }
+
+    /// Drops the client if it is still not playing after `timeout`, or sent
+    /// more than `max_packets_per_second` packets over the last second.
+    pub fn check_limits(
+        &mut self,
+        timeout: Duration,
+        max_packets_per_second: u32,
+    ) -> io::Result<()> {
+        if self.state == State::Play {
+            if max_packets_per_second > 0 && self.packet_rate.current() > max_packets_per_second {
+                return self.disconnect("You are sending too many packets!");
+            }
+        } else if timeout > Duration::from_secs(0) && self.connected_at.elapsed() > timeout {
+            return self.disconnect("Took too long to log in");
+        }
+        Ok(())
+    }
+
+    /// Round-trip time of the last answered keep-alive.
+    pub fn get_latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Sends the disconnect packet matching the current state, and returns
+    /// the error which should be propagated to drop the stream.
+    pub fn disconnect(&mut self, reason: &str) -> io::Result<()> {
+        match self.state {
+            State::Login => {
+                self.send_packet(&clientbound::login::DisconnectPacket::new(Chat::new_text(
+                    reason,
+                )))?;
+            }
+            State::Play => {
+                self.send_packet(&clientbound::play::DisconnectPlayPacket::new(
+                    Chat::new_text(reason),
+                ))?;
+            }
+            _ => {}
+        }
+
+        Err(io::Error::new(io::ErrorKind::InvalidData, reason))
+    }
+
+    /// Disconnects the client from outside the packet handlers, the stream
+    /// is dropped the next time it is read.
+    pub fn kick(&mut self, reason: &str) {
+        let _ = self.disconnect(reason);
+        self.kicked = Some(reason.to_string());
+    }
+
+    pub fn is_kicked(&self) -> bool {
+        self.kicked.is_some()
+    }
+
+    /// Identifies the connection until the server stops.
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    /// Starts recording every frame going through this stream.
+    pub fn set_recorder(&mut self, recorder: CaptureWriter) {
+        self.recorder = Some(recorder);
+    }
+
     pub fn set_state(&mut self, state: State) {
         self.state = state;
     }
@@ -81,6 +327,18 @@ impl Stream {
         &self.state
     }
 
+    pub fn set_version(&mut self, version: &'static SupportedVersion) {
+        self.version = version;
+    }
+
+    pub fn get_version(&self) -> &'static SupportedVersion {
+        self.version
+    }
+
+    pub fn get_protocol_version(&self) -> ProtocolVersion {
+        self.version.version
+    }
+
     pub fn get_verify_challenge(&self) -> &[u8; 4] {
         &self.verify_challenge
     }
@@ -93,6 +351,71 @@ impl Stream {
         &self.username
     }
 
+    pub fn set_authenticated(&mut self, authenticated: bool) {
+        self.authenticated = authenticated;
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    /// Disconnects the client if it goes over `MAX_CHANNELS`.
+    pub fn register_channels(&mut self, channels: &[String]) -> io::Result<()> {
+        for channel in channels {
+            if self.channels.len() >= MAX_CHANNELS && !self.channels.contains(channel) {
+                return self.disconnect("Too many channels registered");
+            }
+            self.channels.insert(channel.clone());
+        }
+        Ok(())
+    }
+
+    pub fn unregister_channels(&mut self, channels: &[String]) {
+        for channel in channels {
+            self.channels.remove(channel);
+        }
+    }
+
+    pub fn is_channel_registered(&self, channel: &str) -> bool {
+        self.channels.contains(channel)
+    }
+
+    pub fn get_channels(&self) -> &HashSet<String> {
+        &self.channels
+    }
+
+    pub fn set_brand(&mut self, brand: &str) {
+        self.brand = Some(brand.to_string());
+    }
+
+    pub fn get_brand(&self) -> Option<&String> {
+        self.brand.as_ref()
+    }
+
+    pub fn set_uuid(&mut self, uuid: Uuid) {
+        self.uuid = uuid;
+    }
+
+    pub fn get_uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn set_properties(&mut self, properties: Vec<ProfileProperty>) {
+        self.properties = properties;
+    }
+
+    pub fn get_properties(&self) -> &[ProfileProperty] {
+        &self.properties
+    }
+
+    pub fn set_remote_address(&mut self, address: IpAddr) {
+        self.remote_address = address;
+    }
+
+    pub fn get_remote_address(&self) -> &IpAddr {
+        &self.remote_address
+    }
+
     pub fn set_encryption_key(&mut self, key: &[u8]) {
         self.in_cipher = Some(
             symm::Crypter::new(
@@ -115,16 +438,80 @@ impl Stream {
         );
     }
 
+    /// Packets left in the buffer can't complete once the client is gone.
+    fn nothing_to_read(&self) -> io::Error {
+        if self.closed {
+            io::Error::new(io::ErrorKind::ConnectionAborted, "Connection closed")
+        } else {
+            io::Error::from(io::ErrorKind::WouldBlock)
+        }
+    }
+
+    /// Tells whether a whole frame is buffered, checking its length against
+    /// the limit of the current state.
+    fn has_frame(&mut self) -> io::Result<bool> {
+        self.buf.reset_cursor();
+        let len = match self.buf.read_varint() {
+            Ok(len) => len,
+            // The length prefix itself may not have fully arrived yet
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(e) => {
+                self.disconnect(&format!("Bad packet: {}", e))?;
+                return Err(e);
+            }
+        };
+        let max = self.state.max_frame_length();
+
+        if len < 0 || len as usize > max {
+            let reason = format!("Frame of {} bytes exceeds the limit of {}", len, max);
+            self.disconnect(&reason)?;
+            return Err(io::Error::new(io::ErrorKind::InvalidData, reason));
+        }
+        Ok(self.buf.has_at_least(len as usize))
+    }
+
+    /// Removes the frame which was just read from the buffer.
+    fn consume_frame(&mut self) {
+        let frame: Vec<u8> = self.buf.drain(0..self.buf.cursor()).collect();
+        self.record(Direction::Inbound, self.state, &frame);
+        self.packet_rate.record();
+    }
+
     fn read(&mut self) -> io::Result<()> {
+        // Whatever is buffered past a maximum sized frame can wait until
+        // that frame is consumed
+        if self.buf.len() > self.state.max_frame_length() + 5 {
+            return Ok(());
+        }
+
         let mut tmp = [0; 1024];
         match self.handle.read(&mut tmp[..]) {
             Ok(count) if count > 0 => {
-                self.buf.extend(&tmp[..count]);
+                match &mut self.in_cipher {
+                    Some(cipher) => {
+                        let mut decrypted = vec![0; count + 16];
+                        let n = cipher.update(&tmp[..count], &mut decrypted)?;
+                        self.buf.extend(&decrypted[..n]);
+                    }
+                    None => self.buf.extend(&tmp[..count]),
+                }
+                Ok(())
+            }
+            Ok(_) => {
+                self.closed = true;
                 Ok(())
             }
-            Ok(_) => Ok(()),
             Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(()),
             Err(e) => Err(e),
         }
     }
+
+    fn record(&mut self, direction: Direction, state: State, frame: &[u8]) {
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record(direction, state, self.version.protocol, frame) {
+                error!("Failed to record frame, stopping capture: {}", e);
+                self.recorder = None;
+            }
+        }
+    }
 }
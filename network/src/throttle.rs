@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Limits protecting the server against connection floods. A zero value
+/// disables the matching limit.
+#[derive(Debug, Clone, Copy)]
+pub struct ThrottleSettings {
+    /// Minimum delay between two logins from the same IP.
+    pub login_delay: Duration,
+    pub max_connections_per_ip: usize,
+    /// Time given to a connection to reach the play state.
+    pub login_timeout: Duration,
+    pub max_packets_per_second: u32,
+}
+
+impl Default for ThrottleSettings {
+    fn default() -> Self {
+        Self {
+            login_delay: Duration::from_secs(4),
+            max_connections_per_ip: 3,
+            login_timeout: Duration::from_secs(30),
+            max_packets_per_second: 500,
+        }
+    }
+}
+
+/// Remembers the last login of each IP to enforce the login delay.
+#[derive(Debug, Default)]
+pub struct LoginThrottle {
+    delay: Duration,
+    last_logins: HashMap<IpAddr, Instant>,
+}
+
+impl LoginThrottle {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            last_logins: HashMap::new(),
+        }
+    }
+
+    /// Records a login attempt, returning false when the previous one from
+    /// the same IP is too recent. Throttled attempts reset the delay.
+    pub fn try_login(&mut self, address: IpAddr) -> bool {
+        if self.delay == Duration::from_secs(0) {
+            return true;
+        }
+
+        let now = Instant::now();
+        let allowed = match self.last_logins.get(&address) {
+            Some(last) => now.duration_since(*last) >= self.delay,
+            None => true,
+        };
+
+        self.last_logins.insert(address, now);
+        allowed
+    }
+
+    /// Forgets the IPs which are allowed to log in again.
+    pub fn cleanup(&mut self) {
+        let delay = self.delay;
+        self.last_logins.retain(|_, last| last.elapsed() < delay);
+    }
+}
+
+/// Counts the packets received over the current second.
+#[derive(Debug)]
+pub struct PacketRate {
+    window_start: Instant,
+    count: u32,
+}
+
+impl PacketRate {
+    pub fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            count: 0,
+        }
+    }
+
+    pub fn record(&mut self) {
+        if self.window_start.elapsed() >= Duration::from_secs(1) {
+            self.window_start = Instant::now();
+            self.count = 0;
+        }
+
+        self.count += 1;
+    }
+
+    /// Number of packets received this second.
+    pub fn current(&self) -> u32 {
+        if self.window_start.elapsed() >= Duration::from_secs(1) {
+            0
+        } else {
+            self.count
+        }
+    }
+}
+
+impl Default for PacketRate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
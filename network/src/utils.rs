@@ -1,11 +1,13 @@
-use openssl::pkey::Private;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{HasPublic, PKey, Private};
 use openssl::rsa::{Padding, Rsa};
+use openssl::sign::Signer;
 use std::io;
 
-pub fn rsa_encrypt(key: &Rsa<Private>, data: &[u8]) -> io::Result<Vec<u8>> {
-    let mut ret = vec![0; 128];
+pub fn rsa_encrypt<T: HasPublic>(key: &Rsa<T>, data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut ret = vec![0; key.size() as usize];
     match key.public_encrypt(data, &mut ret, Padding::PKCS1) {
-        Ok(128) => Ok(ret),
+        Ok(n) if n == ret.len() => Ok(ret),
         _ => Err(io::Error::new(
             io::ErrorKind::InvalidData,
             "Failed to encrypt data",
@@ -28,3 +30,20 @@ pub fn rsa_decrypt(key: &Rsa<Private>, data: &[u8]) -> io::Result<Vec<u8>> {
     ret.truncate(len);
     Ok(ret)
 }
+
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> io::Result<Vec<u8>> {
+    let sign = || {
+        let pkey = PKey::hmac(key)?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &pkey)?;
+        signer.update(data)?;
+        signer.sign_to_vec()
+    };
+
+    match sign() {
+        Ok(signature) => Ok(signature),
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Failed to compute HMAC",
+        )),
+    }
+}
@@ -0,0 +1,306 @@
+use cgmath::Vector3;
+use common::chat::Chat;
+use common::item::ItemStack;
+use common::metadata::{MetadataValue, Pose};
+use network::buffer::Buffer;
+use network::protocol::ProtocolVersion;
+use proptest::prelude::*;
+use uuid::Uuid;
+
+fn buffer_from(bytes: &[u8]) -> Buffer {
+    let mut buffer = Buffer::new();
+    buffer.extend(bytes);
+    buffer
+}
+
+macro_rules! round_trip {
+    ($name:ident, $ty:ty, $write:ident, $read:ident) => {
+        proptest! {
+            #[test]
+            fn $name(value: $ty) {
+                let mut buffer = Buffer::new();
+                buffer.$write(value).unwrap();
+
+                prop_assert_eq!(buffer.$read().unwrap(), value);
+                prop_assert_eq!(buffer.cursor(), buffer.len());
+            }
+        }
+    };
+}
+
+round_trip!(bool_round_trip, bool, write_bool, read_bool);
+round_trip!(byte_round_trip, i8, write_byte, read_byte);
+round_trip!(ubyte_round_trip, u8, write_ubyte, read_ubyte);
+round_trip!(short_round_trip, i16, write_short, read_short);
+round_trip!(ushort_round_trip, u16, write_ushort, read_ushort);
+round_trip!(int_round_trip, i32, write_int, read_int);
+round_trip!(uint_round_trip, u32, write_uint, read_uint);
+round_trip!(long_round_trip, i64, write_long, read_long);
+round_trip!(ulong_round_trip, u64, write_ulong, read_ulong);
+round_trip!(varint_round_trip, i32, write_varint, read_varint);
+round_trip!(varlong_round_trip, i64, write_varlong, read_varlong);
+
+proptest! {
+    #[test]
+    fn float_round_trip(value: f32) {
+        let mut buffer = Buffer::new();
+        buffer.write_float(value).unwrap();
+
+        prop_assert_eq!(buffer.read_float().unwrap().to_bits(), value.to_bits());
+    }
+
+    #[test]
+    fn double_round_trip(value: f64) {
+        let mut buffer = Buffer::new();
+        buffer.write_double(value).unwrap();
+
+        prop_assert_eq!(buffer.read_double().unwrap().to_bits(), value.to_bits());
+    }
+
+    #[test]
+    fn varint_is_at_most_five_bytes(value: i32) {
+        let mut buffer = Buffer::new();
+        buffer.write_varint(value).unwrap();
+
+        prop_assert!(buffer.len() <= 5);
+    }
+
+    #[test]
+    fn varlong_is_at_most_ten_bytes(value: i64) {
+        let mut buffer = Buffer::new();
+        buffer.write_varlong(value).unwrap();
+
+        prop_assert!(buffer.len() <= 10);
+    }
+
+    #[test]
+    fn string_round_trip(value: String) {
+        let mut buffer = Buffer::new();
+        buffer.write_string(&value).unwrap();
+
+        prop_assert_eq!(buffer.read_string().unwrap(), value);
+        prop_assert_eq!(buffer.cursor(), buffer.len());
+    }
+
+    #[test]
+    fn chat_round_trip(text: String) {
+        let chat = Chat::new_text(&text);
+        let mut buffer = Buffer::new();
+        buffer.write_chat(&chat).unwrap();
+
+        prop_assert_eq!(buffer.read_chat().unwrap(), chat);
+    }
+
+    #[test]
+    fn uuid_round_trip(value: u128) {
+        let uuid = Uuid::from_u128(value);
+        let mut buffer = Buffer::new();
+        buffer.write_uuid(&uuid).unwrap();
+
+        prop_assert_eq!(buffer.read_uuid().unwrap(), uuid);
+    }
+
+    #[test]
+    fn position_round_trip(
+        x in -(1 << 25)..(1 << 25),
+        y in -(1 << 11)..(1 << 11),
+        z in -(1 << 25)..(1 << 25),
+    ) {
+        let position = Vector3::new(x, y, z);
+        let mut buffer = Buffer::new();
+        buffer.write_position(&position).unwrap();
+
+        prop_assert_eq!(buffer.read_position().unwrap(), position);
+    }
+
+    #[test]
+    fn position_out_of_range_is_rejected(x: i32, y: i32, z: i32) {
+        let in_range = (-(1 << 25)..(1 << 25)).contains(&x)
+            && (-(1 << 11)..(1 << 11)).contains(&y)
+            && (-(1 << 25)..(1 << 25)).contains(&z);
+        let mut buffer = Buffer::new();
+
+        prop_assert_eq!(buffer.write_position(&Vector3::new(x, y, z)).is_ok(), in_range);
+    }
+
+    #[test]
+    fn byte_array_round_trip(value: Vec<i8>) {
+        let mut buffer = Buffer::new();
+        buffer.write_byte_array(&value).unwrap();
+
+        prop_assert_eq!(buffer.read_byte_array(value.len()).unwrap(), value);
+    }
+
+    #[test]
+    fn ubyte_array_round_trip(value: Vec<u8>) {
+        let mut buffer = Buffer::new();
+        buffer.write_ubyte_array(&value).unwrap();
+
+        prop_assert_eq!(buffer.read_ubyte_array(value.len()).unwrap(), value);
+    }
+
+    #[test]
+    fn reading_arbitrary_bytes_never_panics(bytes: Vec<u8>) {
+        let _ = buffer_from(&bytes).read_varint();
+        let _ = buffer_from(&bytes).read_varlong();
+        let _ = buffer_from(&bytes).read_string();
+        let _ = buffer_from(&bytes).read_chat();
+        let _ = buffer_from(&bytes).read_position();
+        let _ = buffer_from(&bytes).read_uuid();
+        let _ = buffer_from(&bytes).read_ubyte_array(bytes.len() + 1);
+    }
+
+    #[test]
+    fn truncated_string_is_rejected(value in ".+") {
+        let mut buffer = Buffer::new();
+        buffer.write_string(&value).unwrap();
+
+        let truncated = &buffer.as_raw()[..buffer.len() - 1];
+        prop_assert!(buffer_from(truncated).read_string().is_err());
+    }
+}
+
+#[test]
+fn varint_matches_protocol_samples() {
+    let samples: &[(i32, &[u8])] = &[
+        (0, &[0x00]),
+        (1, &[0x01]),
+        (127, &[0x7f]),
+        (128, &[0x80, 0x01]),
+        (255, &[0xff, 0x01]),
+        (2_147_483_647, &[0xff, 0xff, 0xff, 0xff, 0x07]),
+        (-1, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+        (-2_147_483_648, &[0x80, 0x80, 0x80, 0x80, 0x08]),
+    ];
+
+    for (value, bytes) in samples {
+        let mut buffer = Buffer::new();
+        buffer.write_varint(*value).unwrap();
+
+        assert_eq!(buffer.as_raw(), *bytes);
+        assert_eq!(buffer_from(bytes).read_varint().unwrap(), *value);
+    }
+}
+
+#[test]
+fn varlong_matches_protocol_samples() {
+    let samples: &[(i64, &[u8])] = &[
+        (0, &[0x00]),
+        (2_147_483_647, &[0xff, 0xff, 0xff, 0xff, 0x07]),
+        (
+            9_223_372_036_854_775_807,
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+        ),
+        (
+            -1,
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+        (
+            -9_223_372_036_854_775_808,
+            &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01],
+        ),
+    ];
+
+    for (value, bytes) in samples {
+        let mut buffer = Buffer::new();
+        buffer.write_varlong(*value).unwrap();
+
+        assert_eq!(buffer.as_raw(), *bytes);
+        assert_eq!(buffer_from(bytes).read_varlong().unwrap(), *value);
+    }
+}
+
+#[test]
+fn position_matches_protocol_samples() {
+    let samples = [
+        (
+            Vector3::new(18_357_644, 831, -20_882_616),
+            [0x46, 0x07, 0x63, 0x2c, 0x15, 0xb4, 0x83, 0x3f],
+        ),
+        (
+            Vector3::new(1, 2, 3),
+            [0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x30, 0x02],
+        ),
+        (Vector3::new(-1, -1, -1), [0xff; 8]),
+        (
+            Vector3::new(0, -64, 0),
+            [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0xc0],
+        ),
+    ];
+
+    for (position, bytes) in &samples {
+        let mut buffer = Buffer::new();
+        buffer.write_position(position).unwrap();
+
+        assert_eq!(buffer.as_raw(), bytes);
+        assert_eq!(buffer_from(bytes).read_position().unwrap(), *position);
+    }
+}
+
+#[test]
+fn oversized_varint_is_rejected() {
+    assert!(buffer_from(&[0xff, 0xff, 0xff, 0xff, 0xff, 0x01])
+        .read_varint()
+        .is_err());
+}
+
+#[test]
+fn metadata_matches_protocol_sample() {
+    let metadata = vec![
+        (0, MetadataValue::Byte(0x02)),
+        (6, MetadataValue::Pose(Pose::Sneaking)),
+        (7, MetadataValue::Slot(Some(ItemStack::new(1, 64)))),
+        (8, MetadataValue::OptVarInt(None)),
+    ];
+    let bytes = &[
+        0x00, 0x00, 0x02, // Byte
+        0x06, 0x12, 0x05, // Pose
+        0x07, 0x06, 0x01, 0x01, 0x40, 0x00, // Slot without NBT
+        0x08, 0x11, 0x00, // Absent OptVarInt
+        0xff,
+    ];
+
+    let mut buffer = Buffer::new();
+    buffer.write_metadata(&metadata).unwrap();
+    assert_eq!(buffer.as_raw(), bytes);
+    assert_eq!(
+        buffer_from(bytes)
+            .read_metadata(ProtocolVersion::V1_16)
+            .unwrap(),
+        metadata
+    );
+}
+
+#[test]
+fn metadata_positions_match_protocol_sample() {
+    let metadata = vec![
+        (7, MetadataValue::Position(Vector3::new(1, 2, 3))),
+        (8, MetadataValue::OptPosition(Some(Vector3::new(0, -64, 0)))),
+        (9, MetadataValue::OptPosition(None)),
+    ];
+    let bytes = &[
+        0x07, 0x09, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x30, 0x02, // Position
+        0x08, 0x0a, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0xc0, // OptPosition
+        0x09, 0x0a, 0x00, // Absent OptPosition
+        0xff,
+    ];
+
+    let mut buffer = Buffer::new();
+    buffer.write_metadata(&metadata).unwrap();
+    assert_eq!(buffer.as_raw(), bytes);
+    assert_eq!(
+        buffer_from(bytes)
+            .read_metadata(ProtocolVersion::V1_16)
+            .unwrap(),
+        metadata
+    );
+}
+
+#[test]
+fn metadata_end_index_is_rejected() {
+    let mut buffer = Buffer::new();
+
+    assert!(buffer
+        .write_metadata(&[(0xff, MetadataValue::Boolean(true))])
+        .is_err());
+}
@@ -0,0 +1,86 @@
+use network::capture::{CaptureReader, CaptureRecord, CaptureWriter, Direction};
+use network::packet::State;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("minecrate-capture-{}-{}.mcap", name, process::id()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64
+}
+
+#[test]
+fn records_round_trip() {
+    let path = path("round-trip");
+    let records = [
+        (Direction::Inbound, State::Handshake, vec![0x02, 0x00, 0x00]),
+        (Direction::Outbound, State::Login, vec![]),
+        (
+            Direction::Inbound,
+            State::Play,
+            vec![0x09, 0x10, 1, 2, 3, 4, 5, 6, 7, 8],
+        ),
+        (Direction::Outbound, State::Status, vec![0xFF; 300]),
+    ];
+
+    let before = now();
+    let mut writer = CaptureWriter::create(&path).unwrap();
+    for (direction, state, frame) in &records {
+        writer.record(*direction, *state, 754, frame).unwrap();
+    }
+    drop(writer);
+    let after = now();
+
+    let read: Vec<CaptureRecord> = CaptureReader::open(&path)
+        .unwrap()
+        .collect::<io::Result<_>>()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(read.len(), records.len());
+    let mut last = before;
+    for (record, (direction, state, frame)) in read.iter().zip(&records) {
+        assert!(record.timestamp >= last && record.timestamp <= after);
+        last = record.timestamp;
+        assert_eq!(record.direction, *direction);
+        assert_eq!(record.state, *state);
+        assert_eq!(record.protocol, 754);
+        assert_eq!(&record.frame, frame);
+    }
+}
+
+#[test]
+fn truncated_records_are_errors() {
+    let path = path("truncated");
+    let mut writer = CaptureWriter::create(&path).unwrap();
+    writer
+        .record(Direction::Inbound, State::Play, 754, &[1, 2, 3, 4])
+        .unwrap();
+    drop(writer);
+
+    let mut data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    data.pop();
+
+    let mut reader = CaptureReader::new(&data[..]).unwrap();
+    let e = reader.next().unwrap().unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn other_files_are_rejected() {
+    let e = CaptureReader::new(&b"PNG\x0d\x01"[..]).err().unwrap();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+    let e = CaptureReader::new(&b"MCAP\x02"[..]).err().unwrap();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,41 @@
+use network::channel::{BrandMessage, ChannelMessage, RegisterMessage, UnregisterMessage};
+use std::io;
+
+#[test]
+fn channel_lists_are_nul_separated() {
+    let message = RegisterMessage::decode(b"minecrate:test\0bungeecord:main").unwrap();
+    assert_eq!(message.channels, vec!["minecrate:test", "bungeecord:main"]);
+
+    let encoded = RegisterMessage {
+        channels: message.channels.clone(),
+    }
+    .encode()
+    .unwrap();
+    assert_eq!(encoded, b"minecrate:test\0bungeecord:main".to_vec());
+}
+
+#[test]
+fn trailing_and_repeated_separators_are_ignored() {
+    let message = RegisterMessage::decode(b"minecrate:test\0\0bungeecord:main\0").unwrap();
+    assert_eq!(message.channels, vec!["minecrate:test", "bungeecord:main"]);
+
+    let message = UnregisterMessage::decode(b"\0").unwrap();
+    assert!(message.channels.is_empty());
+}
+
+#[test]
+fn channel_lists_must_be_utf8() {
+    let e = RegisterMessage::decode(&[b'a', 0, 0xFF, 0xFE]).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn brand_round_trips() {
+    let encoded = BrandMessage {
+        brand: String::from("vanilla"),
+    }
+    .encode()
+    .unwrap();
+
+    assert_eq!(BrandMessage::decode(&encoded).unwrap().brand, "vanilla");
+}
@@ -0,0 +1,32 @@
+use network::login_query::LoginQueryTracker;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn queries_expire_after_their_timeout() {
+    let mut tracker = LoginQueryTracker::new();
+    let slow = tracker.register("minecrate:slow", Duration::from_secs(60));
+    let fast = tracker.register("minecrate:fast", Duration::from_millis(20));
+
+    assert_ne!(slow, fast);
+    assert_eq!(tracker.next_expired(), None);
+
+    thread::sleep(Duration::from_millis(30));
+    assert_eq!(tracker.next_expired(), Some(fast));
+    assert_eq!(tracker.resolve(fast), Some(String::from("minecrate:fast")));
+    assert_eq!(tracker.next_expired(), None);
+    assert!(!tracker.is_empty());
+}
+
+#[test]
+fn queries_are_resolved_once() {
+    let mut tracker = LoginQueryTracker::new();
+    let message_id = tracker.register("minecrate:test", Duration::from_secs(60));
+
+    assert_eq!(
+        tracker.resolve(message_id),
+        Some(String::from("minecrate:test"))
+    );
+    assert_eq!(tracker.resolve(message_id), None);
+    assert!(tracker.is_empty());
+}
@@ -0,0 +1,904 @@
+use cgmath::Vector3;
+use common::chat::{Chat, ChatMessageType};
+use common::difficulty::Difficulty;
+use common::dimension::Dimension;
+use common::entity_type::EntityType;
+use common::gamemode::Gamemode;
+use common::item::ItemStack;
+use common::level_type::LevelType;
+use common::metadata::{Direction, MetadataValue, Particle, ParticleData, Pose, VillagerData};
+use network::buffer::Buffer;
+use network::clientbound;
+use network::clientbound::ClientboundPacket;
+use network::mojang::{Profile, ProfileProperty};
+use network::packet::{Packet, State, UnknownPacket};
+use network::protocol::ProtocolVersion;
+use network::serverbound;
+use network::serverbound::play::InteractAction;
+use network::serverbound::ServerboundPacket;
+use proptest::prelude::*;
+use std::fmt::Debug;
+use uuid::Uuid;
+
+/// Frames a packet the way `Stream` sends it.
+fn encode<P: Packet>(packet: &P, version: ProtocolVersion) -> Buffer {
+    let mut payload = Buffer::new();
+    payload
+        .write_varint(packet.get_id(version).unwrap())
+        .unwrap();
+    packet.serialize(&mut payload, version).unwrap();
+
+    let mut frame = Buffer::new();
+    frame.write_varint(payload.len() as i32).unwrap();
+    frame.extend(payload.as_raw());
+    frame
+}
+
+/// Packets don't implement `PartialEq`, so they are compared through their
+/// debug representation, which lists every field.
+fn assert_round_trip<P: Packet + Debug>(packet: P, version: ProtocolVersion) {
+    let mut frame = encode(&packet, version);
+    let decoded = P::deserialize(&mut frame, &packet.get_state(), version).unwrap();
+
+    assert_eq!(format!("{:?}", decoded), format!("{:?}", packet));
+    assert_eq!(frame.cursor(), frame.len(), "payload not fully consumed");
+}
+
+fn version() -> impl Strategy<Value = ProtocolVersion> {
+    prop_oneof![Just(ProtocolVersion::V1_15), Just(ProtocolVersion::V1_16)]
+}
+
+fn state() -> impl Strategy<Value = State> {
+    prop_oneof![
+        Just(State::Handshake),
+        Just(State::Status),
+        Just(State::Login),
+        Just(State::Play),
+    ]
+}
+
+fn chat() -> impl Strategy<Value = Chat> {
+    any::<String>().prop_map(|text| Chat::new_text(&text))
+}
+
+fn uuid() -> impl Strategy<Value = Uuid> {
+    any::<u128>().prop_map(Uuid::from_u128)
+}
+
+/// Angles which survive their trip through a byte.
+fn angle() -> impl Strategy<Value = f32> {
+    any::<u8>().prop_map(|angle| f32::from(angle) * 360.0 / 256.0)
+}
+
+fn entity_type() -> impl Strategy<Value = EntityType> {
+    prop_oneof![
+        Just(EntityType::Cow),
+        Just(EntityType::FallingBlock),
+        Just(EntityType::Item),
+        Just(EntityType::Zombie),
+    ]
+}
+
+fn gamemode() -> impl Strategy<Value = Gamemode> {
+    prop_oneof![
+        Just(Gamemode::Survival),
+        Just(Gamemode::Creative),
+        Just(Gamemode::Adventure),
+        Just(Gamemode::Spectator),
+    ]
+}
+
+fn difficulty() -> impl Strategy<Value = Difficulty> {
+    prop_oneof![
+        Just(Difficulty::Peaceful),
+        Just(Difficulty::Easy),
+        Just(Difficulty::Normal),
+        Just(Difficulty::Hard),
+    ]
+}
+
+fn chat_message_type() -> impl Strategy<Value = ChatMessageType> {
+    prop_oneof![
+        Just(ChatMessageType::Chat),
+        Just(ChatMessageType::System),
+        Just(ChatMessageType::GameInfo),
+    ]
+}
+
+fn profile() -> impl Strategy<Value = Profile> {
+    (
+        any::<String>(),
+        any::<String>(),
+        prop::collection::vec(
+            (any::<String>(), any::<String>(), any::<Option<String>>()),
+            0..3,
+        ),
+    )
+        .prop_map(|(id, name, properties)| Profile {
+            id,
+            name,
+            properties: properties
+                .into_iter()
+                .map(|(name, value, signature)| ProfileProperty {
+                    name,
+                    value,
+                    signature,
+                })
+                .collect(),
+        })
+}
+
+fn player_list_entry() -> impl Strategy<Value = clientbound::play::PlayerListEntry> {
+    (
+        uuid(),
+        "[a-zA-Z0-9_]{0,16}",
+        profile().prop_map(|profile| profile.properties),
+        gamemode(),
+        any::<i32>(),
+        prop::option::of(chat()),
+    )
+        .prop_map(
+            |(uuid, name, properties, gamemode, latency, display_name)| {
+                clientbound::play::PlayerListEntry {
+                    uuid,
+                    name,
+                    properties,
+                    gamemode,
+                    latency,
+                    display_name,
+                }
+            },
+        )
+}
+
+fn player_info() -> impl Strategy<Value = ClientboundPacket> {
+    use clientbound::play::PlayerInfoAction;
+
+    prop_oneof![
+        prop::collection::vec(player_list_entry(), 0..3).prop_map(PlayerInfoAction::AddPlayer),
+        prop::collection::vec((uuid(), gamemode()), 0..3)
+            .prop_map(PlayerInfoAction::UpdateGamemode),
+        prop::collection::vec((uuid(), any::<i32>()), 0..3)
+            .prop_map(PlayerInfoAction::UpdateLatency),
+        prop::collection::vec((uuid(), prop::option::of(chat())), 0..3)
+            .prop_map(PlayerInfoAction::UpdateDisplayName),
+        prop::collection::vec(uuid(), 0..3).prop_map(PlayerInfoAction::RemovePlayer),
+    ]
+    .prop_map(clientbound::play::PlayerInfoPacket::new)
+}
+
+/// Positions which fit in the 26/12/26 bits of the protocol.
+fn position() -> impl Strategy<Value = Vector3<i32>> {
+    (
+        -(1 << 25)..(1 << 25),
+        -(1 << 11)..(1 << 11),
+        -(1 << 25)..(1 << 25),
+    )
+        .prop_map(|(x, y, z)| Vector3::new(x, y, z))
+}
+
+/// Compounds with a single tag, as the order of several isn't kept.
+fn nbt() -> impl Strategy<Value = nbt::Blob> {
+    any::<i32>().prop_map(|value| {
+        let mut blob = nbt::Blob::new();
+        blob.insert("value", nbt::Value::Int(value)).unwrap();
+        blob
+    })
+}
+
+fn slot() -> impl Strategy<Value = Option<ItemStack>> {
+    prop::option::of(
+        (any::<i32>(), any::<i8>(), prop::option::of(nbt()))
+            .prop_map(|(item, count, nbt)| ItemStack { item, count, nbt }),
+    )
+}
+
+/// Particles with the data their id calls for in the given version.
+fn particle(version: ProtocolVersion) -> BoxedStrategy<Particle> {
+    let item = match version {
+        ProtocolVersion::V1_15 => 32,
+        ProtocolVersion::V1_16 => 34,
+    };
+
+    prop_oneof![
+        (0..80)
+            .prop_filter("particle with data", move |id| ![3, 14, 23, item]
+                .contains(id))
+            .prop_map(|id| Particle {
+                id,
+                data: ParticleData::None
+            }),
+        (prop_oneof![Just(3), Just(23)], any::<i32>()).prop_map(|(id, state)| Particle {
+            id,
+            data: ParticleData::Block(state)
+        }),
+        any::<(f32, f32, f32, f32)>().prop_map(|(red, green, blue, scale)| Particle {
+            id: 14,
+            data: ParticleData::Dust {
+                red,
+                green,
+                blue,
+                scale
+            }
+        }),
+        slot().prop_map(move |stack| Particle {
+            id: item,
+            data: ParticleData::Item(stack)
+        }),
+    ]
+    .boxed()
+}
+
+fn metadata_value(version: ProtocolVersion) -> BoxedStrategy<MetadataValue> {
+    prop_oneof![
+        any::<i8>().prop_map(MetadataValue::Byte),
+        any::<i32>().prop_map(MetadataValue::VarInt),
+        any::<f32>().prop_map(MetadataValue::Float),
+        any::<String>().prop_map(MetadataValue::String),
+        chat().prop_map(MetadataValue::Chat),
+        prop::option::of(chat()).prop_map(MetadataValue::OptChat),
+        slot().prop_map(MetadataValue::Slot),
+        any::<bool>().prop_map(MetadataValue::Boolean),
+        any::<(f32, f32, f32)>().prop_map(|(x, y, z)| MetadataValue::Rotation(x, y, z)),
+        position().prop_map(MetadataValue::Position),
+        prop::option::of(position()).prop_map(MetadataValue::OptPosition),
+        prop_oneof![
+            Just(Direction::Down),
+            Just(Direction::Up),
+            Just(Direction::North),
+            Just(Direction::South),
+            Just(Direction::West),
+            Just(Direction::East),
+        ]
+        .prop_map(MetadataValue::Direction),
+        prop::option::of(uuid()).prop_map(MetadataValue::OptUuid),
+        prop::option::of(1..i32::MAX).prop_map(MetadataValue::OptBlockId),
+        nbt().prop_map(MetadataValue::Nbt),
+        particle(version).prop_map(MetadataValue::Particle),
+        any::<(i32, i32, i32)>().prop_map(|(villager_type, profession, level)| {
+            MetadataValue::VillagerData(VillagerData {
+                villager_type,
+                profession,
+                level,
+            })
+        }),
+        prop::option::of(0..i32::MAX).prop_map(MetadataValue::OptVarInt),
+        prop_oneof![
+            Just(Pose::Standing),
+            Just(Pose::FallFlying),
+            Just(Pose::Sleeping),
+            Just(Pose::Swimming),
+            Just(Pose::SpinAttack),
+            Just(Pose::Sneaking),
+            Just(Pose::Dying),
+        ]
+        .prop_map(MetadataValue::Pose),
+    ]
+    .boxed()
+}
+
+/// Dimensions and level types which survive a round trip in the given
+/// version: 1.16 only knows the vanilla dimensions, and turned the level
+/// type into a flag.
+fn dimension_and_level_type(
+    version: ProtocolVersion,
+) -> (BoxedStrategy<Dimension>, BoxedStrategy<LevelType>) {
+    match version {
+        ProtocolVersion::V1_15 => (
+            any::<i32>().prop_map(Dimension::from).boxed(),
+            prop_oneof![
+                Just(LevelType::Default),
+                Just(LevelType::Flat),
+                Just(LevelType::LargeBiomes),
+                Just(LevelType::Amplified),
+                Just(LevelType::Customized),
+                Just(LevelType::Buffet),
+                Just(LevelType::Default1_1),
+            ]
+            .boxed(),
+        ),
+        ProtocolVersion::V1_16 => (
+            prop_oneof![
+                Just(Dimension::Overworld),
+                Just(Dimension::Nether),
+                Just(Dimension::End),
+            ]
+            .boxed(),
+            prop_oneof![Just(LevelType::Default), Just(LevelType::Flat)].boxed(),
+        ),
+    }
+}
+
+fn join_game(version: ProtocolVersion) -> BoxedStrategy<ClientboundPacket> {
+    let (dimension, level_type) = dimension_and_level_type(version);
+
+    (
+        any::<i32>(),
+        gamemode(),
+        any::<bool>(),
+        dimension,
+        any::<i64>(),
+        any::<u8>(),
+        level_type,
+        any::<i32>(),
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(
+                entity_id,
+                gamemode,
+                hardcore,
+                dimension,
+                hashed_seed,
+                max_players,
+                level_type,
+                render_distance,
+                reduced_debug_info,
+                enable_respawn_screen,
+            )| {
+                clientbound::play::JoinGamePacket::new(
+                    entity_id,
+                    gamemode,
+                    hardcore,
+                    dimension,
+                    hashed_seed,
+                    max_players,
+                    level_type,
+                    render_distance,
+                    reduced_debug_info,
+                    enable_respawn_screen,
+                )
+            },
+        )
+        .boxed()
+}
+
+fn respawn(version: ProtocolVersion) -> BoxedStrategy<ClientboundPacket> {
+    let (dimension, level_type) = dimension_and_level_type(version);
+
+    (dimension, any::<i64>(), gamemode(), level_type)
+        .prop_map(|(dimension, hashed_seed, gamemode, level_type)| {
+            clientbound::play::RespawnPacket::new(dimension, hashed_seed, gamemode, level_type)
+        })
+        .boxed()
+}
+
+fn combat_event() -> impl Strategy<Value = clientbound::play::CombatEvent> {
+    prop_oneof![
+        Just(clientbound::play::CombatEvent::EnterCombat),
+        any::<(i32, i32)>().prop_map(|(duration, entity_id)| {
+            clientbound::play::CombatEvent::EndCombat {
+                duration,
+                entity_id,
+            }
+        }),
+        (any::<i32>(), any::<i32>(), chat()).prop_map(|(player_id, entity_id, message)| {
+            clientbound::play::CombatEvent::EntityDead {
+                player_id,
+                entity_id,
+                message,
+            }
+        }),
+    ]
+}
+
+fn clientbound_packet(version: ProtocolVersion) -> BoxedStrategy<ClientboundPacket> {
+    let status_response = (
+        any::<String>(),
+        any::<i32>(),
+        any::<u32>(),
+        any::<u32>(),
+        prop::collection::vec(profile(), 0..3),
+        chat(),
+        any::<String>(),
+    )
+        .prop_map(
+            |(name, protocol, max, online, sample, description, favicon)| {
+                clientbound::status::StatusResponsePacket::new(
+                    clientbound::status::StatusResponsePayload {
+                        version: clientbound::status::StatusResponsePayloadVersion {
+                            name,
+                            protocol,
+                        },
+                        players: clientbound::status::StatusResponsePayloadPlayers {
+                            max,
+                            online,
+                            sample,
+                        },
+                        description,
+                        favicon,
+                    },
+                )
+            },
+        );
+
+    // The chat sender only exists since 1.16
+    let sender = match version {
+        ProtocolVersion::V1_15 => Just(Uuid::nil()).boxed(),
+        ProtocolVersion::V1_16 => uuid().boxed(),
+    };
+
+    prop_oneof![
+        status_response,
+        any::<i64>().prop_map(clientbound::status::PongPacket::new),
+        chat().prop_map(clientbound::login::DisconnectPacket::new),
+        (any::<String>(), any::<Vec<u8>>(), any::<Vec<u8>>()).prop_map(
+            |(server_id, public_key, verify_token)| {
+                clientbound::login::EncryptionRequestPacket::new(
+                    &server_id,
+                    &public_key,
+                    &verify_token,
+                )
+            }
+        ),
+        (uuid(), any::<String>()).prop_map(|(uuid, username)| {
+            clientbound::login::LoginSuccessPacket::new(uuid, &username)
+        }),
+        (any::<i32>(), any::<String>(), any::<Vec<u8>>()).prop_map(
+            |(message_id, channel, data)| {
+                clientbound::login::LoginPluginRequestPacket::new(message_id, &channel, &data)
+            }
+        ),
+        (difficulty(), any::<bool>()).prop_map(|(difficulty, locked)| {
+            clientbound::play::ServerDifficultyPacket::new(difficulty, locked)
+        }),
+        (chat(), chat_message_type(), sender).prop_map(|(message, position, sender)| {
+            clientbound::play::ChatMessagePacket::new(message, position, sender)
+        }),
+        (any::<String>(), any::<Vec<u8>>()).prop_map(|(channel, data)| {
+            clientbound::play::PluginMessagePacket::new(&channel, &data)
+        }),
+        chat().prop_map(clientbound::play::DisconnectPlayPacket::new),
+        (any::<i32>(), any::<i8>()).prop_map(|(entity_id, status)| {
+            clientbound::play::EntityStatusPacket::new(entity_id, status)
+        }),
+        (any::<u8>(), any::<f32>()).prop_map(|(reason, value)| {
+            clientbound::play::ChangeGameStatePacket::new(reason, value)
+        }),
+        any::<i64>().prop_map(clientbound::play::KeepAlivePacket::new),
+        join_game(version),
+        player_info(),
+        (chat(), chat()).prop_map(|(header, footer)| {
+            clientbound::play::PlayerListHeaderAndFooterPacket::new(header, footer)
+        }),
+        (
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+            any::<f32>(),
+            any::<f32>(),
+        )
+            .prop_map(|(a, b, c, d, speed, fov)| {
+                clientbound::play::PlayerAbilitiesPacket::new(a, b, c, d, speed, fov)
+            }),
+        (
+            any::<f64>(),
+            any::<f64>(),
+            any::<f64>(),
+            any::<f32>(),
+            any::<f32>(),
+            any::<u8>(),
+            any::<i32>(),
+        )
+            .prop_map(|(x, y, z, yaw, pitch, flags, teleport_id)| {
+                clientbound::play::PlayerPositionAndLookPacket::new(
+                    x,
+                    y,
+                    z,
+                    yaw,
+                    pitch,
+                    flags,
+                    teleport_id,
+                )
+            }),
+        (
+            any::<i32>(),
+            uuid(),
+            entity_type(),
+            (any::<f64>(), any::<f64>(), any::<f64>()),
+            angle(),
+            angle(),
+            any::<i32>(),
+            any::<(i16, i16, i16)>(),
+        )
+            .prop_map(|(id, uuid, kind, (x, y, z), pitch, yaw, data, velocity)| {
+                clientbound::play::SpawnEntityPacket::new(
+                    id, uuid, kind, x, y, z, pitch, yaw, data, velocity,
+                )
+            }),
+        (
+            any::<i32>(),
+            uuid(),
+            entity_type(),
+            (any::<f64>(), any::<f64>(), any::<f64>()),
+            (angle(), angle(), angle()),
+            any::<(i16, i16, i16)>(),
+        )
+            .prop_map(
+                |(id, uuid, kind, (x, y, z), (yaw, pitch, head), velocity)| {
+                    clientbound::play::SpawnLivingEntityPacket::new(
+                        id, uuid, kind, x, y, z, yaw, pitch, head, velocity,
+                    )
+                }
+            ),
+        (
+            any::<i32>(),
+            uuid(),
+            (any::<f64>(), any::<f64>(), any::<f64>()),
+            angle(),
+            angle(),
+        )
+            .prop_map(|(id, uuid, (x, y, z), yaw, pitch)| {
+                clientbound::play::SpawnPlayerPacket::new(id, uuid, x, y, z, yaw, pitch)
+            }),
+        any::<Vec<i32>>().prop_map(clientbound::play::DestroyEntitiesPacket::new),
+        (any::<i32>(), any::<(i16, i16, i16)>(), any::<bool>()).prop_map(
+            |(id, delta, on_ground)| {
+                clientbound::play::EntityPositionPacket::new(id, delta, on_ground)
+            }
+        ),
+        (
+            any::<i32>(),
+            any::<(i16, i16, i16)>(),
+            angle(),
+            angle(),
+            any::<bool>(),
+        )
+            .prop_map(|(id, delta, yaw, pitch, on_ground)| {
+                clientbound::play::EntityPositionAndRotationPacket::new(
+                    id, delta, yaw, pitch, on_ground,
+                )
+            }),
+        (any::<i32>(), angle(), angle(), any::<bool>()).prop_map(|(id, yaw, pitch, on_ground)| {
+            clientbound::play::EntityRotationPacket::new(id, yaw, pitch, on_ground)
+        }),
+        (any::<i32>(), angle())
+            .prop_map(|(id, yaw)| clientbound::play::EntityHeadLookPacket::new(id, yaw)),
+        (
+            any::<i32>(),
+            (any::<f64>(), any::<f64>(), any::<f64>()),
+            angle(),
+            angle(),
+            any::<bool>(),
+        )
+            .prop_map(|(id, (x, y, z), yaw, pitch, on_ground)| {
+                clientbound::play::EntityTeleportPacket::new(id, x, y, z, yaw, pitch, on_ground)
+            }),
+        (
+            any::<i32>(),
+            prop::collection::vec((0..0xFFu8, metadata_value(version)), 0..4)
+        )
+            .prop_map(|(id, metadata)| clientbound::play::EntityMetadataPacket::new(id, metadata)),
+        any::<(i32, i32, i32)>().prop_map(|(collected, collector, count)| {
+            clientbound::play::CollectItemPacket::new(collected, collector, count)
+        }),
+        (position(), any::<i32>()).prop_map(|(location, block_id)| {
+            clientbound::play::BlockChangePacket::new(location, block_id)
+        }),
+        (any::<f32>(), any::<i32>(), any::<f32>()).prop_map(|(health, food, saturation)| {
+            clientbound::play::UpdateHealthPacket::new(health, food, saturation)
+        }),
+        (any::<i32>(), any::<(i16, i16, i16)>()).prop_map(|(id, velocity)| {
+            clientbound::play::EntityVelocityPacket::new(id, velocity)
+        }),
+        (any::<i32>(), any::<u8>()).prop_map(|(id, animation)| {
+            clientbound::play::EntityAnimationPacket::new(id, animation)
+        }),
+        combat_event().prop_map(clientbound::play::CombatEventPacket::new),
+        respawn(version),
+        (any::<u8>(), prop::collection::vec(slot(), 0..46)).prop_map(|(window_id, slots)| {
+            clientbound::play::WindowItemsPacket::new(window_id, slots)
+        }),
+        (any::<i8>(), any::<i16>(), slot()).prop_map(|(window_id, slot, stack)| {
+            clientbound::play::SetSlotPacket::new(window_id, slot, stack)
+        }),
+        (any::<i8>(), any::<i16>(), any::<bool>()).prop_map(
+            |(window_id, action_number, accepted)| {
+                clientbound::play::WindowConfirmationPacket::new(window_id, action_number, accepted)
+            }
+        ),
+        any::<i8>().prop_map(clientbound::play::HeldItemChangePacket::new),
+    ]
+    .boxed()
+}
+
+fn serverbound_packet(version: ProtocolVersion) -> BoxedStrategy<ServerboundPacket> {
+    // Sneaking is only sent since 1.16
+    let sneaking = match version {
+        ProtocolVersion::V1_15 => Just(false).boxed(),
+        ProtocolVersion::V1_16 => any::<bool>().boxed(),
+    };
+
+    prop_oneof![
+        (any::<i32>(), any::<String>(), any::<u16>(), any::<i32>()).prop_map(
+            |(protocol, address, port, next)| {
+                serverbound::handshake::HandshakePacket {
+                    protocol,
+                    address,
+                    port,
+                    next,
+                }
+                .into()
+            }
+        ),
+        Just(()).prop_map(|_| serverbound::status::StatusRequestPacket {}.into()),
+        any::<i64>().prop_map(|payload| serverbound::status::PingPacket { payload }.into()),
+        "[a-zA-Z0-9_]{1,16}"
+            .prop_map(|username| serverbound::login::LoginStartPacket { username }.into()),
+        (any::<Vec<u8>>(), any::<Vec<u8>>()).prop_map(|(shared_secret, verify_token)| {
+            serverbound::login::EncryptionResponsePacket {
+                shared_secret,
+                verify_token,
+            }
+            .into()
+        }),
+        (any::<i32>(), any::<bool>(), any::<Vec<u8>>()).prop_map(
+            |(message_id, successful, data)| {
+                serverbound::login::LoginPluginResponsePacket {
+                    message_id,
+                    successful,
+                    data,
+                }
+                .into()
+            }
+        ),
+        any::<i32>().prop_map(|teleport_id| {
+            serverbound::play::TeleportConfirmPacket { teleport_id }.into()
+        }),
+        "\\PC{0,128}".prop_map(|message| serverbound::play::ChatMessagePacket { message }.into()),
+        (any::<String>(), any::<Vec<u8>>()).prop_map(|(channel, data)| {
+            serverbound::play::PluginMessagePacket { channel, data }.into()
+        }),
+        any::<i64>().prop_map(|id| serverbound::play::KeepAlivePacket { id }.into()),
+        (any::<f64>(), any::<f64>(), any::<f64>(), any::<bool>()).prop_map(
+            |(x, y, z, on_ground)| {
+                serverbound::play::PlayerPositionPacket { x, y, z, on_ground }.into()
+            }
+        ),
+        (
+            any::<f64>(),
+            any::<f64>(),
+            any::<f64>(),
+            any::<f32>(),
+            any::<f32>(),
+            any::<bool>(),
+        )
+            .prop_map(|(x, y, z, yaw, pitch, on_ground)| {
+                serverbound::play::PlayerPositionAndRotationPacket {
+                    x,
+                    y,
+                    z,
+                    yaw,
+                    pitch,
+                    on_ground,
+                }
+                .into()
+            }),
+        (any::<f32>(), any::<f32>(), any::<bool>()).prop_map(|(yaw, pitch, on_ground)| {
+            serverbound::play::PlayerRotationPacket {
+                yaw,
+                pitch,
+                on_ground,
+            }
+            .into()
+        }),
+        any::<bool>()
+            .prop_map(|on_ground| serverbound::play::PlayerMovementPacket { on_ground }.into()),
+        (
+            any::<i32>(),
+            -(1 << 25)..(1 << 25),
+            -(1 << 11)..(1 << 11),
+            -(1 << 25)..(1 << 25),
+            any::<i8>(),
+        )
+            .prop_map(|(status, x, y, z, face)| {
+                serverbound::play::PlayerDiggingPacket {
+                    status,
+                    location: Vector3::new(x, y, z),
+                    face,
+                }
+                .into()
+            }),
+        (any::<i32>(), any::<i32>(), any::<i32>()).prop_map(|(entity_id, action, jump_boost)| {
+            serverbound::play::EntityActionPacket {
+                entity_id,
+                action,
+                jump_boost,
+            }
+            .into()
+        }),
+        (any::<i32>(), interact_action(), sneaking).prop_map(|(entity_id, action, sneaking)| {
+            serverbound::play::InteractEntityPacket {
+                entity_id,
+                action,
+                sneaking,
+            }
+            .into()
+        }),
+        any::<i32>().prop_map(|action| serverbound::play::ClientStatusPacket { action }.into()),
+        any::<i32>().prop_map(|hand| serverbound::play::AnimationPacket { hand }.into()),
+        (
+            any::<u8>(),
+            any::<i16>(),
+            any::<i8>(),
+            any::<i16>(),
+            any::<i32>(),
+            slot(),
+        )
+            .prop_map(
+                |(window_id, slot, button, action_number, mode, clicked_item)| {
+                    serverbound::play::ClickWindowPacket {
+                        window_id,
+                        slot,
+                        button,
+                        action_number,
+                        mode,
+                        clicked_item,
+                    }
+                    .into()
+                }
+            ),
+        (any::<i8>(), any::<i16>(), any::<bool>()).prop_map(
+            |(window_id, action_number, accepted)| {
+                serverbound::play::WindowConfirmationPacket {
+                    window_id,
+                    action_number,
+                    accepted,
+                }
+                .into()
+            }
+        ),
+        any::<u8>().prop_map(|window_id| serverbound::play::CloseWindowPacket { window_id }.into()),
+        any::<i16>().prop_map(|slot| serverbound::play::HeldItemChangePacket { slot }.into()),
+    ]
+    .boxed()
+}
+
+fn interact_action() -> impl Strategy<Value = InteractAction> {
+    prop_oneof![
+        any::<i32>().prop_map(|hand| InteractAction::Interact { hand }),
+        Just(InteractAction::Attack),
+        (any::<(f32, f32, f32)>(), any::<i32>())
+            .prop_map(|(target, hand)| InteractAction::InteractAt { target, hand }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn clientbound_round_trip(
+        (version, packet) in version().prop_flat_map(|v| (Just(v), clientbound_packet(v)))
+    ) {
+        assert_round_trip(packet, version);
+    }
+
+    #[test]
+    fn serverbound_round_trip(
+        (version, packet) in version().prop_flat_map(|v| (Just(v), serverbound_packet(v)))
+    ) {
+        assert_round_trip(packet, version);
+    }
+
+    #[test]
+    fn serverbound_arbitrary_bytes_never_panic(
+        version in version(),
+        state in state(),
+        bytes: Vec<u8>,
+    ) {
+        let mut buffer = Buffer::new();
+        buffer.extend(&bytes);
+
+        while ServerboundPacket::deserialize(&mut buffer, &state, version).is_ok() {}
+    }
+
+    #[test]
+    fn clientbound_arbitrary_bytes_never_panic(
+        version in version(),
+        state in state(),
+        bytes: Vec<u8>,
+    ) {
+        let mut buffer = Buffer::new();
+        buffer.extend(&bytes);
+
+        while ClientboundPacket::deserialize(&mut buffer, &state, version).is_ok() {}
+    }
+
+    #[test]
+    fn serverbound_truncated_packets_are_incomplete(
+        (version, packet) in version().prop_flat_map(|v| (Just(v), serverbound_packet(v)))
+    ) {
+        let frame = encode(&packet, version);
+        let mut truncated = Buffer::new();
+        truncated.extend(&frame.as_raw()[..frame.len() - 1]);
+
+        prop_assert!(ServerboundPacket::deserialize(&mut truncated, &packet.get_state(), version).is_err());
+    }
+}
+
+/// Frames a raw payload, without going through a packet.
+fn frame(payload: &[u8]) -> Buffer {
+    let mut frame = Buffer::new();
+    frame.write_varint(payload.len() as i32).unwrap();
+    frame.extend(payload);
+    frame
+}
+
+#[test]
+fn long_username_is_rejected() {
+    let packet: ServerboundPacket = serverbound::login::LoginStartPacket {
+        username: "a".repeat(17),
+    }
+    .into();
+    let mut frame = encode(&packet, ProtocolVersion::V1_16);
+
+    let e = ServerboundPacket::deserialize(&mut frame, &State::Login, ProtocolVersion::V1_16)
+        .unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn oversized_byte_array_is_rejected() {
+    // Encryption response declaring a 1 MiB shared secret
+    let mut payload = Buffer::new();
+    payload.write_varint(0x01).unwrap();
+    payload.write_varint(1 << 20).unwrap();
+    let mut frame = frame(payload.as_raw());
+
+    let e = ServerboundPacket::deserialize(&mut frame, &State::Login, ProtocolVersion::V1_16)
+        .unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn trailing_bytes_are_rejected() {
+    let packet: ServerboundPacket = serverbound::status::PingPacket { payload: 42 }.into();
+    let encoded = encode(&packet, ProtocolVersion::V1_16);
+    let mut payload = encoded.as_raw()[1..].to_vec();
+    payload.push(0);
+    let mut frame = frame(&payload);
+
+    let e = ServerboundPacket::deserialize(&mut frame, &State::Status, ProtocolVersion::V1_16)
+        .unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn empty_frame_is_rejected() {
+    let mut frame = frame(&[]);
+
+    assert!(
+        ServerboundPacket::deserialize(&mut frame, &State::Play, ProtocolVersion::V1_16).is_err()
+    );
+}
+
+#[test]
+fn unknown_packet_is_skipped() {
+    let mut frame = frame(&[0x7F, 0x01, 0x02]);
+    frame.extend(&[0x00]);
+
+    let e = ServerboundPacket::deserialize(&mut frame, &State::Play, ProtocolVersion::V1_16)
+        .unwrap_err();
+    assert_eq!(
+        UnknownPacket::from_error(&e),
+        Some(UnknownPacket { id: 0x7F })
+    );
+    assert_eq!(frame.cursor(), 4);
+}
+
+#[test]
+fn player_digging_location_matches_protocol_sample() {
+    // Finished digging at (18357644, 831, -20882616), on the top face
+    let mut frame = frame(&[
+        0x1B, 0x02, 0x46, 0x07, 0x63, 0x2C, 0x15, 0xB4, 0x83, 0x3F, 0x01,
+    ]);
+
+    match ServerboundPacket::deserialize(&mut frame, &State::Play, ProtocolVersion::V1_16) {
+        Ok(ServerboundPacket::PlayerDigging(packet)) => {
+            assert_eq!(packet.status, 2);
+            assert_eq!(packet.location, Vector3::new(18_357_644, 831, -20_882_616));
+            assert_eq!(packet.face, 1);
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
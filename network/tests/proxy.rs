@@ -0,0 +1,106 @@
+use network::buffer::Buffer;
+use network::proxy::{parse_bungeecord, parse_velocity};
+use network::utils::hmac_sha256;
+use std::io;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+const SECRET: &str = "s3cret";
+const UUID: &str = "069a79f4-44e9-4726-a5be-fca90e38aaf5";
+
+/// Velocity forwarding payload of the given version, without its signature.
+fn velocity_payload(version: i32) -> Vec<u8> {
+    let mut buffer = Buffer::new();
+    buffer.write_varint(version).unwrap();
+    buffer.write_string("203.0.113.7").unwrap();
+    buffer.write_uuid(&Uuid::parse_str(UUID).unwrap()).unwrap();
+    buffer.write_string("Notch").unwrap();
+    buffer.write_varint(1).unwrap();
+    buffer.write_string("textures").unwrap();
+    buffer.write_string("e30=").unwrap();
+    buffer.write_bool(true).unwrap();
+    buffer.write_string("c2lnbmF0dXJl").unwrap();
+    buffer.as_raw().to_vec()
+}
+
+fn sign(payload: &[u8], secret: &str) -> Vec<u8> {
+    let mut data = hmac_sha256(secret.as_bytes(), payload).unwrap();
+    data.extend(payload);
+    data
+}
+
+#[test]
+fn bungeecord_address_is_parsed() {
+    let uuid = UUID.replace('-', "");
+    let address = [
+        "mc.example.com",
+        "203.0.113.7",
+        &uuid,
+        r#"[{"name":"textures","value":"e30=","signature":"c2ln"}]"#,
+    ]
+    .join("\0");
+    let player = parse_bungeecord(&address).unwrap();
+
+    assert_eq!(player.address, "203.0.113.7".parse::<IpAddr>().unwrap());
+    assert_eq!(player.uuid, Uuid::parse_str(UUID).unwrap());
+    assert_eq!(player.username, None);
+    assert_eq!(player.properties.len(), 1);
+    assert_eq!(player.properties[0].name, "textures");
+    assert_eq!(player.properties[0].signature.as_deref(), Some("c2ln"));
+}
+
+#[test]
+fn malformed_bungeecord_addresses_are_rejected() {
+    for parts in &[
+        vec!["mc.example.com"],
+        vec!["mc.example.com", "203.0.113.7"],
+        vec!["mc.example.com", "not an ip", UUID],
+        vec!["mc.example.com", "203.0.113.7", "not a uuid"],
+        vec!["mc.example.com", "203.0.113.7", UUID, "{"],
+    ] {
+        let address = parts.join("\0");
+        let e = parse_bungeecord(&address).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData, "{:?}", address);
+    }
+}
+
+#[test]
+fn velocity_payload_is_verified_and_parsed() {
+    let player = parse_velocity(&sign(&velocity_payload(1), SECRET), SECRET).unwrap();
+
+    assert_eq!(player.address, "203.0.113.7".parse::<IpAddr>().unwrap());
+    assert_eq!(player.uuid, Uuid::parse_str(UUID).unwrap());
+    assert_eq!(player.username.as_deref(), Some("Notch"));
+    assert_eq!(player.properties.len(), 1);
+    assert_eq!(player.properties[0].value, "e30=");
+    assert_eq!(
+        player.properties[0].signature.as_deref(),
+        Some("c2lnbmF0dXJl")
+    );
+}
+
+#[test]
+fn velocity_payload_with_a_bad_hmac_is_rejected() {
+    let payload = velocity_payload(1);
+
+    let e = parse_velocity(&sign(&payload, "other secret"), SECRET).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+    // Tampering with the payload breaks the signature too
+    let mut data = sign(&payload, SECRET);
+    let last = data.len() - 1;
+    data[last] ^= 1;
+    let e = parse_velocity(&data, SECRET).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+    let e = parse_velocity(&[0; 16], SECRET).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn unsupported_velocity_version_is_rejected() {
+    let e = parse_velocity(&sign(&velocity_payload(2), SECRET), SECRET).unwrap_err();
+
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    assert!(e.to_string().contains("version 2"));
+}
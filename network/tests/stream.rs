@@ -0,0 +1,160 @@
+use network::buffer::Buffer;
+use network::channel::MAX_CHANNELS;
+use network::packet::{Packet, State};
+use network::serverbound;
+use network::serverbound::ServerboundPacket;
+use network::stream::Stream;
+use std::io;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+/// A server side stream in the play state, and the client end of its socket.
+fn connect() -> (Stream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (handle, peer) = listener.accept().unwrap();
+    let mut stream = Stream::new(handle, peer).unwrap();
+
+    stream.set_state(State::Play);
+    (stream, client)
+}
+
+/// Reads a packet, waiting a bit for the bytes to arrive.
+fn read(stream: &mut Stream) -> io::Result<ServerboundPacket> {
+    for _ in 0..100 {
+        match stream.read_packet() {
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(10))
+            }
+            result => return result,
+        }
+    }
+    Err(io::Error::from(io::ErrorKind::WouldBlock))
+}
+
+/// Frames a packet the way a client sends it.
+fn encode(stream: &Stream, packet: &ServerboundPacket) -> Vec<u8> {
+    let version = stream.get_protocol_version();
+    let mut payload = Buffer::new();
+    payload
+        .write_varint(packet.get_id(version).unwrap())
+        .unwrap();
+    packet.serialize(&mut payload, version).unwrap();
+
+    let mut frame = Buffer::new();
+    frame.write_varint(payload.len() as i32).unwrap();
+    frame.extend(payload.as_raw());
+    frame.as_raw().to_vec()
+}
+
+#[test]
+fn unknown_play_packets_are_skipped() {
+    let (mut stream, mut client) = connect();
+    let keep_alive: ServerboundPacket = serverbound::play::KeepAlivePacket { id: 42 }.into();
+
+    // Client Settings, which isn't handled, followed by a keep-alive
+    client
+        .write_all(&[0x09, 0x05, 0x05, b'e', b'n', b'_', b'u', b's', 0x08, 0x00])
+        .unwrap();
+    client.write_all(&encode(&stream, &keep_alive)).unwrap();
+
+    match read(&mut stream).unwrap() {
+        ServerboundPacket::KeepAlive(packet) => assert_eq!(packet.id, 42),
+        packet => panic!("unexpected packet {:?}", packet),
+    }
+}
+
+#[test]
+fn truncated_payload_in_a_complete_frame_is_rejected() {
+    let (mut stream, mut client) = connect();
+    let position: ServerboundPacket = serverbound::play::PlayerPositionPacket {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        on_ground: true,
+    }
+    .into();
+    let frame = encode(&stream, &position);
+
+    // Only the packet id, without the position
+    client.write_all(&[0x01, frame[1]]).unwrap();
+
+    let e = read(&mut stream).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn partial_frames_wait_for_the_rest() {
+    let (mut stream, mut client) = connect();
+    let keep_alive: ServerboundPacket = serverbound::play::KeepAlivePacket { id: 7 }.into();
+    let frame = encode(&stream, &keep_alive);
+
+    client.write_all(&frame[..4]).unwrap();
+    assert_eq!(
+        read(&mut stream).unwrap_err().kind(),
+        io::ErrorKind::WouldBlock
+    );
+
+    client.write_all(&frame[4..]).unwrap();
+    assert!(matches!(
+        read(&mut stream).unwrap(),
+        ServerboundPacket::KeepAlive(_)
+    ));
+}
+
+#[test]
+fn unanswered_login_queries_time_out_as_not_understood() {
+    let (mut stream, _client) = connect();
+    stream.set_state(State::Login);
+
+    let message_id = stream
+        .send_login_query("minecrate:test", b"ping", Duration::from_millis(20))
+        .unwrap();
+    thread::sleep(Duration::from_millis(30));
+
+    match read(&mut stream).unwrap() {
+        ServerboundPacket::LoginPluginResponse(packet) => {
+            assert_eq!(packet.message_id, message_id);
+            assert!(!packet.successful);
+            assert!(packet.data.is_empty());
+        }
+        packet => panic!("unexpected packet {:?}", packet),
+    }
+    assert_eq!(
+        stream.resolve_login_query(message_id),
+        Some(String::from("minecrate:test"))
+    );
+    assert!(!stream.has_pending_login_queries());
+}
+
+#[test]
+fn raw_packets_are_framed() {
+    let (mut stream, mut client) = connect();
+
+    stream.send_raw_packet(0x18, &[1, 2, 3]).unwrap();
+
+    let mut frame = [0; 5];
+    client.read_exact(&mut frame).unwrap();
+    assert_eq!(frame, [0x04, 0x18, 1, 2, 3]);
+}
+
+#[test]
+fn registering_too_many_channels_disconnects() {
+    let (mut stream, _client) = connect();
+    let channels: Vec<String> = (0..MAX_CHANNELS)
+        .map(|i| format!("minecrate:test{}", i))
+        .collect();
+
+    stream.register_channels(&channels).unwrap();
+    // Registering a channel again doesn't count twice
+    stream.register_channels(&channels[..1]).unwrap();
+    assert_eq!(stream.get_channels().len(), MAX_CHANNELS);
+
+    let e = stream
+        .register_channels(&[String::from("minecrate:extra")])
+        .unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    assert!(!stream.is_channel_registered("minecrate:extra"));
+}
@@ -0,0 +1,63 @@
+use network::throttle::{LoginThrottle, PacketRate};
+use std::net::IpAddr;
+use std::thread;
+use std::time::Duration;
+
+const DELAY: Duration = Duration::from_millis(200);
+
+fn address(last: u8) -> IpAddr {
+    IpAddr::from([203, 0, 113, last])
+}
+
+#[test]
+fn logins_from_the_same_ip_are_delayed() {
+    let mut throttle = LoginThrottle::new(DELAY);
+
+    assert!(throttle.try_login(address(1)));
+    assert!(!throttle.try_login(address(1)));
+    // Other IPs aren't affected
+    assert!(throttle.try_login(address(2)));
+
+    thread::sleep(DELAY);
+    assert!(throttle.try_login(address(1)));
+}
+
+#[test]
+fn throttled_attempts_reset_the_delay() {
+    let mut throttle = LoginThrottle::new(DELAY);
+
+    assert!(throttle.try_login(address(1)));
+    thread::sleep(DELAY / 2);
+    assert!(!throttle.try_login(address(1)));
+
+    // The full delay counts from the throttled attempt
+    thread::sleep(DELAY * 3 / 4);
+    assert!(!throttle.try_login(address(1)));
+    thread::sleep(DELAY);
+    assert!(throttle.try_login(address(1)));
+}
+
+#[test]
+fn zero_delay_disables_the_throttle() {
+    let mut throttle = LoginThrottle::new(Duration::from_secs(0));
+
+    for _ in 0..10 {
+        assert!(throttle.try_login(address(1)));
+    }
+}
+
+#[test]
+fn packet_rate_rolls_over_every_second() {
+    let mut rate = PacketRate::new();
+
+    assert_eq!(rate.current(), 0);
+    for _ in 0..5 {
+        rate.record();
+    }
+    assert_eq!(rate.current(), 5);
+
+    thread::sleep(Duration::from_millis(1050));
+    assert_eq!(rate.current(), 0);
+    rate.record();
+    assert_eq!(rate.current(), 1);
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+use crate::EventKind;
+
+/// Players are identified by their hyphenated UUID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerPreLogin {
+    pub uuid: String,
+    pub username: String,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerJoin {
+    pub uuid: String,
+    pub username: String,
+    /// Protocol version of the client, which packets sent to it follow.
+    #[serde(default)]
+    pub protocol: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerQuit {
+    pub uuid: String,
+    pub username: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerChat {
+    pub uuid: String,
+    pub username: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerMove {
+    pub uuid: String,
+    pub from: Location,
+    pub to: Location,
+    pub on_ground: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockBreak {
+    pub uuid: String,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerTick {
+    pub tick: u64,
+}
+
+/// An event as seen by plugins.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    PlayerPreLogin(PlayerPreLogin),
+    PlayerJoin(PlayerJoin),
+    PlayerQuit(PlayerQuit),
+    PlayerChat(PlayerChat),
+    PlayerMove(PlayerMove),
+    BlockBreak(BlockBreak),
+    ServerTick(ServerTick),
+}
+
+impl Event {
+    pub fn get_kind(&self) -> EventKind {
+        match self {
+            Event::PlayerPreLogin(_) => EventKind::PlayerPreLogin,
+            Event::PlayerJoin(_) => EventKind::PlayerJoin,
+            Event::PlayerQuit(_) => EventKind::PlayerQuit,
+            Event::PlayerChat(_) => EventKind::PlayerChat,
+            Event::PlayerMove(_) => EventKind::PlayerMove,
+            Event::BlockBreak(_) => EventKind::BlockBreak,
+            Event::ServerTick(_) => EventKind::ServerTick,
+        }
+    }
+
+    /// The kind and payload passed to `minecrate_on_event`.
+    pub fn encode(&self) -> serde_json::Result<(i32, Vec<u8>)> {
+        let payload = match self {
+            Event::PlayerPreLogin(event) => serde_json::to_vec(event),
+            Event::PlayerJoin(event) => serde_json::to_vec(event),
+            Event::PlayerQuit(event) => serde_json::to_vec(event),
+            Event::PlayerChat(event) => serde_json::to_vec(event),
+            Event::PlayerMove(event) => serde_json::to_vec(event),
+            Event::BlockBreak(event) => serde_json::to_vec(event),
+            Event::ServerTick(event) => serde_json::to_vec(event),
+        }?;
+
+        Ok((self.get_kind().into(), payload))
+    }
+
+    pub fn decode(kind: i32, payload: &[u8]) -> Result<Self, String> {
+        let kind = EventKind::try_from(kind)?;
+        let event = match kind {
+            EventKind::PlayerPreLogin => serde_json::from_slice(payload).map(Event::PlayerPreLogin),
+            EventKind::PlayerJoin => serde_json::from_slice(payload).map(Event::PlayerJoin),
+            EventKind::PlayerQuit => serde_json::from_slice(payload).map(Event::PlayerQuit),
+            EventKind::PlayerChat => serde_json::from_slice(payload).map(Event::PlayerChat),
+            EventKind::PlayerMove => serde_json::from_slice(payload).map(Event::PlayerMove),
+            EventKind::BlockBreak => serde_json::from_slice(payload).map(Event::BlockBreak),
+            EventKind::ServerTick => serde_json::from_slice(payload).map(Event::ServerTick),
+        };
+
+        event.map_err(|e| e.to_string())
+    }
+}
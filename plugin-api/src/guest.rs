@@ -0,0 +1,233 @@
+//! Safe wrappers around the functions offered by the host, and the
+//! [`plugin!`](crate::plugin) macro exporting a [`Plugin`].
+//!
+//! ```ignore
+//! use plugin_api::guest::{self, Plugin};
+//! use plugin_api::{Event, EventKind, Priority};
+//!
+//! #[derive(Default)]
+//! struct Greeter;
+//!
+//! impl Plugin for Greeter {
+//!     fn enable(&mut self) {
+//!         guest::subscribe(EventKind::PlayerJoin, Priority::Normal);
+//!     }
+//!
+//!     fn on_event(&mut self, event: Event) -> bool {
+//!         if let Event::PlayerJoin(join) = event {
+//!             guest::send_message(&join.uuid, "Welcome!");
+//!         }
+//!         false
+//!     }
+//! }
+//!
+//! plugin_api::plugin!(Greeter);
+//! ```
+
+use std::ptr;
+
+use crate::{CommandCall, Event, EventKind, LogLevel, Priority};
+
+mod host {
+    #[link(wasm_import_module = "minecrate")]
+    extern "C" {
+        pub fn log(level: i32, ptr: *const u8, len: usize);
+        pub fn register_command(
+            name_ptr: *const u8,
+            name_len: usize,
+            usage_ptr: *const u8,
+            usage_len: usize,
+            permission_level: i32,
+        ) -> i32;
+        pub fn subscribe(kind: i32, priority: i32) -> i32;
+        pub fn schedule(delay: i32, period: i32) -> i32;
+        pub fn cancel_task(task: i32);
+        pub fn send_message(
+            uuid_ptr: *const u8,
+            uuid_len: usize,
+            message_ptr: *const u8,
+            message_len: usize,
+        );
+        pub fn send_packet(
+            uuid_ptr: *const u8,
+            uuid_len: usize,
+            packet_id: i32,
+            data_ptr: *const u8,
+            data_len: usize,
+        );
+        pub fn broadcast(ptr: *const u8, len: usize);
+        pub fn kick(uuid_ptr: *const u8, uuid_len: usize, reason_ptr: *const u8, reason_len: usize);
+        pub fn reply(ptr: *const u8, len: usize);
+    }
+}
+
+/// Implemented by the plugin, and exported with [`plugin!`](crate::plugin).
+pub trait Plugin {
+    fn enable(&mut self) {}
+
+    fn disable(&mut self) {}
+
+    /// Returns true to cancel the event, if it can be.
+    fn on_event(&mut self, _event: Event) -> bool {
+        false
+    }
+
+    fn on_command(&mut self, _call: CommandCall) {}
+
+    fn on_task(&mut self, _task: i32) {}
+}
+
+pub fn log(level: LogLevel, message: &str) {
+    unsafe { host::log(level.into(), message.as_ptr(), message.len()) }
+}
+
+/// Only works while the plugin is being enabled. Returns false if the name
+/// is taken.
+pub fn register_command(name: &str, usage: &str, permission_level: u8) -> bool {
+    unsafe {
+        host::register_command(
+            name.as_ptr(),
+            name.len(),
+            usage.as_ptr(),
+            usage.len(),
+            i32::from(permission_level),
+        ) == 0
+    }
+}
+
+pub fn subscribe(kind: EventKind, priority: Priority) {
+    unsafe {
+        host::subscribe(kind.into(), priority.into());
+    }
+}
+
+/// Runs `on_task` after `delay` ticks, then every `period` ticks if it isn't
+/// zero. Returns the id of the task.
+pub fn schedule(delay: u32, period: u32) -> i32 {
+    unsafe { host::schedule(delay as i32, period as i32) }
+}
+
+pub fn cancel_task(task: i32) {
+    unsafe { host::cancel_task(task) }
+}
+
+/// Sends a chat message to a player, identified by their UUID.
+pub fn send_message(uuid: &str, message: &str) {
+    unsafe { host::send_message(uuid.as_ptr(), uuid.len(), message.as_ptr(), message.len()) }
+}
+
+/// Sends a play packet to a player, `data` being its payload without the
+/// length and id. Ids and payloads change between releases, so they must
+/// follow the `protocol` the player joined with.
+pub fn send_packet(uuid: &str, packet_id: i32, data: &[u8]) {
+    unsafe {
+        host::send_packet(
+            uuid.as_ptr(),
+            uuid.len(),
+            packet_id,
+            data.as_ptr(),
+            data.len(),
+        )
+    }
+}
+
+pub fn broadcast(message: &str) {
+    unsafe { host::broadcast(message.as_ptr(), message.len()) }
+}
+
+pub fn kick(uuid: &str, reason: &str) {
+    unsafe { host::kick(uuid.as_ptr(), uuid.len(), reason.as_ptr(), reason.len()) }
+}
+
+/// Answers the sender of the command being run.
+pub fn reply(message: &str) {
+    unsafe { host::reply(message.as_ptr(), message.len()) }
+}
+
+#[doc(hidden)]
+pub fn alloc(len: i32) -> *mut u8 {
+    let buffer = vec![0u8; len.max(0) as usize].into_boxed_slice();
+    Box::into_raw(buffer) as *mut u8
+}
+
+/// Takes back a buffer given by `alloc`.
+///
+/// # Safety
+///
+/// `ptr` and `len` must come from a call to `alloc`, and be taken once.
+#[doc(hidden)]
+pub unsafe fn take(ptr: *mut u8, len: i32) -> Box<[u8]> {
+    Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len.max(0) as usize))
+}
+
+/// Exports a [`Plugin`] implementing `Default` to the host.
+#[macro_export]
+macro_rules! plugin {
+    ($plugin:ty) => {
+        thread_local! {
+            static PLUGIN: std::cell::RefCell<$plugin> =
+                std::cell::RefCell::new(<$plugin as Default>::default());
+        }
+
+        #[no_mangle]
+        pub extern "C" fn minecrate_abi_version() -> i32 {
+            $crate::ABI_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn minecrate_alloc(len: i32) -> *mut u8 {
+            $crate::guest::alloc(len)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn minecrate_enable() {
+            PLUGIN.with(|plugin| $crate::guest::Plugin::enable(&mut *plugin.borrow_mut()));
+        }
+
+        #[no_mangle]
+        pub extern "C" fn minecrate_disable() {
+            PLUGIN.with(|plugin| $crate::guest::Plugin::disable(&mut *plugin.borrow_mut()));
+        }
+
+        #[no_mangle]
+        pub extern "C" fn minecrate_on_event(kind: i32, ptr: *mut u8, len: i32) -> i32 {
+            let payload = unsafe { $crate::guest::take(ptr, len) };
+
+            match $crate::Event::decode(kind, &payload) {
+                Ok(event) => PLUGIN.with(|plugin| {
+                    if $crate::guest::Plugin::on_event(&mut *plugin.borrow_mut(), event) {
+                        $crate::EVENT_CANCELLED
+                    } else {
+                        0
+                    }
+                }),
+                Err(e) => {
+                    $crate::guest::log($crate::LogLevel::Error, &e);
+                    0
+                }
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn minecrate_on_command(ptr: *mut u8, len: i32) {
+            let payload = unsafe { $crate::guest::take(ptr, len) };
+
+            match $crate::guest::decode_command(&payload) {
+                Ok(call) => PLUGIN.with(|plugin| {
+                    $crate::guest::Plugin::on_command(&mut *plugin.borrow_mut(), call)
+                }),
+                Err(e) => $crate::guest::log($crate::LogLevel::Error, &e),
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn minecrate_on_task(task: i32) {
+            PLUGIN.with(|plugin| $crate::guest::Plugin::on_task(&mut *plugin.borrow_mut(), task));
+        }
+    };
+}
+
+#[doc(hidden)]
+pub fn decode_command(payload: &[u8]) -> Result<CommandCall, String> {
+    serde_json::from_slice(payload).map_err(|e| e.to_string())
+}
@@ -0,0 +1,172 @@
+//! Interface between Minecrate and its plugins, which are WebAssembly
+//! modules loaded from the `plugins/` directory.
+//!
+//! A plugin exports its `memory` and these functions, the optional ones
+//! being skipped when missing:
+//!
+//! - `minecrate_abi_version() -> i32`, returning [`ABI_VERSION`]
+//! - `minecrate_alloc(len: i32) -> i32`, giving the host a buffer to write
+//!   event and command payloads to, which the plugin then owns
+//! - `minecrate_enable()` and `minecrate_disable()`, optional
+//! - `minecrate_on_event(kind: i32, ptr: i32, len: i32) -> i32`, optional,
+//!   returning 1 to cancel the event
+//! - `minecrate_on_command(ptr: i32, len: i32)`, optional
+//! - `minecrate_on_task(task: i32)`, optional
+//!
+//! Payloads are JSON. The functions the host offers are imported from the
+//! [`HOST_MODULE`] module, and are wrapped by the [`guest`] module when
+//! building for `wasm32`. Strings and byte arrays are passed as a pointer
+//! and a length.
+
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+mod events;
+#[cfg(target_arch = "wasm32")]
+pub mod guest;
+
+pub use events::{
+    BlockBreak, Event, Location, PlayerChat, PlayerJoin, PlayerMove, PlayerPreLogin, PlayerQuit,
+    ServerTick,
+};
+
+/// Bumped on every change breaking existing plugins.
+pub const ABI_VERSION: i32 = 1;
+
+pub const HOST_MODULE: &str = "minecrate";
+
+pub const EXPORT_ABI_VERSION: &str = "minecrate_abi_version";
+pub const EXPORT_ALLOC: &str = "minecrate_alloc";
+pub const EXPORT_ENABLE: &str = "minecrate_enable";
+pub const EXPORT_DISABLE: &str = "minecrate_disable";
+pub const EXPORT_ON_EVENT: &str = "minecrate_on_event";
+pub const EXPORT_ON_COMMAND: &str = "minecrate_on_command";
+pub const EXPORT_ON_TASK: &str = "minecrate_on_task";
+
+/// Returned by `minecrate_on_event` to cancel a cancellable event.
+pub const EVENT_CANCELLED: i32 = 1;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum EventKind {
+    PlayerPreLogin,
+    PlayerJoin,
+    PlayerQuit,
+    PlayerChat,
+    PlayerMove,
+    BlockBreak,
+    ServerTick,
+}
+
+impl From<EventKind> for i32 {
+    fn from(value: EventKind) -> Self {
+        match value {
+            EventKind::PlayerPreLogin => 0,
+            EventKind::PlayerJoin => 1,
+            EventKind::PlayerQuit => 2,
+            EventKind::PlayerChat => 3,
+            EventKind::PlayerMove => 4,
+            EventKind::BlockBreak => 5,
+            EventKind::ServerTick => 6,
+        }
+    }
+}
+
+impl TryFrom<i32> for EventKind {
+    type Error = &'static str;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(EventKind::PlayerPreLogin),
+            1 => Ok(EventKind::PlayerJoin),
+            2 => Ok(EventKind::PlayerQuit),
+            3 => Ok(EventKind::PlayerChat),
+            4 => Ok(EventKind::PlayerMove),
+            5 => Ok(EventKind::BlockBreak),
+            6 => Ok(EventKind::ServerTick),
+            _ => Err("Unknown EventKind"),
+        }
+    }
+}
+
+/// When a listener is called relative to the others of the same event.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Priority {
+    Lowest,
+    Low,
+    Normal,
+    High,
+    Highest,
+    Monitor,
+}
+
+impl From<Priority> for i32 {
+    fn from(value: Priority) -> Self {
+        match value {
+            Priority::Lowest => 0,
+            Priority::Low => 1,
+            Priority::Normal => 2,
+            Priority::High => 3,
+            Priority::Highest => 4,
+            Priority::Monitor => 5,
+        }
+    }
+}
+
+impl TryFrom<i32> for Priority {
+    type Error = &'static str;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Priority::Lowest),
+            1 => Ok(Priority::Low),
+            2 => Ok(Priority::Normal),
+            3 => Ok(Priority::High),
+            4 => Ok(Priority::Highest),
+            5 => Ok(Priority::Monitor),
+            _ => Err("Unknown Priority"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl From<LogLevel> for i32 {
+    fn from(value: LogLevel) -> Self {
+        match value {
+            LogLevel::Error => 0,
+            LogLevel::Warn => 1,
+            LogLevel::Info => 2,
+            LogLevel::Debug => 3,
+        }
+    }
+}
+
+impl TryFrom<i32> for LogLevel {
+    type Error = &'static str;
+
+    fn try_from(value: i32) -> Result<Self, &'static str> {
+        match value {
+            0 => Ok(LogLevel::Error),
+            1 => Ok(LogLevel::Warn),
+            2 => Ok(LogLevel::Info),
+            3 => Ok(LogLevel::Debug),
+            _ => Err("Unknown LogLevel"),
+        }
+    }
+}
+
+/// A command registered by the plugin being run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandCall {
+    pub command: String,
+    pub args: Vec<String>,
+    /// UUID of the player running the command, `None` for the console.
+    pub sender: Option<String>,
+    pub sender_name: String,
+}
@@ -0,0 +1,42 @@
+use plugin_api::{Event, EventKind, Location, PlayerChat, PlayerMove, ServerTick};
+use std::convert::TryFrom;
+
+fn round_trip(event: Event) {
+    let (kind, payload) = event.encode().unwrap();
+
+    assert_eq!(EventKind::try_from(kind), Ok(event.get_kind()));
+    assert_eq!(Event::decode(kind, &payload), Ok(event));
+}
+
+#[test]
+fn events_round_trip() {
+    let location = Location {
+        x: 0.5,
+        y: 64.0,
+        z: -12.25,
+        yaw: 90.0,
+        pitch: -10.0,
+    };
+
+    round_trip(Event::PlayerChat(PlayerChat {
+        uuid: String::from("069a79f4-44e9-4726-a5be-fca90e38aaf5"),
+        username: String::from("Notch"),
+        message: String::from("Hello \"world\""),
+    }));
+    round_trip(Event::PlayerMove(PlayerMove {
+        uuid: String::from("069a79f4-44e9-4726-a5be-fca90e38aaf5"),
+        from: location,
+        to: Location {
+            y: 65.0,
+            ..location
+        },
+        on_ground: false,
+    }));
+    round_trip(Event::ServerTick(ServerTick { tick: u64::MAX }));
+}
+
+#[test]
+fn unknown_kind_is_rejected() {
+    assert!(Event::decode(42, b"{}").is_err());
+    assert!(Event::decode(EventKind::PlayerJoin.into(), b"{}").is_err());
+}
@@ -0,0 +1,529 @@
+use chrono::{DateTime, FixedOffset, Local};
+use network::login_query::LoginHook;
+use network::serverbound::login::LoginPluginResponsePacket;
+use network::stream::Stream;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use uuid::Uuid;
+
+use crate::permissions::Permissions;
+
+const BANNED_PLAYERS_FILE: &str = "banned-players.json";
+const BANNED_IPS_FILE: &str = "banned-ips.json";
+const WHITELIST_FILE: &str = "whitelist.json";
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+pub const DEFAULT_BAN_REASON: &str = "Banned by an operator.";
+
+/// Fields shared by player and IP bans, as vanilla writes them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanDetails {
+    #[serde(with = "date")]
+    pub created: DateTime<FixedOffset>,
+    pub source: String,
+    /// `None` for bans written as "forever".
+    #[serde(with = "expiry")]
+    pub expires: Option<DateTime<FixedOffset>>,
+    pub reason: String,
+}
+
+impl BanDetails {
+    pub fn new(source: &str, reason: &str, expires: Option<DateTime<FixedOffset>>) -> Self {
+        Self {
+            created: Local::now().into(),
+            source: source.to_string(),
+            expires,
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        match self.expires {
+            Some(expires) => expires <= Local::now(),
+            None => false,
+        }
+    }
+
+    fn message(&self, header: &str) -> String {
+        let mut message = format!("{}\nReason: {}", header, self.reason);
+
+        if let Some(expires) = self.expires {
+            message.push_str(&format!(
+                "\nYour ban will be removed on {}",
+                expires.format(DATE_FORMAT)
+            ));
+        }
+        message
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerBan {
+    pub uuid: Uuid,
+    pub name: String,
+    #[serde(flatten)]
+    pub details: BanDetails,
+}
+
+impl PlayerBan {
+    /// Reason shown to the player when disconnected.
+    pub fn message(&self) -> String {
+        self.details.message("You are banned from this server.")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpBan {
+    pub ip: IpAddr,
+    #[serde(flatten)]
+    pub details: BanDetails,
+}
+
+impl IpBan {
+    pub fn message(&self) -> String {
+        self.details
+            .message("Your IP address is banned from this server.")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhitelistEntry {
+    pub uuid: Uuid,
+    pub name: String,
+}
+
+/// The ban lists and whitelist, stored next to the server in the files
+/// vanilla uses. Every change is written back immediately.
+pub struct AccessLists {
+    dir: PathBuf,
+    banned_players: Vec<PlayerBan>,
+    banned_ips: Vec<IpBan>,
+    whitelist: Vec<WhitelistEntry>,
+    whitelist_enabled: bool,
+}
+
+impl AccessLists {
+    pub fn load(dir: &Path, whitelist_enabled: bool) -> io::Result<Self> {
+        let mut lists = Self {
+            dir: dir.to_path_buf(),
+            banned_players: vec![],
+            banned_ips: vec![],
+            whitelist: vec![],
+            whitelist_enabled,
+        };
+
+        lists.reload()?;
+        Ok(lists)
+    }
+
+    /// Reads the files again, picking up changes made by hand.
+    pub fn reload(&mut self) -> io::Result<()> {
+        self.banned_players = read_list(&self.dir.join(BANNED_PLAYERS_FILE))?;
+        self.banned_ips = read_list(&self.dir.join(BANNED_IPS_FILE))?;
+        self.whitelist = read_list(&self.dir.join(WHITELIST_FILE))?;
+        Ok(())
+    }
+
+    /// Returns the disconnect reason when the player may not join.
+    pub fn check_login(&self, uuid: &Uuid, address: &IpAddr, is_op: bool) -> Result<(), String> {
+        if let Some(ban) = self.get_player_ban(uuid) {
+            return Err(ban.message());
+        }
+        if let Some(ban) = self.get_ip_ban(address) {
+            return Err(ban.message());
+        }
+        if self.whitelist_enabled && !is_op && !self.is_whitelisted(uuid) {
+            return Err(String::from("You are not white-listed on this server!"));
+        }
+        Ok(())
+    }
+
+    pub fn get_player_ban(&self, uuid: &Uuid) -> Option<&PlayerBan> {
+        self.banned_players
+            .iter()
+            .find(|ban| ban.uuid == *uuid && !ban.details.is_expired())
+    }
+
+    pub fn get_ip_ban(&self, address: &IpAddr) -> Option<&IpBan> {
+        self.banned_ips
+            .iter()
+            .find(|ban| ban.ip == *address && !ban.details.is_expired())
+    }
+
+    /// Bans a player, replacing any previous ban of theirs.
+    pub fn ban_player(&mut self, ban: PlayerBan) -> io::Result<()> {
+        self.banned_players.retain(|other| other.uuid != ban.uuid);
+        self.banned_players.push(ban);
+        self.save_banned_players()
+    }
+
+    /// Returns false if no player with this name was banned.
+    pub fn pardon_player(&mut self, name: &str) -> io::Result<bool> {
+        let len = self.banned_players.len();
+
+        self.banned_players
+            .retain(|ban| !ban.name.eq_ignore_ascii_case(name));
+        if self.banned_players.len() == len {
+            return Ok(false);
+        }
+        self.save_banned_players().map(|_| true)
+    }
+
+    pub fn ban_ip(&mut self, ban: IpBan) -> io::Result<()> {
+        self.banned_ips.retain(|other| other.ip != ban.ip);
+        self.banned_ips.push(ban);
+        self.save_banned_ips()
+    }
+
+    /// Returns false if this address wasn't banned.
+    pub fn pardon_ip(&mut self, address: &IpAddr) -> io::Result<bool> {
+        let len = self.banned_ips.len();
+
+        self.banned_ips.retain(|ban| ban.ip != *address);
+        if self.banned_ips.len() == len {
+            return Ok(false);
+        }
+        self.save_banned_ips().map(|_| true)
+    }
+
+    pub fn get_banned_players(&self) -> &[PlayerBan] {
+        &self.banned_players
+    }
+
+    pub fn get_banned_ips(&self) -> &[IpBan] {
+        &self.banned_ips
+    }
+
+    pub fn is_whitelisted(&self, uuid: &Uuid) -> bool {
+        self.whitelist.iter().any(|entry| entry.uuid == *uuid)
+    }
+
+    /// Returns false if the player was already whitelisted.
+    pub fn whitelist_add(&mut self, entry: WhitelistEntry) -> io::Result<bool> {
+        if self.is_whitelisted(&entry.uuid) {
+            return Ok(false);
+        }
+
+        self.whitelist.push(entry);
+        self.save_whitelist().map(|_| true)
+    }
+
+    /// Returns false if no player with this name was whitelisted.
+    pub fn whitelist_remove(&mut self, name: &str) -> io::Result<bool> {
+        let len = self.whitelist.len();
+
+        self.whitelist
+            .retain(|entry| !entry.name.eq_ignore_ascii_case(name));
+        if self.whitelist.len() == len {
+            return Ok(false);
+        }
+        self.save_whitelist().map(|_| true)
+    }
+
+    pub fn get_whitelist(&self) -> &[WhitelistEntry] {
+        &self.whitelist
+    }
+
+    /// Finds the UUID and name of a player in the ban list or the whitelist,
+    /// ignoring case.
+    pub fn find_known_player(&self, name: &str) -> Option<(Uuid, String)> {
+        let bans = self.banned_players.iter().map(|ban| (&ban.uuid, &ban.name));
+        let whitelist = self
+            .whitelist
+            .iter()
+            .map(|entry| (&entry.uuid, &entry.name));
+
+        bans.chain(whitelist)
+            .find(|(_, known)| known.eq_ignore_ascii_case(name))
+            .map(|(uuid, name)| (*uuid, name.clone()))
+    }
+
+    pub fn set_whitelist_enabled(&mut self, enabled: bool) {
+        self.whitelist_enabled = enabled;
+    }
+
+    pub fn is_whitelist_enabled(&self) -> bool {
+        self.whitelist_enabled
+    }
+
+    fn save_banned_players(&self) -> io::Result<()> {
+        write_list(&self.dir.join(BANNED_PLAYERS_FILE), &self.banned_players)
+    }
+
+    fn save_banned_ips(&self) -> io::Result<()> {
+        write_list(&self.dir.join(BANNED_IPS_FILE), &self.banned_ips)
+    }
+
+    fn save_whitelist(&self) -> io::Result<()> {
+        write_list(&self.dir.join(WHITELIST_FILE), &self.whitelist)
+    }
+}
+
+/// Refuses banned and non-whitelisted players once they are authenticated.
+/// Operators don't need to be whitelisted.
+pub struct AccessHook {
+    lists: Rc<RefCell<AccessLists>>,
+    permissions: Rc<RefCell<Permissions>>,
+}
+
+impl AccessHook {
+    pub fn new(lists: Rc<RefCell<AccessLists>>, permissions: Rc<RefCell<Permissions>>) -> Self {
+        Self { lists, permissions }
+    }
+}
+
+impl LoginHook for AccessHook {
+    fn on_authenticated(&self, stream: &mut Stream) -> io::Result<()> {
+        let is_op = self.permissions.borrow().get_op_level(stream.get_uuid()) > 0;
+        let result =
+            self.lists
+                .borrow()
+                .check_login(stream.get_uuid(), stream.get_remote_address(), is_op);
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(reason) => stream.disconnect(&reason),
+        }
+    }
+
+    fn on_response(
+        &self,
+        _stream: &mut Stream,
+        _channel: &str,
+        _packet: &LoginPluginResponsePacket,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Missing files are created empty, like vanilla does on startup.
+pub fn read_list<T: DeserializeOwned + Serialize>(path: &Path) -> io::Result<Vec<T>> {
+    if !path.exists() {
+        write_list::<T>(path, &[])?;
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Failed to parse {}: {}", path.display(), e),
+        )
+    })
+}
+
+pub fn write_list<T: Serialize>(path: &Path, list: &[T]) -> io::Result<()> {
+    let content = serde_json::to_string_pretty(list)?;
+    fs::write(path, content)
+}
+
+mod date {
+    use chrono::{DateTime, FixedOffset};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use super::DATE_FORMAT;
+
+    pub fn serialize<S: Serializer>(
+        date: &DateTime<FixedOffset>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&date.format(DATE_FORMAT).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<FixedOffset>, D::Error> {
+        let date = String::deserialize(deserializer)?;
+        DateTime::parse_from_str(&date, DATE_FORMAT).map_err(de::Error::custom)
+    }
+}
+
+mod expiry {
+    use chrono::{DateTime, FixedOffset};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    const FOREVER: &str = "forever";
+
+    pub fn serialize<S: Serializer>(
+        expires: &Option<DateTime<FixedOffset>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match expires {
+            Some(date) => super::date::serialize(date, serializer),
+            None => serializer.serialize_str(FOREVER),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<FixedOffset>>, D::Error> {
+        let expires = String::deserialize(deserializer)?;
+
+        if expires == FOREVER {
+            return Ok(None);
+        }
+        DateTime::parse_from_str(&expires, super::DATE_FORMAT)
+            .map(Some)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use serde_json::Value;
+    use std::env;
+    use std::fs;
+    use std::net::IpAddr;
+    use std::path::{Path, PathBuf};
+    use std::process;
+    use uuid::Uuid;
+
+    use super::{read_list, write_list, AccessLists, IpBan, PlayerBan, WhitelistEntry};
+    use crate::permissions::Operator;
+
+    const NOTCH: &str = "069a79f4-44e9-4726-a5be-fca90e38aaf5";
+
+    fn dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("minecrate-access-{}-{}", name, process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Reads a list written by vanilla and writes it back, which must give
+    /// the same JSON.
+    fn assert_round_trip<T: DeserializeOwned + Serialize>(dir: &Path, json: &str) {
+        let path = dir.join("list.json");
+        fs::write(&path, json).unwrap();
+
+        let list: Vec<T> = read_list(&path).unwrap();
+        write_list(&path, &list).unwrap();
+
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, serde_json::from_str::<Value>(json).unwrap());
+    }
+
+    #[test]
+    fn vanilla_lists_round_trip() {
+        let dir = dir("round-trip");
+
+        assert_round_trip::<PlayerBan>(
+            &dir,
+            r#"[
+                {
+                    "uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+                    "name": "Notch",
+                    "created": "2020-05-01 12:30:00 +0200",
+                    "source": "Server",
+                    "expires": "forever",
+                    "reason": "Banned by an operator."
+                },
+                {
+                    "uuid": "853c80ef-3c37-49fd-aa49-938b674adae6",
+                    "name": "jeb_",
+                    "created": "2020-05-01 12:30:00 -0500",
+                    "source": "Notch",
+                    "expires": "2030-01-02 03:04:05 +0000",
+                    "reason": "Griefing"
+                }
+            ]"#,
+        );
+        assert_round_trip::<IpBan>(
+            &dir,
+            r#"[
+                {
+                    "ip": "203.0.113.7",
+                    "created": "2020-05-01 12:30:00 +0200",
+                    "source": "Server",
+                    "expires": "forever",
+                    "reason": "Banned by an operator."
+                }
+            ]"#,
+        );
+        assert_round_trip::<WhitelistEntry>(
+            &dir,
+            r#"[{ "uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5", "name": "Notch" }]"#,
+        );
+        assert_round_trip::<Operator>(
+            &dir,
+            r#"[
+                {
+                    "uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+                    "name": "Notch",
+                    "level": 4,
+                    "bypassesPlayerLimit": false
+                }
+            ]"#,
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn expired_bans_are_not_enforced() {
+        let dir = dir("expired");
+        let ban = |expires: &str| {
+            format!(
+                r#"[{{
+                    "uuid": "{}",
+                    "name": "Notch",
+                    "created": "2020-05-01 12:30:00 +0200",
+                    "source": "Server",
+                    "expires": "{}",
+                    "reason": "Griefing"
+                }}]"#,
+                NOTCH, expires
+            )
+        };
+        let uuid = Uuid::parse_str(NOTCH).unwrap();
+        let address: IpAddr = "203.0.113.7".parse().unwrap();
+
+        fs::write(
+            dir.join(super::BANNED_PLAYERS_FILE),
+            ban("2020-05-02 12:30:00 +0200"),
+        )
+        .unwrap();
+        let lists = AccessLists::load(&dir, false).unwrap();
+        assert!(lists.get_player_ban(&uuid).is_none());
+        assert!(lists.check_login(&uuid, &address, false).is_ok());
+
+        fs::write(
+            dir.join(super::BANNED_PLAYERS_FILE),
+            ban("2999-01-01 00:00:00 +0000"),
+        )
+        .unwrap();
+        let lists = AccessLists::load(&dir, false).unwrap();
+        let message = lists.check_login(&uuid, &address, false).unwrap_err();
+        assert!(message.contains("Griefing"));
+        assert!(message.contains("2999-01-01 00:00:00 +0000"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn known_players_are_found_by_name() {
+        let dir = dir("known");
+        let uuid = Uuid::parse_str(NOTCH).unwrap();
+        let mut lists = AccessLists::load(&dir, false).unwrap();
+
+        assert_eq!(lists.find_known_player("notch"), None);
+        lists
+            .whitelist_add(WhitelistEntry {
+                uuid,
+                name: String::from("Notch"),
+            })
+            .unwrap();
+        assert_eq!(
+            lists.find_known_player("notch"),
+            Some((uuid, String::from("Notch")))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use network::channel::ChannelMessage;
+use network::stream::Stream;
+use std::collections::HashMap;
+use std::io;
+
+use crate::server::MinecraftServer;
+
+type ChannelHandler = Box<dyn Fn(&mut MinecraftServer, &mut Stream, &[u8]) -> io::Result<()>>;
+
+/// Routes incoming plugin messages to the server modules subscribed to
+/// their channel.
+#[derive(Default)]
+pub struct ChannelRegistry {
+    handlers: HashMap<String, Vec<ChannelHandler>>,
+}
+
+impl ChannelRegistry {
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    pub fn subscribe<M, F>(&mut self, handler: F)
+    where
+        M: ChannelMessage + 'static,
+        F: Fn(&mut MinecraftServer, &mut Stream, M) -> io::Result<()> + 'static,
+    {
+        self.handlers
+            .entry(M::CHANNEL.to_string())
+            .or_default()
+            .push(Box::new(move |server, stream, data| {
+                handler(server, stream, M::decode(data)?)
+            }));
+    }
+
+    pub fn get_channels(&self) -> Vec<String> {
+        self.handlers.keys().cloned().collect()
+    }
+
+    pub fn dispatch(
+        &self,
+        server: &mut MinecraftServer,
+        stream: &mut Stream,
+        channel: &str,
+        data: &[u8],
+    ) -> io::Result<()> {
+        if let Some(handlers) = self.handlers.get(channel) {
+            for handler in handlers {
+                handler(server, stream, data)?;
+            }
+        }
+        Ok(())
+    }
+}
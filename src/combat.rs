@@ -0,0 +1,184 @@
+use common::chat::{Chat, ChatMessageType};
+use common::dimension::Dimension;
+use common::entity_type::EntityType;
+use common::gamemode::Gamemode;
+use common::level_type::LevelType;
+use entity::combat::CombatEvent;
+use entity::components::{Health, Player, Velocity};
+use entity::tracker::{EntityKind, Tracked};
+use entity::Entity;
+use log::info;
+use network::clientbound;
+use network::stream::Stream;
+use std::io;
+use uuid::Uuid;
+
+use crate::server::{MinecraftServer, SPAWN_LOCATION};
+use crate::tracker::to_velocity;
+
+/// Entity statuses playing the hurt and death animations.
+const HURT_STATUS: i8 = 2;
+const DEATH_STATUS: i8 = 3;
+
+/// Food isn't simulated, so players are always full.
+const FULL_FOOD: i32 = 20;
+const FULL_SATURATION: f32 = 5.0;
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Makes a player attack the entity with the given network id, if it's
+    /// in reach.
+    pub fn attack_entity(&mut self, uuid: &Uuid, target_id: i32) -> bool {
+        match (self.get_player_entity(uuid), self.ecs.get_entity(target_id)) {
+            (Some(attacker), Some(target)) => self.ecs.attack(attacker, target),
+            _ => false,
+        }
+    }
+
+    /// Brings a dead player back to life at the spawn.
+    pub fn respawn_player(&mut self, stream: &mut Stream) -> io::Result<()> {
+        let uuid = *stream.get_uuid();
+        let entity = match self.get_player_entity(&uuid) {
+            Some(entity) => entity,
+            None => return Ok(()),
+        };
+        if !self.ecs.respawn_player(entity) {
+            return Ok(());
+        }
+
+        stream.send_packet(&clientbound::play::RespawnPacket::new(
+            Dimension::Overworld,
+            0,
+            self.get_gamemode(&uuid).unwrap_or(Gamemode::Survival),
+            LevelType::Default,
+        ))?;
+        self.send_health(stream)?;
+        self.teleport(stream, SPAWN_LOCATION)
+    }
+
+    /// Sends a player their health, along with a full food bar.
+    pub fn send_health(&self, stream: &mut Stream) -> io::Result<()> {
+        let health = self
+            .get_player_entity(stream.get_uuid())
+            .and_then(|entity| self.ecs.get_component::<Health>(entity))
+            .map_or(Health::PLAYER_MAX, |health| health.current);
+
+        stream.send_packet(&clientbound::play::UpdateHealthPacket::new(
+            health,
+            FULL_FOOD,
+            FULL_SATURATION,
+        ))
+    }
+
+    /// Shows the players the hits and deaths of the last tick.
+    pub(crate) fn send_combat_events(&mut self) {
+        for event in self.ecs.take_combat_events() {
+            match event {
+                CombatEvent::Hurt {
+                    entity, animated, ..
+                } => self.send_hurt(entity, animated),
+                CombatEvent::Critical { entity } => {
+                    self.broadcast_packet(clientbound::play::EntityAnimationPacket::new(
+                        entity::get_network_id(entity),
+                        clientbound::play::CRITICAL_EFFECT,
+                    ))
+                }
+                CombatEvent::Death { entity, attacker } => self.send_death(entity, attacker),
+            }
+        }
+    }
+
+    fn send_hurt(&mut self, entity: Entity, animated: bool) {
+        let id = entity::get_network_id(entity);
+
+        if animated {
+            self.broadcast_packet(clientbound::play::EntityStatusPacket::new(id, HURT_STATUS));
+        }
+        // Players move themselves, so they are told about their knockback
+        if let Some(player) = self.ecs.get_component::<Player>(entity) {
+            let health = self
+                .ecs
+                .get_component::<Health>(entity)
+                .map_or(0.0, |health| health.current);
+
+            self.send_packet(
+                player.uuid,
+                clientbound::play::UpdateHealthPacket::new(health, FULL_FOOD, FULL_SATURATION),
+            );
+            if animated {
+                let velocity = self
+                    .ecs
+                    .get_component::<Velocity>(entity)
+                    .unwrap_or_default();
+
+                self.send_packet(
+                    player.uuid,
+                    clientbound::play::EntityVelocityPacket::new(
+                        id,
+                        (
+                            to_velocity(velocity.x),
+                            to_velocity(velocity.y),
+                            to_velocity(velocity.z),
+                        ),
+                    ),
+                );
+            }
+        }
+    }
+
+    fn send_death(&mut self, entity: Entity, attacker: Option<Entity>) {
+        let id = entity::get_network_id(entity);
+
+        self.broadcast_packet(clientbound::play::EntityStatusPacket::new(id, DEATH_STATUS));
+
+        let player = match self.ecs.get_component::<Player>(entity) {
+            Some(player) => player,
+            None => return,
+        };
+        let victim = Chat::new_text(&player.username);
+        let message = match attacker.and_then(|attacker| self.get_name(attacker)) {
+            Some((name, true)) => Chat::new_translation("death.attack.player", vec![victim, name]),
+            Some((name, false)) => Chat::new_translation("death.attack.mob", vec![victim, name]),
+            None => Chat::new_translation("death.attack.generic", vec![victim]),
+        };
+        let killer_id = attacker.map_or(-1, entity::get_network_id);
+
+        info!("{} died", player.username);
+        self.send_packet(
+            player.uuid,
+            clientbound::play::CombatEventPacket::new(clientbound::play::CombatEvent::EntityDead {
+                player_id: id,
+                entity_id: killer_id,
+                message: message.clone(),
+            }),
+        );
+        self.broadcast_packet(clientbound::play::ChatMessagePacket::new(
+            message,
+            ChatMessageType::System,
+            Uuid::nil(),
+        ));
+    }
+
+    /// Name of an entity in death messages, and whether it's a player.
+    fn get_name(&self, entity: Entity) -> Option<(Chat, bool)> {
+        if let Some(player) = self.ecs.get_component::<Player>(entity) {
+            return Some((Chat::new_text(&player.username), true));
+        }
+
+        match self.ecs.get_component::<Tracked>(entity)?.kind {
+            EntityKind::Living(entity_type) => Some((
+                Chat::new_translation(get_translation_key(entity_type), vec![]),
+                false,
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn get_translation_key(entity_type: EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Cow => "entity.minecraft.cow",
+        EntityType::FallingBlock => "entity.minecraft.falling_block",
+        EntityType::Item => "entity.minecraft.item",
+        EntityType::Zombie => "entity.minecraft.zombie",
+    }
+}
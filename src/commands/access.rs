@@ -0,0 +1,240 @@
+use std::net::IpAddr;
+
+use super::{resolve_profile, CommandContext, CommandError, CommandRegistry, CommandResult};
+use crate::access::{BanDetails, IpBan, PlayerBan, WhitelistEntry, DEFAULT_BAN_REASON};
+use crate::server::MinecraftServer;
+
+const PERMISSION_LEVEL: u8 = 3;
+
+pub fn register(registry: &mut CommandRegistry) {
+    registry.register("ban", "<player> [reason]", PERMISSION_LEVEL, ban);
+    registry.register(
+        "ban-ip",
+        "<address|player> [reason]",
+        PERMISSION_LEVEL,
+        ban_ip,
+    );
+    registry.register("pardon", "<player>", PERMISSION_LEVEL, pardon);
+    registry.register("pardon-ip", "<address>", PERMISSION_LEVEL, pardon_ip);
+    registry.register("banlist", "[ips|players]", PERMISSION_LEVEL, banlist);
+    registry.register(
+        "whitelist",
+        "<on|off|list|reload|add|remove> [player]",
+        PERMISSION_LEVEL,
+        whitelist,
+    );
+}
+
+fn ban(server: &mut MinecraftServer, context: &mut CommandContext, args: &[&str]) -> CommandResult {
+    let (name, reason) = match args.split_first() {
+        Some((name, reason)) => (*name, join_reason(reason)),
+        None => return Err(CommandError::Usage),
+    };
+    let (uuid, name) = resolve_profile(server, context, name)?;
+
+    let ban = PlayerBan {
+        uuid,
+        name,
+        details: BanDetails::new(context.sender_name(), &reason, None),
+    };
+    server.access.borrow_mut().ban_player(ban.clone())?;
+
+    if let Some(stream) = context.streams.iter_mut().find(|s| *s.get_uuid() == uuid) {
+        stream.kick(&ban.message());
+    }
+    context.reply(&format!("Banned {}: {}", ban.name, reason));
+    Ok(())
+}
+
+fn ban_ip(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    let (target, reason) = match args.split_first() {
+        Some((target, reason)) => (*target, join_reason(reason)),
+        None => return Err(CommandError::Usage),
+    };
+    let ip = match target.parse::<IpAddr>() {
+        Ok(ip) => ip,
+        Err(_) => match context.find_player(target) {
+            Some(stream) => *stream.get_remote_address(),
+            None => {
+                return Err(CommandError::Failed(String::from(
+                    "Invalid IP address or unknown player",
+                )))
+            }
+        },
+    };
+
+    let ban = IpBan {
+        ip,
+        details: BanDetails::new(context.sender_name(), &reason, None),
+    };
+    server.access.borrow_mut().ban_ip(ban.clone())?;
+
+    let mut kicked = 0;
+    for stream in context.streams.iter_mut() {
+        if *stream.get_remote_address() == ip && !stream.is_kicked() {
+            stream.kick(&ban.message());
+            kicked += 1;
+        }
+    }
+    context.reply(&format!(
+        "Banned IP {}: {}, {} player(s) disconnected",
+        ip, reason, kicked
+    ));
+    Ok(())
+}
+
+fn pardon(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    let name = match args {
+        [name] => *name,
+        _ => return Err(CommandError::Usage),
+    };
+
+    if server.access.borrow_mut().pardon_player(name)? {
+        context.reply(&format!("Unbanned {}", name));
+    } else {
+        context.reply("Nothing changed. The player isn't banned");
+    }
+    Ok(())
+}
+
+fn pardon_ip(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    let ip = match args {
+        [ip] => ip
+            .parse::<IpAddr>()
+            .map_err(|_| CommandError::Failed(String::from("Invalid IP address")))?,
+        _ => return Err(CommandError::Usage),
+    };
+
+    if server.access.borrow_mut().pardon_ip(&ip)? {
+        context.reply(&format!("Unbanned IP {}", ip));
+    } else {
+        context.reply("Nothing changed. That IP isn't banned");
+    }
+    Ok(())
+}
+
+fn banlist(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    let (players, ips) = match args {
+        [] => (true, true),
+        ["players"] => (true, false),
+        ["ips"] => (false, true),
+        _ => return Err(CommandError::Usage),
+    };
+
+    let mut lines = vec![];
+    let access = server.access.borrow();
+    if players {
+        for ban in access.get_banned_players() {
+            lines.push(ban_line(&ban.name, &ban.details));
+        }
+    }
+    if ips {
+        for ban in access.get_banned_ips() {
+            lines.push(ban_line(&ban.ip.to_string(), &ban.details));
+        }
+    }
+    drop(access);
+
+    if lines.is_empty() {
+        context.reply("There are no bans");
+    } else {
+        context.reply(&format!("There are {} ban(s):", lines.len()));
+        for line in lines {
+            context.reply(&line);
+        }
+    }
+    Ok(())
+}
+
+fn whitelist(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    match args {
+        ["on"] => {
+            server.access.borrow_mut().set_whitelist_enabled(true);
+            context.reply("Whitelist is now turned on");
+        }
+        ["off"] => {
+            server.access.borrow_mut().set_whitelist_enabled(false);
+            context.reply("Whitelist is now turned off");
+        }
+        ["list"] => {
+            let names: Vec<String> = server
+                .access
+                .borrow()
+                .get_whitelist()
+                .iter()
+                .map(|entry| entry.name.clone())
+                .collect();
+
+            if names.is_empty() {
+                context.reply("There are no whitelisted players");
+            } else {
+                context.reply(&format!(
+                    "There are {} whitelisted player(s): {}",
+                    names.len(),
+                    names.join(", ")
+                ));
+            }
+        }
+        ["reload"] => {
+            server.access.borrow_mut().reload()?;
+            context.reply("Reloaded the whitelist");
+        }
+        ["add", name] => {
+            let (uuid, name) = resolve_profile(server, context, name)?;
+            let entry = WhitelistEntry {
+                uuid,
+                name: name.clone(),
+            };
+
+            if server.access.borrow_mut().whitelist_add(entry)? {
+                context.reply(&format!("Added {} to the whitelist", name));
+            } else {
+                context.reply("Player is already whitelisted");
+            }
+        }
+        ["remove", name] => {
+            if server.access.borrow_mut().whitelist_remove(name)? {
+                context.reply(&format!("Removed {} from the whitelist", name));
+            } else {
+                context.reply("Player is not whitelisted");
+            }
+        }
+        _ => return Err(CommandError::Usage),
+    }
+    Ok(())
+}
+
+fn join_reason(words: &[&str]) -> String {
+    if words.is_empty() {
+        String::from(DEFAULT_BAN_REASON)
+    } else {
+        words.join(" ")
+    }
+}
+
+fn ban_line(target: &str, details: &BanDetails) -> String {
+    format!(
+        "{} was banned by {}: {}",
+        target, details.source, details.reason
+    )
+}
@@ -0,0 +1,51 @@
+use common::gamemode::Gamemode;
+
+use super::{CommandContext, CommandError, CommandRegistry, CommandResult, CommandSender};
+use crate::server::MinecraftServer;
+
+pub fn register(registry: &mut CommandRegistry) {
+    registry.register(
+        "gamemode",
+        "<survival|creative|adventure|spectator> [player]",
+        2,
+        gamemode,
+    );
+}
+
+fn gamemode(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    let (gamemode, target) = match args {
+        [gamemode] => (*gamemode, None),
+        [gamemode, target] => (*gamemode, Some(*target)),
+        _ => return Err(CommandError::Usage),
+    };
+    let gamemode = match Gamemode::from_string(&gamemode.to_lowercase()) {
+        Some(gamemode) => gamemode,
+        None => return Err(CommandError::Usage),
+    };
+    let (uuid, name) = match (target, &context.sender) {
+        (Some(target), _) => match context.find_player(target) {
+            Some(stream) => (*stream.get_uuid(), stream.get_username().clone()),
+            None => return Err(CommandError::Failed(String::from("No player was found"))),
+        },
+        (None, CommandSender::Player { uuid, username }) => (*uuid, username.clone()),
+        (None, CommandSender::Console) => return Err(CommandError::Usage),
+    };
+
+    if !server.set_gamemode(&uuid, gamemode) {
+        return Err(CommandError::Failed(String::from("No player was found")));
+    }
+    if target.is_some() {
+        context.reply(&format!(
+            "Set {}'s game mode to {}",
+            name,
+            gamemode.to_string()
+        ));
+    } else {
+        context.reply(&format!("Set own game mode to {}", gamemode.to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use common::chat::{Chat, ChatMessageType};
+use log::info;
+use network::clientbound;
+use network::mojang;
+use network::packet::State;
+use network::stream::Stream;
+use std::collections::HashMap;
+use std::io;
+use std::rc::Rc;
+use uuid::Uuid;
+
+pub mod access;
+pub mod gamemode;
+pub mod permissions;
+pub mod plugins;
+
+use crate::server::MinecraftServer;
+
+#[derive(Debug, Clone)]
+pub enum CommandSender {
+    Console,
+    Player { uuid: Uuid, username: String },
+}
+
+#[derive(Debug)]
+pub enum CommandError {
+    /// Replies with the usage of the command.
+    Usage,
+    Failed(String),
+}
+
+impl From<io::Error> for CommandError {
+    fn from(error: io::Error) -> Self {
+        CommandError::Failed(error.to_string())
+    }
+}
+
+pub type CommandResult = Result<(), CommandError>;
+
+/// What a command can reach besides the server: who ran it, and every
+/// connected client.
+pub struct CommandContext<'s> {
+    pub sender: CommandSender,
+    pub streams: &'s mut [Stream],
+}
+
+impl<'s> CommandContext<'s> {
+    pub fn reply(&mut self, message: &str) {
+        match &self.sender {
+            CommandSender::Console => info!("{}", message),
+            CommandSender::Player { uuid, .. } => {
+                let uuid = *uuid;
+
+                if let Some(stream) = self.streams.iter_mut().find(|s| *s.get_uuid() == uuid) {
+                    let _ = stream.send_packet(&clientbound::play::ChatMessagePacket::new(
+                        Chat::new_text(message),
+                        ChatMessageType::System,
+                        Uuid::nil(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Name recorded as the source of bans and other changes.
+    pub fn sender_name(&self) -> &str {
+        match &self.sender {
+            CommandSender::Console => "Server",
+            CommandSender::Player { username, .. } => username,
+        }
+    }
+
+    /// Finds a playing client by username, ignoring case.
+    pub fn find_player(&mut self, username: &str) -> Option<&mut Stream> {
+        self.streams.iter_mut().find(|stream| {
+            *stream.get_state() == State::Play
+                && !stream.is_kicked()
+                && stream.get_username().eq_ignore_ascii_case(username)
+        })
+    }
+}
+
+type CommandHandler =
+    Rc<dyn Fn(&mut MinecraftServer, &mut CommandContext, &[&str]) -> CommandResult>;
+
+#[derive(Clone)]
+struct Command {
+    usage: String,
+    permission_level: u8,
+    handler: CommandHandler,
+}
+
+/// Commands typed in the console or sent in chat with a leading slash.
+#[derive(Default)]
+pub struct CommandRegistry {
+    commands: HashMap<String, Command>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self {
+            commands: HashMap::new(),
+        }
+    }
+
+    /// Registers a command, runnable by players granted its permission node,
+    /// `minecrate.command.<name>`, or else by operators of at least the given
+    /// level. `usage` lists its arguments.
+    pub fn register<F>(&mut self, name: &str, usage: &str, permission_level: u8, handler: F)
+    where
+        F: Fn(&mut MinecraftServer, &mut CommandContext, &[&str]) -> CommandResult + 'static,
+    {
+        self.commands.insert(
+            name.to_string(),
+            Command {
+                usage: usage.to_string(),
+                permission_level,
+                handler: Rc::new(handler),
+            },
+        );
+    }
+
+    /// Returns false if there was no such command.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.commands.remove(name).is_some()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.commands.contains_key(name)
+    }
+
+    pub fn get_names(&self) -> Vec<String> {
+        self.commands.keys().cloned().collect()
+    }
+}
+
+/// Runs a command line, without its leading slash, replying to the sender
+/// with any error.
+pub fn dispatch(server: &mut MinecraftServer, context: &mut CommandContext, line: &str) {
+    let mut args = line.split_whitespace();
+    let name = match args.next() {
+        Some(name) => name.to_lowercase(),
+        None => return,
+    };
+    let args: Vec<&str> = args.collect();
+
+    // Cloned out of the registry, which commands can change while they run
+    let command = match server.commands.commands.get(&name) {
+        Some(command) if can_run(server, &context.sender, &name, command) => command.clone(),
+        // Like vanilla, commands out of reach are reported as unknown
+        _ => {
+            context.reply(&format!("Unknown command: {}", name));
+            return;
+        }
+    };
+
+    match (command.handler)(server, context, &args) {
+        Ok(()) => {}
+        Err(CommandError::Usage) => context.reply(&format!("Usage: /{} {}", name, command.usage)),
+        Err(CommandError::Failed(message)) => context.reply(&message),
+    }
+}
+
+pub fn permission_node(command: &str) -> String {
+    format!("minecrate.command.{}", command)
+}
+
+fn can_run(
+    server: &MinecraftServer,
+    sender: &CommandSender,
+    name: &str,
+    command: &Command,
+) -> bool {
+    match sender {
+        CommandSender::Console => true,
+        CommandSender::Player { uuid, .. } => server.permissions.borrow().is_allowed(
+            uuid,
+            &permission_node(name),
+            command.permission_level,
+        ),
+    }
+}
+
+/// Finds the UUID of a player, who doesn't have to be online. Players
+/// neither online nor in the access lists are looked up on the Mojang API,
+/// blocking the tick for at most `mojang::PROFILE_LOOKUP_TIMEOUT`.
+pub fn resolve_profile(
+    server: &MinecraftServer,
+    context: &mut CommandContext,
+    name: &str,
+) -> Result<(Uuid, String), CommandError> {
+    if let Some(stream) = context.find_player(name) {
+        return Ok((*stream.get_uuid(), stream.get_username().clone()));
+    }
+    if let Some(known) = server.access.borrow().find_known_player(name) {
+        return Ok(known);
+    }
+
+    if !server.config.online_mode {
+        return Ok((mojang::offline_uuid(name), name.to_string()));
+    }
+
+    match mojang::lookup_profile(name)? {
+        Some(profile) => match Uuid::parse_str(&profile.id) {
+            Ok(uuid) => Ok((uuid, profile.name)),
+            Err(e) => Err(CommandError::Failed(e.to_string())),
+        },
+        None => Err(CommandError::Failed(String::from(
+            "That player does not exist",
+        ))),
+    }
+}
@@ -0,0 +1,152 @@
+use network::packet::State;
+use uuid::Uuid;
+
+use super::{resolve_profile, CommandContext, CommandError, CommandRegistry, CommandResult};
+use crate::permissions::{Operator, MAX_OP_LEVEL};
+use crate::server::MinecraftServer;
+
+pub fn register(registry: &mut CommandRegistry) {
+    registry.register("op", "<player>", 3, op);
+    registry.register("deop", "<player>", 3, deop);
+    registry.register(
+        "permission",
+        "check <player> <node> | player <player> <add|remove|addgroup|removegroup> <value> | group <group> <add|remove|addparent|removeparent> <value> | reload",
+        MAX_OP_LEVEL,
+        permission,
+    );
+}
+
+fn op(server: &mut MinecraftServer, context: &mut CommandContext, args: &[&str]) -> CommandResult {
+    let name = match args {
+        [name] => *name,
+        _ => return Err(CommandError::Usage),
+    };
+    let (uuid, name) = resolve_profile(server, context, name)?;
+    let level = server.config.op_permission_level.clamp(1, MAX_OP_LEVEL);
+
+    if server.permissions.borrow().get_op_level(&uuid) == level {
+        context.reply("Nothing changed. The player already is an operator");
+        return Ok(());
+    }
+
+    server.permissions.borrow_mut().op(Operator {
+        uuid,
+        name: name.clone(),
+        level,
+        bypasses_player_limit: false,
+    })?;
+    update_op_level(server, context, &uuid)?;
+    context.reply(&format!("Made {} a server operator", name));
+    Ok(())
+}
+
+fn deop(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    let name = match args {
+        [name] => *name,
+        _ => return Err(CommandError::Usage),
+    };
+    let (uuid, name) = resolve_profile(server, context, name)?;
+
+    if server.permissions.borrow_mut().deop(&uuid)? {
+        update_op_level(server, context, &uuid)?;
+        context.reply(&format!("Made {} no longer a server operator", name));
+    } else {
+        context.reply("Nothing changed. The player is not an operator");
+    }
+    Ok(())
+}
+
+fn permission(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    match args {
+        ["reload"] => {
+            server.permissions.borrow_mut().reload()?;
+            context.reply("Reloaded the operators and permissions");
+        }
+        ["check", name, node] => {
+            let (uuid, name) = resolve_profile(server, context, name)?;
+            let permissions = server.permissions.borrow();
+            let message = match permissions.check(&uuid, node) {
+                Some(true) => format!("{} is granted {}", name, node),
+                Some(false) => format!("{} is denied {}", name, node),
+                None => format!(
+                    "{} has no node matching {}, op level {} applies",
+                    name,
+                    node,
+                    permissions.get_op_level(&uuid)
+                ),
+            };
+
+            drop(permissions);
+            context.reply(&message);
+        }
+        ["player", name, action, value] => {
+            if !["add", "remove", "addgroup", "removegroup"].contains(action) {
+                return Err(CommandError::Usage);
+            }
+            let (uuid, name) = resolve_profile(server, context, name)?;
+            let value = value.to_string();
+
+            server
+                .permissions
+                .borrow_mut()
+                .edit_player(uuid, &name, |player| match *action {
+                    "add" => add(&mut player.permissions, value),
+                    "remove" => player.permissions.retain(|node| *node != value),
+                    "addgroup" => add(&mut player.groups, value),
+                    "removegroup" => player.groups.retain(|group| *group != value),
+                    _ => {}
+                })?;
+            context.reply(&format!("Updated the permissions of {}", name));
+        }
+        ["group", name, action, node] => {
+            if !["add", "remove", "addparent", "removeparent"].contains(action) {
+                return Err(CommandError::Usage);
+            }
+            let node = node.to_string();
+
+            server
+                .permissions
+                .borrow_mut()
+                .edit_group(name, |group| match *action {
+                    "add" => add(&mut group.permissions, node),
+                    "remove" => group.permissions.retain(|other| *other != node),
+                    "addparent" => add(&mut group.inherits, node),
+                    "removeparent" => group.inherits.retain(|other| *other != node),
+                    _ => {}
+                })?;
+            context.reply(&format!("Updated the permissions of group {}", name));
+        }
+        _ => return Err(CommandError::Usage),
+    }
+    Ok(())
+}
+
+fn add(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+fn update_op_level(
+    server: &MinecraftServer,
+    context: &mut CommandContext,
+    uuid: &Uuid,
+) -> CommandResult {
+    let stream = context
+        .streams
+        .iter_mut()
+        .find(|stream| *stream.get_uuid() == *uuid && *stream.get_state() == State::Play);
+
+    if let Some(stream) = stream {
+        server.send_op_level(stream)?;
+    }
+    Ok(())
+}
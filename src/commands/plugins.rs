@@ -0,0 +1,54 @@
+use super::{CommandContext, CommandError, CommandRegistry, CommandResult};
+use crate::permissions::MAX_OP_LEVEL;
+use crate::server::MinecraftServer;
+
+pub fn register(registry: &mut CommandRegistry) {
+    registry.register(
+        "plugins",
+        "[list | enable <plugin> | disable <plugin> | reload [plugin]]",
+        MAX_OP_LEVEL,
+        plugins,
+    );
+}
+
+fn plugins(
+    server: &mut MinecraftServer,
+    context: &mut CommandContext,
+    args: &[&str],
+) -> CommandResult {
+    match args {
+        [] | ["list"] => {
+            let plugins = server.plugins.get_plugins();
+            let names: Vec<String> = plugins
+                .iter()
+                .map(|(name, enabled)| {
+                    if *enabled {
+                        name.clone()
+                    } else {
+                        format!("{} (disabled)", name)
+                    }
+                })
+                .collect();
+
+            context.reply(&format!("Plugins ({}): {}", names.len(), names.join(", ")));
+        }
+        ["enable", name] => {
+            server.enable_plugin(name)?;
+            context.reply(&format!("Enabled plugin {}", name));
+        }
+        ["disable", name] => {
+            server.disable_plugin(name)?;
+            context.reply(&format!("Disabled plugin {}", name));
+        }
+        ["reload"] => {
+            server.reload_plugins()?;
+            context.reply("Reloaded the plugins");
+        }
+        ["reload", name] => {
+            server.reload_plugin(name)?;
+            context.reply(&format!("Reloaded plugin {}", name));
+        }
+        _ => return Err(CommandError::Usage),
+    }
+    Ok(())
+}
@@ -1,7 +1,163 @@
+use common::chat::Chat;
+use common::gamemode::Gamemode;
+use network::proxy::ProxyForwarding;
+use network::throttle::ThrottleSettings;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io;
 use std::io::Read;
+use std::time::Duration;
+use world::spawning::SpawnSettings;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    None,
+    BungeeCord,
+    Velocity,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    #[serde(default)]
+    pub secret: String,
+}
+
+impl ProxyConfig {
+    pub fn to_forwarding(&self) -> ProxyForwarding {
+        match self.mode {
+            ProxyMode::None => ProxyForwarding::None,
+            ProxyMode::BungeeCord => ProxyForwarding::BungeeCord,
+            ProxyMode::Velocity => ProxyForwarding::Velocity {
+                secret: self.secret.clone(),
+            },
+        }
+    }
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            mode: ProxyMode::None,
+            secret: String::new(),
+        }
+    }
+}
+
+/// Connection limits, a zero value disables the matching limit.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    /// Milliseconds between two logins from the same IP.
+    pub login_delay: u64,
+    pub max_connections_per_ip: usize,
+    /// Seconds given to a connection to log in.
+    pub login_timeout: u64,
+    pub max_packets_per_second: u32,
+}
+
+impl ThrottleConfig {
+    pub fn to_settings(&self) -> ThrottleSettings {
+        ThrottleSettings {
+            login_delay: Duration::from_millis(self.login_delay),
+            max_connections_per_ip: self.max_connections_per_ip,
+            login_timeout: Duration::from_secs(self.login_timeout),
+            max_packets_per_second: self.max_packets_per_second,
+        }
+    }
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        let settings = ThrottleSettings::default();
+
+        Self {
+            login_delay: settings.login_delay.as_millis() as u64,
+            max_connections_per_ip: settings.max_connections_per_ip,
+            login_timeout: settings.login_timeout.as_secs(),
+            max_packets_per_second: settings.max_packets_per_second,
+        }
+    }
+}
+
+/// Natural spawning of the mobs in the world, which lobbies may disable.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpawningConfig {
+    pub enabled: bool,
+    /// Mobs of each category there can be around a player.
+    pub monsters: u32,
+    pub creatures: u32,
+    pub ambient: u32,
+    pub water_creatures: u32,
+}
+
+impl SpawningConfig {
+    pub fn to_settings(&self) -> SpawnSettings {
+        SpawnSettings {
+            enabled: self.enabled,
+            monster_cap: self.monsters,
+            creature_cap: self.creatures,
+            ambient_cap: self.ambient,
+            water_creature_cap: self.water_creatures,
+            ..SpawnSettings::default()
+        }
+    }
+}
+
+impl Default for SpawningConfig {
+    fn default() -> Self {
+        let settings = SpawnSettings::default();
+
+        Self {
+            enabled: settings.enabled,
+            monsters: settings.monster_cap,
+            creatures: settings.creature_cap,
+            ambient: settings.ambient_cap,
+            water_creatures: settings.water_creature_cap,
+        }
+    }
+}
+
+/// Text shown above and below the tab list, either plain or a JSON chat
+/// component.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TabListConfig {
+    pub header: String,
+    pub footer: String,
+}
+
+impl TabListConfig {
+    pub fn get_header(&self) -> Chat {
+        to_chat(&self.header)
+    }
+
+    pub fn get_footer(&self) -> Chat {
+        to_chat(&self.footer)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty() && self.footer.is_empty()
+    }
+}
+
+fn to_chat(text: &str) -> Chat {
+    Chat::from_string(text).unwrap_or_else(|_| Chat::new_text(text))
+}
+
+fn default_online_mode() -> bool {
+    true
+}
+
+fn default_op_permission_level() -> u8 {
+    4
+}
+
+fn default_gamemode() -> Gamemode {
+    Gamemode::Survival
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -9,6 +165,27 @@ pub struct Config {
     pub port: u16,
     pub motd: String,
     pub max_players: u32,
+    /// Gamemode of the players joining.
+    #[serde(default = "default_gamemode")]
+    pub gamemode: Gamemode,
+    #[serde(default = "default_online_mode")]
+    pub online_mode: bool,
+    /// Only lets the players listed in whitelist.json join.
+    #[serde(default)]
+    pub whitelist: bool,
+    /// Level given to players made operator with /op.
+    #[serde(default = "default_op_permission_level")]
+    pub op_permission_level: u8,
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    #[serde(default)]
+    pub throttle: ThrottleConfig,
+    #[serde(default)]
+    pub capture_dir: Option<String>,
+    #[serde(default)]
+    pub tab_list: TabListConfig,
+    #[serde(default)]
+    pub spawning: SpawningConfig,
 }
 
 impl Config {
@@ -26,6 +203,15 @@ impl Config {
             port: 25565,
             motd: String::from("Minecrate server"),
             max_players: 20,
+            gamemode: Gamemode::Survival,
+            online_mode: true,
+            whitelist: false,
+            op_permission_level: 4,
+            proxy: ProxyConfig::default(),
+            throttle: ThrottleConfig::default(),
+            capture_dir: None,
+            tab_list: TabListConfig::default(),
+            spawning: SpawningConfig::default(),
         }
     }
 }
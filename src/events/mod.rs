@@ -0,0 +1,227 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Implements `Event` and `Cancellable` for events with a `cancelled` field.
+macro_rules! cancellable {
+    ($($event:ident),*) => {
+        $(impl crate::events::Event for $event {
+            fn is_cancelled(&self) -> bool {
+                self.cancelled
+            }
+        }
+
+        impl crate::events::Cancellable for $event {
+            fn set_cancelled(&mut self, cancelled: bool) {
+                self.cancelled = cancelled;
+            }
+        })*
+    };
+}
+
+mod player;
+mod server;
+mod world;
+
+pub use player::{PlayerChat, PlayerJoin, PlayerMove, PlayerPreLogin, PlayerQuit, PreLoginHook};
+pub use server::ServerTick;
+pub use world::BlockBreak;
+
+use crate::server::MinecraftServer;
+
+/// Something happening on the server which listeners can react to.
+pub trait Event: Any {
+    /// Cancelled events skip the listeners left, but the `Monitor` ones.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// An event whose default outcome can be prevented by a listener.
+pub trait Cancellable: Event {
+    fn set_cancelled(&mut self, cancelled: bool);
+}
+
+/// Order in which listeners are called, from `Lowest` to `Monitor`. The last
+/// listeners to run have the final say; `Monitor` ones should only observe
+/// the outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventPriority {
+    Lowest,
+    Low,
+    Normal,
+    High,
+    Highest,
+    Monitor,
+}
+
+type Listener = Rc<dyn Fn(&mut MinecraftServer, &mut dyn Any)>;
+
+/// Identifies a subscription, to end it later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+/// Routes events to the listeners subscribed to their type.
+#[derive(Default)]
+pub struct EventBus {
+    listeners: HashMap<TypeId, Vec<(EventPriority, ListenerId, Listener)>>,
+    next_id: u64,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            listeners: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Listeners of the same priority are called in subscription order.
+    pub fn subscribe<E, F>(&mut self, priority: EventPriority, listener: F) -> ListenerId
+    where
+        E: Event,
+        F: Fn(&mut MinecraftServer, &mut E) + 'static,
+    {
+        let id = ListenerId(self.next_id);
+        self.next_id += 1;
+
+        let listeners = self.listeners.entry(TypeId::of::<E>()).or_default();
+        listeners.push((
+            priority,
+            id,
+            Rc::new(move |server, event| {
+                if let Some(event) = event.downcast_mut::<E>() {
+                    listener(server, event);
+                }
+            }),
+        ));
+        listeners.sort_by_key(|(priority, _, _)| *priority);
+        id
+    }
+
+    /// Returns false if the listener was already gone.
+    pub fn unsubscribe(&mut self, id: ListenerId) -> bool {
+        for listeners in self.listeners.values_mut() {
+            if let Some(index) = listeners.iter().position(|(_, other, _)| *other == id) {
+                listeners.remove(index);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn get_listeners<E: Event>(&self) -> Vec<(EventPriority, Listener)> {
+        match self.listeners.get(&TypeId::of::<E>()) {
+            Some(listeners) => listeners
+                .iter()
+                .map(|(priority, _, listener)| (*priority, listener.clone()))
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Calls the listeners of the event, which can be posted again from a
+    /// listener, and returns it as they left it. Once cancelled, only the
+    /// `Monitor` listeners still see it.
+    pub fn post_event<E: Event>(&mut self, mut event: E) -> E {
+        for (priority, listener) in self.events.get_listeners::<E>() {
+            if event.is_cancelled() && priority != EventPriority::Monitor {
+                continue;
+            }
+            listener(self, &mut event);
+        }
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use uuid::Uuid;
+
+    use super::{EventPriority, PlayerChat};
+    use crate::server::MinecraftServer;
+
+    fn chat() -> PlayerChat {
+        PlayerChat {
+            uuid: Uuid::nil(),
+            username: String::from("Steve"),
+            message: String::from("hello"),
+            cancelled: false,
+        }
+    }
+
+    /// Subscribes a listener recording its name when called.
+    fn record(
+        server: &mut MinecraftServer,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        priority: EventPriority,
+        name: &'static str,
+        cancel: bool,
+    ) {
+        let calls = calls.clone();
+
+        server
+            .events
+            .subscribe(priority, move |_, event: &mut PlayerChat| {
+                calls.borrow_mut().push(name);
+                if cancel {
+                    event.cancelled = true;
+                }
+            });
+    }
+
+    #[test]
+    fn listeners_are_called_by_priority() {
+        let mut server = MinecraftServer::for_tests();
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        record(
+            &mut server,
+            &calls,
+            EventPriority::Monitor,
+            "monitor",
+            false,
+        );
+        record(&mut server, &calls, EventPriority::High, "high", false);
+        record(&mut server, &calls, EventPriority::Lowest, "lowest", false);
+        record(&mut server, &calls, EventPriority::Normal, "normal", false);
+        record(
+            &mut server,
+            &calls,
+            EventPriority::Normal,
+            "normal 2",
+            false,
+        );
+
+        server.post_event(chat());
+        assert_eq!(
+            *calls.borrow(),
+            vec!["lowest", "normal", "normal 2", "high", "monitor"]
+        );
+    }
+
+    #[test]
+    fn cancelled_events_skip_later_listeners_but_monitors() {
+        let mut server = MinecraftServer::for_tests();
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        record(&mut server, &calls, EventPriority::Low, "low", true);
+        record(&mut server, &calls, EventPriority::Lowest, "lowest", false);
+        record(&mut server, &calls, EventPriority::High, "high", false);
+        record(
+            &mut server,
+            &calls,
+            EventPriority::Monitor,
+            "monitor",
+            false,
+        );
+
+        let event = server.post_event(chat());
+        assert!(event.cancelled);
+        assert_eq!(*calls.borrow(), vec!["lowest", "low", "monitor"]);
+    }
+}
@@ -0,0 +1,105 @@
+use common::location::Location;
+use network::login_query::LoginHook;
+use network::serverbound::login::LoginPluginResponsePacket;
+use network::stream::Stream;
+use std::cell::RefCell;
+use std::io;
+use std::net::IpAddr;
+use std::rc::Rc;
+use uuid::Uuid;
+
+use super::Event;
+use crate::server::MinecraftServer;
+
+/// Posted once a player is authenticated, before LoginSuccess is sent.
+/// Cancelling it disconnects them with `kick_message`.
+#[derive(Debug, Clone)]
+pub struct PlayerPreLogin {
+    pub uuid: Uuid,
+    pub username: String,
+    pub address: IpAddr,
+    pub kick_message: String,
+    pub cancelled: bool,
+}
+
+/// Posted once a player is in the world. The join message is broadcast
+/// unless a listener removes it.
+#[derive(Debug, Clone)]
+pub struct PlayerJoin {
+    pub uuid: Uuid,
+    pub username: String,
+    pub protocol: i32,
+    pub join_message: Option<String>,
+}
+
+/// Posted when a player in the world disconnects, for any reason.
+#[derive(Debug, Clone)]
+pub struct PlayerQuit {
+    pub uuid: Uuid,
+    pub username: String,
+    pub quit_message: Option<String>,
+}
+
+/// Posted for chat messages which aren't commands. Listeners can change the
+/// message before it is broadcast.
+#[derive(Debug, Clone)]
+pub struct PlayerChat {
+    pub uuid: Uuid,
+    pub username: String,
+    pub message: String,
+    pub cancelled: bool,
+}
+
+/// Posted when a player moves or turns. Cancelling it sends them back to
+/// `from`, and changing `to` teleports them there.
+#[derive(Debug, Clone)]
+pub struct PlayerMove {
+    pub uuid: Uuid,
+    pub from: Location,
+    pub to: Location,
+    pub on_ground: bool,
+    pub cancelled: bool,
+}
+
+impl Event for PlayerJoin {}
+impl Event for PlayerQuit {}
+
+cancellable!(PlayerPreLogin, PlayerChat, PlayerMove);
+
+/// Posts `PlayerPreLogin` for every authenticated player.
+pub struct PreLoginHook {
+    server: Rc<RefCell<MinecraftServer<'static, 'static>>>,
+}
+
+impl PreLoginHook {
+    pub fn new(server: Rc<RefCell<MinecraftServer<'static, 'static>>>) -> Self {
+        Self { server }
+    }
+}
+
+impl LoginHook for PreLoginHook {
+    fn on_authenticated(&self, stream: &mut Stream) -> io::Result<()> {
+        let event = self.server.borrow_mut().post_event(PlayerPreLogin {
+            uuid: *stream.get_uuid(),
+            username: stream.get_username().clone(),
+            address: *stream.get_remote_address(),
+            kick_message: String::from("You are not allowed to join this server"),
+            cancelled: false,
+        });
+
+        if event.cancelled {
+            stream.disconnect(&event.kick_message)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn on_response(
+        &self,
+        _stream: &mut Stream,
+        _channel: &str,
+        _packet: &LoginPluginResponsePacket,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+}
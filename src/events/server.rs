@@ -0,0 +1,10 @@
+use super::Event;
+
+/// Posted at the end of every server tick.
+#[derive(Debug, Clone, Copy)]
+pub struct ServerTick {
+    /// Ticks since the server started.
+    pub tick: u64,
+}
+
+impl Event for ServerTick {}
@@ -0,0 +1,13 @@
+use cgmath::Vector3;
+use uuid::Uuid;
+
+/// Posted when a player within reach finishes digging a block, which is
+/// removed and drops its item unless the event is cancelled.
+#[derive(Debug, Clone)]
+pub struct BlockBreak {
+    pub uuid: Uuid,
+    pub location: Vector3<i32>,
+    pub cancelled: bool,
+}
+
+cancellable!(BlockBreak);
@@ -0,0 +1,165 @@
+use entity::inventory::{ClickMode, Inventory, HOTBAR_SIZE};
+use log::warn;
+use network::clientbound;
+use network::serverbound::play::ClickWindowPacket;
+use network::stream::Stream;
+use std::io;
+use uuid::Uuid;
+
+use crate::server::MinecraftServer;
+
+/// Id of the inventory window of the player, the only one which exists.
+const PLAYER_WINDOW: u8 = 0;
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Sends a player their whole inventory, the stack of their cursor and
+    /// the hotbar slot they hold.
+    pub fn send_inventory(&self, stream: &mut Stream) -> io::Result<()> {
+        let inventory = match self
+            .get_player_entity(stream.get_uuid())
+            .and_then(|entity| self.ecs.get_component::<Inventory>(entity))
+        {
+            Some(inventory) => inventory,
+            None => return Ok(()),
+        };
+
+        stream.send_packet(&clientbound::play::WindowItemsPacket::new(
+            PLAYER_WINDOW,
+            inventory.get_window_items(),
+        ))?;
+        stream.send_packet(&clientbound::play::SetSlotPacket::new(
+            clientbound::play::CURSOR_WINDOW,
+            clientbound::play::CURSOR_SLOT,
+            inventory.get_cursor().cloned(),
+        ))?;
+        stream.send_packet(&clientbound::play::HeldItemChangePacket::new(
+            inventory.get_selected() as i8,
+        ))
+    }
+
+    /// Applies a click of a player in their inventory. Clicks the client
+    /// got wrong are rejected and the whole inventory is sent again, the
+    /// next ones being ignored until the client acknowledges it.
+    pub fn click_window(
+        &mut self,
+        stream: &mut Stream,
+        packet: &ClickWindowPacket,
+    ) -> io::Result<()> {
+        let uuid = *stream.get_uuid();
+        if packet.window_id != PLAYER_WINDOW || self.rejected_clicks.contains_key(&uuid) {
+            return Ok(());
+        }
+        let (entity, mode) = match (
+            self.get_player_entity(&uuid),
+            ClickMode::from_id(packet.mode),
+        ) {
+            (Some(entity), Some(mode)) => (entity, mode),
+            _ => return Ok(()),
+        };
+        let result = match self
+            .ecs
+            .click_window(entity, packet.slot, packet.button, mode)
+        {
+            Some(result) => result,
+            None => return Ok(()),
+        };
+
+        for stack in result.dropped {
+            self.throw_item(&uuid, stack);
+        }
+        // The client changed its slots on its own, whether it got them right
+        // or is about to get them all again
+        if let Some(mut inventory) = self.ecs.get_component::<Inventory>(entity) {
+            inventory.take_changes();
+            self.ecs.set_component(entity, inventory);
+        }
+
+        let accepted = result.clicked == packet.clicked_item;
+        stream.send_packet(&clientbound::play::WindowConfirmationPacket::new(
+            packet.window_id as i8,
+            packet.action_number,
+            accepted,
+        ))?;
+        if !accepted {
+            self.rejected_clicks.insert(uuid, packet.action_number);
+            self.send_inventory(stream)?;
+        }
+        Ok(())
+    }
+
+    /// Lets a player click again once their client undid a rejected click.
+    pub fn confirm_window_action(&mut self, uuid: &Uuid, action_number: i16, accepted: bool) {
+        if accepted && self.rejected_clicks.get(uuid) == Some(&action_number) {
+            self.rejected_clicks.remove(uuid);
+        }
+    }
+
+    /// Throws the stack of the cursor and puts the crafting grid back in the
+    /// inventory of a player.
+    pub fn close_window(&mut self, uuid: &Uuid) {
+        let entity = match self.get_player_entity(uuid) {
+            Some(entity) => entity,
+            None => return,
+        };
+        let mut inventory = match self.ecs.get_component::<Inventory>(entity) {
+            Some(inventory) => inventory,
+            None => return,
+        };
+
+        let dropped = inventory.close();
+        self.ecs.set_component(entity, inventory);
+        for stack in dropped {
+            self.throw_item(uuid, stack);
+        }
+    }
+
+    /// Records the hotbar slot a player selected.
+    pub fn change_held_item(&mut self, uuid: &Uuid, slot: i16) {
+        if slot < 0 || slot as usize >= HOTBAR_SIZE {
+            warn!("{} tried to select the invalid slot {}", uuid, slot);
+            return;
+        }
+        if let Some(entity) = self.get_player_entity(uuid) {
+            if let Some(mut inventory) = self.ecs.get_component::<Inventory>(entity) {
+                inventory.set_selected(slot as usize);
+                self.ecs.set_component(entity, inventory);
+            }
+        }
+    }
+
+    /// Makes a player hold another hotbar slot.
+    pub fn set_held_item(&mut self, uuid: &Uuid, slot: usize) -> bool {
+        if slot >= HOTBAR_SIZE {
+            return false;
+        }
+        let entity = match self.get_player_entity(uuid) {
+            Some(entity) => entity,
+            None => return false,
+        };
+        let mut inventory = match self.ecs.get_component::<Inventory>(entity) {
+            Some(inventory) => inventory,
+            None => return false,
+        };
+
+        inventory.set_selected(slot);
+        self.ecs.set_component(entity, inventory);
+        self.send_packet(
+            *uuid,
+            clientbound::play::HeldItemChangePacket::new(slot as i8),
+        );
+        true
+    }
+
+    /// Sends the players the slots which changed outside of their clicks,
+    /// like items they picked up.
+    pub(crate) fn send_inventory_changes(&mut self) {
+        for (uuid, changes) in self.ecs.take_inventory_changes() {
+            for (slot, stack) in changes {
+                self.send_packet(
+                    uuid,
+                    clientbound::play::SetSlotPacket::new(PLAYER_WINDOW as i8, slot, stack),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use cgmath::Vector3;
+use common::gamemode::Gamemode;
+use common::item::ItemStack;
+use entity::components::{BoundingBox, Position, Velocity};
+use entity::inventory::Inventory;
+use entity::item::{BLOCK_DROP_DELAY, PLAYER_DROP_DELAY};
+use entity::Entity;
+use network::clientbound;
+use rand::Rng;
+use std::f64::consts::PI;
+use uuid::Uuid;
+use world::block::AIR;
+
+use crate::server::MinecraftServer;
+
+/// Farthest a player can break a block from, between their eyes and its
+/// center.
+const BLOCK_REACH: f64 = 6.0;
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Drops an item stack from a block, popping out of it.
+    pub fn drop_block_item(&mut self, location: Vector3<i32>, stack: ItemStack) -> Entity {
+        let mut rng = rand::thread_rng();
+        let position = Position::new(
+            f64::from(location.x) + 0.5 + rng.gen_range(-0.25, 0.25),
+            f64::from(location.y) + 0.375 + rng.gen_range(-0.25, 0.25),
+            f64::from(location.z) + 0.5 + rng.gen_range(-0.25, 0.25),
+        );
+        let velocity = Velocity::new(rng.gen_range(-0.1, 0.1), 0.2, rng.gen_range(-0.1, 0.1));
+
+        self.ecs
+            .spawn_item(position, velocity, stack, BLOCK_DROP_DELAY)
+    }
+
+    /// Throws the item the player holds, or the whole stack, where they
+    /// look.
+    pub fn drop_held_item(&mut self, uuid: &Uuid, whole_stack: bool) -> Option<Entity> {
+        let entity = self.get_player_entity(uuid)?;
+        let mut inventory = self.ecs.get_component::<Inventory>(entity)?;
+        let stack = inventory.take_held(if whole_stack { i8::MAX } else { 1 })?;
+        self.ecs.set_component(entity, inventory);
+
+        self.throw_item(uuid, stack)
+    }
+
+    /// Throws a stack out of the inventory of a player, where they look.
+    pub fn throw_item(&mut self, uuid: &Uuid, stack: ItemStack) -> Option<Entity> {
+        let location = self.get_player_location(uuid)?;
+        let mut rng = rand::thread_rng();
+        let yaw = f64::from(location.yaw).to_radians();
+        let pitch = f64::from(location.pitch).to_radians();
+        let angle = rng.gen::<f64>() * PI * 2.0;
+        let spread = rng.gen::<f64>() * 0.02;
+        let position = Position::new(
+            location.x,
+            location.y + BoundingBox::PLAYER_EYE_HEIGHT - 0.3,
+            location.z,
+        );
+        let velocity = Velocity::new(
+            -yaw.sin() * pitch.cos() * 0.3 + angle.cos() * spread,
+            -pitch.sin() * 0.3 + 0.1 + (rng.gen::<f64>() - rng.gen::<f64>()) * 0.1,
+            yaw.cos() * pitch.cos() * 0.3 + angle.sin() * spread,
+        );
+
+        Some(
+            self.ecs
+                .spawn_item(position, velocity, stack, PLAYER_DROP_DELAY),
+        )
+    }
+
+    /// Whether a player is allowed to break a block, and within reach of it.
+    pub fn can_break_block(&self, uuid: &Uuid, location: Vector3<i32>) -> bool {
+        match self.get_gamemode(uuid) {
+            Some(Gamemode::Survival) | Some(Gamemode::Creative) => {}
+            _ => return false,
+        }
+        let player = match self.get_player_location(uuid) {
+            Some(player) => player,
+            None => return false,
+        };
+
+        let dx = f64::from(location.x) + 0.5 - player.x;
+        let dy = f64::from(location.y) + 0.5 - (player.y + BoundingBox::PLAYER_EYE_HEIGHT);
+        let dz = f64::from(location.z) + 0.5 - player.z;
+        dx * dx + dy * dy + dz * dz <= BLOCK_REACH * BLOCK_REACH
+    }
+
+    /// Removes a block broken by a player and shows it to everyone, dropping
+    /// its item unless they are in creative. Returns false if the player
+    /// can't break it.
+    pub fn break_block(&mut self, uuid: &Uuid, location: Vector3<i32>) -> bool {
+        if !self.can_break_block(uuid, location) {
+            return false;
+        }
+
+        let item = {
+            let mut terrain = self.ecs.get_terrain_mut();
+            let state = terrain.get_block(location.x, location.y, location.z);
+
+            if state == AIR {
+                return false;
+            }
+            terrain.set_block(location.x, location.y, location.z, AIR);
+            terrain.drops.get(state)
+        };
+        self.broadcast_packet(clientbound::play::BlockChangePacket::new(
+            location,
+            i32::from(AIR),
+        ));
+
+        if let Some(item) = item {
+            if self.get_gamemode(uuid) != Some(Gamemode::Creative) {
+                self.drop_block_item(location, ItemStack::new(item, 1));
+            }
+        }
+        true
+    }
+
+    /// Shows a player the block the world has at a location, such as one
+    /// they failed to break.
+    pub fn send_block(&mut self, uuid: Uuid, location: Vector3<i32>) {
+        let state = self
+            .ecs
+            .get_terrain()
+            .get_block(location.x, location.y, location.z);
+
+        self.send_packet(
+            uuid,
+            clientbound::play::BlockChangePacket::new(location, i32::from(state)),
+        );
+    }
+}
@@ -1,16 +1,36 @@
 use ctrlc;
 use env_logger::Env;
-use log::{info, warn};
+use log::{error, info, warn};
 use network::connection::ConnectionHandler;
+use network::packet::State;
 use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+pub mod access;
+pub mod channels;
+pub mod combat;
+pub mod commands;
 pub mod config;
+pub mod console;
+pub mod events;
+pub mod inventory;
+pub mod items;
 pub mod packet_consumers;
+pub mod permissions;
+pub mod player_list;
+pub mod plugins;
+pub mod scheduler;
 pub mod server;
+pub mod tracker;
 
-use config::Config;
+use access::{AccessHook, AccessLists};
+use config::{Config, ProxyMode};
+use events::{PlayerQuit, PreLoginHook};
+use permissions::Permissions;
 use server::MinecraftServer;
 
 fn main() {
@@ -19,11 +39,68 @@ fn main() {
 
     info!("Loading configuration...");
     let config = Config::from_path("server.toml").unwrap_or_else(|_| Config::default());
-    let server = RefCell::new(MinecraftServer::new(config));
+    let access = match AccessLists::load(Path::new("."), config.whitelist) {
+        Ok(access) => access,
+        Err(e) => {
+            error!("Failed to load the ban lists and whitelist: {}", e);
+            return;
+        }
+    };
+    let permissions = match Permissions::load(Path::new(".")) {
+        Ok(permissions) => permissions,
+        Err(e) => {
+            error!("Failed to load the operators and permissions: {}", e);
+            return;
+        }
+    };
+    let server = Rc::new(RefCell::new(MinecraftServer::new(
+        config,
+        access,
+        permissions,
+    )));
     let mut connection = ConnectionHandler::new();
     let run = Arc::new(AtomicBool::new(true));
     let run_cpy = run.clone();
 
+    {
+        let server = server.borrow();
+        let proxy = &server.config.proxy;
+
+        if proxy.mode == ProxyMode::Velocity && proxy.secret.is_empty() {
+            error!("Velocity forwarding requires a secret, refusing to start.");
+            return;
+        }
+        if proxy.mode != ProxyMode::None {
+            info!("Accepting players forwarded by a {:?} proxy.", proxy.mode);
+        }
+        connection.set_proxy_forwarding(proxy.to_forwarding());
+
+        if !server.config.online_mode && proxy.mode == ProxyMode::None {
+            warn!("Running in offline mode, players won't be authenticated!");
+        }
+        connection.set_online_mode(server.config.online_mode);
+        connection.set_throttle(server.config.throttle.to_settings());
+
+        if let Some(dir) = &server.config.capture_dir {
+            if let Err(e) = fs::create_dir_all(dir) {
+                error!("Failed to create the capture directory {}: {}", dir, e);
+                return;
+            }
+            warn!("Capturing every connection to {}.", dir);
+            connection.set_capture_directory(Some(PathBuf::from(dir)));
+        }
+
+        connection.add_login_hook(Box::new(AccessHook::new(
+            server.access.clone(),
+            server.permissions.clone(),
+        )));
+    }
+    connection.add_login_hook(Box::new(PreLoginHook::new(server.clone())));
+    if let Err(e) = server.borrow_mut().load_plugins() {
+        error!("Failed to load the plugins: {}", e);
+    }
+    server.borrow_mut().set_console(console::spawn());
+
     ctrlc::set_handler(move || {
         warn!("Received interruption signal...");
         run_cpy.store(false, Ordering::SeqCst);
@@ -41,14 +118,33 @@ fn main() {
             run,
             &host,
             port,
-            || {
-                server.borrow_mut().tick();
+            |streams| {
+                server.borrow_mut().tick(streams);
             },
             |stream, packet| {
                 packet_consumers::packet_process(&mut server.borrow_mut(), stream, packet)
             },
+            |stream| {
+                let mut server = server.borrow_mut();
+                if *stream.get_state() != State::Play || !server.is_current_session(stream) {
+                    return;
+                }
+                let uuid = *stream.get_uuid();
+
+                let event = server.post_event(PlayerQuit {
+                    uuid,
+                    username: stream.get_username().clone(),
+                    quit_message: Some(format!("{} left the game", stream.get_username())),
+                });
+                server.remove_player(stream);
+                if let Some(message) = event.quit_message {
+                    server.broadcast_message(&message);
+                }
+            },
         )
         .expect("Failed to start the server");
 
+    server.borrow_mut().disable_plugins();
+
     println!("Goodbye!");
 }
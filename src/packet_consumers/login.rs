@@ -1,29 +1,37 @@
 use common::dimension::Dimension;
-use common::gamemode::Gamemode;
 use common::level_type::LevelType;
+use network::channel::RegisterMessage;
 use network::clientbound;
+use network::packet::State;
 use network::serverbound::ServerboundPacket;
 use network::stream::Stream;
+use std::convert::TryFrom;
 use std::io;
 
-use crate::server::MinecraftServer;
+use crate::events::PlayerJoin;
+use crate::player_list;
+use crate::server::{MinecraftServer, SPAWN_LOCATION};
 
 const SERVER_VENDOR: &str = "minecrate";
 
 pub fn packet_process(
     server: &mut MinecraftServer,
     stream: &mut Stream,
-    packet: &ServerboundPacket,
+    _packet: &ServerboundPacket,
 ) -> io::Result<()> {
-    match packet {
-        ServerboundPacket::EncryptionResponse(_) => {
+    // The connection handler switches to Play once LoginSuccess is sent,
+    // whether the login went through Mojang or a forwarding proxy.
+    match stream.get_state() {
+        State::Play => {
+            let player = server.create_player(stream);
+
             stream.send_packet(&clientbound::play::JoinGamePacket::new(
-                0,
-                Gamemode::Survival,
+                entity::get_network_id(player),
+                server.config.gamemode,
                 false,
                 Dimension::Overworld,
                 0,
-                0,
+                u8::try_from(server.config.max_players).unwrap_or(u8::MAX),
                 LevelType::Default,
                 32,
                 false,
@@ -34,14 +42,33 @@ pub fn packet_process(
                 &clientbound::play::PluginMessagePacket::new_minecraft_brand(SERVER_VENDOR)?,
             )?;
 
+            let channels = server.channels.get_channels();
+            if !channels.is_empty() {
+                stream.send_channel_message(&RegisterMessage { channels })?;
+            }
+
             stream.send_packet(&clientbound::play::ServerDifficultyPacket::new(
                 server.difficulty,
                 server.difficulty_locked,
             ))?;
 
-            stream.send_packet(&clientbound::play::PlayerAbilitiesPacket::new(
-                false, false, false, false, 0.05, 0.1,
-            ))?;
+            stream.send_packet(&player_list::get_abilities(server.config.gamemode))?;
+
+            server.send_op_level(stream)?;
+            server.add_to_player_list(stream)?;
+            server.teleport(stream, SPAWN_LOCATION)?;
+            server.send_health(stream)?;
+            server.send_inventory(stream)?;
+
+            let event = server.post_event(PlayerJoin {
+                uuid: *stream.get_uuid(),
+                username: stream.get_username().clone(),
+                protocol: stream.get_version().protocol,
+                join_message: Some(format!("{} joined the game", stream.get_username())),
+            });
+            if let Some(message) = event.join_message {
+                server.broadcast_message(&message);
+            }
             Ok(())
         }
         _ => Ok(()),
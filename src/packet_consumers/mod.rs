@@ -4,6 +4,7 @@ use network::stream::Stream;
 use std::io;
 
 mod login;
+mod play;
 mod status;
 
 use crate::server::MinecraftServer;
@@ -16,6 +17,7 @@ pub fn packet_process(
     match packet.get_state() {
         State::Status => status::packet_process(server, stream, packet),
         State::Login => login::packet_process(server, stream, packet),
+        State::Play => play::packet_process(server, stream, packet),
         _ => Ok(()),
     }
 }
@@ -0,0 +1,236 @@
+use common::chat::{Chat, ChatMessageType};
+use common::location::Location;
+use common::metadata::Pose;
+use entity::metadata::EntityFlag;
+use log::info;
+use network::clientbound;
+use network::serverbound::play::InteractAction;
+use network::serverbound::ServerboundPacket;
+use network::stream::Stream;
+use std::io;
+use std::mem;
+use uuid::Uuid;
+
+use crate::commands::CommandSender;
+use crate::events::{BlockBreak, PlayerChat, PlayerMove};
+use crate::server::{MinecraftServer, SPAWN_LOCATION};
+
+/// Digging status sent once a block is broken.
+const FINISHED_DIGGING: i32 = 2;
+/// Digging statuses sent to throw the held stack, or a single item of it.
+const DROP_ITEM_STACK: i32 = 3;
+const DROP_ITEM: i32 = 4;
+
+/// Entity actions changing how the player is shown.
+const START_SNEAKING: i32 = 0;
+const STOP_SNEAKING: i32 = 1;
+const START_SPRINTING: i32 = 3;
+const STOP_SPRINTING: i32 = 4;
+
+/// Client status sent from the death screen.
+const PERFORM_RESPAWN: i32 = 0;
+/// Hand of the arm swung, the main hand being 0.
+const OFF_HAND: i32 = 1;
+
+pub fn packet_process(
+    server: &mut MinecraftServer,
+    stream: &mut Stream,
+    packet: &ServerboundPacket,
+) -> io::Result<()> {
+    match packet {
+        ServerboundPacket::PluginMessage(ref x) => {
+            // Handlers get the server mutably, so the registry is moved out while they run
+            let channels = mem::take(&mut server.channels);
+            let result = channels.dispatch(server, stream, &x.channel, &x.data);
+
+            server.channels = channels;
+            result
+        }
+        ServerboundPacket::ChatMessage(ref x) => {
+            if x.message.starts_with('/') {
+                let sender = CommandSender::Player {
+                    uuid: *stream.get_uuid(),
+                    username: stream.get_username().clone(),
+                };
+                server.queue_command(sender, &x.message[1..]);
+                return Ok(());
+            }
+
+            let event = server.post_event(PlayerChat {
+                uuid: *stream.get_uuid(),
+                username: stream.get_username().clone(),
+                message: x.message.clone(),
+                cancelled: false,
+            });
+            if !event.cancelled {
+                let message = format!("<{}> {}", event.username, event.message);
+
+                info!("{}", message);
+                server.broadcast_packet(clientbound::play::ChatMessagePacket::new(
+                    Chat::new_text(&message),
+                    ChatMessageType::Chat,
+                    event.uuid,
+                ));
+            }
+            Ok(())
+        }
+        ServerboundPacket::TeleportConfirm(ref x) => {
+            server.confirm_teleport(stream.get_uuid(), x.teleport_id);
+            Ok(())
+        }
+        ServerboundPacket::PlayerPosition(ref x) => {
+            player_move(server, stream, x.on_ground, |to| {
+                to.x = x.x;
+                to.y = x.y;
+                to.z = x.z;
+            })
+        }
+        ServerboundPacket::PlayerPositionAndRotation(ref x) => {
+            player_move(server, stream, x.on_ground, |to| {
+                *to = Location::new(x.x, x.y, x.z, x.yaw, x.pitch);
+            })
+        }
+        ServerboundPacket::PlayerRotation(ref x) => {
+            player_move(server, stream, x.on_ground, |to| {
+                to.yaw = x.yaw;
+                to.pitch = x.pitch;
+            })
+        }
+        ServerboundPacket::PlayerDigging(ref x) if x.status == FINISHED_DIGGING => {
+            let uuid = *stream.get_uuid();
+
+            if server.can_break_block(&uuid, x.location) {
+                let event = server.post_event(BlockBreak {
+                    uuid,
+                    location: x.location,
+                    cancelled: false,
+                });
+                if !event.cancelled && server.break_block(&event.uuid, event.location) {
+                    return Ok(());
+                }
+            }
+            // The client already removed the block
+            server.send_block(uuid, x.location);
+            Ok(())
+        }
+        ServerboundPacket::PlayerDigging(ref x)
+            if x.status == DROP_ITEM_STACK || x.status == DROP_ITEM =>
+        {
+            server.drop_held_item(stream.get_uuid(), x.status == DROP_ITEM_STACK);
+            Ok(())
+        }
+        ServerboundPacket::EntityAction(ref x) => {
+            let sneaking = x.action == START_SNEAKING;
+            let sprinting = x.action == START_SPRINTING;
+
+            match x.action {
+                START_SNEAKING | STOP_SNEAKING => {
+                    server.update_player_metadata(stream.get_uuid(), |metadata| {
+                        metadata.set_flag(EntityFlag::Sneaking, sneaking);
+                        metadata.set_pose(if sneaking {
+                            Pose::Sneaking
+                        } else {
+                            Pose::Standing
+                        });
+                    })
+                }
+                START_SPRINTING | STOP_SPRINTING => server
+                    .update_player_metadata(stream.get_uuid(), |metadata| {
+                        metadata.set_flag(EntityFlag::Sprinting, sprinting)
+                    }),
+                _ => {}
+            }
+            Ok(())
+        }
+        ServerboundPacket::InteractEntity(ref x) if x.action == InteractAction::Attack => {
+            server.attack_entity(stream.get_uuid(), x.entity_id);
+            Ok(())
+        }
+        ServerboundPacket::ClientStatus(ref x) if x.action == PERFORM_RESPAWN => {
+            server.respawn_player(stream)
+        }
+        ServerboundPacket::ClickWindow(ref x) => server.click_window(stream, x),
+        ServerboundPacket::WindowConfirmation(ref x) => {
+            server.confirm_window_action(stream.get_uuid(), x.action_number, x.accepted);
+            Ok(())
+        }
+        ServerboundPacket::CloseWindow(_) => {
+            server.close_window(stream.get_uuid());
+            Ok(())
+        }
+        ServerboundPacket::HeldItemChange(ref x) => {
+            server.change_held_item(stream.get_uuid(), x.slot);
+            Ok(())
+        }
+        ServerboundPacket::Animation(ref x) => {
+            let uuid = *stream.get_uuid();
+            let entity = match server.get_player_entity(&uuid) {
+                Some(entity) => entity,
+                None => return Ok(()),
+            };
+            let animation = if x.hand == OFF_HAND {
+                clientbound::play::SWING_OFFHAND
+            } else {
+                clientbound::play::SWING_MAIN_ARM
+            };
+            let others: Vec<Uuid> = server
+                .player_list
+                .get_entries()
+                .iter()
+                .map(|entry| entry.uuid)
+                .filter(|other| *other != uuid)
+                .collect();
+
+            for other in others {
+                server.send_packet(
+                    other,
+                    clientbound::play::EntityAnimationPacket::new(
+                        entity::get_network_id(entity),
+                        animation,
+                    ),
+                );
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Posts the move reported by the client, sending the player back or
+/// elsewhere if a listener wants to.
+fn player_move<F>(
+    server: &mut MinecraftServer,
+    stream: &mut Stream,
+    on_ground: bool,
+    update: F,
+) -> io::Result<()>
+where
+    F: FnOnce(&mut Location),
+{
+    let uuid = *stream.get_uuid();
+
+    // Moves sent before the client caught up with a teleport are stale
+    if server.is_teleporting(&uuid) {
+        return Ok(());
+    }
+
+    let from = server.get_player_location(&uuid).unwrap_or(SPAWN_LOCATION);
+    let mut to = from;
+    update(&mut to);
+
+    let event = server.post_event(PlayerMove {
+        uuid,
+        from,
+        to,
+        on_ground,
+        cancelled: false,
+    });
+    if event.cancelled {
+        server.teleport(stream, from)
+    } else if event.to != to {
+        server.teleport(stream, event.to)
+    } else {
+        server.set_player_location(&uuid, to, on_ground);
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use common::chat::Chat;
 use network::clientbound;
-use network::connection::{PROTOCOL_NAME, PROTOCOL_VERSION};
+use network::protocol::supported_versions_name;
 use network::serverbound::ServerboundPacket;
 use network::stream::Stream;
 use std::io;
@@ -16,8 +16,8 @@ pub fn packet_process(
         ServerboundPacket::StatusRequest(_) => {
             let payload = clientbound::status::StatusResponsePayload {
                 version: clientbound::status::StatusResponsePayloadVersion {
-                    name: PROTOCOL_NAME.to_string(),
-                    protocol: PROTOCOL_VERSION,
+                    name: supported_versions_name(),
+                    protocol: stream.get_version().protocol,
                 },
                 players: clientbound::status::StatusResponsePayloadPlayers {
                     max: server.config.max_players,
@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::access::{read_list, write_list};
+
+const OPS_FILE: &str = "ops.json";
+const PERMISSIONS_FILE: &str = "permissions.toml";
+
+/// Group every player belongs to, if it exists.
+pub const DEFAULT_GROUP: &str = "default";
+pub const MAX_OP_LEVEL: u8 = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operator {
+    pub uuid: Uuid,
+    pub name: String,
+    pub level: u8,
+    #[serde(rename = "bypassesPlayerLimit")]
+    pub bypasses_player_limit: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Group {
+    /// Groups whose permissions this one gets too.
+    pub inherits: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerPermissions {
+    /// Only there to tell players apart when editing the file.
+    pub name: String,
+    pub groups: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct PermissionsFile {
+    groups: BTreeMap<String, Group>,
+    players: BTreeMap<Uuid, PlayerPermissions>,
+}
+
+/// Operators from ops.json, and permission nodes given to players and
+/// groups in permissions.toml.
+///
+/// Nodes are dot separated, like `minecrate.command.ban`. A trailing `*`
+/// matches every node below it, and a leading `-` denies the node. The most
+/// specific node wins, and the player's own nodes win over their groups'.
+pub struct Permissions {
+    dir: PathBuf,
+    ops: Vec<Operator>,
+    file: PermissionsFile,
+}
+
+impl Permissions {
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut permissions = Self {
+            dir: dir.to_path_buf(),
+            ops: vec![],
+            file: PermissionsFile::default(),
+        };
+
+        permissions.reload()?;
+        Ok(permissions)
+    }
+
+    pub fn reload(&mut self) -> io::Result<()> {
+        self.ops = read_list(&self.dir.join(OPS_FILE))?;
+
+        let path = self.dir.join(PERMISSIONS_FILE);
+        self.file = if path.exists() {
+            toml::from_str(&fs::read_to_string(&path)?).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Failed to parse {}: {}", path.display(), e),
+                )
+            })?
+        } else {
+            PermissionsFile::default()
+        };
+        Ok(())
+    }
+
+    /// Level 0 for players who aren't operators.
+    pub fn get_op_level(&self, uuid: &Uuid) -> u8 {
+        self.ops
+            .iter()
+            .find(|op| op.uuid == *uuid)
+            .map_or(0, |op| op.level)
+    }
+
+    pub fn get_ops(&self) -> &[Operator] {
+        &self.ops
+    }
+
+    /// Makes a player operator, replacing their previous level.
+    pub fn op(&mut self, operator: Operator) -> io::Result<()> {
+        self.ops.retain(|other| other.uuid != operator.uuid);
+        self.ops.push(operator);
+        write_list(&self.dir.join(OPS_FILE), &self.ops)
+    }
+
+    /// Returns false if the player wasn't an operator.
+    pub fn deop(&mut self, uuid: &Uuid) -> io::Result<bool> {
+        let len = self.ops.len();
+
+        self.ops.retain(|op| op.uuid != *uuid);
+        if self.ops.len() == len {
+            return Ok(false);
+        }
+        write_list(&self.dir.join(OPS_FILE), &self.ops).map(|_| true)
+    }
+
+    /// Whether a node is granted, `None` when no node matches it.
+    pub fn check(&self, uuid: &Uuid, node: &str) -> Option<bool> {
+        if let Some(player) = self.file.players.get(uuid) {
+            if let Some(allowed) = lookup(&player.permissions, node) {
+                return Some(allowed);
+            }
+
+            let mut visited = HashSet::new();
+            for group in &player.groups {
+                if let Some(allowed) = self.check_group(group, node, &mut visited) {
+                    return Some(allowed);
+                }
+            }
+        }
+
+        self.check_group(DEFAULT_GROUP, node, &mut HashSet::new())
+    }
+
+    /// Nodes decide when they match, otherwise the op level does.
+    pub fn is_allowed(&self, uuid: &Uuid, node: &str, op_level: u8) -> bool {
+        match self.check(uuid, node) {
+            Some(allowed) => allowed,
+            None => self.get_op_level(uuid) >= op_level,
+        }
+    }
+
+    pub fn get_groups(&self) -> &BTreeMap<String, Group> {
+        &self.file.groups
+    }
+
+    pub fn get_player(&self, uuid: &Uuid) -> Option<&PlayerPermissions> {
+        self.file.players.get(uuid)
+    }
+
+    /// Changes the permissions of a player and saves them.
+    pub fn edit_player<F>(&mut self, uuid: Uuid, name: &str, edit: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut PlayerPermissions),
+    {
+        let player = self.file.players.entry(uuid).or_default();
+
+        player.name = name.to_string();
+        edit(player);
+        self.save()
+    }
+
+    /// Changes the permissions of a group, creating it if needed, and saves
+    /// them.
+    pub fn edit_group<F>(&mut self, name: &str, edit: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut Group),
+    {
+        edit(self.file.groups.entry(name.to_string()).or_default());
+        self.save()
+    }
+
+    fn check_group(&self, name: &str, node: &str, visited: &mut HashSet<String>) -> Option<bool> {
+        // Inheritance loops are cut where they close
+        if !visited.insert(name.to_string()) {
+            return None;
+        }
+
+        let group = self.file.groups.get(name)?;
+        if let Some(allowed) = lookup(&group.permissions, node) {
+            return Some(allowed);
+        }
+
+        group
+            .inherits
+            .iter()
+            .find_map(|parent| self.check_group(parent, node, visited))
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let content = toml::to_string(&self.file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(self.dir.join(PERMISSIONS_FILE), content)
+    }
+}
+
+/// Finds the most specific node matching `node`, a denial winning over a
+/// grant of the same node.
+fn lookup(nodes: &[String], node: &str) -> Option<bool> {
+    let mut best: Option<(usize, bool)> = None;
+
+    for pattern in nodes {
+        let (pattern, allowed) = match pattern.strip_prefix('-') {
+            Some(pattern) => (pattern, false),
+            None => (pattern.as_str(), true),
+        };
+        let specificity = match specificity(pattern, node) {
+            Some(specificity) => specificity,
+            None => continue,
+        };
+
+        best = match best {
+            Some((best_specificity, best_allowed))
+                if best_specificity > specificity
+                    || (best_specificity == specificity && !best_allowed) =>
+            {
+                Some((best_specificity, best_allowed))
+            }
+            _ => Some((specificity, allowed)),
+        };
+    }
+    best.map(|(_, allowed)| allowed)
+}
+
+/// How closely a pattern matches a node, `None` if it doesn't.
+fn specificity(pattern: &str, node: &str) -> Option<usize> {
+    if pattern == node {
+        return Some(usize::MAX);
+    }
+
+    let prefix = pattern.strip_suffix('*')?;
+    if prefix.is_empty() || (prefix.ends_with('.') && node.starts_with(prefix)) {
+        Some(prefix.len())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+    use uuid::Uuid;
+
+    use super::{lookup, Permissions, DEFAULT_GROUP};
+
+    fn nodes(nodes: &[&str]) -> Vec<String> {
+        nodes.iter().map(|node| node.to_string()).collect()
+    }
+
+    fn dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("minecrate-permissions-{}-{}", name, process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn most_specific_node_wins() {
+        let granted = nodes(&["-a.*", "a.b"]);
+        assert_eq!(lookup(&granted, "a.b"), Some(true));
+        assert_eq!(lookup(&granted, "a.c"), Some(false));
+
+        let denied = nodes(&["a.*", "-a.b"]);
+        assert_eq!(lookup(&denied, "a.b"), Some(false));
+        assert_eq!(lookup(&denied, "a.b.c"), Some(true));
+
+        // Longer wildcards are more specific
+        let nested = nodes(&["a.*", "-a.b.*"]);
+        assert_eq!(lookup(&nested, "a.b.c"), Some(false));
+        assert_eq!(lookup(&nested, "a.c"), Some(true));
+    }
+
+    #[test]
+    fn denial_wins_over_a_grant_of_the_same_node() {
+        assert_eq!(lookup(&nodes(&["a.b", "-a.b"]), "a.b"), Some(false));
+        assert_eq!(lookup(&nodes(&["-a.*", "a.*"]), "a.b"), Some(false));
+    }
+
+    #[test]
+    fn wildcards_only_match_whole_segments() {
+        assert_eq!(lookup(&nodes(&["a.*"]), "ab.c"), None);
+        assert_eq!(lookup(&nodes(&["a.*"]), "a"), None);
+        assert_eq!(lookup(&nodes(&["*"]), "a.b"), Some(true));
+    }
+
+    #[test]
+    fn op_level_decides_when_no_node_matches() {
+        let dir = dir("default");
+        let mut permissions = Permissions::load(&dir).unwrap();
+        let uuid = Uuid::new_v4();
+
+        permissions
+            .edit_group(DEFAULT_GROUP, |group| {
+                group.permissions = nodes(&["minecrate.command.list"])
+            })
+            .unwrap();
+        permissions
+            .edit_player(uuid, "Steve", |player| {
+                player.permissions = nodes(&["-minecrate.command.*"])
+            })
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // The player's own nodes win over those of their groups
+        assert_eq!(
+            permissions.check(&uuid, "minecrate.command.list"),
+            Some(false)
+        );
+        assert_eq!(
+            permissions.check(&Uuid::new_v4(), "minecrate.command.list"),
+            Some(true)
+        );
+
+        assert_eq!(permissions.check(&uuid, "minecrate.other"), None);
+        assert!(permissions.is_allowed(&uuid, "minecrate.other", 0));
+        assert!(!permissions.is_allowed(&uuid, "minecrate.other", 1));
+    }
+}
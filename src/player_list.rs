@@ -0,0 +1,186 @@
+use common::chat::Chat;
+use common::gamemode::Gamemode;
+use entity::components::Player;
+use network::clientbound;
+use network::clientbound::play::{PlayerInfoAction, PlayerListEntry};
+use network::clientbound::ClientboundPacket;
+use network::packet::State;
+use network::stream::Stream;
+use std::io;
+use uuid::Uuid;
+
+use crate::server::MinecraftServer;
+
+/// Ticks between two latency updates, like vanilla.
+pub const LATENCY_UPDATE_INTERVAL: u64 = 600;
+
+/// Players in the tab list of every client, in the order they joined.
+#[derive(Debug, Default)]
+pub struct PlayerList {
+    entries: Vec<PlayerListEntry>,
+}
+
+impl PlayerList {
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    pub fn get(&self, uuid: &Uuid) -> Option<&PlayerListEntry> {
+        self.entries.iter().find(|entry| entry.uuid == *uuid)
+    }
+
+    pub fn get_entries(&self) -> &[PlayerListEntry] {
+        &self.entries
+    }
+
+    fn get_mut(&mut self, uuid: &Uuid) -> Option<&mut PlayerListEntry> {
+        self.entries.iter_mut().find(|entry| entry.uuid == *uuid)
+    }
+}
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Shows a player who just joined to everyone, and sends them the whole
+    /// list along with its header and footer.
+    pub fn add_to_player_list(&mut self, stream: &mut Stream) -> io::Result<()> {
+        let uuid = *stream.get_uuid();
+        let entry = PlayerListEntry {
+            uuid,
+            name: stream.get_username().clone(),
+            properties: stream.get_properties().to_vec(),
+            gamemode: self.get_gamemode(&uuid).unwrap_or(Gamemode::Survival),
+            latency: to_millis(stream),
+            display_name: None,
+        };
+
+        self.player_list.entries.retain(|entry| entry.uuid != uuid);
+        let others: Vec<Uuid> = self.player_list.entries.iter().map(|e| e.uuid).collect();
+        for other in others {
+            self.send_packet(
+                other,
+                clientbound::play::PlayerInfoPacket::new(PlayerInfoAction::AddPlayer(vec![
+                    entry.clone()
+                ])),
+            );
+        }
+        self.player_list.entries.push(entry);
+
+        stream.send_packet(&clientbound::play::PlayerInfoPacket::new(
+            PlayerInfoAction::AddPlayer(self.player_list.entries.clone()),
+        ))?;
+        if !self.config.tab_list.is_empty() {
+            stream.send_packet(&clientbound::play::PlayerListHeaderAndFooterPacket::new(
+                self.config.tab_list.get_header(),
+                self.config.tab_list.get_footer(),
+            ))?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_from_player_list(&mut self, uuid: &Uuid) {
+        let count = self.player_list.entries.len();
+
+        self.player_list.entries.retain(|entry| entry.uuid != *uuid);
+        if self.player_list.entries.len() != count {
+            self.broadcast_packet(clientbound::play::PlayerInfoPacket::new(
+                PlayerInfoAction::RemovePlayer(vec![*uuid]),
+            ));
+        }
+    }
+
+    pub fn get_gamemode(&self, uuid: &Uuid) -> Option<Gamemode> {
+        let player = self.get_player_entity(uuid)?;
+
+        self.ecs
+            .get_component::<Player>(player)
+            .map(|player| player.gamemode)
+    }
+
+    /// Changes the gamemode of a player, returning false if they aren't
+    /// playing.
+    pub fn set_gamemode(&mut self, uuid: &Uuid, gamemode: Gamemode) -> bool {
+        let entity = match self.get_player_entity(uuid) {
+            Some(entity) => entity,
+            None => return false,
+        };
+        let mut player = match self.ecs.get_component::<Player>(entity) {
+            Some(player) => player,
+            None => return false,
+        };
+
+        player.gamemode = gamemode;
+        self.ecs.set_component(entity, player);
+        if let Some(entry) = self.player_list.get_mut(uuid) {
+            entry.gamemode = gamemode;
+        }
+
+        self.send_packet(
+            *uuid,
+            clientbound::play::ChangeGameStatePacket::new(
+                clientbound::play::CHANGE_GAMEMODE,
+                f32::from(u8::from(gamemode)),
+            ),
+        );
+        self.send_packet(*uuid, get_abilities(gamemode));
+        self.broadcast_packet(clientbound::play::PlayerInfoPacket::new(
+            PlayerInfoAction::UpdateGamemode(vec![(*uuid, gamemode)]),
+        ));
+        true
+    }
+
+    /// Shows another name in the tab list, or the username again with `None`.
+    pub fn set_display_name(&mut self, uuid: &Uuid, display_name: Option<Chat>) {
+        if let Some(entry) = self.player_list.get_mut(uuid) {
+            entry.display_name = display_name.clone();
+            self.broadcast_packet(clientbound::play::PlayerInfoPacket::new(
+                PlayerInfoAction::UpdateDisplayName(vec![(*uuid, display_name)]),
+            ));
+        }
+    }
+
+    /// Sends everyone the latencies measured by the keep-alives.
+    pub(crate) fn update_latencies(&mut self, streams: &[Stream]) {
+        for stream in streams {
+            if *stream.get_state() != State::Play {
+                continue;
+            }
+            if let Some(entry) = self.player_list.get_mut(stream.get_uuid()) {
+                entry.latency = to_millis(stream);
+            }
+        }
+
+        if !self.player_list.entries.is_empty() {
+            let latencies = self
+                .player_list
+                .entries
+                .iter()
+                .map(|entry| (entry.uuid, entry.latency))
+                .collect();
+
+            self.broadcast_packet(clientbound::play::PlayerInfoPacket::new(
+                PlayerInfoAction::UpdateLatency(latencies),
+            ));
+        }
+    }
+}
+
+/// Abilities a gamemode grants.
+pub(crate) fn get_abilities(gamemode: Gamemode) -> ClientboundPacket {
+    let (invulnerable, flying, allow_flight, instant_break) = match gamemode {
+        Gamemode::Survival | Gamemode::Adventure => (false, false, false, false),
+        Gamemode::Creative => (true, false, true, true),
+        Gamemode::Spectator => (true, true, true, false),
+    };
+
+    clientbound::play::PlayerAbilitiesPacket::new(
+        invulnerable,
+        flying,
+        allow_flight,
+        instant_break,
+        0.05,
+        0.1,
+    )
+}
+
+fn to_millis(stream: &Stream) -> i32 {
+    stream.get_latency().as_millis().min(i32::MAX as u128) as i32
+}
@@ -0,0 +1,181 @@
+use log::error;
+use plugin_api::{EventKind, Location, Priority};
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use super::Plugin;
+use crate::events::{
+    BlockBreak, Event, EventBus, EventPriority, ListenerId, PlayerChat, PlayerJoin, PlayerMove,
+    PlayerPreLogin, PlayerQuit, ServerTick,
+};
+
+/// An event plugins can subscribe to.
+trait PluginEvent: Event {
+    const KIND: EventKind;
+
+    fn to_plugin_event(&self) -> plugin_api::Event;
+
+    /// Called when a plugin cancels the event, which most events ignore.
+    fn cancel(&mut self) {}
+}
+
+/// Forwards an event to the plugin as long as it is loaded.
+pub fn subscribe(
+    bus: &mut EventBus,
+    kind: EventKind,
+    priority: Priority,
+    plugin: Weak<RefCell<Plugin>>,
+) -> ListenerId {
+    let priority = match priority {
+        Priority::Lowest => EventPriority::Lowest,
+        Priority::Low => EventPriority::Low,
+        Priority::Normal => EventPriority::Normal,
+        Priority::High => EventPriority::High,
+        Priority::Highest => EventPriority::Highest,
+        Priority::Monitor => EventPriority::Monitor,
+    };
+
+    match kind {
+        EventKind::PlayerPreLogin => subscribe_to::<PlayerPreLogin>(bus, priority, plugin),
+        EventKind::PlayerJoin => subscribe_to::<PlayerJoin>(bus, priority, plugin),
+        EventKind::PlayerQuit => subscribe_to::<PlayerQuit>(bus, priority, plugin),
+        EventKind::PlayerChat => subscribe_to::<PlayerChat>(bus, priority, plugin),
+        EventKind::PlayerMove => subscribe_to::<PlayerMove>(bus, priority, plugin),
+        EventKind::BlockBreak => subscribe_to::<BlockBreak>(bus, priority, plugin),
+        EventKind::ServerTick => subscribe_to::<ServerTick>(bus, priority, plugin),
+    }
+}
+
+fn subscribe_to<E: PluginEvent>(
+    bus: &mut EventBus,
+    priority: EventPriority,
+    plugin: Weak<RefCell<Plugin>>,
+) -> ListenerId {
+    bus.subscribe(priority, move |server, event: &mut E| {
+        let plugin = match plugin.upgrade() {
+            Some(plugin) => plugin,
+            None => return,
+        };
+        let payload = match event.to_plugin_event().encode() {
+            Ok((_, payload)) => payload,
+            Err(e) => {
+                error!("Failed to encode {:?} for plugins: {}", E::KIND, e);
+                return;
+            }
+        };
+
+        let cancelled = server.call_plugin(&plugin, None, |instance| {
+            instance.on_event(E::KIND, &payload)
+        });
+        if cancelled == Some(true) {
+            event.cancel();
+        }
+    })
+}
+
+fn to_plugin_location(location: &common::location::Location) -> Location {
+    Location {
+        x: location.x,
+        y: location.y,
+        z: location.z,
+        yaw: location.yaw,
+        pitch: location.pitch,
+    }
+}
+
+impl PluginEvent for PlayerPreLogin {
+    const KIND: EventKind = EventKind::PlayerPreLogin;
+
+    fn to_plugin_event(&self) -> plugin_api::Event {
+        plugin_api::Event::PlayerPreLogin(plugin_api::PlayerPreLogin {
+            uuid: self.uuid.to_hyphenated().to_string(),
+            username: self.username.clone(),
+            address: self.address.to_string(),
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl PluginEvent for PlayerJoin {
+    const KIND: EventKind = EventKind::PlayerJoin;
+
+    fn to_plugin_event(&self) -> plugin_api::Event {
+        plugin_api::Event::PlayerJoin(plugin_api::PlayerJoin {
+            uuid: self.uuid.to_hyphenated().to_string(),
+            username: self.username.clone(),
+            protocol: self.protocol,
+        })
+    }
+}
+
+impl PluginEvent for PlayerQuit {
+    const KIND: EventKind = EventKind::PlayerQuit;
+
+    fn to_plugin_event(&self) -> plugin_api::Event {
+        plugin_api::Event::PlayerQuit(plugin_api::PlayerQuit {
+            uuid: self.uuid.to_hyphenated().to_string(),
+            username: self.username.clone(),
+        })
+    }
+}
+
+impl PluginEvent for PlayerChat {
+    const KIND: EventKind = EventKind::PlayerChat;
+
+    fn to_plugin_event(&self) -> plugin_api::Event {
+        plugin_api::Event::PlayerChat(plugin_api::PlayerChat {
+            uuid: self.uuid.to_hyphenated().to_string(),
+            username: self.username.clone(),
+            message: self.message.clone(),
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl PluginEvent for PlayerMove {
+    const KIND: EventKind = EventKind::PlayerMove;
+
+    fn to_plugin_event(&self) -> plugin_api::Event {
+        plugin_api::Event::PlayerMove(plugin_api::PlayerMove {
+            uuid: self.uuid.to_hyphenated().to_string(),
+            from: to_plugin_location(&self.from),
+            to: to_plugin_location(&self.to),
+            on_ground: self.on_ground,
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl PluginEvent for BlockBreak {
+    const KIND: EventKind = EventKind::BlockBreak;
+
+    fn to_plugin_event(&self) -> plugin_api::Event {
+        plugin_api::Event::BlockBreak(plugin_api::BlockBreak {
+            uuid: self.uuid.to_hyphenated().to_string(),
+            x: self.location.x,
+            y: self.location.y,
+            z: self.location.z,
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl PluginEvent for ServerTick {
+    const KIND: EventKind = EventKind::ServerTick;
+
+    fn to_plugin_event(&self) -> plugin_api::Event {
+        plugin_api::Event::ServerTick(plugin_api::ServerTick { tick: self.tick })
+    }
+}
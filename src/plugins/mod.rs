@@ -0,0 +1,381 @@
+use common::chat::{Chat, ChatMessageType};
+use log::{error, info, warn};
+use network::clientbound;
+use plugin_api::CommandCall;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use uuid::Uuid;
+use wasmtime::Module;
+
+mod events;
+mod runtime;
+#[cfg(test)]
+mod tests;
+
+use crate::commands::{CommandContext, CommandError, CommandResult, CommandSender};
+use crate::events::ListenerId;
+use crate::scheduler::TaskHandle;
+use crate::server::MinecraftServer;
+use runtime::{Action, PluginInstance, Runtime};
+
+/// Extensions of plugin modules, either binary or text WebAssembly.
+const PLUGIN_EXTENSIONS: [&str; 2] = ["wasm", "wat"];
+
+/// A plugin module, enabled when it has a running instance.
+pub struct Plugin {
+    name: String,
+    path: PathBuf,
+    module: Module,
+    instance: Option<PluginInstance>,
+    commands: Vec<String>,
+    listeners: Vec<ListenerId>,
+    tasks: HashMap<i32, TaskHandle>,
+}
+
+impl Plugin {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.instance.is_some()
+    }
+}
+
+/// Plugins loaded from a directory, named after their file.
+pub struct PluginManager {
+    dir: PathBuf,
+    runtime: Runtime,
+    plugins: Vec<Rc<RefCell<Plugin>>>,
+}
+
+impl PluginManager {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            runtime: Runtime::new().expect("Failed to set up the plugin runtime"),
+            plugins: vec![],
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Rc<RefCell<Plugin>>> {
+        self.plugins
+            .iter()
+            .find(|plugin| plugin.borrow().name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Names of the plugins, and whether they are enabled.
+    pub fn get_plugins(&self) -> Vec<(String, bool)> {
+        self.plugins
+            .iter()
+            .map(|plugin| {
+                let plugin = plugin.borrow();
+                (plugin.name.clone(), plugin.is_enabled())
+            })
+            .collect()
+    }
+}
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Loads and enables the plugins of the plugin directory which aren't
+    /// loaded yet. Plugins failing to load are skipped.
+    pub fn load_plugins(&mut self) -> io::Result<()> {
+        let dir = self.plugins.dir.clone();
+        fs::create_dir_all(&dir)?;
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| PLUGIN_EXTENSIONS.contains(&extension))
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            if self.plugins.get(&name).is_some() {
+                continue;
+            }
+
+            let module = match self.plugins.runtime.compile(&path) {
+                Ok(module) => module,
+                Err(e) => {
+                    error!("Failed to load plugin {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            self.plugins.plugins.push(Rc::new(RefCell::new(Plugin {
+                name: name.clone(),
+                path,
+                module,
+                instance: None,
+                commands: vec![],
+                listeners: vec![],
+                tasks: HashMap::new(),
+            })));
+
+            if let Err(e) = self.enable_plugin(&name) {
+                error!("Failed to enable plugin {}: {}", name, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts a fresh instance of the plugin, which registers its commands
+    /// and listeners.
+    pub fn enable_plugin(&mut self, name: &str) -> io::Result<()> {
+        let plugin = self.get_plugin(name)?;
+        if plugin.borrow().is_enabled() {
+            return Err(invalid_input(&format!(
+                "Plugin {} is already enabled",
+                name
+            )));
+        }
+
+        let mut instance = {
+            let plugin = plugin.borrow();
+            self.plugins
+                .runtime
+                .instantiate(&plugin.name, &plugin.module)?
+        };
+        instance.enable(self.commands.get_names().into_iter().collect())?;
+
+        let actions = instance.take_actions();
+        plugin.borrow_mut().instance = Some(instance);
+        self.apply_actions(&plugin, actions, None);
+
+        info!("Enabled plugin {}", plugin.borrow().name);
+        Ok(())
+    }
+
+    /// Stops the plugin, removing its commands, listeners and tasks.
+    pub fn disable_plugin(&mut self, name: &str) -> io::Result<()> {
+        let plugin = self.get_plugin(name)?;
+        if !plugin.borrow().is_enabled() {
+            return Err(invalid_input(&format!("Plugin {} is not enabled", name)));
+        }
+
+        self.call_plugin(&plugin, None, |instance| instance.disable());
+
+        let mut plugin = plugin.borrow_mut();
+        for command in plugin.commands.drain(..) {
+            self.commands.unregister(&command);
+        }
+        for listener in plugin.listeners.drain(..) {
+            self.events.unsubscribe(listener);
+        }
+        for (_, task) in plugin.tasks.drain() {
+            task.cancel();
+        }
+        plugin.instance = None;
+
+        info!("Disabled plugin {}", plugin.name);
+        Ok(())
+    }
+
+    /// Disables the plugin, reads its file again, and enables it.
+    pub fn reload_plugin(&mut self, name: &str) -> io::Result<()> {
+        let plugin = self.get_plugin(name)?;
+        if plugin.borrow().is_enabled() {
+            self.disable_plugin(name)?;
+        }
+
+        let module = self.plugins.runtime.compile(&plugin.borrow().path)?;
+        plugin.borrow_mut().module = module;
+        self.enable_plugin(name)
+    }
+
+    /// Unloads every plugin, then loads the plugin directory again.
+    pub fn reload_plugins(&mut self) -> io::Result<()> {
+        self.disable_plugins();
+        self.plugins.plugins.clear();
+        self.load_plugins()
+    }
+
+    pub fn disable_plugins(&mut self) {
+        for (name, enabled) in self.plugins.get_plugins() {
+            if enabled {
+                let _ = self.disable_plugin(&name);
+            }
+        }
+    }
+
+    /// Calls into an enabled plugin, then applies what it asked for. Errors
+    /// are logged, and `None` is returned instead.
+    fn call_plugin<R, F>(
+        &mut self,
+        plugin: &Rc<RefCell<Plugin>>,
+        context: Option<&mut CommandContext>,
+        call: F,
+    ) -> Option<R>
+    where
+        F: FnOnce(&mut PluginInstance) -> io::Result<R>,
+    {
+        let (name, result, actions) = {
+            // A plugin can't be called again while it runs
+            let mut plugin = plugin.try_borrow_mut().ok()?;
+            let name = plugin.name.clone();
+            let instance = plugin.instance.as_mut()?;
+            let result = call(instance);
+
+            (name, result, instance.take_actions())
+        };
+
+        self.apply_actions(plugin, actions, context);
+        match result {
+            Ok(result) => Some(result),
+            Err(e) => {
+                error!("Plugin {} failed: {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Calls `on_task` of the plugin after `delay` ticks, then every `period`
+    /// ticks unless it is zero.
+    fn schedule_plugin_task(
+        &mut self,
+        plugin: &Rc<RefCell<Plugin>>,
+        task: i32,
+        delay: u32,
+        period: u32,
+    ) -> TaskHandle {
+        let plugin = Rc::downgrade(plugin);
+        let delay = u64::from(delay);
+
+        if period == 0 {
+            self.scheduler.run_later(delay, move |server| {
+                if let Some(plugin) = plugin.upgrade() {
+                    plugin.borrow_mut().tasks.remove(&task);
+                    server.call_plugin(&plugin, None, |instance| instance.on_task(task));
+                }
+            })
+        } else {
+            self.scheduler
+                .run_repeating(delay, u64::from(period), move |server| {
+                    if let Some(plugin) = plugin.upgrade() {
+                        server.call_plugin(&plugin, None, |instance| instance.on_task(task));
+                    }
+                })
+        }
+    }
+
+    fn apply_actions(
+        &mut self,
+        plugin: &Rc<RefCell<Plugin>>,
+        actions: Vec<Action>,
+        mut context: Option<&mut CommandContext>,
+    ) {
+        for action in actions {
+            match action {
+                Action::RegisterCommand {
+                    name,
+                    usage,
+                    permission_level,
+                } => {
+                    if self.commands.contains(&name) {
+                        warn!("Command {} is already registered", name);
+                        continue;
+                    }
+
+                    let handler = command_handler(Rc::downgrade(plugin), name.clone());
+                    self.commands
+                        .register(&name, &usage, permission_level, handler);
+                    plugin.borrow_mut().commands.push(name);
+                }
+                Action::Subscribe { kind, priority } => {
+                    let listener =
+                        events::subscribe(&mut self.events, kind, priority, Rc::downgrade(plugin));
+                    plugin.borrow_mut().listeners.push(listener);
+                }
+                Action::Schedule {
+                    task,
+                    delay,
+                    period,
+                } => {
+                    let handle = self.schedule_plugin_task(plugin, task, delay, period);
+                    plugin.borrow_mut().tasks.insert(task, handle);
+                }
+                Action::CancelTask(task) => {
+                    if let Some(handle) = plugin.borrow_mut().tasks.remove(&task) {
+                        handle.cancel();
+                    }
+                }
+                Action::SendMessage { uuid, message } => {
+                    self.send_packet(
+                        uuid,
+                        clientbound::play::ChatMessagePacket::new(
+                            Chat::new_text(&message),
+                            ChatMessageType::System,
+                            Uuid::nil(),
+                        ),
+                    );
+                }
+                Action::SendPacket {
+                    uuid,
+                    packet_id,
+                    data,
+                } => self.send_raw_packet(uuid, packet_id, data),
+                Action::Broadcast(message) => self.broadcast_message(&message),
+                Action::Kick { uuid, reason } => self.kick_player(uuid, &reason),
+                Action::Reply(message) => {
+                    if let Some(context) = context.as_mut() {
+                        context.reply(&message);
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_plugin(&self, name: &str) -> io::Result<Rc<RefCell<Plugin>>> {
+        self.plugins
+            .get(name)
+            .ok_or_else(|| invalid_input(&format!("Unknown plugin {}", name)))
+    }
+}
+
+fn command_handler(
+    plugin: Weak<RefCell<Plugin>>,
+    command: String,
+) -> impl Fn(&mut MinecraftServer, &mut CommandContext, &[&str]) -> CommandResult {
+    move |server, context, args| {
+        let plugin = match plugin.upgrade() {
+            Some(plugin) => plugin,
+            None => return Ok(()),
+        };
+        let sender = match &context.sender {
+            CommandSender::Console => None,
+            CommandSender::Player { uuid, .. } => Some(uuid.to_hyphenated().to_string()),
+        };
+        let call = CommandCall {
+            command: command.clone(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            sender,
+            sender_name: context.sender_name().to_string(),
+        };
+        let payload = serde_json::to_vec(&call).map_err(|e| CommandError::Failed(e.to_string()))?;
+
+        match server.call_plugin(&plugin, Some(context), |instance| {
+            instance.on_command(&payload)
+        }) {
+            Some(()) => Ok(()),
+            None => Err(CommandError::Failed(String::from(
+                "An internal error occurred while attempting to perform this command",
+            ))),
+        }
+    }
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
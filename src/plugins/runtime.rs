@@ -0,0 +1,423 @@
+use log::{debug, error, info, warn};
+use plugin_api::{EventKind, LogLevel, Priority, ABI_VERSION, HOST_MODULE};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::io;
+use std::path::Path;
+use uuid::Uuid;
+use wasmtime::{
+    Caller, Config, Engine, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, TypedFunc, WasmParams, WasmResults,
+};
+
+/// Instructions a plugin can run in a single call before it is stopped.
+const FUEL_PER_CALL: u64 = 10_000_000;
+const MAX_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+const MAX_STRING_LENGTH: i32 = 32767;
+/// The biggest payload a client accepts.
+const MAX_PACKET_LENGTH: i32 = 2 * 1024 * 1024;
+
+/// What a plugin asked the server for during a call, applied once it
+/// returns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    RegisterCommand {
+        name: String,
+        usage: String,
+        permission_level: u8,
+    },
+    Subscribe {
+        kind: EventKind,
+        priority: Priority,
+    },
+    Schedule {
+        task: i32,
+        delay: u32,
+        period: u32,
+    },
+    CancelTask(i32),
+    SendMessage {
+        uuid: Uuid,
+        message: String,
+    },
+    SendPacket {
+        uuid: Uuid,
+        packet_id: i32,
+        data: Vec<u8>,
+    },
+    Broadcast(String),
+    Kick {
+        uuid: Uuid,
+        reason: String,
+    },
+    Reply(String),
+}
+
+pub struct PluginState {
+    name: String,
+    limits: StoreLimits,
+    /// Commands can only be registered while the plugin is enabled.
+    enabling: bool,
+    taken_commands: HashSet<String>,
+    next_task: i32,
+    actions: Vec<Action>,
+}
+
+/// Compiles plugins and links them to the functions offered by the server.
+pub struct Runtime {
+    engine: Engine,
+    linker: Linker<PluginState>,
+}
+
+impl Runtime {
+    pub fn new() -> io::Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+
+        let engine = Engine::new(&config).map_err(to_io_error)?;
+        let mut linker = Linker::new(&engine);
+        define_host_functions(&mut linker).map_err(to_io_error)?;
+        Ok(Self { engine, linker })
+    }
+
+    /// Reads a binary or text WebAssembly module.
+    pub fn compile(&self, path: &Path) -> io::Result<Module> {
+        Module::from_file(&self.engine, path).map_err(to_io_error)
+    }
+
+    pub fn instantiate(&self, name: &str, module: &Module) -> io::Result<PluginInstance> {
+        let state = PluginState {
+            name: name.to_string(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MAX_MEMORY_SIZE)
+                .build(),
+            enabling: false,
+            taken_commands: HashSet::new(),
+            next_task: 0,
+            actions: vec![],
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.add_fuel(FUEL_PER_CALL).map_err(to_io_error)?;
+
+        let instance = self
+            .linker
+            .instantiate(&mut store, module)
+            .map_err(to_io_error)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| invalid_plugin("Plugin doesn't export its memory"))?;
+        let alloc = instance
+            .get_typed_func(&mut store, plugin_api::EXPORT_ALLOC)
+            .map_err(to_io_error)?;
+        let mut plugin = PluginInstance {
+            store,
+            instance,
+            memory,
+            alloc,
+        };
+
+        let version = plugin
+            .call::<(), i32>(plugin_api::EXPORT_ABI_VERSION, ())?
+            .ok_or_else(|| invalid_plugin("Plugin doesn't export its ABI version"))?;
+        if version != ABI_VERSION {
+            return Err(invalid_plugin(&format!(
+                "Plugin targets ABI version {}, the server is on {}",
+                version, ABI_VERSION
+            )));
+        }
+        Ok(plugin)
+    }
+}
+
+/// A plugin module running in its own sandbox.
+pub struct PluginInstance {
+    store: Store<PluginState>,
+    instance: Instance,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+}
+
+impl PluginInstance {
+    /// Commands in `taken_commands` can't be registered by the plugin.
+    pub fn enable(&mut self, taken_commands: HashSet<String>) -> io::Result<()> {
+        let state = self.store.data_mut();
+        state.enabling = true;
+        state.taken_commands = taken_commands;
+
+        let result = self.call::<(), ()>(plugin_api::EXPORT_ENABLE, ());
+        self.store.data_mut().enabling = false;
+        result.map(|_| ())
+    }
+
+    pub fn disable(&mut self) -> io::Result<()> {
+        self.call::<(), ()>(plugin_api::EXPORT_DISABLE, ())
+            .map(|_| ())
+    }
+
+    /// Returns true if the plugin cancels the event.
+    pub fn on_event(&mut self, kind: EventKind, payload: &[u8]) -> io::Result<bool> {
+        let (ptr, len) = self.write(payload)?;
+        let result = self
+            .call::<(i32, i32, i32), i32>(plugin_api::EXPORT_ON_EVENT, (kind.into(), ptr, len))?;
+
+        Ok(result == Some(plugin_api::EVENT_CANCELLED))
+    }
+
+    pub fn on_command(&mut self, payload: &[u8]) -> io::Result<()> {
+        let args = self.write(payload)?;
+        self.call::<(i32, i32), ()>(plugin_api::EXPORT_ON_COMMAND, args)
+            .map(|_| ())
+    }
+
+    pub fn on_task(&mut self, task: i32) -> io::Result<()> {
+        self.call::<i32, ()>(plugin_api::EXPORT_ON_TASK, task)
+            .map(|_| ())
+    }
+
+    pub fn take_actions(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.store.data_mut().actions)
+    }
+
+    /// Copies a payload to a buffer the plugin allocates, and now owns.
+    fn write(&mut self, payload: &[u8]) -> io::Result<(i32, i32)> {
+        let len = payload.len() as i32;
+
+        self.refuel()?;
+        let ptr = self.alloc.call(&mut self.store, len).map_err(to_io_error)?;
+        self.memory
+            .write(&mut self.store, ptr as usize, payload)
+            .map_err(|e| invalid_plugin(&e.to_string()))?;
+        Ok((ptr, len))
+    }
+
+    /// Calls an export, returning `None` if the plugin doesn't have it.
+    fn call<P, R>(&mut self, name: &str, params: P) -> io::Result<Option<R>>
+    where
+        P: WasmParams,
+        R: WasmResults,
+    {
+        let func = match self.instance.get_func(&mut self.store, name) {
+            Some(func) => func.typed::<P, R>(&self.store).map_err(to_io_error)?,
+            None => return Ok(None),
+        };
+
+        self.refuel()?;
+        func.call(&mut self.store, params)
+            .map(Some)
+            .map_err(to_io_error)
+    }
+
+    fn refuel(&mut self) -> io::Result<()> {
+        let remaining = self.store.consume_fuel(0).map_err(to_io_error)?;
+        self.store
+            .add_fuel(FUEL_PER_CALL.saturating_sub(remaining))
+            .map_err(to_io_error)
+    }
+}
+
+fn define_host_functions(linker: &mut Linker<PluginState>) -> wasmtime::Result<()> {
+    linker.func_wrap(
+        HOST_MODULE,
+        "log",
+        |mut caller: Caller<'_, PluginState>, level: i32, ptr: i32, len: i32| {
+            let message = read_string(&mut caller, ptr, len)?;
+            let name = &caller.data().name;
+
+            match LogLevel::try_from(level).map_err(wasmtime::Error::msg)? {
+                LogLevel::Error => error!("[{}] {}", name, message),
+                LogLevel::Warn => warn!("[{}] {}", name, message),
+                LogLevel::Info => info!("[{}] {}", name, message),
+                LogLevel::Debug => debug!("[{}] {}", name, message),
+            }
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "register_command",
+        |mut caller: Caller<'_, PluginState>,
+         name_ptr: i32,
+         name_len: i32,
+         usage_ptr: i32,
+         usage_len: i32,
+         permission_level: i32| {
+            let name = read_string(&mut caller, name_ptr, name_len)?.to_lowercase();
+            let usage = read_string(&mut caller, usage_ptr, usage_len)?;
+            let state = caller.data_mut();
+
+            if !state.enabling
+                || name.is_empty()
+                || name.contains(char::is_whitespace)
+                || !state.taken_commands.insert(name.clone())
+            {
+                return Ok(-1);
+            }
+            state.actions.push(Action::RegisterCommand {
+                name,
+                usage,
+                permission_level: permission_level.clamp(0, i32::from(u8::MAX)) as u8,
+            });
+            Ok(0)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "subscribe",
+        |mut caller: Caller<'_, PluginState>, kind: i32, priority: i32| {
+            let kind = EventKind::try_from(kind).map_err(wasmtime::Error::msg)?;
+            let priority = Priority::try_from(priority).map_err(wasmtime::Error::msg)?;
+
+            caller
+                .data_mut()
+                .actions
+                .push(Action::Subscribe { kind, priority });
+            Ok(0)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "schedule",
+        |mut caller: Caller<'_, PluginState>, delay: i32, period: i32| {
+            let state = caller.data_mut();
+            let task = state.next_task;
+
+            state.next_task += 1;
+            state.actions.push(Action::Schedule {
+                task,
+                delay: delay.max(0) as u32,
+                period: period.max(0) as u32,
+            });
+            task
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "cancel_task",
+        |mut caller: Caller<'_, PluginState>, task: i32| {
+            caller.data_mut().actions.push(Action::CancelTask(task));
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "send_message",
+        |mut caller: Caller<'_, PluginState>,
+         uuid_ptr: i32,
+         uuid_len: i32,
+         message_ptr: i32,
+         message_len: i32| {
+            let uuid = read_uuid(&mut caller, uuid_ptr, uuid_len)?;
+            let message = read_string(&mut caller, message_ptr, message_len)?;
+
+            caller
+                .data_mut()
+                .actions
+                .push(Action::SendMessage { uuid, message });
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "send_packet",
+        |mut caller: Caller<'_, PluginState>,
+         uuid_ptr: i32,
+         uuid_len: i32,
+         packet_id: i32,
+         data_ptr: i32,
+         data_len: i32| {
+            let uuid = read_uuid(&mut caller, uuid_ptr, uuid_len)?;
+            let data = read_bytes(&mut caller, data_ptr, data_len, MAX_PACKET_LENGTH)?;
+
+            caller.data_mut().actions.push(Action::SendPacket {
+                uuid,
+                packet_id,
+                data,
+            });
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "broadcast",
+        |mut caller: Caller<'_, PluginState>, ptr: i32, len: i32| {
+            let message = read_string(&mut caller, ptr, len)?;
+
+            caller.data_mut().actions.push(Action::Broadcast(message));
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "kick",
+        |mut caller: Caller<'_, PluginState>,
+         uuid_ptr: i32,
+         uuid_len: i32,
+         reason_ptr: i32,
+         reason_len: i32| {
+            let uuid = read_uuid(&mut caller, uuid_ptr, uuid_len)?;
+            let reason = read_string(&mut caller, reason_ptr, reason_len)?;
+
+            caller
+                .data_mut()
+                .actions
+                .push(Action::Kick { uuid, reason });
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "reply",
+        |mut caller: Caller<'_, PluginState>, ptr: i32, len: i32| {
+            let message = read_string(&mut caller, ptr, len)?;
+
+            caller.data_mut().actions.push(Action::Reply(message));
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
+
+/// Failing to read traps, which stops the plugin call.
+fn read_string(
+    caller: &mut Caller<'_, PluginState>,
+    ptr: i32,
+    len: i32,
+) -> wasmtime::Result<String> {
+    Ok(String::from_utf8(read_bytes(
+        caller,
+        ptr,
+        len,
+        MAX_STRING_LENGTH,
+    )?)?)
+}
+
+fn read_bytes(
+    caller: &mut Caller<'_, PluginState>,
+    ptr: i32,
+    len: i32,
+    max_len: i32,
+) -> wasmtime::Result<Vec<u8>> {
+    if !(0..=max_len).contains(&len) {
+        return Err(wasmtime::Error::msg(format!("Invalid length {}", len)));
+    }
+
+    let memory = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+        .ok_or_else(|| wasmtime::Error::msg("Plugin doesn't export its memory"))?;
+    let mut buffer = vec![0; len as usize];
+    memory.read(&caller, ptr as u32 as usize, &mut buffer)?;
+    Ok(buffer)
+}
+
+fn read_uuid(caller: &mut Caller<'_, PluginState>, ptr: i32, len: i32) -> wasmtime::Result<Uuid> {
+    Ok(Uuid::parse_str(&read_string(caller, ptr, len)?)?)
+}
+
+fn to_io_error(error: wasmtime::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, format!("{:#}", error))
+}
+
+fn invalid_plugin(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
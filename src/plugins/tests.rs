@@ -0,0 +1,160 @@
+use common::chat::Chat;
+use network::clientbound::ClientboundPacket;
+use std::cell::Cell;
+use std::path::Path;
+use std::rc::Rc;
+use uuid::Uuid;
+
+use super::PluginManager;
+use crate::commands::{self, CommandContext, CommandSender};
+use crate::events::{EventPriority, PlayerChat, PlayerJoin};
+use crate::server::MinecraftServer;
+
+/// A server running the sample plugins of `tests/plugins`.
+fn server() -> MinecraftServer<'static, 'static> {
+    let mut server = MinecraftServer::for_tests();
+    server.plugins =
+        PluginManager::new(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/plugins"));
+    server.load_plugins().unwrap();
+    server
+}
+
+fn broadcasts(server: &mut MinecraftServer) -> Vec<Chat> {
+    server
+        .take_outgoing_packets()
+        .into_iter()
+        .filter_map(|packet| match packet {
+            (None, ClientboundPacket::ChatMessage(packet)) => Some(packet.message),
+            _ => None,
+        })
+        .collect()
+}
+
+fn chat(server: &mut MinecraftServer, message: &str) -> PlayerChat {
+    server.post_event(PlayerChat {
+        uuid: Uuid::nil(),
+        username: String::from("Steve"),
+        message: message.to_string(),
+        cancelled: false,
+    })
+}
+
+#[test]
+fn broken_plugins_are_left_disabled() {
+    let server = server();
+    let mut plugins = server.plugins.get_plugins();
+    plugins.sort();
+
+    assert_eq!(
+        plugins,
+        vec![
+            (String::from("chat_filter"), true),
+            (String::from("greeter"), true),
+            (String::from("outdated"), false),
+            (String::from("runaway"), false),
+            (String::from("ticker"), true),
+        ]
+    );
+}
+
+#[test]
+fn runaway_plugin_runs_out_of_fuel() {
+    let mut server = server();
+    let error = server.enable_plugin("runaway").unwrap_err();
+
+    assert!(error.to_string().contains("fuel"), "{}", error);
+    assert!(!server.plugins.get("runaway").unwrap().borrow().is_enabled());
+}
+
+#[test]
+fn outdated_plugin_is_rejected() {
+    let mut server = server();
+    let error = server.enable_plugin("outdated").unwrap_err();
+
+    assert!(error.to_string().contains("ABI version 0"), "{}", error);
+}
+
+#[test]
+fn greeter_greets_joining_players() {
+    let mut server = server();
+    server.post_event(PlayerJoin {
+        uuid: Uuid::nil(),
+        username: String::from("Steve"),
+        protocol: 754,
+        join_message: None,
+    });
+
+    assert_eq!(
+        broadcasts(&mut server),
+        vec![Chat::new_text("Welcome to the server!")]
+    );
+}
+
+#[test]
+fn greeter_command_is_registered_while_enabled() {
+    let mut server = server();
+    let mut context = CommandContext {
+        sender: CommandSender::Console,
+        streams: &mut [],
+    };
+
+    commands::dispatch(&mut server, &mut context, "greet");
+    assert_eq!(
+        broadcasts(&mut server),
+        vec![Chat::new_text("Hello from the greeter plugin!")]
+    );
+
+    server.disable_plugin("greeter").unwrap();
+    assert!(!server.commands.contains("greet"));
+    commands::dispatch(&mut server, &mut context, "greet");
+    assert!(broadcasts(&mut server).is_empty());
+
+    server.enable_plugin("greeter").unwrap();
+    assert!(server.commands.contains("greet"));
+}
+
+#[test]
+fn chat_filter_cancels_spam() {
+    let mut server = server();
+    let seen_cancelled = Rc::new(Cell::new(None));
+    let seen = seen_cancelled.clone();
+
+    server
+        .events
+        .subscribe(EventPriority::Monitor, move |_, event: &mut PlayerChat| {
+            seen.set(Some(event.cancelled));
+        });
+
+    assert!(chat(&mut server, "buy cheap spam").cancelled);
+    assert_eq!(seen_cancelled.get(), Some(true));
+    assert!(!chat(&mut server, "hello").cancelled);
+    assert_eq!(seen_cancelled.get(), Some(false));
+
+    server.disable_plugin("chat_filter").unwrap();
+    assert!(!chat(&mut server, "buy cheap spam").cancelled);
+
+    server.reload_plugin("chat_filter").unwrap();
+    assert!(chat(&mut server, "buy cheap spam").cancelled);
+}
+
+#[test]
+fn ticker_runs_every_other_tick_until_cancelled() {
+    let mut server = server();
+    let mut runs = vec![];
+
+    for tick in 0..10 {
+        server.run_tasks();
+        if !broadcasts(&mut server).is_empty() {
+            runs.push(tick);
+        }
+    }
+
+    assert_eq!(runs, vec![0, 2, 4]);
+    assert!(server
+        .plugins
+        .get("ticker")
+        .unwrap()
+        .borrow()
+        .tasks
+        .is_empty());
+}
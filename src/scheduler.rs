@@ -0,0 +1,359 @@
+use log::error;
+use std::any::Any;
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::server::MinecraftServer;
+
+/// Ticks per second the server aims for.
+pub const TICKS_PER_SECOND: u64 = 20;
+
+const MAX_WORKERS: usize = 4;
+
+type SyncTask = Box<dyn FnMut(&mut MinecraftServer)>;
+type AsyncCallback = Box<dyn FnOnce(&mut MinecraftServer, Box<dyn Any + Send>)>;
+type Job = Box<dyn FnOnce() + Send>;
+/// Results of async jobs, `None` if the job panicked.
+type AsyncResult = (u64, Option<Box<dyn Any + Send>>);
+
+/// Stops a scheduled task from running again, from anywhere on the tick
+/// thread, including the task itself.
+#[derive(Debug, Clone, Default)]
+pub struct TaskHandle {
+    cancelled: Rc<Cell<bool>>,
+}
+
+impl TaskHandle {
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+}
+
+struct ScheduledTask {
+    period: Option<u64>,
+    handle: TaskHandle,
+    run: SyncTask,
+}
+
+/// Threads running async jobs, started with the first one.
+struct WorkerPool {
+    jobs: Sender<Job>,
+}
+
+impl WorkerPool {
+    fn new(size: usize) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for i in 0..size {
+            let receiver = receiver.clone();
+
+            thread::Builder::new()
+                .name(format!("Async worker #{}", i))
+                .spawn(move || loop {
+                    // The lock is released before the job runs
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+                .expect("Failed to start an async worker");
+        }
+        Self { jobs }
+    }
+}
+
+/// Runs tasks on the tick thread after a number of ticks, and jobs on a
+/// worker pool whose results are handed back to the tick thread.
+pub struct Scheduler {
+    tick: u64,
+    next_id: u64,
+    queue: BinaryHeap<Reverse<(u64, u64)>>,
+    tasks: HashMap<u64, ScheduledTask>,
+    workers: Option<WorkerPool>,
+    callbacks: HashMap<u64, (TaskHandle, AsyncCallback)>,
+    results: Receiver<AsyncResult>,
+    results_sender: Sender<AsyncResult>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        let (results_sender, results) = mpsc::channel();
+
+        Self {
+            tick: 0,
+            next_id: 0,
+            queue: BinaryHeap::new(),
+            tasks: HashMap::new(),
+            workers: None,
+            callbacks: HashMap::new(),
+            results,
+            results_sender,
+        }
+    }
+
+    /// Ticks run since the server started.
+    pub fn get_current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Runs a task once, `delay` ticks from now. Tasks run on the next tick
+    /// at the earliest.
+    pub fn run_later<F>(&mut self, delay: u64, task: F) -> TaskHandle
+    where
+        F: FnOnce(&mut MinecraftServer) + 'static,
+    {
+        let mut task = Some(task);
+
+        self.schedule(delay, None, move |server| {
+            if let Some(task) = task.take() {
+                task(server);
+            }
+        })
+    }
+
+    /// Runs a task `delay` ticks from now, then every `period` ticks until it
+    /// is cancelled.
+    pub fn run_repeating<F>(&mut self, delay: u64, period: u64, task: F) -> TaskHandle
+    where
+        F: FnMut(&mut MinecraftServer) + 'static,
+    {
+        self.schedule(delay, Some(period.max(1)), task)
+    }
+
+    /// Runs `job` on a worker thread, then `then` with its result on the
+    /// tick thread, unless the handle is cancelled meanwhile.
+    pub fn run_async<T, F, G>(&mut self, job: F, then: G) -> TaskHandle
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+        G: FnOnce(&mut MinecraftServer, T) + 'static,
+    {
+        let id = self.next_id();
+        let handle = TaskHandle::default();
+
+        self.callbacks.insert(
+            id,
+            (
+                handle.clone(),
+                Box::new(move |server, result| {
+                    if let Ok(result) = result.downcast::<T>() {
+                        then(server, *result);
+                    }
+                }),
+            ),
+        );
+
+        let results = self.results_sender.clone();
+        let workers = self.workers.get_or_insert_with(|| {
+            let size = thread::available_parallelism().map_or(1, |n| n.get());
+            WorkerPool::new(size.min(MAX_WORKERS))
+        });
+        let _ = workers.jobs.send(Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(job))
+                .ok()
+                .map(|result| Box::new(result) as Box<dyn Any + Send>);
+            let _ = results.send((id, result));
+        }));
+        handle
+    }
+
+    /// Number of sync tasks which haven't run for the last time.
+    pub fn get_pending_tasks(&self) -> usize {
+        self.tasks
+            .values()
+            .filter(|task| !task.handle.is_cancelled())
+            .count()
+    }
+
+    fn schedule<F>(&mut self, delay: u64, period: Option<u64>, task: F) -> TaskHandle
+    where
+        F: FnMut(&mut MinecraftServer) + 'static,
+    {
+        let id = self.next_id();
+        let handle = TaskHandle::default();
+
+        self.tasks.insert(
+            id,
+            ScheduledTask {
+                period,
+                handle: handle.clone(),
+                run: Box::new(task),
+            },
+        );
+        self.queue.push(Reverse((self.tick + delay.max(1), id)));
+        handle
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Takes out the next task due this tick, dropping cancelled ones.
+    fn pop_due(&mut self) -> Option<(u64, ScheduledTask)> {
+        while let Some(Reverse((due, id))) = self.queue.peek().copied() {
+            if due > self.tick {
+                return None;
+            }
+
+            self.queue.pop();
+            if let Some(task) = self.tasks.remove(&id) {
+                if !task.handle.is_cancelled() {
+                    return Some((id, task));
+                }
+            }
+        }
+        None
+    }
+
+    fn reschedule(&mut self, id: u64, task: ScheduledTask) {
+        if let Some(period) = task.period {
+            if !task.handle.is_cancelled() {
+                self.queue.push(Reverse((self.tick + period, id)));
+                self.tasks.insert(id, task);
+            }
+        }
+    }
+}
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Starts a new tick, running the tasks due and the callbacks of the
+    /// async jobs which are done.
+    pub(crate) fn run_tasks(&mut self) {
+        self.scheduler.tick += 1;
+
+        while let Some((id, mut task)) = self.scheduler.pop_due() {
+            (task.run)(self);
+            self.scheduler.reschedule(id, task);
+        }
+
+        let results: Vec<AsyncResult> = self.scheduler.results.try_iter().collect();
+        for (id, result) in results {
+            let (handle, callback) = match self.scheduler.callbacks.remove(&id) {
+                Some(callback) => callback,
+                None => continue,
+            };
+
+            match result {
+                Some(result) if !handle.is_cancelled() => callback(self, result),
+                Some(_) => {}
+                None => error!("An async task panicked"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    use super::TaskHandle;
+    use crate::server::MinecraftServer;
+
+    /// Ticks at which the tasks pushed to `runs` ran.
+    fn run(server: &mut MinecraftServer, ticks: u64, runs: &Rc<RefCell<Vec<u64>>>) -> Vec<u64> {
+        for _ in 0..ticks {
+            server.run_tasks();
+        }
+        runs.borrow_mut().drain(..).collect()
+    }
+
+    #[test]
+    fn delayed_task_runs_once() {
+        let mut server = MinecraftServer::for_tests();
+        let runs = Rc::new(RefCell::new(vec![]));
+        let task_runs = runs.clone();
+
+        server.scheduler.run_later(3, move |server| {
+            task_runs
+                .borrow_mut()
+                .push(server.scheduler.get_current_tick());
+        });
+
+        assert_eq!(run(&mut server, 10, &runs), vec![3]);
+        assert_eq!(server.scheduler.get_pending_tasks(), 0);
+    }
+
+    #[test]
+    fn repeating_task_cancels_itself() {
+        let mut server = MinecraftServer::for_tests();
+        let runs = Rc::new(RefCell::new(vec![]));
+        let task_runs = runs.clone();
+        let handle = Rc::new(RefCell::new(None::<TaskHandle>));
+        let task_handle = handle.clone();
+
+        *handle.borrow_mut() = Some(server.scheduler.run_repeating(0, 5, move |server| {
+            let mut runs = task_runs.borrow_mut();
+            runs.push(server.scheduler.get_current_tick());
+            if runs.len() == 3 {
+                if let Some(handle) = task_handle.borrow().as_ref() {
+                    handle.cancel();
+                }
+            }
+        }));
+
+        assert_eq!(run(&mut server, 20, &runs), vec![1, 6, 11]);
+        assert_eq!(server.scheduler.get_pending_tasks(), 0);
+    }
+
+    #[test]
+    fn cancelled_task_never_runs() {
+        let mut server = MinecraftServer::for_tests();
+        let runs = Rc::new(RefCell::new(vec![]));
+        let task_runs = runs.clone();
+
+        let handle = server.scheduler.run_later(2, move |_| {
+            task_runs.borrow_mut().push(0);
+        });
+        server.run_tasks();
+        handle.cancel();
+
+        assert!(run(&mut server, 5, &runs).is_empty());
+    }
+
+    #[test]
+    fn async_results_come_back_to_the_tick_thread() {
+        let mut server = MinecraftServer::for_tests();
+        let tick_thread = std::thread::current().id();
+        let result = Rc::new(RefCell::new(None));
+        let task_result = result.clone();
+
+        server.scheduler.run_async(
+            || (1..=10).sum::<u32>(),
+            move |_, sum| {
+                assert_eq!(std::thread::current().id(), tick_thread);
+                *task_result.borrow_mut() = Some(sum);
+            },
+        );
+        server
+            .scheduler
+            .run_async(|| panic!("Failed job"), |_, ()| {});
+
+        let start = Instant::now();
+        while result.borrow().is_none() && start.elapsed() < Duration::from_secs(5) {
+            server.run_tasks();
+        }
+        assert_eq!(*result.borrow(), Some(55));
+    }
+}
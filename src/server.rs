@@ -1,10 +1,65 @@
+use common::chat::{Chat, ChatMessageType};
 use common::difficulty::Difficulty;
 use common::dimension::Dimension;
-use entity::ECSWorld;
+use common::location::Location;
+use entity::combat::Combat;
+use entity::components::{OnGround, Player, Position, Rotation};
+use entity::metadata::Metadata;
+use entity::{ECSWorld, Entity};
+use log::info;
+use network::clientbound;
+use network::clientbound::ClientboundPacket;
+use network::packet::State;
+use network::stream::Stream;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::mpsc::Receiver;
+use uuid::Uuid;
 use world::world::World;
 
+use crate::access::AccessLists;
+use crate::channels::ChannelRegistry;
+use crate::commands;
+use crate::commands::{CommandContext, CommandRegistry, CommandSender};
 use crate::config::Config;
+use crate::events::{EventBus, ServerTick};
+use crate::permissions::{Permissions, MAX_OP_LEVEL};
+use crate::player_list::{PlayerList, LATENCY_UPDATE_INTERVAL};
+use crate::plugins::PluginManager;
+use crate::scheduler::Scheduler;
+
+/// Directory plugins are loaded from.
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// Entity statuses 24 to 28 set the op level of the player, which decides
+/// the commands offered by the client.
+const OP_LEVEL_STATUS: i8 = 24;
+
+/// Where players appear when they join.
+pub const SPAWN_LOCATION: Location = Location {
+    x: 0.5,
+    y: 64.0,
+    z: 0.5,
+    yaw: 0.0,
+    pitch: 0.0,
+};
+
+/// Packets and kicks waiting for the end of the tick, when every client can
+/// be reached.
+enum Outgoing {
+    Packet(Uuid, ClientboundPacket),
+    Broadcast(ClientboundPacket),
+    /// A packet id and its payload, already serialized for the player.
+    RawPacket(Uuid, i32, Vec<u8>),
+    Kick(Uuid, String),
+    /// Kicks a connection rather than a player, who may have several while
+    /// an old one times out.
+    KickConnection(u64, String),
+}
 
 pub struct MinecraftServer<'a, 'b> {
     pub config: Config,
@@ -12,18 +67,374 @@ pub struct MinecraftServer<'a, 'b> {
     pub difficulty_locked: bool,
     pub ecs: ECSWorld<'a, 'b>,
     pub worlds: HashMap<Dimension, World>,
+    pub channels: ChannelRegistry,
+    pub commands: CommandRegistry,
+    pub access: Rc<RefCell<AccessLists>>,
+    pub permissions: Rc<RefCell<Permissions>>,
+    pub events: EventBus,
+    pub plugins: PluginManager,
+    pub scheduler: Scheduler,
+    pub player_list: PlayerList,
+    console: Option<Receiver<String>>,
+    pending_commands: Vec<(CommandSender, String)>,
+    outgoing: Vec<Outgoing>,
+    pending_teleports: HashMap<Uuid, i32>,
+    /// Action number of the last click rejected for each player, until
+    /// their client acknowledges it.
+    pub(crate) rejected_clicks: HashMap<Uuid, i16>,
+    next_teleport_id: i32,
 }
 
 impl<'a, 'b> MinecraftServer<'a, 'b> {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, access: AccessLists, permissions: Permissions) -> Self {
+        let mut commands = CommandRegistry::new();
+        commands::access::register(&mut commands);
+        commands::permissions::register(&mut commands);
+        commands::plugins::register(&mut commands);
+        commands::gamemode::register(&mut commands);
+        let mut ecs = ECSWorld::new();
+        ecs.set_ai_seed(rand::random());
+        ecs.get_terrain_mut().spawning = config.spawning.to_settings();
+
         Self {
             config,
             difficulty: Difficulty::Normal,
             difficulty_locked: false,
-            ecs: ECSWorld::new(),
+            ecs,
             worlds: HashMap::new(),
+            channels: ChannelRegistry::new(),
+            commands,
+            access: Rc::new(RefCell::new(access)),
+            permissions: Rc::new(RefCell::new(permissions)),
+            events: EventBus::new(),
+            plugins: PluginManager::new(Path::new(PLUGINS_DIR)),
+            scheduler: Scheduler::new(),
+            player_list: PlayerList::new(),
+            console: None,
+            pending_commands: vec![],
+            outgoing: vec![],
+            pending_teleports: HashMap::new(),
+            rejected_clicks: HashMap::new(),
+            next_teleport_id: 0,
+        }
+    }
+
+    /// A server with the default config and empty access and permission
+    /// lists.
+    #[cfg(test)]
+    pub(crate) fn for_tests() -> Self {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::{env, fs, process};
+
+        static SERVERS: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = env::temp_dir().join(format!(
+            "minecrate-server-{}-{}",
+            process::id(),
+            SERVERS.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let access = AccessLists::load(&dir, false).unwrap();
+        let permissions = Permissions::load(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        Self::new(Config::default(), access, permissions)
+    }
+
+    /// Runs the lines received on `console` as commands.
+    pub fn set_console(&mut self, console: Receiver<String>) {
+        self.console = Some(console);
+    }
+
+    /// Queues a command line, without its leading slash, to run on the
+    /// next tick.
+    pub fn queue_command(&mut self, sender: CommandSender, line: &str) {
+        self.pending_commands.push((sender, line.to_string()));
+    }
+
+    /// Tells the client its op level, to be called again when it changes.
+    pub fn send_op_level(&self, stream: &mut Stream) -> io::Result<()> {
+        let player = match self.ecs.get_player(stream.get_uuid()) {
+            Some(player) => player,
+            None => return Ok(()),
+        };
+        let level = self
+            .permissions
+            .borrow()
+            .get_op_level(stream.get_uuid())
+            .min(MAX_OP_LEVEL);
+
+        stream.send_packet(&clientbound::play::EntityStatusPacket::new(
+            entity::get_network_id(player),
+            OP_LEVEL_STATUS + level as i8,
+        ))
+    }
+
+    /// Sends a packet to a player in the world at the end of the tick.
+    pub fn send_packet(&mut self, uuid: Uuid, packet: ClientboundPacket) {
+        self.outgoing.push(Outgoing::Packet(uuid, packet));
+    }
+
+    /// Sends a serialized play packet to a player at the end of the tick.
+    pub fn send_raw_packet(&mut self, uuid: Uuid, packet_id: i32, data: Vec<u8>) {
+        self.outgoing
+            .push(Outgoing::RawPacket(uuid, packet_id, data));
+    }
+
+    /// Sends a packet to every player in the world at the end of the tick.
+    pub fn broadcast_packet(&mut self, packet: ClientboundPacket) {
+        self.outgoing.push(Outgoing::Broadcast(packet));
+    }
+
+    /// Logs a message and shows it in the chat of every player.
+    pub fn broadcast_message(&mut self, message: &str) {
+        info!("{}", message);
+        self.broadcast_packet(clientbound::play::ChatMessagePacket::new(
+            Chat::new_text(message),
+            ChatMessageType::System,
+            Uuid::nil(),
+        ));
+    }
+
+    /// Disconnects a player at the end of the tick.
+    pub fn kick_player(&mut self, uuid: Uuid, reason: &str) {
+        self.outgoing.push(Outgoing::Kick(uuid, reason.to_string()));
+    }
+
+    /// Creates the entity of a player who just logged in, at the spawn. A
+    /// session the player still has is kicked and replaced.
+    pub fn create_player(&mut self, stream: &Stream) -> Entity {
+        if let Some(entity) = self.get_player_entity(stream.get_uuid()) {
+            if let Some(player) = self.ecs.get_component::<Player>(entity) {
+                self.outgoing.push(Outgoing::KickConnection(
+                    player.connection,
+                    String::from("You logged in from another location"),
+                ));
+            }
+            self.remove_player_state(stream.get_uuid());
+        }
+
+        let player = Player::new(
+            *stream.get_uuid(),
+            stream.get_username(),
+            self.config.gamemode,
+            stream.get_id(),
+        );
+
+        self.ecs.create_player(
+            player,
+            Position::new(SPAWN_LOCATION.x, SPAWN_LOCATION.y, SPAWN_LOCATION.z),
+            Rotation::new(SPAWN_LOCATION.yaw, SPAWN_LOCATION.pitch),
+        )
+    }
+
+    pub fn get_player_entity(&self, uuid: &Uuid) -> Option<Entity> {
+        self.ecs.get_player(uuid)
+    }
+
+    pub fn get_player_location(&self, uuid: &Uuid) -> Option<Location> {
+        let entity = self.ecs.get_player(uuid)?;
+        let position = self.ecs.get_component::<Position>(entity)?;
+        let rotation = self.ecs.get_component::<Rotation>(entity)?;
+
+        Some(Location::new(
+            position.x,
+            position.y,
+            position.z,
+            rotation.yaw,
+            rotation.pitch,
+        ))
+    }
+
+    /// Records a move of the player, reported by its client.
+    pub fn set_player_location(&mut self, uuid: &Uuid, location: Location, on_ground: bool) {
+        if let Some(entity) = self.ecs.get_player(uuid) {
+            // Attacks made while falling are critical hits
+            let falling = self
+                .ecs
+                .get_component::<Position>(entity)
+                .is_some_and(|from| !on_ground && location.y < from.y);
+            if let Some(mut combat) = self.ecs.get_component::<Combat>(entity) {
+                combat.set_falling(falling);
+                self.ecs.set_component(entity, combat);
+            }
+
+            self.move_entity(entity, location);
+            self.ecs.set_component(entity, OnGround(on_ground));
         }
     }
 
-    pub fn tick(&mut self) {}
+    /// Changes the metadata of a player, sent to those around on the next
+    /// tick.
+    pub fn update_player_metadata<F>(&mut self, uuid: &Uuid, update: F)
+    where
+        F: FnOnce(&mut Metadata),
+    {
+        let entity = match self.ecs.get_player(uuid) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        if let Some(mut metadata) = self.ecs.get_component::<Metadata>(entity) {
+            update(&mut metadata);
+            self.ecs.set_component(entity, metadata);
+        }
+    }
+
+    /// Tells whether the player of a connection is still playing through
+    /// it, rather than through a newer session.
+    pub fn is_current_session(&self, stream: &Stream) -> bool {
+        self.get_player_entity(stream.get_uuid())
+            .and_then(|entity| self.ecs.get_component::<Player>(entity))
+            .is_some_and(|player| player.connection == stream.get_id())
+    }
+
+    /// Removes a player who disconnected from the world and the tab list,
+    /// unless they already logged in again through another connection.
+    pub fn remove_player(&mut self, stream: &Stream) {
+        if self.is_current_session(stream) {
+            self.remove_player_state(stream.get_uuid());
+            self.remove_from_player_list(stream.get_uuid());
+        }
+    }
+
+    fn remove_player_state(&mut self, uuid: &Uuid) {
+        if let Some(entity) = self.ecs.get_player(uuid) {
+            self.ecs.remove_entity(entity);
+        }
+        self.pending_teleports.remove(uuid);
+        self.rejected_clicks.remove(uuid);
+    }
+
+    /// Moves the player, whose movements are ignored until the client
+    /// confirms the teleport.
+    pub fn teleport(&mut self, stream: &mut Stream, location: Location) -> io::Result<()> {
+        let uuid = *stream.get_uuid();
+        let teleport_id = self.next_teleport_id;
+
+        self.next_teleport_id = self.next_teleport_id.wrapping_add(1);
+        if let Some(entity) = self.ecs.get_player(&uuid) {
+            self.move_entity(entity, location);
+        }
+        self.pending_teleports.insert(uuid, teleport_id);
+
+        stream.send_packet(&clientbound::play::PlayerPositionAndLookPacket::new(
+            location.x,
+            location.y,
+            location.z,
+            location.yaw,
+            location.pitch,
+            0,
+            teleport_id,
+        ))
+    }
+
+    pub fn confirm_teleport(&mut self, uuid: &Uuid, teleport_id: i32) {
+        if self.pending_teleports.get(uuid) == Some(&teleport_id) {
+            self.pending_teleports.remove(uuid);
+        }
+    }
+
+    pub fn is_teleporting(&self, uuid: &Uuid) -> bool {
+        self.pending_teleports.contains_key(uuid)
+    }
+
+    pub fn tick(&mut self, streams: &mut [Stream]) {
+        self.run_commands(streams);
+        self.run_tasks();
+        self.ecs.tick();
+        self.send_tracker_updates();
+        self.send_combat_events();
+        self.send_inventory_changes();
+
+        let tick = self.scheduler.get_current_tick();
+        if tick.is_multiple_of(LATENCY_UPDATE_INTERVAL) {
+            self.update_latencies(streams);
+        }
+        self.post_event(ServerTick { tick });
+
+        self.flush_outgoing(streams);
+    }
+
+    /// Packets waiting for the end of the tick, `None` standing for every
+    /// player.
+    #[cfg(test)]
+    pub(crate) fn take_outgoing_packets(&mut self) -> Vec<(Option<Uuid>, ClientboundPacket)> {
+        mem::take(&mut self.outgoing)
+            .into_iter()
+            .filter_map(|outgoing| match outgoing {
+                Outgoing::Packet(uuid, packet) => Some((Some(uuid), packet)),
+                Outgoing::Broadcast(packet) => Some((None, packet)),
+                Outgoing::RawPacket(..) | Outgoing::Kick(..) | Outgoing::KickConnection(..) => None,
+            })
+            .collect()
+    }
+
+    fn move_entity(&mut self, entity: Entity, location: Location) {
+        self.ecs
+            .set_component(entity, Position::new(location.x, location.y, location.z));
+        self.ecs
+            .set_component(entity, Rotation::new(location.yaw, location.pitch));
+    }
+
+    fn flush_outgoing(&mut self, streams: &mut [Stream]) {
+        let mut players: Vec<&mut Stream> = streams
+            .iter_mut()
+            .filter(|stream| *stream.get_state() == State::Play && !stream.is_kicked())
+            .collect();
+
+        for outgoing in mem::take(&mut self.outgoing) {
+            // Connections kicked along the way get nothing more
+            let mut players = players.iter_mut().filter(|s| !s.is_kicked());
+
+            // Failed sends show up as errors when the stream is next read
+            match outgoing {
+                Outgoing::Packet(uuid, packet) => {
+                    if let Some(stream) = players.find(|s| *s.get_uuid() == uuid) {
+                        let _ = stream.send_packet(&packet);
+                    }
+                }
+                Outgoing::Broadcast(packet) => {
+                    for stream in players {
+                        let _ = stream.send_packet(&packet);
+                    }
+                }
+                Outgoing::RawPacket(uuid, packet_id, data) => {
+                    if let Some(stream) = players.find(|s| *s.get_uuid() == uuid) {
+                        let _ = stream.send_raw_packet(packet_id, &data);
+                    }
+                }
+                Outgoing::Kick(uuid, reason) => {
+                    if let Some(stream) = players.find(|s| *s.get_uuid() == uuid) {
+                        stream.kick(&reason);
+                    }
+                }
+                Outgoing::KickConnection(id, reason) => {
+                    if let Some(stream) = players.find(|s| s.get_id() == id) {
+                        stream.kick(&reason);
+                    }
+                }
+            }
+        }
+    }
+
+    fn run_commands(&mut self, streams: &mut [Stream]) {
+        if let Some(console) = &self.console {
+            for line in console.try_iter() {
+                self.pending_commands.push((CommandSender::Console, line));
+            }
+        }
+        if self.pending_commands.is_empty() {
+            return;
+        }
+
+        for (sender, line) in mem::take(&mut self.pending_commands) {
+            if let CommandSender::Player { username, .. } = &sender {
+                info!("{} issued server command: /{}", username, line);
+            }
+
+            let mut context = CommandContext { sender, streams };
+            commands::dispatch(self, &mut context, &line);
+        }
+    }
 }
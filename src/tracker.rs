@@ -0,0 +1,129 @@
+use entity::tracker::{EntityKind, TrackerUpdate};
+use network::clientbound;
+use network::clientbound::ClientboundPacket;
+
+use crate::server::MinecraftServer;
+
+/// Velocities are sent in 1/8000 of a block per tick.
+const VELOCITY_SCALE: f64 = 8000.0;
+
+impl<'a, 'b> MinecraftServer<'a, 'b> {
+    /// Sends the players what the entity tracker saw change around them.
+    pub(crate) fn send_tracker_updates(&mut self) {
+        for (uuid, update) in self.ecs.take_tracker_updates() {
+            self.send_packet(uuid, to_packet(update));
+        }
+    }
+}
+
+fn to_packet(update: TrackerUpdate) -> ClientboundPacket {
+    match update {
+        TrackerUpdate::Spawn {
+            id,
+            uuid,
+            kind,
+            position,
+            rotation,
+            velocity,
+        } => {
+            let velocity = (
+                to_velocity(velocity.x),
+                to_velocity(velocity.y),
+                to_velocity(velocity.z),
+            );
+
+            match kind {
+                EntityKind::Player => clientbound::play::SpawnPlayerPacket::new(
+                    id,
+                    uuid,
+                    position.x,
+                    position.y,
+                    position.z,
+                    rotation.yaw,
+                    rotation.pitch,
+                ),
+                EntityKind::Living(entity_type) => clientbound::play::SpawnLivingEntityPacket::new(
+                    id,
+                    uuid,
+                    entity_type,
+                    position.x,
+                    position.y,
+                    position.z,
+                    rotation.yaw,
+                    rotation.pitch,
+                    rotation.yaw,
+                    velocity,
+                ),
+                EntityKind::Object(entity_type, data) => clientbound::play::SpawnEntityPacket::new(
+                    id,
+                    uuid,
+                    entity_type,
+                    position.x,
+                    position.y,
+                    position.z,
+                    rotation.pitch,
+                    rotation.yaw,
+                    data,
+                    velocity,
+                ),
+            }
+        }
+        TrackerUpdate::Destroy(ids) => clientbound::play::DestroyEntitiesPacket::new(ids),
+        TrackerUpdate::Move {
+            id,
+            delta,
+            on_ground,
+        } => clientbound::play::EntityPositionPacket::new(id, delta, on_ground),
+        TrackerUpdate::MoveAndRotate {
+            id,
+            delta,
+            rotation,
+            on_ground,
+        } => clientbound::play::EntityPositionAndRotationPacket::new(
+            id,
+            delta,
+            rotation.yaw,
+            rotation.pitch,
+            on_ground,
+        ),
+        TrackerUpdate::Rotate {
+            id,
+            rotation,
+            on_ground,
+        } => clientbound::play::EntityRotationPacket::new(
+            id,
+            rotation.yaw,
+            rotation.pitch,
+            on_ground,
+        ),
+        TrackerUpdate::HeadLook { id, yaw } => {
+            clientbound::play::EntityHeadLookPacket::new(id, yaw)
+        }
+        TrackerUpdate::Teleport {
+            id,
+            position,
+            rotation,
+            on_ground,
+        } => clientbound::play::EntityTeleportPacket::new(
+            id,
+            position.x,
+            position.y,
+            position.z,
+            rotation.yaw,
+            rotation.pitch,
+            on_ground,
+        ),
+        TrackerUpdate::Collect {
+            id,
+            collector,
+            count,
+        } => clientbound::play::CollectItemPacket::new(id, collector, i32::from(count)),
+        TrackerUpdate::Metadata { id, metadata } => {
+            clientbound::play::EntityMetadataPacket::new(id, metadata)
+        }
+    }
+}
+
+pub(crate) fn to_velocity(blocks_per_tick: f64) -> i16 {
+    (blocks_per_tick * VELOCITY_SCALE).clamp(f64::from(i16::MIN), f64::from(i16::MAX)) as i16
+}
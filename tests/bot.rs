@@ -0,0 +1,136 @@
+use network::client::Client;
+use network::clientbound::ClientboundPacket;
+use network::packet::State;
+use std::env;
+use std::fs;
+use std::io;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{self, Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Keep-alives are sent every 15 seconds.
+const KEEP_ALIVE_WAIT: Duration = Duration::from_secs(20);
+
+/// The server binary running in its own directory, stopped when dropped.
+struct Server {
+    child: Child,
+    dir: PathBuf,
+    port: u16,
+}
+
+impl Server {
+    fn start() -> Self {
+        let dir = env::temp_dir().join(format!("minecrate-e2e-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // The port is free once the listener is dropped
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        fs::write(
+            dir.join("server.toml"),
+            format!(
+                r#"
+                host = "127.0.0.1"
+                port = {}
+                max_players = 20
+                motd = "Minecrate test server"
+                online_mode = false
+                [throttle]
+                login_delay = 0
+                [spawning]
+                enabled = false
+                "#,
+                port
+            ),
+        )
+        .unwrap();
+
+        let child = Command::new(env!("CARGO_BIN_EXE_minecrate"))
+            .current_dir(&dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        Self { child, dir, port }
+    }
+
+    /// Connects once the server listens.
+    fn connect(&self) -> Client {
+        let started = Instant::now();
+
+        loop {
+            match Client::connect("127.0.0.1", self.port) {
+                Ok(client) => return client,
+                Err(e) if started.elapsed() > Duration::from_secs(30) => {
+                    panic!("server never listened: {}", e)
+                }
+                Err(_) => thread::sleep(Duration::from_millis(100)),
+            }
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Reads packets until one matches, failing after `timeout`.
+fn wait_for<F>(client: &mut Client, timeout: Duration, matches: F) -> ClientboundPacket
+where
+    F: Fn(&ClientboundPacket) -> bool,
+{
+    let started = Instant::now();
+    client
+        .set_read_timeout(Some(Duration::from_millis(500)))
+        .unwrap();
+
+    while started.elapsed() < timeout {
+        match client.read_packet() {
+            Ok(packet) if matches(&packet) => return packet,
+            Ok(_) => {}
+            Err(ref e)
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {
+            }
+            Err(e) => panic!("connection lost: {}", e),
+        }
+    }
+    panic!("no matching packet within {:?}", timeout);
+}
+
+#[test]
+fn bot_joins_and_answers_keep_alives() {
+    let server = Server::start();
+    let mut client = server.connect();
+
+    client.login("TestBot", None).unwrap();
+    assert_eq!(*client.get_state(), State::Play);
+    wait_for(&mut client, Duration::from_secs(5), |packet| {
+        matches!(packet, ClientboundPacket::JoinGame(_))
+    });
+
+    // The client answers the keep-alive on its own
+    wait_for(&mut client, KEEP_ALIVE_WAIT, |packet| {
+        matches!(packet, ClientboundPacket::KeepAlive(_))
+    });
+
+    // The server still talks to the bot afterwards
+    client.send_chat("still here").unwrap();
+    wait_for(&mut client, Duration::from_secs(5), |packet| match packet {
+        ClientboundPacket::ChatMessage(chat) => chat
+            .message
+            .to_string()
+            .is_ok_and(|message| message.contains("still here")),
+        _ => false,
+    });
+}
@@ -0,0 +1,43 @@
+;; Cancels chat messages mentioning spam.
+(module
+  (import "minecrate" "subscribe" (func $subscribe (param i32 i32) (result i32)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "spam")
+
+  (func (export "minecrate_abi_version") (result i32)
+    i32.const 1)
+
+  (func (export "minecrate_alloc") (param $len i32) (result i32)
+    i32.const 1024)
+
+  (func (export "minecrate_enable")
+    ;; PlayerChat at High priority
+    (drop (call $subscribe (i32.const 3) (i32.const 3))))
+
+  (func (export "minecrate_on_event") (param $kind i32) (param $ptr i32) (param $len i32)
+    (result i32)
+    (call $contains (local.get $ptr) (local.get $len)))
+
+  ;; Whether the payload contains the 4 bytes at address 0
+  (func $contains (param $ptr i32) (param $len i32) (result i32)
+    (local $i i32)
+    (local $j i32)
+    (block $done
+      (loop $outer
+        (br_if $done
+          (i32.gt_u (i32.add (local.get $i) (i32.const 4)) (local.get $len)))
+        (local.set $j (i32.const 0))
+        (block $mismatch
+          (loop $inner
+            (br_if $mismatch
+              (i32.ne
+                (i32.load8_u
+                  (i32.add (local.get $ptr) (i32.add (local.get $i) (local.get $j))))
+                (i32.load8_u (local.get $j))))
+            (local.set $j (i32.add (local.get $j) (i32.const 1)))
+            (br_if $inner (i32.lt_u (local.get $j) (i32.const 4)))
+            (return (i32.const 1))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $outer)))
+    i32.const 0))
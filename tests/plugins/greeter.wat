@@ -0,0 +1,33 @@
+;; Welcomes players as they join, and greets whoever runs /greet.
+(module
+  (import "minecrate" "register_command"
+    (func $register_command (param i32 i32 i32 i32 i32) (result i32)))
+  (import "minecrate" "subscribe" (func $subscribe (param i32 i32) (result i32)))
+  (import "minecrate" "broadcast" (func $broadcast (param i32 i32)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "greet")
+  (data (i32.const 16) "Welcome to the server!")
+  (data (i32.const 48) "Hello from the greeter plugin!")
+
+  (func (export "minecrate_abi_version") (result i32)
+    i32.const 1)
+
+  ;; Payloads are read before the next one comes, so one buffer is enough
+  (func (export "minecrate_alloc") (param $len i32) (result i32)
+    i32.const 1024)
+
+  (func (export "minecrate_enable")
+    ;; /greet, without arguments, for everyone
+    (drop (call $register_command
+      (i32.const 0) (i32.const 5) (i32.const 0) (i32.const 0) (i32.const 0)))
+    ;; PlayerJoin at Normal priority
+    (drop (call $subscribe (i32.const 1) (i32.const 2))))
+
+  (func (export "minecrate_on_event") (param $kind i32) (param $ptr i32) (param $len i32)
+    (result i32)
+    (call $broadcast (i32.const 16) (i32.const 22))
+    i32.const 0)
+
+  (func (export "minecrate_on_command") (param $ptr i32) (param $len i32)
+    (call $broadcast (i32.const 48) (i32.const 30))))
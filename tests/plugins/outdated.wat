@@ -0,0 +1,9 @@
+;; Built against an ABI the server no longer offers.
+(module
+  (memory (export "memory") 1)
+
+  (func (export "minecrate_abi_version") (result i32)
+    i32.const 0)
+
+  (func (export "minecrate_alloc") (param $len i32) (result i32)
+    i32.const 1024))
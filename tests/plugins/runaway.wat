@@ -0,0 +1,13 @@
+;; Never returns from being enabled, and has to be stopped.
+(module
+  (memory (export "memory") 1)
+
+  (func (export "minecrate_abi_version") (result i32)
+    i32.const 1)
+
+  (func (export "minecrate_alloc") (param $len i32) (result i32)
+    i32.const 1024)
+
+  (func (export "minecrate_enable")
+    (loop $forever
+      (br $forever))))
@@ -0,0 +1,25 @@
+;; Broadcasts every other tick, three times.
+(module
+  (import "minecrate" "schedule" (func $schedule (param i32 i32) (result i32)))
+  (import "minecrate" "cancel_task" (func $cancel_task (param i32)))
+  (import "minecrate" "broadcast" (func $broadcast (param i32 i32)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "Tick!")
+  (global $task (mut i32) (i32.const 0))
+  (global $runs (mut i32) (i32.const 0))
+
+  (func (export "minecrate_abi_version") (result i32)
+    i32.const 1)
+
+  (func (export "minecrate_alloc") (param $len i32) (result i32)
+    i32.const 1024)
+
+  (func (export "minecrate_enable")
+    (global.set $task (call $schedule (i32.const 0) (i32.const 2))))
+
+  (func (export "minecrate_on_task") (param $task i32)
+    (call $broadcast (i32.const 0) (i32.const 5))
+    (global.set $runs (i32.add (global.get $runs) (i32.const 1)))
+    (if (i32.eq (global.get $runs) (i32.const 3))
+      (then (call $cancel_task (global.get $task))))))
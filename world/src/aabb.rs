@@ -0,0 +1,121 @@
+/// Margin under which boxes are considered touching rather than overlapping.
+const EPSILON: f64 = 1e-7;
+
+/// Axis-aligned bounding box, in blocks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub min_z: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+    pub max_z: f64,
+}
+
+impl Aabb {
+    pub const fn new(
+        min_x: f64,
+        min_y: f64,
+        min_z: f64,
+        max_x: f64,
+        max_y: f64,
+        max_z: f64,
+    ) -> Self {
+        Self {
+            min_x,
+            min_y,
+            min_z,
+            max_x,
+            max_y,
+            max_z,
+        }
+    }
+
+    pub fn offset(&self, x: f64, y: f64, z: f64) -> Self {
+        Self::new(
+            self.min_x + x,
+            self.min_y + y,
+            self.min_z + z,
+            self.max_x + x,
+            self.max_y + y,
+            self.max_z + z,
+        )
+    }
+
+    /// Grows the box in the direction of a move, to cover all of it.
+    pub fn expand_towards(&self, x: f64, y: f64, z: f64) -> Self {
+        Self::new(
+            self.min_x + x.min(0.0),
+            self.min_y + y.min(0.0),
+            self.min_z + z.min(0.0),
+            self.max_x + x.max(0.0),
+            self.max_y + y.max(0.0),
+            self.max_z + z.max(0.0),
+        )
+    }
+
+    /// Grows the box by the given amounts on both sides of each axis.
+    pub fn grow(&self, x: f64, y: f64, z: f64) -> Self {
+        Self::new(
+            self.min_x - x,
+            self.min_y - y,
+            self.min_z - z,
+            self.max_x + x,
+            self.max_y + y,
+            self.max_z + z,
+        )
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min_x < other.max_x
+            && self.max_x > other.min_x
+            && self.min_y < other.max_y
+            && self.max_y > other.min_y
+            && self.min_z < other.max_z
+            && self.max_z > other.min_z
+    }
+
+    /// Shortens a move of `other` along x so it stops against this box.
+    pub fn clip_x(&self, other: &Aabb, delta: f64) -> f64 {
+        if !overlaps(self.min_y, self.max_y, other.min_y, other.max_y)
+            || !overlaps(self.min_z, self.max_z, other.min_z, other.max_z)
+        {
+            return delta;
+        }
+        clip(self.min_x, self.max_x, other.min_x, other.max_x, delta)
+    }
+
+    /// Shortens a move of `other` along y so it stops against this box.
+    pub fn clip_y(&self, other: &Aabb, delta: f64) -> f64 {
+        if !overlaps(self.min_x, self.max_x, other.min_x, other.max_x)
+            || !overlaps(self.min_z, self.max_z, other.min_z, other.max_z)
+        {
+            return delta;
+        }
+        clip(self.min_y, self.max_y, other.min_y, other.max_y, delta)
+    }
+
+    /// Shortens a move of `other` along z so it stops against this box.
+    pub fn clip_z(&self, other: &Aabb, delta: f64) -> f64 {
+        if !overlaps(self.min_x, self.max_x, other.min_x, other.max_x)
+            || !overlaps(self.min_y, self.max_y, other.min_y, other.max_y)
+        {
+            return delta;
+        }
+        clip(self.min_z, self.max_z, other.min_z, other.max_z, delta)
+    }
+}
+
+fn overlaps(min: f64, max: f64, other_min: f64, other_max: f64) -> bool {
+    min < other_max - EPSILON && max > other_min + EPSILON
+}
+
+fn clip(min: f64, max: f64, other_min: f64, other_max: f64, delta: f64) -> f64 {
+    if delta > 0.0 && other_max <= min + EPSILON {
+        delta.min(min - other_max)
+    } else if delta < 0.0 && other_min >= max - EPSILON {
+        delta.max(max - other_min)
+    } else {
+        delta
+    }
+}
@@ -0,0 +1,81 @@
+use common::entity_type::{EntityType, MobCategory};
+use std::collections::HashMap;
+
+/// Id of a biome in the biome registry.
+pub type Biome = i32;
+
+pub const PLAINS: Biome = 1;
+
+/// A mob which can spawn in a biome, in groups of `min_group` to
+/// `max_group`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnEntry {
+    pub entity_type: EntityType,
+    pub weight: u32,
+    pub min_group: u32,
+    pub max_group: u32,
+}
+
+impl SpawnEntry {
+    pub fn new(entity_type: EntityType, weight: u32, min_group: u32, max_group: u32) -> Self {
+        Self {
+            entity_type,
+            weight,
+            min_group,
+            max_group,
+        }
+    }
+}
+
+/// Mobs spawning in each biome, by category. Biomes without a list of
+/// their own use the default one, which has the mobs of most biomes.
+#[derive(Debug)]
+pub struct BiomeSpawns {
+    defaults: HashMap<MobCategory, Vec<SpawnEntry>>,
+    spawns: HashMap<(Biome, MobCategory), Vec<SpawnEntry>>,
+}
+
+impl BiomeSpawns {
+    pub fn new() -> Self {
+        let mut defaults = HashMap::new();
+
+        defaults.insert(
+            MobCategory::Monster,
+            vec![SpawnEntry::new(EntityType::Zombie, 95, 4, 4)],
+        );
+        defaults.insert(
+            MobCategory::Creature,
+            vec![SpawnEntry::new(EntityType::Cow, 8, 4, 4)],
+        );
+        Self {
+            defaults,
+            spawns: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, biome: Biome, category: MobCategory) -> &[SpawnEntry] {
+        match self.spawns.get(&(biome, category)) {
+            Some(entries) => entries,
+            None => self
+                .defaults
+                .get(&category)
+                .map_or(&[], |entries| entries.as_slice()),
+        }
+    }
+
+    /// Replaces the mobs of a category in a biome, none stopping them from
+    /// spawning there.
+    pub fn set(&mut self, biome: Biome, category: MobCategory, entries: Vec<SpawnEntry>) {
+        self.spawns.insert((biome, category), entries);
+    }
+
+    pub fn set_default(&mut self, category: MobCategory, entries: Vec<SpawnEntry>) {
+        self.defaults.insert(category, entries);
+    }
+}
+
+impl Default for BiomeSpawns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use crate::aabb::Aabb;
+
+/// Id of a block state in the global palette.
+pub type BlockState = u16;
+
+pub const AIR: BlockState = 0;
+
+const FULL_CUBE: [Aabb; 1] = [Aabb::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)];
+
+/// Collision boxes of the block states, relative to their block. States
+/// without a registered shape are full cubes, except air.
+#[derive(Debug, Default)]
+pub struct BlockShapes {
+    shapes: HashMap<BlockState, Vec<Aabb>>,
+}
+
+impl BlockShapes {
+    pub fn new() -> Self {
+        Self {
+            shapes: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, state: BlockState) -> &[Aabb] {
+        match self.shapes.get(&state) {
+            Some(boxes) => boxes,
+            None if state == AIR => &[],
+            None => &FULL_CUBE,
+        }
+    }
+
+    /// Gives a shape to a state, an empty one letting entities through.
+    pub fn set(&mut self, state: BlockState, boxes: Vec<Aabb>) {
+        self.shapes.insert(state, boxes);
+    }
+}
+
+/// Items the block states drop when broken, by id in the item registry.
+#[derive(Debug, Default)]
+pub struct BlockDrops {
+    drops: HashMap<BlockState, i32>,
+}
+
+impl BlockDrops {
+    pub fn new() -> Self {
+        Self {
+            drops: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, state: BlockState) -> Option<i32> {
+        self.drops.get(&state).copied()
+    }
+
+    pub fn set(&mut self, state: BlockState, item: i32) {
+        self.drops.insert(state, item);
+    }
+}
@@ -1 +1,117 @@
-pub struct Chunk {}
+use crate::biome::{Biome, PLAINS};
+use crate::block::{BlockState, AIR};
+
+pub const SECTION_COUNT: usize = 16;
+const SECTION_VOLUME: usize = 16 * 16 * 16;
+/// Biomes are stored for cells of 4x4x4 blocks, like in the protocol.
+const BIOME_COUNT: usize = 4 * 64 * 4;
+
+/// Column of 16x256x16 blocks, whose empty sections take no memory.
+#[derive(Debug, Clone, Default)]
+pub struct Chunk {
+    sections: [Option<Box<[BlockState]>>; SECTION_COUNT],
+    /// Light of the blocks which glow or are lit by them, from 0 to 15.
+    block_light: [Option<Box<[u8]>>; SECTION_COUNT],
+    /// Plains everywhere until set.
+    biomes: Option<Box<[Biome]>>,
+}
+
+impl Chunk {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Block at coordinates relative to the chunk, air above and below it.
+    pub fn get_block(&self, x: usize, y: i32, z: usize) -> BlockState {
+        if !(0..256).contains(&y) {
+            return AIR;
+        }
+
+        let y = y as usize;
+        match &self.sections[y / 16] {
+            Some(blocks) => blocks[get_index(x, y % 16, z)],
+            None => AIR,
+        }
+    }
+
+    /// Places a block at coordinates relative to the chunk, doing nothing
+    /// above or below it.
+    pub fn set_block(&mut self, x: usize, y: i32, z: usize, state: BlockState) {
+        if !(0..256).contains(&y) {
+            return;
+        }
+
+        let y = y as usize;
+        let section = &mut self.sections[y / 16];
+        if section.is_none() && state == AIR {
+            return;
+        }
+        section.get_or_insert_with(|| vec![AIR; SECTION_VOLUME].into_boxed_slice())
+            [get_index(x, y % 16, z)] = state;
+    }
+
+    /// Height above the highest block of a column, 0 if it's empty.
+    pub fn get_height(&self, x: usize, z: usize) -> i32 {
+        for (index, section) in self.sections.iter().enumerate().rev() {
+            if let Some(blocks) = section {
+                for y in (0..16).rev() {
+                    if blocks[get_index(x, y, z)] != AIR {
+                        return (index * 16 + y) as i32 + 1;
+                    }
+                }
+            }
+        }
+        0
+    }
+
+    pub fn get_block_light(&self, x: usize, y: i32, z: usize) -> u8 {
+        if !(0..256).contains(&y) {
+            return 0;
+        }
+
+        let y = y as usize;
+        match &self.block_light[y / 16] {
+            Some(light) => light[get_index(x, y % 16, z)],
+            None => 0,
+        }
+    }
+
+    pub fn set_block_light(&mut self, x: usize, y: i32, z: usize, light: u8) {
+        if !(0..256).contains(&y) {
+            return;
+        }
+
+        let y = y as usize;
+        let section = &mut self.block_light[y / 16];
+        if section.is_none() && light == 0 {
+            return;
+        }
+        section.get_or_insert_with(|| vec![0; SECTION_VOLUME].into_boxed_slice())
+            [get_index(x, y % 16, z)] = light.min(15);
+    }
+
+    pub fn get_biome(&self, x: usize, y: i32, z: usize) -> Biome {
+        match &self.biomes {
+            Some(biomes) => biomes[get_biome_index(x, y, z)],
+            None => PLAINS,
+        }
+    }
+
+    /// Sets the biome of the 4x4x4 cell holding a block.
+    pub fn set_biome(&mut self, x: usize, y: i32, z: usize, biome: Biome) {
+        let index = get_biome_index(x, y, z);
+
+        self.biomes
+            .get_or_insert_with(|| vec![PLAINS; BIOME_COUNT].into_boxed_slice())[index] = biome;
+    }
+}
+
+fn get_index(x: usize, y: usize, z: usize) -> usize {
+    (y * 16 + z) * 16 + x
+}
+
+fn get_biome_index(x: usize, y: i32, z: usize) -> usize {
+    let y = y.clamp(0, 255) as usize;
+
+    ((y / 4) * 4 + z / 4) * 4 + x / 4
+}
@@ -1,2 +1,6 @@
+pub mod aabb;
+pub mod biome;
+pub mod block;
 pub mod chunk;
+pub mod spawning;
 pub mod world;
@@ -0,0 +1,53 @@
+use common::entity_type::MobCategory;
+
+use crate::block::BlockState;
+
+/// How mobs spawn naturally in a world.
+#[derive(Debug, Clone)]
+pub struct SpawnSettings {
+    /// Whether mobs spawn at all, worlds like lobbies having none.
+    pub enabled: bool,
+    pub monster_cap: u32,
+    pub creature_cap: u32,
+    pub ambient_cap: u32,
+    pub water_creature_cap: u32,
+    /// Blocks creatures spawn on, grass by default.
+    pub creature_blocks: Vec<BlockState>,
+    /// Blocks water creatures spawn in, water by default.
+    pub water_blocks: Vec<BlockState>,
+}
+
+impl SpawnSettings {
+    /// Mobs of a category there can be around 17x17 loaded chunks.
+    pub fn get_cap(&self, category: MobCategory) -> u32 {
+        match category {
+            MobCategory::Monster => self.monster_cap,
+            MobCategory::Creature => self.creature_cap,
+            MobCategory::Ambient => self.ambient_cap,
+            MobCategory::WaterCreature => self.water_creature_cap,
+        }
+    }
+
+    pub fn set_cap(&mut self, category: MobCategory, cap: u32) {
+        match category {
+            MobCategory::Monster => self.monster_cap = cap,
+            MobCategory::Creature => self.creature_cap = cap,
+            MobCategory::Ambient => self.ambient_cap = cap,
+            MobCategory::WaterCreature => self.water_creature_cap = cap,
+        }
+    }
+}
+
+impl Default for SpawnSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            monster_cap: 70,
+            creature_cap: 10,
+            ambient_cap: 15,
+            water_creature_cap: 5,
+            creature_blocks: vec![8, 9],
+            water_blocks: (34..=49).collect(),
+        }
+    }
+}
@@ -1,12 +1,24 @@
 use common::dimension::Dimension;
 use common::level_type::LevelType;
+use std::collections::HashMap;
 
+use crate::aabb::Aabb;
+use crate::biome::{Biome, BiomeSpawns, PLAINS};
+use crate::block::{BlockDrops, BlockShapes, BlockState, AIR};
 use crate::chunk::Chunk;
+use crate::spawning::SpawnSettings;
+
+/// Light of the blocks the sky shines on.
+pub const SKY_LIGHT: u8 = 15;
 
 pub struct World {
     pub dimension: Dimension,
     pub level_type: LevelType,
-    pub chunk: Chunk,
+    pub shapes: BlockShapes,
+    pub drops: BlockDrops,
+    pub spawns: BiomeSpawns,
+    pub spawning: SpawnSettings,
+    chunks: HashMap<(i32, i32), Chunk>,
 }
 
 impl World {
@@ -14,7 +26,108 @@ impl World {
         Self {
             dimension,
             level_type,
-            chunk: Chunk {},
+            shapes: BlockShapes::new(),
+            drops: BlockDrops::new(),
+            spawns: BiomeSpawns::new(),
+            spawning: SpawnSettings::default(),
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Block at the given coordinates, air in chunks which aren't loaded.
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> BlockState {
+        match self.chunks.get(&(x >> 4, z >> 4)) {
+            Some(chunk) => chunk.get_block((x & 15) as usize, y, (z & 15) as usize),
+            None => AIR,
+        }
+    }
+
+    /// Places a block, loading its chunk if needed.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, state: BlockState) {
+        self.chunks.entry((x >> 4, z >> 4)).or_default().set_block(
+            (x & 15) as usize,
+            y,
+            (z & 15) as usize,
+            state,
+        );
+    }
+
+    pub fn is_chunk_loaded(&self, chunk_x: i32, chunk_z: i32) -> bool {
+        self.chunks.contains_key(&(chunk_x, chunk_z))
+    }
+
+    /// Height above the highest block of a column, 0 if it's empty or not
+    /// loaded.
+    pub fn get_height(&self, x: i32, z: i32) -> i32 {
+        self.chunks.get(&(x >> 4, z >> 4)).map_or(0, |chunk| {
+            chunk.get_height((x & 15) as usize, (z & 15) as usize)
+        })
+    }
+
+    /// Light at a block, from the sky if nothing is above it, or else from
+    /// the blocks around.
+    pub fn get_light(&self, x: i32, y: i32, z: i32) -> u8 {
+        let chunk = match self.chunks.get(&(x >> 4, z >> 4)) {
+            Some(chunk) => chunk,
+            None => return SKY_LIGHT,
+        };
+        let (x, z) = ((x & 15) as usize, (z & 15) as usize);
+
+        if y >= chunk.get_height(x, z) {
+            SKY_LIGHT
+        } else {
+            chunk.get_block_light(x, y, z)
         }
     }
+
+    pub fn set_block_light(&mut self, x: i32, y: i32, z: i32, light: u8) {
+        self.chunks
+            .entry((x >> 4, z >> 4))
+            .or_default()
+            .set_block_light((x & 15) as usize, y, (z & 15) as usize, light);
+    }
+
+    /// Biome at a block, plains in chunks which aren't loaded.
+    pub fn get_biome(&self, x: i32, y: i32, z: i32) -> Biome {
+        self.chunks.get(&(x >> 4, z >> 4)).map_or(PLAINS, |chunk| {
+            chunk.get_biome((x & 15) as usize, y, (z & 15) as usize)
+        })
+    }
+
+    pub fn set_biome(&mut self, x: i32, y: i32, z: i32, biome: Biome) {
+        self.chunks.entry((x >> 4, z >> 4)).or_default().set_biome(
+            (x & 15) as usize,
+            y,
+            (z & 15) as usize,
+            biome,
+        );
+    }
+
+    /// Collision boxes of the blocks touching an area.
+    pub fn get_collision_boxes(&self, area: &Aabb) -> Vec<Aabb> {
+        let mut boxes = vec![];
+
+        // Boxes may stick out of their block, like those of fences
+        for x in area.min_x.floor() as i32..=area.max_x.floor() as i32 {
+            for y in area.min_y.floor() as i32 - 1..=area.max_y.floor() as i32 {
+                for z in area.min_z.floor() as i32..=area.max_z.floor() as i32 {
+                    let state = self.get_block(x, y, z);
+
+                    for shape in self.shapes.get(state) {
+                        let shape = shape.offset(f64::from(x), f64::from(y), f64::from(z));
+                        if shape.intersects(area) {
+                            boxes.push(shape);
+                        }
+                    }
+                }
+            }
+        }
+        boxes
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new(Dimension::Overworld, LevelType::Default)
+    }
 }